@@ -0,0 +1,21 @@
+#!/usr/bin/env bash
+
+# Writes the expected output of each test with GNU base64 and basenc
+
+OUTDIR="tests/expected"
+[[ ! -d "$OUTDIR" ]] && mkdir -p "$OUTDIR"
+
+BYTES="tests/inputs/bytes.bin"
+BUSTLE="tests/inputs/the-bustle.txt"
+FOX="tests/inputs/fox.txt"
+EMPTY="tests/inputs/empty.txt"
+GARBAGE="tests/inputs/garbage.txt"
+
+base64 $EMPTY > $OUTDIR/empty.txt.out
+base64 $FOX > $OUTDIR/fox.txt.out
+base64 $BUSTLE > $OUTDIR/the-bustle.txt.out
+base64 $BYTES > $OUTDIR/bytes.bin.out
+base64 -w 0 $BYTES > $OUTDIR/bytes.bin.w0.out
+base64 -w 10 $BYTES > $OUTDIR/bytes.bin.w10.out
+basenc --base64url $BYTES > $OUTDIR/bytes.bin.url.out
+base64 -d -i $GARBAGE > $OUTDIR/garbage.txt.di.out
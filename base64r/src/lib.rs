@@ -0,0 +1,285 @@
+use clap::Parser;
+use std::io::{self, ErrorKind, Read, Write};
+
+use coreutils_common::{open, parse_args, CoreError, MyResult, Output};
+
+/// How much is read at a time; a multiple of 3, so only the last block
+/// encodes with padding.
+const BLOCK_SIZE: usize = 3 * 1024;
+
+const STANDARD: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL_SAFE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+#[derive(Debug)]
+pub struct Config {
+    file: String,
+    decode: bool,
+    ignore_garbage: bool,
+    wrap: usize,
+    alphabet: &'static [u8; 64],
+}
+
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust base64")]
+struct Args {
+    /// Input file
+    #[arg(value_name = "FILE", default_value = "-")]
+    file: String,
+
+    /// Decode data
+    #[arg(short = 'd', long)]
+    decode: bool,
+
+    /// When decoding, ignore characters outside the alphabet
+    #[arg(short = 'i', long)]
+    ignore_garbage: bool,
+
+    /// Wrap encoded lines after COLS characters, or 0 for no wrapping
+    #[arg(short = 'w', long, value_name = "COLS", default_value = "76")]
+    wrap: String,
+
+    /// Use the URL and filename safe alphabet, with - and _ for + and /
+    #[arg(short = 'u', long)]
+    url: bool,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let args: Args = parse_args();
+
+    let wrap = args
+        .wrap
+        .parse()
+        .map_err(|_| CoreError::Parse(format!("invalid wrap size: '{}'", args.wrap)))?;
+
+    Ok(Config {
+        file: args.file,
+        decode: args.decode,
+        ignore_garbage: args.ignore_garbage,
+        wrap,
+        alphabet: if args.url { URL_SAFE } else { STANDARD },
+    })
+}
+
+/// Reads into `buf` until it is full or the input ends, returning how much
+/// was read.
+fn read_full(input: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+    while len < buf.len() {
+        match input.read(&mut buf[len..]) {
+            Ok(0) => break,
+            Ok(n) => len += n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(len)
+}
+
+/// Encodes `bytes`, padding the last group of characters with '='.
+fn encode(bytes: &[u8], alphabet: &[u8; 64]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let bits = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |bits, (i, &byte)| bits | (byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            out.push(if i <= chunk.len() {
+                alphabet[(bits >> (18 - 6 * i) & 0x3f) as usize]
+            } else {
+                b'='
+            });
+        }
+    }
+    out
+}
+
+/// Writes `input` encoded, with a newline every `wrap` characters and at
+/// the end.
+fn encode_stream(input: &mut impl Read, config: &Config, out: &mut impl Write) -> MyResult<()> {
+    let mut buf = vec![0; BLOCK_SIZE];
+    let mut column = 0;
+    loop {
+        let len = read_full(input, &mut buf).map_err(|e| CoreError::io(&config.file, e))?;
+        if len == 0 {
+            break;
+        }
+        let encoded = encode(&buf[..len], config.alphabet);
+        if config.wrap == 0 {
+            out.write_all(&encoded)?;
+            continue;
+        }
+        let mut rest = &encoded[..];
+        while !rest.is_empty() {
+            let (line, tail) = rest.split_at((config.wrap - column).min(rest.len()));
+            out.write_all(line)?;
+            column += line.len();
+            if column == config.wrap {
+                out.write_all(b"\n")?;
+                column = 0;
+            }
+            rest = tail;
+        }
+    }
+    if column > 0 {
+        out.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+/// A decoder fed one character at a time, so the input never has to be
+/// held whole.
+struct Decoder {
+    /// The value of each character of the group so far
+    values: [u8; 4],
+    /// How many characters of the group have been seen, '=' included
+    len: usize,
+    /// How many of them were '='
+    padding: usize,
+    /// The value of each byte, or 0xff if it isn't in the alphabet
+    lookup: [u8; 256],
+}
+
+impl Decoder {
+    fn new(alphabet: &[u8; 64]) -> Self {
+        let mut lookup = [0xff; 256];
+        for (value, &c) in alphabet.iter().enumerate() {
+            lookup[c as usize] = value as u8;
+        }
+        Decoder { values: [0; 4], len: 0, padding: 0, lookup }
+    }
+
+    /// The bytes that the first `count` characters of the group decode to.
+    fn bytes(&self, count: usize) -> Vec<u8> {
+        let bits = self.values[..count]
+            .iter()
+            .enumerate()
+            .fold(0u32, |bits, (i, &value)| bits | (value as u32) << (18 - 6 * i));
+        (0..count.saturating_sub(1)).map(|i| (bits >> (16 - 8 * i)) as u8).collect()
+    }
+
+    /// Takes the next character, returning any bytes it completes or None
+    /// if it can't come there: '=' only pads the last one or two places of
+    /// a group, and nothing but '=' follows it in that group.
+    fn push(&mut self, c: u8) -> Option<Vec<u8>> {
+        if c == b'=' {
+            if self.len < 2 {
+                return None;
+            }
+            self.padding += 1;
+        } else {
+            let value = self.lookup[c as usize];
+            if value == 0xff || self.padding > 0 {
+                return None;
+            }
+            self.values[self.len] = value;
+        }
+        self.len += 1;
+        if self.len < 4 {
+            return Some(vec![]);
+        }
+        let bytes = self.bytes(4 - self.padding);
+        self.len = 0;
+        self.padding = 0;
+        Some(bytes)
+    }
+
+    /// What an unfinished group at the end decodes to; it's still an error.
+    fn rest(&self) -> Option<Vec<u8>> {
+        match self.len {
+            0 => None,
+            len => Some(self.bytes(len - self.padding)),
+        }
+    }
+}
+
+/// Writes `input` decoded. Newlines are skipped, and with -i anything else
+/// outside the alphabet as well. On bad input, whatever decoded up to it
+/// is still written.
+fn decode_stream(input: &mut impl Read, config: &Config, out: &mut impl Write) -> MyResult<()> {
+    let invalid = || CoreError::Input("invalid input".to_string());
+    let mut decoder = Decoder::new(config.alphabet);
+    let mut buf = vec![0; BLOCK_SIZE];
+    loop {
+        let len = read_full(input, &mut buf).map_err(|e| CoreError::io(&config.file, e))?;
+        if len == 0 {
+            break;
+        }
+        for &c in &buf[..len] {
+            if c == b'\n' {
+                continue;
+            }
+            match decoder.push(c) {
+                Some(bytes) => out.write_all(&bytes)?,
+                None if config.ignore_garbage && c != b'=' && decoder.lookup[c as usize] == 0xff => {}
+                None => return Err(invalid()),
+            }
+        }
+    }
+    match decoder.rest() {
+        Some(bytes) => {
+            out.write_all(&bytes)?;
+            Err(invalid())
+        }
+        None => Ok(()),
+    }
+}
+
+pub fn run(config: Config) -> MyResult<i32> {
+    let mut input = open(&config.file)?;
+    let mut out = Output::stdout();
+    if config.decode {
+        decode_stream(&mut input, &config, &mut out)?;
+    } else {
+        encode_stream(&mut input, &config, &mut out)?;
+    }
+    out.flush()?;
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode, Decoder, STANDARD, URL_SAFE};
+
+    fn decode(text: &str) -> Option<Vec<u8>> {
+        let mut decoder = Decoder::new(STANDARD);
+        let mut out = vec![];
+        for c in text.bytes() {
+            out.extend(decoder.push(c)?);
+        }
+        decoder.rest().is_none().then_some(out)
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode(b"", STANDARD), b"");
+        assert_eq!(encode(b"h", STANDARD), b"aA==");
+        assert_eq!(encode(b"hi", STANDARD), b"aGk=");
+        assert_eq!(encode(b"hi!", STANDARD), b"aGkh");
+        assert_eq!(encode(b"\xfb\xff", STANDARD), b"+/8=");
+        assert_eq!(encode(b"\xfb\xff", URL_SAFE), b"-_8=");
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode("aGkh").unwrap(), b"hi!");
+        assert_eq!(decode("aGk=").unwrap(), b"hi");
+        assert_eq!(decode("aA==").unwrap(), b"h");
+        // Padded groups may follow one another
+        assert_eq!(decode("aGk=aGk=").unwrap(), b"hihi");
+        assert_eq!(decode("aGk"), None);
+        assert_eq!(decode("aGk=="), None);
+        assert_eq!(decode("aG=k"), None);
+        assert_eq!(decode("=aGk"), None);
+        assert_eq!(decode("-_8="), None);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        for len in 0..bytes.len() {
+            let text = String::from_utf8(encode(&bytes[..len], STANDARD)).unwrap();
+            assert_eq!(decode(&text).unwrap(), &bytes[..len]);
+        }
+    }
+}
@@ -0,0 +1,10 @@
+fn main() {
+    coreutils_common::reset_sigpipe();
+    match base64r::get_args().and_then(base64r::run) {
+        Ok(status) => std::process::exit(status),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(e.exit_code());
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "base64r";
+const BYTES: &str = "tests/inputs/bytes.bin";
+const BUSTLE: &str = "tests/inputs/the-bustle.txt";
+const FOX: &str = "tests/inputs/fox.txt";
+const EMPTY: &str = "tests/inputs/empty.txt";
+const GARBAGE: &str = "tests/inputs/garbage.txt";
+
+// --------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> TestResult {
+    let expected = fs::read(expected_file)?;
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_file() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .arg("tests/inputs/does-not-exist")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::starts_with("tests/inputs/does-not-exist: "));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_wrap() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-w", "x", FOX])
+        .assert()
+        .code(1)
+        .stderr("invalid wrap size: 'x'\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn empty() -> TestResult {
+    run(&[EMPTY], "tests/expected/empty.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fox() -> TestResult {
+    run(&[FOX], "tests/expected/fox.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn bustle_stdin() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .write_stdin(fs::read(BUSTLE)?)
+        .assert()
+        .success()
+        .stdout(fs::read_to_string("tests/expected/the-bustle.txt.out")?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn bytes() -> TestResult {
+    run(&[BYTES], "tests/expected/bytes.bin.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn bytes_no_wrap() -> TestResult {
+    run(&["-w", "0", BYTES], "tests/expected/bytes.bin.w0.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn bytes_wrap() -> TestResult {
+    run(&["--wrap", "10", BYTES], "tests/expected/bytes.bin.w10.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn bytes_url() -> TestResult {
+    run(&["-u", BYTES], "tests/expected/bytes.bin.url.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn decode() -> TestResult {
+    for (args, expected) in [
+        (&["-d", "tests/expected/bytes.bin.out"][..], BYTES),
+        (&["-d", "tests/expected/bytes.bin.w0.out"], BYTES),
+        (&["--decode", "tests/expected/bytes.bin.w10.out"], BYTES),
+        (&["-d", "-u", "tests/expected/bytes.bin.url.out"], BYTES),
+        (&["-d", "tests/expected/the-bustle.txt.out"], BUSTLE),
+        (&["-d", "tests/expected/empty.txt.out"], EMPTY),
+    ] {
+        run(args, expected)?;
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn decode_ignore_garbage() -> TestResult {
+    run(&["-d", "-i", GARBAGE], "tests/expected/garbage.txt.di.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_decode_garbage() -> TestResult {
+    // What decodes before the bad character is still written
+    Command::cargo_bin(PRG)?
+        .args(["-d", GARBAGE])
+        .assert()
+        .code(1)
+        .stdout("The")
+        .stderr("invalid input\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_decode_wrong_alphabet() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-d", "tests/expected/bytes.bin.url.out"])
+        .assert()
+        .code(1)
+        .stderr("invalid input\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn round_trip_across_blocks() -> TestResult {
+    let bytes: Vec<u8> = (0..100_000u32).map(|n| (n * 7 % 251) as u8).collect();
+    let encoded = Command::cargo_bin(PRG)?.write_stdin(bytes.clone()).output()?;
+    Command::cargo_bin(PRG)?
+        .arg("-d")
+        .write_stdin(encoded.stdout)
+        .assert()
+        .success()
+        .stdout(bytes);
+    Ok(())
+}
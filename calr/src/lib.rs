@@ -1,48 +1,47 @@
 use ansi_term::Style;
 use chrono::{Datelike, Local, NaiveDate};
-use clap::{App, Arg};
+use clap::Parser;
+use coreutils_common::{
+    i18n::{tr, MONTHS},
+    parse_args, ColorChoice, CoreError, MyResult,
+};
 use itertools::{izip, Itertools};
-use std::error::Error;
 
 #[derive(Debug)]
 pub struct Config {
     month: Option<u32>,
     year: i32,
-    today: NaiveDate,
+    /// The day to highlight, unless color is off
+    today: Option<NaiveDate>,
 }
 
-type MyResult<T> = Result<T, Box<dyn Error>>;
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust cal")]
+struct Args {
+    /// Month name or number 1-12
+    #[arg(short, value_name = "MONTH")]
+    month: Option<String>,
+
+    /// Show whole current year
+    #[arg(short = 'y', long = "year", conflicts_with_all = ["month", "year"])]
+    show_current_year: bool,
+
+    /// Year (1-9999)
+    #[arg(value_name = "YEAR")]
+    year: Option<String>,
+
+    /// Highlight today's date
+    #[arg(long, value_name = "WHEN", default_value = "auto")]
+    color: ColorChoice,
+}
 
 pub fn get_args() -> MyResult<Config> {
-    let matches = App::new("calr")
-        .author("OFFBLACK")
-        .about("Rust cal")
-        .version("0.1.0")
-        .arg(
-            Arg::with_name("month")
-                .value_name("MONTH")
-                .short("m")
-                .help("Month name or number 1-12")
-                .takes_value(true),
-        )
-        .arg(
-            Arg::with_name("show_current_year")
-                .short("y")
-                .long("year")
-                .help("Show whole current year")
-                .conflicts_with_all(&["month", "year"]),
-        )
-        .arg(
-            Arg::with_name("year")
-                .value_name("YEAR")
-                .help("Year (1-9999)"),
-        )
-        .get_matches();
+    let args: Args = parse_args();
 
     let today = Local::today();
-    let mut year = matches.value_of("year").map(parse_year).transpose()?;
-    let mut month = matches.value_of("month").map(parse_month).transpose()?;
-    if matches.is_present("show_current_year") {
+    let mut year = args.year.as_deref().map(parse_year).transpose()?;
+    let mut month = args.month.as_deref().map(parse_month).transpose()?;
+    if args.show_current_year {
         month = None;
         year = Some(today.year());
     } else if month.is_none() && year.is_none() {
@@ -53,71 +52,67 @@ pub fn get_args() -> MyResult<Config> {
     Ok(Config {
         month,
         year: year.unwrap_or_else(|| today.year()),
-        today: today.naive_local(),
+        today: args.color.enabled().then(|| today.naive_local()),
     })
 }
 
-const MONTHS: [&str; 12] = [
-    "January",
-    "February",
-    "March",
-    "April",
-    "May",
-    "June",
-    "July",
-    "August",
-    "September",
-    "October",
-    "November",
-    "December",
-];
-
 fn parse_month(month: &str) -> MyResult<u32> {
     if let Ok(val) = month.parse::<u32>() {
-        if 1 <= val && val <= 12 {
-            return Ok(val);
+        if (1..=12).contains(&val) {
+            Ok(val)
         } else {
-            return Err(format!("month \"{month}\" not in the range 1 through 12").into());
+            Err(CoreError::Parse(format!("month \"{month}\" not in the range 1 through 12")))
         }
     } else {
+        // A month may be named in English or in the current language
         let matches = MONTHS
             .iter()
             .enumerate()
-            .filter(|(_, v)| v.to_lowercase().starts_with(month))
+            .filter(|(_, v)| {
+                [v, tr(v)].iter().any(|name| name.to_lowercase().starts_with(month))
+            })
             .collect_vec();
 
         if matches.len() == 1 {
             Ok(matches[0].0 as u32 + 1)
         } else {
-            Err(format!("Invalid month \"{month}\"").into())
+            Err(CoreError::Parse(format!("Invalid month \"{month}\"")))
         }
     }
 }
 
 fn parse_year(year: &str) -> MyResult<i32> {
     year.parse()
-        .map_err(|_| format!("Invalid integer \"{year}\"").into())
+        .map_err(|_| CoreError::Parse(format!("Invalid integer \"{year}\"")))
         .and_then(|v| {
-            if v < 1 || v > 9999 {
-                Err(format!("year \"{year}\" not in the range 1 through 9999").into())
+            if !(1..=9999).contains(&v) {
+                Err(CoreError::Parse(format!("year \"{year}\" not in the range 1 through 9999")))
             } else {
                 Ok(v)
             }
         })
 }
 
-fn format_month(year: i32, month: u32, print_year: bool, today: NaiveDate) -> Vec<String> {
+fn format_month(
+    year: i32,
+    month: u32,
+    print_year: bool,
+    today: Option<NaiveDate>,
+) -> Vec<String> {
     const LINE_LEN: usize = 22;
     let first = NaiveDate::from_ymd(year, month, 1);
     let mut days: Vec<String> = (1..first.weekday().number_from_sunday())
-        .into_iter()
         .map(|_| "  ".to_string())
         .collect();
 
-    let is_today = |day: u32| year == today.year() && month == today.month() && day == today.day();
+    let is_today = |day: u32| {
+        today.is_some_and(|today| {
+            year == today.year() && month == today.month() && day == today.day()
+        })
+    };
 
     let last = last_day_in_month(year, month);
-    days.extend((first.day()..=last.day()).into_iter().map(|num| {
+    days.extend((first.day()..=last.day()).map(|num| {
         let fmt = format!("{:>2}", num);
         if is_today(num) {
             Style::new().reverse().paint(fmt).to_string()
@@ -125,7 +120,7 @@ fn format_month(year: i32, month: u32, print_year: bool, today: NaiveDate) -> Ve
             fmt
         }
     }));
-    let month_name = MONTHS[month as usize - 1];
+    let month_name = tr(MONTHS[month as usize - 1]);
     let mut lines = Vec::with_capacity(8);
     lines.push(format!(
         "{:^20}  ",
@@ -135,7 +130,7 @@ fn format_month(year: i32, month: u32, print_year: bool, today: NaiveDate) -> Ve
             month_name.to_string()
         }
     ));
-    lines.push("Su Mo Tu We Th Fr Sa  ".to_string());
+    lines.push(format!("{}  ", tr("Su Mo Tu We Th Fr Sa")));
 
     for week in days.chunks(7) {
         lines.push(format!("{:width$}  ", week.join(" "), width = LINE_LEN - 2));
@@ -169,7 +164,6 @@ pub fn run(config: Config) -> MyResult<()> {
         None => {
             println!("{:>32}", config.year);
             let months: Vec<_> = (1..=12)
-                .into_iter()
                 .map(|month| format_month(config.year, month, false, config.today))
                 .collect();
 
@@ -188,9 +182,22 @@ pub fn run(config: Config) -> MyResult<()> {
     Ok(())
 }
 
+/// Entry points for the cargo-fuzz targets in `../fuzz`, which can't reach
+/// private items. None of them may panic, whatever the input.
+#[cfg(feature = "fuzzing")]
+#[doc(hidden)]
+pub mod fuzzing {
+    pub fn parse_date(month: &str, year: &str) {
+        if let (Ok(month), Ok(year)) = (super::parse_month(month), super::parse_year(year)) {
+            let _ = super::format_month(year, month, true, None);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tets {
-    use super::{format_month, last_day_in_month, parse_month, parse_year, NaiveDate};
+    use super::{format_month, last_day_in_month, parse_month, parse_year, NaiveDate, MONTHS};
+    use proptest::prelude::*;
 
     #[test]
     fn test_parse_year() {
@@ -256,7 +263,7 @@ mod tets {
 
     #[test]
     fn test_format_month() {
-        let today = NaiveDate::from_ymd(0, 1, 1);
+        let today = None;
         let leap_february = vec![
             "   February 2020      ",
             "Su Mo Tu We Th Fr Sa  ",
@@ -291,7 +298,7 @@ mod tets {
             "25 26 27 28 29 30     ",
             "                      ",
         ];
-        let today = NaiveDate::from_ymd(2021, 4, 7);
+        let today = Some(NaiveDate::from_ymd(2021, 4, 7));
         assert_eq!(format_month(2021, 4, true, today), april_hl);
     }
 
@@ -301,4 +308,32 @@ mod tets {
         assert_eq!(last_day_in_month(2020, 2), NaiveDate::from_ymd(2020, 2, 29));
         assert_eq!(last_day_in_month(2020, 4), NaiveDate::from_ymd(2020, 4, 30));
     }
+
+    proptest! {
+        #[test]
+        fn parse_month_never_panics(month in "\\PC*") {
+            let _ = parse_month(&month);
+        }
+
+        #[test]
+        fn parse_month_accepts_numbers_and_names(month in 1..=12u32, len in 3..=9usize) {
+            prop_assert_eq!(parse_month(&month.to_string()).unwrap(), month);
+            let name = MONTHS[month as usize - 1].to_lowercase();
+            let prefix: String = name.chars().take(len).collect();
+            prop_assert_eq!(parse_month(&prefix).unwrap(), month);
+        }
+
+        #[test]
+        fn parse_year_checks_range(year in any::<i64>()) {
+            let res = parse_year(&year.to_string());
+            prop_assert_eq!(res.is_ok(), (1..=9999).contains(&year));
+        }
+
+        #[test]
+        fn format_month_is_a_fixed_grid(year in 1..=9999i32, month in 1..=12u32, print_year: bool) {
+            let lines = format_month(year, month, print_year, None);
+            prop_assert_eq!(lines.len(), 8);
+            prop_assert!(lines.iter().all(|line| line.chars().count() == 22));
+        }
+    }
 }
@@ -1,6 +1,7 @@
 fn main() {
+    coreutils_common::reset_sigpipe();
     if let Err(e) = calr::get_args().and_then(calr::run) {
         eprintln!("{e}");
-        std::process::exit(1);
+        std::process::exit(e.exit_code());
     }
 }
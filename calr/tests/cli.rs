@@ -43,7 +43,7 @@ fn dies_invalid_year() -> TestResult {
 #[test]
 fn dies_month_0() -> TestResult {
     Command::cargo_bin(PRG)?
-        .args(&["-m", "0"])
+        .args(["-m", "0"])
         .assert()
         .failure()
         .stderr("month \"0\" not in the range 1 through 12\n");
@@ -54,7 +54,7 @@ fn dies_month_0() -> TestResult {
 #[test]
 fn dies_month_13() -> TestResult {
     Command::cargo_bin(PRG)?
-        .args(&["-m", "13"])
+        .args(["-m", "13"])
         .assert()
         .failure()
         .stderr("month \"13\" not in the range 1 through 12\n");
@@ -65,7 +65,7 @@ fn dies_month_13() -> TestResult {
 #[test]
 fn dies_invalid_month() -> TestResult {
     Command::cargo_bin(PRG)?
-        .args(&["-m", "foo"])
+        .args(["-m", "foo"])
         .assert()
         .failure()
         .stderr("Invalid month \"foo\"\n");
@@ -75,9 +75,9 @@ fn dies_invalid_month() -> TestResult {
 // --------------------------------------------------
 #[test]
 fn dies_y_and_month() -> TestResult {
-    let expected = "The argument '-m <MONTH>' cannot be used with '--year'";
+    let expected = "the argument '-m <MONTH>' cannot be used with '--year'";
     Command::cargo_bin(PRG)?
-        .args(&["-m", "1", "-y"])
+        .args(["-m", "1", "-y"])
         .assert()
         .failure()
         .stderr(predicate::str::contains(expected));
@@ -87,9 +87,9 @@ fn dies_y_and_month() -> TestResult {
 // --------------------------------------------------
 #[test]
 fn dies_y_and_year() -> TestResult {
-    let expected = "The argument '<YEAR>' cannot be used with '--year'";
+    let expected = "the argument '--year' cannot be used with '[YEAR]'";
     Command::cargo_bin(PRG)?
-        .args(&["-y", "2000"])
+        .args(["-y", "2000"])
         .assert()
         .failure()
         .stderr(predicate::str::contains(expected));
@@ -116,7 +116,7 @@ fn month_num() -> TestResult {
 
     for (num, month) in expected {
         Command::cargo_bin(PRG)?
-            .args(&["-m", num])
+            .args(["-m", num])
             .assert()
             .success()
             .stdout(predicates::str::contains(month.to_string()));
@@ -143,7 +143,7 @@ fn partial_month() -> TestResult {
 
     for (arg, month) in expected {
         Command::cargo_bin(PRG)?
-            .args(&["-m", arg])
+            .args(["-m", arg])
             .assert()
             .success()
             .stdout(predicates::str::contains(month.to_string()));
@@ -207,3 +207,55 @@ fn year() -> TestResult {
     assert_eq!(lines.len(), 37);
     Ok(())
 }
+
+// --------------------------------------------------
+#[test]
+fn color_always_highlights_today() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--color", "always"])
+        .env("NO_COLOR", "1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\u{1b}[7m"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn color_off_when_not_a_terminal() -> TestResult {
+    for args in [&[][..], &["--color=never"], &["--color=auto"]] {
+        Command::cargo_bin(PRG)?
+            .args(args)
+            .env_remove("NO_COLOR")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("\u{1b}").not());
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_color() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--color", "sometimes"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "invalid value 'sometimes' for '--color <WHEN>'",
+        ));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn month_in_german() -> TestResult {
+    let expected = fs::read_to_string("tests/expected/3-2020.de.txt")?;
+    Command::cargo_bin(PRG)?
+        .env("LC_ALL", "de_DE.UTF-8")
+        .args(["-m", "märz", "2020"])
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
@@ -1,87 +1,714 @@
-use std::{error::Error, fs::File, io::{self, BufRead, BufReader}};
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Read, Seek, Write},
+    ops::RangeInclusive,
+    process::{Child, ChildStdout, Command, Stdio},
+    thread,
+};
 
-use clap::{App, Arg};
+use clap::Parser;
+use hashr::{Algorithm, DigestWriter};
 
-type MyResult<T> = Result<T, Box<dyn Error>>;
+use coreutils_common::{
+    block_sigpipe, open, parse_args, CoreError, FilesFromArgs, MyResult, Output, STDIN,
+};
 
 #[derive(Debug)]
 pub struct Config {
     files: Vec<String>,
     number_lines: bool,
     number_nonblank_lines: bool,
+    blank_means_whitespace: bool,
+    show_ends: bool,
+    show_tabs: bool,
+    show_nonprinting: bool,
+    squeeze_blank: bool,
+    line_ending: Option<LineEnding>,
+    restart_numbering: bool,
+    number_width: usize,
+    number_separator: String,
+    line_range: Option<RangeInclusive<usize>>,
+    /// How many lines to print in all, for a bare --lines N
+    max_lines: Option<usize>,
+    max_bytes: Option<u64>,
+    show_bytes: bool,
+    decompress: bool,
+    hash: Option<Algorithm>,
+    unbuffered: bool,
+}
+
+/// The newline to end every line with, whatever each ended with before.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum LineEnding {
+    /// LF, dropping the CR of a CRLF, as dos2unix does
+    Lf,
+    /// CRLF, as unix2dos does
+    Crlf,
+}
+
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust cat")]
+struct Args {
+    /// Files to process
+    #[arg(value_name = "FILE", default_value = "-", conflicts_with = "files_from")]
+    files: Vec<String>,
+
+    #[command(flatten)]
+    files_from: FilesFromArgs,
+
+    /// Number lines
+    #[arg(short = 'n', long = "number")]
+    number_lines: bool,
+
+    /// Number nonblank lines
+    #[arg(short = 'b', long = "number-nonblank", conflicts_with = "number_lines")]
+    number_nonblank_lines: bool,
+
+    /// With -b, count lines of only spaces and tabs as blank too
+    #[arg(long, requires = "number_nonblank_lines")]
+    blank_means_whitespace: bool,
+
+    /// Restart line numbers at 1 for each file
+    #[arg(long)]
+    restart_numbering: bool,
+
+    /// Pad line numbers to N columns
+    #[arg(long, value_name = "N", default_value = "6")]
+    number_width: String,
+
+    /// Separate line numbers from lines with STR
+    #[arg(long, value_name = "STR", default_value = "\t", hide_default_value = true)]
+    number_separator: String,
+
+    /// Equivalent to -vET
+    #[arg(short = 'A', long)]
+    show_all: bool,
+
+    /// Display $ at end of each line
+    #[arg(short = 'E', long)]
+    show_ends: bool,
+
+    /// Display TAB characters as ^I
+    #[arg(short = 'T', long)]
+    show_tabs: bool,
+
+    /// Use ^ and M- notation, except for LFD and TAB
+    #[arg(short = 'v', long)]
+    show_nonprinting: bool,
+
+    /// Suppress repeated empty output lines
+    #[arg(short = 's', long)]
+    squeeze_blank: bool,
+
+    /// End lines with LF, removing the CR before it
+    #[arg(long, conflicts_with = "crlf")]
+    lf: bool,
+
+    /// End lines with CRLF, adding a CR where there isn't one
+    #[arg(long)]
+    crlf: bool,
+
+    /// Print only lines START through END of each file, or N lines in all
+    #[arg(long = "lines", value_name = "START:END|N")]
+    line_range: Option<String>,
+
+    /// Print only N bytes in all
+    #[arg(long = "bytes", value_name = "N")]
+    max_bytes: Option<String>,
+
+    /// Display each file as a hex and ASCII dump
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "number_lines",
+            "number_nonblank_lines",
+            "show_all",
+            "show_ends",
+            "show_tabs",
+            "show_nonprinting",
+            "squeeze_blank",
+            "lf",
+            "crlf",
+            "line_range",
+            "max_bytes",
+        ]
+    )]
+    show_bytes: bool,
+
+    /// Decompress gzip, bzip2 and xz inputs, like zcat
+    #[arg(short = 'z', long)]
+    decompress: bool,
+
+    /// Print the digest of all output to stderr at the end
+    #[arg(long, value_name = "ALGORITHM", value_enum)]
+    hash: Option<Algorithm>,
+
+    /// Flush output after every read
+    #[arg(short = 'u')]
+    unbuffered: bool,
 }
 
 pub fn get_args() -> MyResult<Config> {
-    let matches = App::new("catr")
-        .version("0.1.0")
-        .author("OFFBLACK")
-        .about("Rust cat")
-        .arg(
-            Arg::with_name("number_lines")
-                .short("n")
-                .long("number")
-                .help("Number lines")
-                .takes_value(false)
-        )
-        .arg(
-            Arg::with_name("number_nonblank_lines")
-                .short("b")
-                .long("number-nonblank")
-                .help("Number nonblank lines")
-                .conflicts_with("number_lines")
-                .takes_value(false)
-        )
-        .arg(
-            Arg::with_name("files")
-                .help("Files to process")
-                .value_name("FILE")
-                .multiple(true)
-                .default_value("-")
-        )
-        .get_matches();
-    
+    let args: Args = parse_args();
+
+    let number_width = args
+        .number_width
+        .parse::<usize>()
+        .map_err(|_| CoreError::Parse(format!("illegal number width -- {}", args.number_width)))?;
+
+    let (line_range, max_lines) = match args.line_range.as_deref() {
+        None => (None, None),
+        Some(val) if val.contains(':') => (Some(parse_line_range(val)?), None),
+        Some(val) => (None, Some(parse_count(val, "line")?)),
+    };
+    let max_bytes = args
+        .max_bytes
+        .as_deref()
+        .map(|val| parse_count(val, "byte"))
+        .transpose()?;
+
+    let line_ending = match (args.lf, args.crlf) {
+        (true, _) => Some(LineEnding::Lf),
+        (_, true) => Some(LineEnding::Crlf),
+        _ => None,
+    };
+
     Ok(
         Config {
-            files: matches.values_of_lossy("files").unwrap(),
-            number_lines: matches.is_present("number_lines"),
-            number_nonblank_lines: matches.is_present("number_nonblank_lines"),       
+            files: args.files_from.files(args.files)?,
+            number_lines: args.number_lines,
+            number_nonblank_lines: args.number_nonblank_lines,
+            blank_means_whitespace: args.blank_means_whitespace,
+            show_ends: args.show_all || args.show_ends,
+            show_tabs: args.show_all || args.show_tabs,
+            show_nonprinting: args.show_all || args.show_nonprinting,
+            squeeze_blank: args.squeeze_blank,
+            line_ending,
+            restart_numbering: args.restart_numbering,
+            number_width,
+            number_separator: args.number_separator,
+            line_range,
+            max_lines,
+            max_bytes,
+            show_bytes: args.show_bytes,
+            decompress: args.decompress,
+            hash: args.hash,
+            unbuffered: args.unbuffered,
         }
     )
 }
 
-fn open(filename: &str) -> MyResult<Box<dyn BufRead>> {
-    match filename {
-        "-" => Ok(Box::new(BufReader::new(io::stdin()))),
-        _ => Ok(Box::new(BufReader::new(File::open(filename)?))),
-    }
-}
-
-
-pub fn run(config: Config) -> MyResult<()> {
-    for file in config.files {
-        match open(&file) {
-            Err(err) => eprintln!("Failed to open {}: {}", file, err),
-            Ok(file_handle) => {
-                let mut line_no = 1;
-                for opt_line in file_handle.lines() {
-                    if let Ok(line) = opt_line {
-                        if config.number_lines {
-                            println!("{:>6}\t{line}", line_no);
-                            line_no += 1;
-                        } else if config.number_nonblank_lines {
-                            if line.is_empty() {
-                                println!();
-                            } else {
-                                println!("{:>6}\t{line}", line_no);
-                                line_no += 1;
-                            }
-                        } else {
-                            println!("{line}");
+impl Config {
+    /// Plain cat of `files`, for callers other than the command line.
+    pub fn new(files: Vec<String>) -> Self {
+        Config {
+            files,
+            number_lines: false,
+            number_nonblank_lines: false,
+            blank_means_whitespace: false,
+            show_ends: false,
+            show_tabs: false,
+            show_nonprinting: false,
+            squeeze_blank: false,
+            line_ending: None,
+            restart_numbering: false,
+            number_width: 6,
+            number_separator: "\t".to_string(),
+            line_range: None,
+            max_lines: None,
+            max_bytes: None,
+            show_bytes: false,
+            decompress: false,
+            hash: None,
+            unbuffered: false,
+        }
+    }
+
+    /// Whether any flag requires inspecting each line before printing it.
+    fn is_formatted(&self) -> bool {
+        self.number_lines
+            || self.number_nonblank_lines
+            || self.show_ends
+            || self.show_tabs
+            || self.show_nonprinting
+            || self.squeeze_blank
+            || self.line_ending.is_some()
+            || self.line_range.is_some()
+            || self.max_lines.is_some()
+    }
+}
+
+/// Parses the N of --lines or --bytes, naming `what` was counted if it isn't
+/// a number.
+fn parse_count<T: std::str::FromStr>(val: &str, what: &str) -> MyResult<T> {
+    val.parse().map_err(|_| CoreError::Parse(format!("illegal {what} count -- {val}")))
+}
+
+/// Parses "START:END" into an inclusive, 1-based line range. Either side may
+/// be left empty to mean the first or last line.
+fn parse_line_range(val: &str) -> MyResult<RangeInclusive<usize>> {
+    let parse_bound = |bound: &str, default: usize| match bound {
+        "" => Some(default),
+        _ => bound.parse().ok().filter(|&n| n > 0),
+    };
+    let (start, end) = val
+        .split_once(':')
+        .and_then(|(start, end)| {
+            Some((parse_bound(start, 1)?, parse_bound(end, usize::MAX)?))
+        })
+        .filter(|(start, end)| start <= end)
+        .ok_or_else(|| CoreError::Parse(format!("illegal line range -- {}", val)))?;
+    Ok(start..=end)
+}
+
+
+/// `err` worded as GNU cat words it, "catr: FILE: No such file or
+/// directory", without the "(os error 2)" that Rust adds.
+fn file_error(err: &CoreError) -> String {
+    let message = err.to_string();
+    let message = match message.rfind(" (os error ") {
+        Some(i) if message.ends_with(')') => &message[..i],
+        _ => &message,
+    };
+    format!("catr: {message}")
+}
+
+/// Prints every input and returns the exit status: 1 if any file failed to
+/// open or read.
+pub fn run(config: Config) -> MyResult<i32> {
+    let mut out = Output::stdout();
+    let status = match config.hash {
+        None => cat_files(&config, &mut out)?,
+        Some(algorithm) => {
+            let mut hashed = Hashed { out: &mut out, sum: algorithm.writer() };
+            let status = cat_files(&config, &mut hashed)?;
+            let digest = hashed.sum.finish();
+            out.flush()?;
+            // As `catr ... | sha256sum` would print it
+            eprintln!("{digest}  -");
+            status
+        }
+    };
+    out.flush()?;
+    Ok(status)
+}
+
+/// Writes to `out`, and sums what was written for --hash.
+struct Hashed<'a, W> {
+    out: &'a mut W,
+    sum: DigestWriter,
+}
+
+impl<W: Write> Write for Hashed<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.out.write(buf)?;
+        self.sum.write_all(&buf[..written])?;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Writes every input to `out`, reporting the files that can't be opened or
+/// read on stderr, and returns the exit status as `run` does.
+pub fn cat_files(config: &Config, out: &mut impl Write) -> MyResult<i32> {
+    let mut line = Vec::new();
+    let mut rendered = Vec::new();
+    let mut line_no = 1;
+    // Whether the last line printed was empty, carried across files as GNU
+    // cat does, so -s squeezes a run that spans two
+    let mut last_blank = false;
+    // Whether the last file ended without a newline, so the first line of the
+    // next goes on with its last, and isn't numbered or counted as blank
+    let mut mid_line = false;
+    // What is left to print of --lines N and --bytes N
+    let mut lines_left = config.max_lines;
+    let mut bytes_left = config.max_bytes;
+    let mut status = 0;
+    for file in &config.files {
+        // Don't open what won't be printed, much as cat | head would stop
+        if lines_left == Some(0) || bytes_left == Some(0) {
+            break;
+        }
+        let opened = if config.decompress { open_decompressed(file) } else { open(file) };
+        let printed = opened.and_then(|mut file_handle| {
+            if config.show_bytes {
+                return hex_dump(&mut file_handle, file, out, config.unbuffered);
+            }
+            if !config.is_formatted() {
+                let mut file_handle = file_handle.take(bytes_left.unwrap_or(u64::MAX));
+                let copied = copy_blocks(&mut file_handle, file, out, config.unbuffered)?;
+                if let Some(left) = &mut bytes_left {
+                    *left -= copied;
+                }
+                return Ok(());
+            }
+            if config.restart_numbering {
+                line_no = 1;
+            }
+            let mut file_line_no = 0;
+            let (first, last) = config
+                .line_range
+                .as_ref()
+                .map_or((1, usize::MAX), |range| (*range.start(), *range.end()));
+            loop {
+                // Stop before reading past the range or the limits, so a
+                // later "-" gets the rest of stdin
+                if file_line_no >= last || lines_left == Some(0) || bytes_left == Some(0) {
+                    break;
+                }
+                line.clear();
+                let read = file_handle.read_until(b'\n', &mut line);
+                if read.map_err(|e| CoreError::io(file, e))? == 0 {
+                    break;
+                }
+                file_line_no += 1;
+                if file_line_no < first {
+                    continue;
+                }
+                let (content, has_newline) = match line.strip_suffix(b"\n") {
+                    Some(content) => (content, true),
+                    None => (&line[..], false),
+                };
+                // The CR of a CRLF is part of the newline being replaced
+                let content = match content.strip_suffix(b"\r") {
+                    Some(stripped) if has_newline && config.line_ending.is_some() => stripped,
+                    _ => content,
+                };
+                let continued = mid_line;
+                mid_line = !has_newline;
+                let blank = content.is_empty() && !continued;
+                if config.squeeze_blank && blank && last_blank {
+                    continue;
+                }
+                last_blank = blank;
+
+                rendered.clear();
+                let unnumbered = blank
+                    || (config.blank_means_whitespace
+                        && content.iter().all(|&byte| byte == b' ' || byte == b'\t'));
+                let numbered =
+                    config.number_lines || (config.number_nonblank_lines && !unnumbered);
+                if numbered && !continued {
+                    write!(
+                        rendered,
+                        "{:>width$}{}",
+                        line_no,
+                        config.number_separator,
+                        width = config.number_width
+                    )?;
+                    line_no += 1;
+                }
+                render(content, config, &mut rendered);
+                if has_newline {
+                    if config.show_ends {
+                        // Like GNU cat, make a CR before the newline visible
+                        if rendered.last() == Some(&b'\r') {
+                            rendered.pop();
+                            rendered.extend_from_slice(b"^M");
                         }
+                        rendered.push(b'$');
                     }
+                    if config.line_ending == Some(LineEnding::Crlf) {
+                        rendered.push(b'\r');
+                    }
+                    rendered.push(b'\n');
+                }
+                if let Some(left) = &mut bytes_left {
+                    rendered.truncate(usize::try_from(*left).unwrap_or(usize::MAX));
+                    *left -= rendered.len() as u64;
+                }
+                if let Some(left) = &mut lines_left {
+                    *left -= 1;
                 }
+                out.write_all(&rendered)?;
+                if config.unbuffered {
+                    out.flush()?;
+                }
+            }
+            Ok(())
+        });
+        // A file that can't be opened or read, like a directory, is reported
+        // and the rest still printed; only failing to write ends the run
+        match printed {
+            Err(err @ (CoreError::Io { .. } | CoreError::Input(_))) => {
+                // Keep the error in order with the output before it
+                out.flush()?;
+                eprintln!("{}", file_error(&err));
+                status = 1;
             }
+            printed => printed?,
         }
     }
+    Ok(status)
+}
+
+/// The tool that decompresses data starting with `magic`, if it is
+/// compressed.
+fn decompressor(magic: &[u8]) -> Option<&'static str> {
+    if magic.starts_with(&[0x1f, 0x8b]) {
+        Some("gzip")
+    } else if magic.starts_with(b"BZh") {
+        Some("bzip2")
+    } else if magic.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0]) {
+        Some("xz")
+    } else {
+        None
+    }
+}
+
+/// Opens `filename`, or stdin for "-", decompressed by gzip, bzip2 or xz if
+/// its first bytes say it is compressed, and as it is otherwise, like
+/// `zcat -f`. A file that can be rewound is handed to the tool as it is;
+/// anything else, like stdin, is fed to it from a thread, so the bytes that
+/// were looked at aren't lost.
+fn open_decompressed(filename: &str) -> MyResult<Box<dyn BufRead>> {
+    let file = match filename {
+        STDIN => None,
+        _ => Some(File::open(filename).map_err(|e| CoreError::io(filename, e))?),
+    };
+    let mut input: Box<dyn Read + Send> = match &file {
+        Some(file) => Box::new(file.try_clone().map_err(|e| CoreError::io(filename, e))?),
+        None => Box::new(io::stdin()),
+    };
+    let mut magic = [0; 6];
+    let mut len = 0;
+    while len < magic.len() {
+        match input.read(&mut magic[len..]).map_err(|e| CoreError::io(filename, e))? {
+            0 => break,
+            n => len += n,
+        }
+    }
+    let magic = magic[..len].to_vec();
+    let Some(tool) = decompressor(&magic) else {
+        return Ok(Box::new(BufReader::new(io::Cursor::new(magic).chain(input))));
+    };
+
+    let mut command = Command::new(tool);
+    command.arg("-dc").stdout(Stdio::piped());
+    let feed = match file {
+        Some(file) if (&file).rewind().is_ok() => {
+            command.stdin(file);
+            false
+        }
+        _ => {
+            command.stdin(Stdio::piped());
+            true
+        }
+    };
+    let mut child = command
+        .spawn()
+        .map_err(|e| CoreError::Input(format!("{filename}: cannot run {tool}: {e}")))?;
+    if feed {
+        let mut compressed = child.stdin.take().expect("stdin is piped");
+        thread::spawn(move || {
+            // The tool may give up early, which it reports itself; the write
+            // then fails rather than SIGPIPE stopping catr
+            block_sigpipe();
+            let _ =
+                compressed.write_all(&magic).and_then(|()| io::copy(&mut input, &mut compressed));
+        });
+    }
+    let stdout = child.stdout.take().expect("stdout is piped");
+    Ok(Box::new(BufReader::new(Decompressed { tool, child, stdout })))
+}
+
+/// The output of a decompressing tool, which fails at the end if the tool
+/// did, as on a corrupt file.
+struct Decompressed {
+    tool: &'static str,
+    child: Child,
+    stdout: ChildStdout,
+}
+
+impl Read for Decompressed {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.stdout.read(buf)?;
+        if n == 0 && !buf.is_empty() {
+            let status = self.child.wait()?;
+            if !status.success() {
+                return Err(io::Error::other(format!("{} failed: {status}", self.tool)));
+            }
+        }
+        Ok(n)
+    }
+}
+
+impl Drop for Decompressed {
+    fn drop(&mut self) {
+        // Left before the end, the tool may still be writing, so it's stopped
+        // before it is reaped
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Copies `reader`, which reads `filename`, to `out` one read block at a
+/// time, flushing after each if `flush` is set, and returns how many bytes
+/// there were.
+fn copy_blocks(
+    reader: &mut dyn BufRead,
+    filename: &str,
+    out: &mut impl Write,
+    flush: bool,
+) -> MyResult<u64> {
+    let mut copied = 0;
+    loop {
+        let block = reader.fill_buf().map_err(|e| CoreError::io(filename, e))?;
+        if block.is_empty() {
+            return Ok(copied);
+        }
+        out.write_all(block)?;
+        if flush {
+            out.flush()?;
+        }
+        let len = block.len();
+        copied += len as u64;
+        reader.consume(len);
+    }
+}
+
+/// Writes `reader`, which reads `filename`, as rows of offset, 16 hex bytes
+/// and their printable characters, in the layout of `hexdump -C`.
+fn hex_dump(
+    reader: &mut dyn BufRead,
+    filename: &str,
+    out: &mut impl Write,
+    flush: bool,
+) -> MyResult<()> {
+    let mut offset = 0;
+    let mut row = [0; 16];
+    loop {
+        let mut len = 0;
+        while len < row.len() {
+            match reader.read(&mut row[len..]).map_err(|e| CoreError::io(filename, e))? {
+                0 => break,
+                n => len += n,
+            }
+        }
+        if len == 0 {
+            break;
+        }
+
+        write!(out, "{:08x} ", offset)?;
+        for (i, byte) in row.iter().enumerate() {
+            if i % 8 == 0 {
+                write!(out, " ")?;
+            }
+            if i < len {
+                write!(out, "{:02x} ", byte)?;
+            } else {
+                write!(out, "   ")?;
+            }
+        }
+        let printable: Vec<u8> = row[..len]
+            .iter()
+            .map(|&byte| if byte.is_ascii_graphic() || byte == b' ' { byte } else { b'.' })
+            .collect();
+        write!(out, " |")?;
+        out.write_all(&printable)?;
+        writeln!(out, "|")?;
+        if flush {
+            out.flush()?;
+        }
+        offset += len;
+    }
+    if offset > 0 {
+        writeln!(out, "{:08x}", offset)?;
+    }
     Ok(())
 }
+
+/// Appends `content` to `out`, applying the -T and -v transformations.
+fn render(content: &[u8], config: &Config, out: &mut Vec<u8>) {
+    for &byte in content {
+        if byte == b'\t' {
+            if config.show_tabs {
+                out.extend_from_slice(b"^I");
+            } else {
+                out.push(byte);
+            }
+        } else if config.show_nonprinting {
+            push_nonprinting(byte, out);
+        } else {
+            out.push(byte);
+        }
+    }
+}
+
+/// Renders a byte in GNU cat's ^ and M- notation.
+fn push_nonprinting(byte: u8, out: &mut Vec<u8>) {
+    let byte = if byte >= 128 {
+        out.extend_from_slice(b"M-");
+        byte - 128
+    } else {
+        byte
+    };
+    match byte {
+        0..=31 => out.extend_from_slice(&[b'^', byte + 64]),
+        127 => out.extend_from_slice(b"^?"),
+        _ => out.push(byte),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cat_files, decompressor, file_error, parse_line_range, Config};
+    use coreutils_common::CoreError;
+    use std::io;
+
+    #[test]
+    fn test_cat_files() {
+        let fox = "The quick brown fox jumps over the lazy dog.\n";
+        let files = vec!["tests/inputs/fox.txt".to_string(), "tests/inputs/empty.txt".to_string()];
+        let mut out = vec![];
+        assert_eq!(cat_files(&Config::new(files.clone()), &mut out).unwrap(), 0);
+        assert_eq!(String::from_utf8(out).unwrap(), fox);
+
+        let config = Config { number_lines: true, ..Config::new(files) };
+        let mut out = vec![];
+        assert_eq!(cat_files(&config, &mut out).unwrap(), 0);
+        assert_eq!(String::from_utf8(out).unwrap(), format!("     1\t{fox}"));
+
+        let missing = Config::new(vec!["tests/inputs/does-not-exist".to_string()]);
+        assert_eq!(cat_files(&missing, &mut vec![]).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_decompressor() {
+        assert_eq!(decompressor(&[0x1f, 0x8b, 8, 0]), Some("gzip"));
+        assert_eq!(decompressor(b"BZh91AY"), Some("bzip2"));
+        assert_eq!(decompressor(b"\xfd7zXZ\0\0"), Some("xz"));
+        assert_eq!(decompressor(b"\xfd7zXZ"), None);
+        assert_eq!(decompressor(b"The quick"), None);
+        assert_eq!(decompressor(b""), None);
+    }
+
+    #[test]
+    fn test_file_error() {
+        let err = CoreError::io("foo.txt", io::Error::from_raw_os_error(2));
+        assert_eq!(file_error(&err), "catr: foo.txt: No such file or directory");
+        let err = CoreError::io("foo.txt", io::Error::other("oops"));
+        assert_eq!(file_error(&err), "catr: foo.txt: oops");
+    }
+
+    #[test]
+    fn test_parse_line_range() {
+        assert_eq!(parse_line_range("2:4").unwrap(), 2..=4);
+        assert_eq!(parse_line_range("3:3").unwrap(), 3..=3);
+        assert_eq!(parse_line_range(":4").unwrap(), 1..=4);
+        assert_eq!(parse_line_range("5:").unwrap(), 5..=usize::MAX);
+        assert_eq!(parse_line_range(":").unwrap(), 1..=usize::MAX);
+
+        for bad in ["", "3", "0:2", "4:2", "a:b", "-1:3", "1:2:3"] {
+            let res = parse_line_range(bad);
+            assert!(matches!(res, Err(CoreError::Parse(_))));
+            assert_eq!(
+                res.unwrap_err().to_string(),
+                format!("illegal line range -- {}", bad)
+            );
+        }
+    }
+}
@@ -1,6 +1,10 @@
 fn main() {
-    if let Err(e) = catr::get_args().and_then(catr::run) {
-        eprintln!("{}", e);
-        std::process::exit(1);
+    coreutils_common::reset_sigpipe();
+    match catr::get_args().and_then(catr::run) {
+        Ok(status) => std::process::exit(status),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(e.exit_code());
+        }
     }
 }
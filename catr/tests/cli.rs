@@ -10,6 +10,12 @@ const EMPTY: &str = "tests/inputs/empty.txt";
 const FOX: &str = "tests/inputs/fox.txt";
 const SPIDERS: &str = "tests/inputs/spiders.txt";
 const BUSTLE: &str = "tests/inputs/the-bustle.txt";
+const NONPRINTING: &str = "tests/inputs/nonprinting.txt";
+const BINARY: &str = "tests/inputs/binary.bin";
+const BLANKS: &str = "tests/inputs/blanks.txt";
+const BUSTLE_GZ: &str = "tests/inputs/the-bustle.txt.gz";
+const MIXED: &str = "tests/inputs/mixed.txt";
+const LISTING: &str = "tests/inputs/listing.txt";
 
 // --------------------------------------------------
 #[test]
@@ -18,7 +24,7 @@ fn usage() -> Result<()> {
         Command::cargo_bin(PRG)?
             .arg(flag)
             .assert()
-            .stdout(predicate::str::contains("USAGE"));
+            .stdout(predicate::str::contains("Usage"));
     }
     Ok(())
 }
@@ -42,12 +48,26 @@ fn gen_bad_file() -> String {
 #[test]
 fn skips_bad_file() -> Result<()> {
     let bad = gen_bad_file();
-    let expected = format!("{bad}: .* [(]os error 2[)]");
     Command::cargo_bin(PRG)?
         .arg(&bad)
         .assert()
-        .success()
-        .stderr(predicate::str::is_match(expected)?);
+        .failure()
+        .code(1)
+        .stderr(format!("catr: {bad}: No such file or directory\n"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn bad_file_does_not_stop_others() -> Result<()> {
+    let bad = gen_bad_file();
+    let expected = fs::read_to_string("tests/expected/fox.txt.out")?;
+    Command::cargo_bin(PRG)?
+        .args([&bad, FOX])
+        .assert()
+        .failure()
+        .code(1)
+        .stdout(expected);
     Ok(())
 }
 
@@ -109,6 +129,33 @@ fn bustle_stdin_b() -> Result<()> {
     )
 }
 
+// --------------------------------------------------
+#[test]
+fn stdin_interleaved() -> Result<()> {
+    // The first "-" reads all of stdin, leaving nothing for the second
+    let fox = fs::read_to_string(FOX)?;
+    Command::cargo_bin(PRG)?
+        .args([FOX, "-", FOX, "-"])
+        .write_stdin("in\n")
+        .assert()
+        .success()
+        .stdout(format!("{fox}in\n{fox}"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn stdin_read_again() -> Result<()> {
+    // Each "-" takes its first line of what is left
+    Command::cargo_bin(PRG)?
+        .args(["--lines", "1:1", "-n", "-", FOX, "-"])
+        .write_stdin("one\ntwo\nthree\n")
+        .assert()
+        .success()
+        .stdout(format!("     1\tone\n     2\t{}     3\ttwo\n", fs::read_to_string(FOX)?));
+    Ok(())
+}
+
 // --------------------------------------------------
 #[test]
 fn empty() -> Result<()> {
@@ -201,3 +248,711 @@ fn all_n() -> Result<()> {
 fn all_b() -> Result<()> {
     run(&[FOX, SPIDERS, BUSTLE, "-b"], "tests/expected/all.b.out")
 }
+
+// --------------------------------------------------
+#[test]
+fn all_n_restart() -> Result<()> {
+    run(
+        &[FOX, SPIDERS, BUSTLE, "-n", "--restart-numbering"],
+        "tests/expected/all.n.restart.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn all_b_restart() -> Result<()> {
+    run(
+        &[FOX, SPIDERS, BUSTLE, "-b", "--restart-numbering"],
+        "tests/expected/all.b.restart.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn bustle_v() -> Result<()> {
+    run(&["-v", BUSTLE], "tests/expected/the-bustle.txt.v.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn bustle_b_e() -> Result<()> {
+    run(&["-b", "-E", BUSTLE], "tests/expected/the-bustle.txt.bE.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn bustle_n_e() -> Result<()> {
+    run(&["-n", "-E", BUSTLE], "tests/expected/the-bustle.txt.nE.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn spiders_b_show_ends() -> Result<()> {
+    run(
+        &["--number-nonblank", "--show-ends", SPIDERS],
+        "tests/expected/spiders.txt.bE.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn nonprinting_e() -> Result<()> {
+    run(&["-E", NONPRINTING], "tests/expected/nonprinting.txt.E.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn nonprinting_t() -> Result<()> {
+    run(
+        &["--show-tabs", NONPRINTING],
+        "tests/expected/nonprinting.txt.T.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn nonprinting_v() -> Result<()> {
+    run(
+        &["--show-nonprinting", NONPRINTING],
+        "tests/expected/nonprinting.txt.v.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn nonprinting_a() -> Result<()> {
+    run(&["-A", NONPRINTING], "tests/expected/nonprinting.txt.A.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn nonprinting_a_is_vet() -> Result<()> {
+    run(
+        &["-v", "-E", "-T", NONPRINTING],
+        "tests/expected/nonprinting.txt.A.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn nonprinting_n_a() -> Result<()> {
+    run(
+        &["-n", "--show-all", NONPRINTING],
+        "tests/expected/nonprinting.txt.nA.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn binary_v() -> Result<()> {
+    // Every byte value, with M-^ and M-^? for the high control bytes
+    run(&["-v", BINARY], "tests/expected/binary.bin.v.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn binary_a() -> Result<()> {
+    run(&["-A", BINARY], "tests/expected/binary.bin.A.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn blanks_s() -> Result<()> {
+    run(&["-s", BLANKS], "tests/expected/blanks.txt.s.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn blanks_s_n() -> Result<()> {
+    run(&["--squeeze-blank", "-n", BLANKS], "tests/expected/blanks.txt.sn.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn blanks_s_b() -> Result<()> {
+    run(&["-s", "-b", BLANKS], "tests/expected/blanks.txt.sb.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn blanks_twice_s_n() -> Result<()> {
+    // The blank lines ending the first file and starting the second are one run
+    run(&["-s", "-n", BLANKS, BLANKS], "tests/expected/blanks.txt.twice.sn.out")
+}
+
+// --------------------------------------------------
+/// Whether `tool` can be run, as -z needs gzip, bzip2 and xz.
+fn have(tool: &str) -> bool {
+    let found = std::process::Command::new(tool).arg("--version").output().is_ok();
+    if !found {
+        eprintln!("{tool} not found, skipping");
+    }
+    found
+}
+
+// --------------------------------------------------
+#[test]
+fn decompress() -> Result<()> {
+    if !(have("gzip") && have("bzip2") && have("xz")) {
+        return Ok(());
+    }
+    run(
+        &["-z", "tests/inputs/fox.txt.xz", "tests/inputs/spiders.txt.bz2", BUSTLE_GZ],
+        "tests/expected/all.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn decompress_n() -> Result<()> {
+    if !have("gzip") {
+        return Ok(());
+    }
+    run(&["--decompress", "-n", BUSTLE_GZ], "tests/expected/the-bustle.txt.n.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn decompress_stdin() -> Result<()> {
+    if !have("gzip") {
+        return Ok(());
+    }
+    Command::cargo_bin(PRG)?
+        .arg("-z")
+        .write_stdin(fs::read(BUSTLE_GZ)?)
+        .assert()
+        .success()
+        .stdout(fs::read_to_string("tests/expected/the-bustle.txt.out")?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn decompress_passes_plain_files() -> Result<()> {
+    run(&["-z", FOX, EMPTY], "tests/expected/fox.txt.out")?;
+    let output = Command::cargo_bin(PRG)?.args(["-z", BINARY]).output().unwrap();
+    assert_eq!(output.stdout, fs::read(BINARY)?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn numbering_goes_on_mid_line() -> Result<()> {
+    // mixed.txt doesn't end in a newline, so fox.txt's line goes on with it
+    let fox = "The quick brown fox jumps over the lazy dog.\n";
+    Command::cargo_bin(PRG)?
+        .args(["-n", MIXED, FOX])
+        .assert()
+        .success()
+        .stdout(format!("     1\tdos\r\n     2\tunix\n     3\t\r\n     4\tlast\r{fox}"));
+    Command::cargo_bin(PRG)?
+        .args(["-b", MIXED, BLANKS])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("     4\tlast\r\n\n     5\tone\n"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn unreadable_file_does_not_stop_others() -> Result<()> {
+    let expected = fs::read_to_string("tests/expected/fox.txt.out")?;
+    let numbered = fs::read_to_string("tests/expected/fox.txt.n.out")?;
+    for (flags, expected) in [(&[][..], &expected), (&["-u"], &expected), (&["-n"], &numbered)] {
+        Command::cargo_bin(PRG)?
+            .args(flags)
+            .args(["tests", FOX])
+            .assert()
+            .code(1)
+            .stdout(expected.clone())
+            .stderr("catr: tests: Is a directory\n");
+    }
+    Command::cargo_bin(PRG)?
+        .args(["--show-bytes", "tests", EMPTY])
+        .assert()
+        .code(1)
+        .stderr("catr: tests: Is a directory\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn decompress_corrupt() -> Result<()> {
+    if !have("gzip") {
+        return Ok(());
+    }
+    Command::cargo_bin(PRG)?
+        .args(["-z", "tests/inputs/corrupt.gz"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("tests/inputs/corrupt.gz: gzip failed"));
+    // The files after it are still printed
+    Command::cargo_bin(PRG)?
+        .args(["-z", "tests/inputs/corrupt.gz", FOX])
+        .assert()
+        .code(1)
+        .stdout(fs::read_to_string("tests/expected/fox.txt.out")?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn decompress_large_corrupt() -> Result<()> {
+    if !have("gzip") {
+        return Ok(());
+    }
+    // gzip gives up on the first block, long before it has read all of this
+    let mut corrupt = b"\x1f\x8b\x08\0\0\0\0\0\0\x03".to_vec();
+    corrupt.resize(5_000_000, 0xff);
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("corrupt.gz");
+    fs::write(&path, &corrupt)?;
+    let expected = fs::read_to_string("tests/expected/fox.txt.out")?;
+    let file = path.to_string_lossy();
+    for (args, name) in [([&*file, FOX], &*file), (["-", FOX], "-")] {
+        Command::cargo_bin(PRG)?
+            .arg("-z")
+            .args(args)
+            .write_stdin(corrupt.clone())
+            .assert()
+            .code(1)
+            .stdout(expected.clone())
+            .stderr(predicate::str::contains(format!("catr: {name}: gzip failed")));
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn binary_is_copied_verbatim() -> Result<()> {
+    let expected = fs::read(BINARY)?;
+    let output = Command::cargo_bin(PRG)?.arg(BINARY).output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn binary_n_keeps_invalid_utf8() -> Result<()> {
+    // Numbering reads lines as bytes, so nothing is lost to UTF-8 decoding
+    let expected = fs::read("tests/expected/binary.bin.n.out")?;
+    let output = Command::cargo_bin(PRG)?.args(["-n", BINARY]).output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn binary_stdin_is_copied_verbatim() -> Result<()> {
+    let expected = fs::read(BINARY)?;
+    let output = Command::cargo_bin(PRG)?
+        .write_stdin(expected.clone())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn numbered_keeps_missing_final_newline() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .write_stdin("a\nb")
+        .arg("-n")
+        .assert()
+        .success()
+        .stdout("     1\ta\n     2\tb");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn mixed_lf() -> Result<()> {
+    // A CR that doesn't end a line is left alone
+    let output = Command::cargo_bin(PRG)?.args(["--lf", MIXED]).output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"dos\nunix\n\nlast\r");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn mixed_crlf() -> Result<()> {
+    let output = Command::cargo_bin(PRG)?.args(["--crlf", MIXED]).output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"dos\r\nunix\r\n\r\nlast\r");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn mixed_lf_b() -> Result<()> {
+    // Without its CR, the third line is blank
+    Command::cargo_bin(PRG)?
+        .args(["--lf", "-b", MIXED])
+        .assert()
+        .success()
+        .stdout("     1\tdos\n     2\tunix\n\n     3\tlast\r");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_lf_with_crlf() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["--lf", "--crlf", MIXED])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("cannot be used with"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn listing_b_blank_means_whitespace() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-b", "--blank-means-whitespace", LISTING])
+        .assert()
+        .success()
+        .stdout("     1\tfn main() {\n    \n     2\t\tlet x = 1;\n \t \n     3\t}\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn listing_b_counts_whitespace() -> Result<()> {
+    // Without the flag, as in GNU cat, only empty lines are blank
+    Command::cargo_bin(PRG)?
+        .args(["-b", LISTING])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("     2\t    \n"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_blank_means_whitespace_without_b() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["--blank-means-whitespace", LISTING])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("--number-nonblank"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn bustle_u() -> Result<()> {
+    run(&["-u", BUSTLE], "tests/expected/the-bustle.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn bustle_u_n() -> Result<()> {
+    run(&["-u", "-n", BUSTLE], "tests/expected/the-bustle.txt.n.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn binary_u_is_copied_verbatim() -> Result<()> {
+    let expected = fs::read(BINARY)?;
+    let output = Command::cargo_bin(PRG)?.args(["-u", BINARY]).output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn spiders_number_width_separator() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-n", "--number-width", "3", "--number-separator", ": ", SPIDERS])
+        .assert()
+        .success()
+        .stdout("  1: Don't worry, spiders,\n  2: I keep house\n  3: casually.\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn spiders_number_width_zero() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-b", "--number-width=0", "--number-separator=", SPIDERS])
+        .assert()
+        .success()
+        .stdout("1Don't worry, spiders,\n2I keep house\n3casually.\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_number_width() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["-n", "--number-width", "foo", SPIDERS])
+        .assert()
+        .failure()
+        .stderr("illegal number width -- foo\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn bustle_lines_range() -> Result<()> {
+    run(&["--lines", "6:9", BUSTLE], "tests/expected/the-bustle.txt.lines6-9.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn all_lines_range_n() -> Result<()> {
+    run(
+        &["-n", "--lines", "2:3", FOX, SPIDERS, BUSTLE],
+        "tests/expected/all.n.lines2-3.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_lines_range() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["--lines", "5:2", BUSTLE])
+        .assert()
+        .failure()
+        .stderr("illegal line range -- 5:2\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn all_lines_total() -> Result<()> {
+    // A bare N counts lines across all the files, like cat | head -n N
+    Command::cargo_bin(PRG)?
+        .args(["--lines", "3", FOX, SPIDERS, BUSTLE])
+        .assert()
+        .success()
+        .stdout(concat!(
+            "The quick brown fox jumps over the lazy dog.\n",
+            "Don't worry, spiders,\n",
+            "I keep house\n",
+        ));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn all_lines_total_n() -> Result<()> {
+    let expected = fs::read_to_string("tests/expected/all.n.out")?;
+    let expected: String = expected.split_inclusive('\n').take(5).collect();
+    Command::cargo_bin(PRG)?
+        .args(["-n", "--lines", "5", FOX, SPIDERS, BUSTLE])
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn all_bytes_total() -> Result<()> {
+    for args in [&["--bytes", "50"][..], &["-u", "--bytes", "50"]] {
+        Command::cargo_bin(PRG)?
+            .args(args)
+            .args([FOX, SPIDERS])
+            .assert()
+            .success()
+            .stdout("The quick brown fox jumps over the lazy dog.\nDon't");
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_bytes_n() -> Result<()> {
+    // The limit is on what is printed, numbers and all
+    Command::cargo_bin(PRG)?
+        .args(["-n", "--bytes", "10", FOX])
+        .assert()
+        .success()
+        .stdout("     1\tThe");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn limits_stop_reading() -> Result<()> {
+    let input = "y\n".repeat(1 << 20);
+    for (args, expected) in [(["--lines", "2"], "y\ny\n"), (["--bytes", "3"], "y\ny")] {
+        Command::cargo_bin(PRG)?
+            .args(args)
+            .write_stdin(input.as_str())
+            .assert()
+            .success()
+            .stdout(expected);
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn zero_limits() -> Result<()> {
+    for flag in ["--lines", "--bytes"] {
+        Command::cargo_bin(PRG)?.args([flag, "0", FOX]).assert().success().stdout("");
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_counts() -> Result<()> {
+    for (flag, what) in [("--lines", "line"), ("--bytes", "byte")] {
+        Command::cargo_bin(PRG)?
+            .args([flag, "many", FOX])
+            .assert()
+            .code(1)
+            .stderr(format!("illegal {what} count -- many\n"));
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn all_n_hash() -> Result<()> {
+    // The digest is of what was printed, as `catr -n ... | sha256sum` gives
+    Command::cargo_bin(PRG)?
+        .args(["-n", "--hash", "sha256", FOX, SPIDERS, BUSTLE])
+        .assert()
+        .success()
+        .stdout(fs::read_to_string("tests/expected/all.n.out")?)
+        .stderr("73e69a18f293842d5bd8d5a6bcade3725bcfe2be29dc5bef909b6225db8bbedc  -\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn hash_after_errors() -> Result<()> {
+    let bad = gen_bad_file();
+    Command::cargo_bin(PRG)?
+        .args(["--hash", "md5", &bad, FOX])
+        .assert()
+        .code(1)
+        .stdout(fs::read_to_string(FOX)?)
+        .stderr(format!(
+            "catr: {bad}: No such file or directory\n0d7006cd055e94cf614587e1d2ae0c8e  -\n"
+        ));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn empty_hash() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["--hash", "md5", EMPTY])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("d41d8cd98f00b204e9800998ecf8427e  -\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_show_bytes() -> Result<()> {
+    run(&["--show-bytes", FOX], "tests/expected/fox.txt.show-bytes.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn nonprinting_show_bytes() -> Result<()> {
+    run(
+        &["--show-bytes", NONPRINTING],
+        "tests/expected/nonprinting.txt.show-bytes.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn empty_show_bytes() -> Result<()> {
+    run(&["--show-bytes", EMPTY], "tests/expected/empty.txt.show-bytes.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_show_bytes_with_number() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["--show-bytes", "-n", FOX])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn usage_error_exits_1() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .arg("--bogus")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("unexpected argument '--bogus'"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+#[cfg(unix)]
+fn closed_pipe_stops_quietly() -> Result<()> {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        os::unix::process::ExitStatusExt,
+        process::{Command, Stdio},
+        thread,
+    };
+
+    let mut child = Command::new(assert_cmd::cargo::cargo_bin(PRG))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let mut stdin = child.stdin.take().unwrap();
+    let writer = thread::spawn(move || {
+        // Keeps writing until catr is gone, like `yes | catr | head -1`
+        while stdin.write_all(&[b'y'; 8192]).is_ok() {}
+    });
+
+    // Read a little, then hang up like `head` does
+    let mut line = Vec::new();
+    BufReader::new(child.stdout.take().unwrap()).read_until(b'y', &mut line)?;
+    let output = child.wait_with_output()?;
+    writer.join().unwrap();
+
+    assert_eq!(output.status.signal(), Some(13));
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn files_from_stdin() -> Result<()> {
+    let expected = fs::read_to_string("tests/expected/all.out")?;
+    Command::cargo_bin(PRG)?
+        .arg("--files-from=-")
+        .write_stdin(format!("{FOX}\n{SPIDERS}\n{BUSTLE}\n"))
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_files_from_with_files() -> Result<()> {
+    Command::cargo_bin(PRG)?
+        .args(["--files-from", "-", FOX])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+    Ok(())
+}
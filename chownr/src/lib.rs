@@ -0,0 +1,271 @@
+use clap::{ArgAction, Parser};
+use std::{fs, io, path::Path};
+use walkdir::WalkDir;
+
+use coreutils_common::{parse_args, CoreError, MyResult};
+
+#[derive(Debug)]
+pub struct Config {
+    files: Vec<String>,
+    owner: Owner,
+    from: Owner,
+    recursive: bool,
+    dereference: bool,
+}
+
+/// A user and group to change to or to match, each left alone if None.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Owner {
+    uid: Option<u32>,
+    gid: Option<u32>,
+}
+
+#[derive(Debug, Parser)]
+#[command(
+    author = "OFFBLACK",
+    version = "0.1.0",
+    about = "Rust chown",
+    // -h is for --no-dereference, as in GNU chown
+    disable_help_flag = true
+)]
+struct Args {
+    /// The new owner as USER, USER:GROUP, USER: for the user's login group,
+    /// or :GROUP (unless --reference is given), then the file(s) to change
+    #[arg(value_name = "[OWNER][:[GROUP]] FILE", required = true)]
+    operands: Vec<String>,
+
+    /// Change the files in directories too, and links rather than what
+    /// they point to
+    #[arg(short = 'R', long)]
+    recursive: bool,
+
+    /// Change links themselves rather than what they point to
+    #[arg(short = 'h', long, overrides_with = "dereference")]
+    no_dereference: bool,
+
+    /// Change what links point to (the default)
+    #[arg(long, overrides_with = "no_dereference")]
+    dereference: bool,
+
+    /// Only change files owned by this user and group, either of which may
+    /// be left out
+    #[arg(long, value_name = "[OWNER][:[GROUP]]")]
+    from: Option<String>,
+
+    /// Give the files the owner and group of RFILE
+    #[arg(long, value_name = "RFILE")]
+    reference: Option<String>,
+
+    /// Print help
+    #[arg(long, action = ArgAction::Help)]
+    help: Option<bool>,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let args: Args = parse_args();
+
+    let mut operands = args.operands;
+    let owner = match &args.reference {
+        Some(reference) => {
+            let metadata = fs::metadata(reference).map_err(|e| {
+                CoreError::io(format!("failed to get attributes of '{reference}'"), e)
+            })?;
+            let (uid, gid) = owner_of(&metadata);
+            Owner { uid: Some(uid), gid: Some(gid) }
+        }
+        None => {
+            let spec = operands.remove(0);
+            if operands.is_empty() {
+                return Err(CoreError::Usage(format!("missing operand after '{spec}'")));
+            }
+            parse_owner(&spec)?
+        }
+    };
+    Ok(Config {
+        files: operands,
+        owner,
+        from: args.from.as_deref().map(parse_owner).transpose()?.unwrap_or_default(),
+        recursive: args.recursive,
+        dereference: !args.no_dereference,
+    })
+}
+
+/// The user and group named by `spec`, as OWNER, OWNER:GROUP, OWNER: or
+/// :GROUP. Names are looked up first and then taken as ids, unless they
+/// start with `+`, which makes them ids.
+fn parse_owner(spec: &str) -> MyResult<Owner> {
+    let (user, group) = match spec.split_once(':') {
+        Some((user, group)) => (user, Some(group)),
+        None => (spec, None),
+    };
+    let uid = match user {
+        "" => None,
+        user => Some(
+            parse_id(user, user_id)
+                .ok_or_else(|| CoreError::Parse(format!("invalid user: '{spec}'")))?,
+        ),
+    };
+    let gid = match (group, uid) {
+        (None, _) => None,
+        (Some(""), None) => None,
+        // OWNER: means the user's login group
+        (Some(""), Some(uid)) => Some(
+            login_group(uid)
+                .ok_or_else(|| CoreError::Parse(format!("invalid spec: '{spec}'")))?,
+        ),
+        (Some(group), _) => Some(
+            parse_id(group, group_id)
+                .ok_or_else(|| CoreError::Parse(format!("invalid group: '{spec}'")))?,
+        ),
+    };
+    Ok(Owner { uid, gid })
+}
+
+/// The id `name` stands for, by `lookup` or as a number.
+fn parse_id(name: &str, lookup: fn(&str) -> Option<u32>) -> Option<u32> {
+    match name.strip_prefix('+') {
+        Some(id) => id.parse().ok(),
+        None => lookup(name).or_else(|| name.parse().ok()),
+    }
+}
+
+#[cfg(unix)]
+fn user_id(name: &str) -> Option<u32> {
+    users::get_user_by_name(name).map(|user| user.uid())
+}
+
+#[cfg(unix)]
+fn group_id(name: &str) -> Option<u32> {
+    users::get_group_by_name(name).map(|group| group.gid())
+}
+
+#[cfg(unix)]
+fn login_group(uid: u32) -> Option<u32> {
+    users::get_user_by_uid(uid).map(|user| user.primary_group_id())
+}
+
+#[cfg(unix)]
+fn owner_of(metadata: &fs::Metadata) -> (u32, u32) {
+    use std::os::unix::fs::MetadataExt;
+    (metadata.uid(), metadata.gid())
+}
+
+#[cfg(unix)]
+fn set_owner(path: &Path, owner: Owner, dereference: bool) -> io::Result<()> {
+    if dereference {
+        std::os::unix::fs::chown(path, owner.uid, owner.gid)
+    } else {
+        std::os::unix::fs::lchown(path, owner.uid, owner.gid)
+    }
+}
+
+// Files have no owning user and group on other platforms, so no name or id
+// is valid and nothing can be changed.
+
+#[cfg(not(unix))]
+fn user_id(_name: &str) -> Option<u32> {
+    None
+}
+
+#[cfg(not(unix))]
+fn group_id(_name: &str) -> Option<u32> {
+    None
+}
+
+#[cfg(not(unix))]
+fn login_group(_uid: u32) -> Option<u32> {
+    None
+}
+
+#[cfg(not(unix))]
+fn owner_of(_metadata: &fs::Metadata) -> (u32, u32) {
+    (0, 0)
+}
+
+#[cfg(not(unix))]
+fn set_owner(_path: &Path, _owner: Owner, _dereference: bool) -> io::Result<()> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+/// Changes the owner of `path` if it is owned by `config.from`.
+fn change(path: &Path, dereference: bool, config: &Config) -> MyResult<()> {
+    let metadata = if dereference { fs::metadata(path) } else { fs::symlink_metadata(path) };
+    let metadata = metadata
+        .map_err(|e| CoreError::io(format!("cannot access '{}'", path.display()), e))?;
+    let (uid, gid) = owner_of(&metadata);
+    let from = config.from;
+    if from.uid.is_some_and(|from| from != uid) || from.gid.is_some_and(|from| from != gid) {
+        return Ok(());
+    }
+    set_owner(path, config.owner, dereference)
+        .map_err(|e| CoreError::io(format!("changing ownership of '{}'", path.display()), e))
+}
+
+/// Changes the owner of each file, or with -R, of everything under it, and
+/// returns the exit status: 1 if anything couldn't be changed. As in GNU
+/// chown, -R never follows links, not even one named on the command line,
+/// and changes the links themselves.
+pub fn run(config: Config) -> MyResult<i32> {
+    let mut status = 0;
+    let mut report = |e: CoreError| {
+        eprintln!("{e}");
+        status = 1;
+    };
+    for file in &config.files {
+        if !config.recursive {
+            change(Path::new(file), config.dereference, &config).unwrap_or_else(&mut report);
+            continue;
+        }
+        for entry in WalkDir::new(file).follow_root_links(false) {
+            match entry {
+                Ok(entry) => change(entry.path(), false, &config).unwrap_or_else(&mut report),
+                Err(e) => report(walk_error(e)),
+            }
+        }
+    }
+    Ok(status)
+}
+
+/// The error for what couldn't be walked.
+fn walk_error(e: walkdir::Error) -> CoreError {
+    let path = e.path().map(|path| path.display().to_string()).unwrap_or_default();
+    let message = e.to_string();
+    match e.into_io_error() {
+        Some(source) => CoreError::io(format!("cannot access '{path}'"), source),
+        // A loop of links, which can't happen without following them
+        None => CoreError::Input(message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_owner, Owner};
+
+    #[test]
+    fn test_parse_owner() {
+        let owner = |uid, gid| Owner { uid, gid };
+        assert_eq!(parse_owner("").unwrap(), owner(None, None));
+        assert_eq!(parse_owner(":").unwrap(), owner(None, None));
+        assert_eq!(parse_owner("12").unwrap(), owner(Some(12), None));
+        assert_eq!(parse_owner("+12:+34").unwrap(), owner(Some(12), Some(34)));
+        assert_eq!(parse_owner(":34").unwrap(), owner(None, Some(34)));
+
+        let err = |spec| parse_owner(spec).unwrap_err().to_string();
+        assert_eq!(err("no-such-user"), "invalid user: 'no-such-user'");
+        assert_eq!(err("+root"), "invalid user: '+root'");
+        assert_eq!(err("0:no-such-group"), "invalid group: '0:no-such-group'");
+        assert_eq!(err("a:b:c"), "invalid user: 'a:b:c'");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_names() {
+        // Root is user 0 everywhere, though its group's name varies
+        let root = users::get_user_by_uid(0).unwrap();
+        let group = root.primary_group_id();
+        let owner = |uid, gid| Owner { uid, gid };
+        assert_eq!(parse_owner("root").unwrap(), owner(Some(0), None));
+        assert_eq!(parse_owner("root:").unwrap(), owner(Some(0), Some(group)));
+        assert_eq!(parse_owner("0:").unwrap(), owner(Some(0), Some(group)));
+    }
+}
@@ -0,0 +1,10 @@
+fn main() {
+    coreutils_common::reset_sigpipe();
+    match chownr::get_args().and_then(chownr::run) {
+        Ok(status) => std::process::exit(status),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(e.exit_code());
+        }
+    }
+}
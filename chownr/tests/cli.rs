@@ -0,0 +1,164 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::{fs, path::Path};
+use tempfile::TempDir;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "chownr";
+
+// --------------------------------------------------
+/// A directory with a file `f` and a tree with `d/sub/x`.
+fn setup() -> std::io::Result<TempDir> {
+    let dir = tempfile::tempdir()?;
+    fs::write(dir.path().join("f"), "")?;
+    fs::create_dir_all(dir.path().join("d/sub"))?;
+    fs::write(dir.path().join("d/sub/x"), "")?;
+    Ok(dir)
+}
+
+// --------------------------------------------------
+fn chownr(dir: &Path) -> Result<Command, Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(PRG)?;
+    cmd.current_dir(dir);
+    Ok(cmd)
+}
+
+// --------------------------------------------------
+/// The user and group ids of what's at `path`, not following links.
+#[cfg(unix)]
+fn owner(dir: &Path, path: &str) -> std::io::Result<(u32, u32)> {
+    use std::os::unix::fs::MetadataExt;
+    let metadata = fs::symlink_metadata(dir.join(path))?;
+    Ok((metadata.uid(), metadata.gid()))
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_no_file() -> TestResult {
+    let dir = setup()?;
+    chownr(dir.path())?
+        .arg("0")
+        .assert()
+        .code(1)
+        .stderr("missing operand after '0'\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_owner() -> TestResult {
+    let dir = setup()?;
+    for (spec, msg) in [
+        ("no-such-user", "invalid user: 'no-such-user'"),
+        ("+x", "invalid user: '+x'"),
+        ("0:no-such-group", "invalid group: '0:no-such-group'"),
+    ] {
+        chownr(dir.path())?
+            .args([spec, "f"])
+            .assert()
+            .code(1)
+            .stderr(format!("{msg}\n"));
+        chownr(dir.path())?
+            .args(["--from", spec, ":", "f"])
+            .assert()
+            .code(1)
+            .stderr(format!("{msg}\n"));
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_reference() -> TestResult {
+    let dir = setup()?;
+    chownr(dir.path())?
+        .args(["--reference=does-not-exist", "f"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::starts_with("failed to get attributes of 'does-not-exist': "));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn skips_bad_file() -> TestResult {
+    let dir = setup()?;
+    chownr(dir.path())?
+        .args([":", "does-not-exist", "f"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::starts_with("cannot access 'does-not-exist': "));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+#[cfg(unix)]
+fn same_owner() -> TestResult {
+    // Anyone may give their own files to themselves
+    let dir = setup()?;
+    let (uid, gid) = owner(dir.path(), "f")?;
+    chownr(dir.path())?.args([&format!("{uid}:{gid}"), "f"]).assert().success().stdout("");
+    let name = users::get_user_by_uid(uid).unwrap().name().to_string_lossy().into_owned();
+    chownr(dir.path())?.args([&format!("{name}:"), "-R", "d"]).assert().success();
+    chownr(dir.path())?.args(["--reference=d", "f", "d/sub"]).assert().success();
+    assert_eq!(owner(dir.path(), "f")?, (uid, gid));
+    Ok(())
+}
+
+// --------------------------------------------------
+/// Changes ownership for real, which only root may do.
+#[test]
+#[cfg(unix)]
+fn changes_owner() -> TestResult {
+    use std::os::unix::fs::symlink;
+
+    if users::get_current_uid() != 0 {
+        return Ok(());
+    }
+    let dir = setup()?;
+    symlink("f", dir.path().join("link"))?;
+    chownr(dir.path())?.args(["+1:+2", "f"]).assert().success();
+    assert_eq!(owner(dir.path(), "f")?, (1, 2));
+    chownr(dir.path())?.args([":+3", "f"]).assert().success();
+    assert_eq!(owner(dir.path(), "f")?, (1, 3));
+
+    // Only files owned as --from says
+    chownr(dir.path())?.args(["--from=+9", "+4", "f"]).assert().success();
+    assert_eq!(owner(dir.path(), "f")?, (1, 3));
+    chownr(dir.path())?.args(["--from=+1:+3", "+4", "f"]).assert().success();
+    assert_eq!(owner(dir.path(), "f")?, (4, 3));
+
+    // Through links, unless -h
+    chownr(dir.path())?.args(["+5", "link"]).assert().success();
+    assert_eq!(owner(dir.path(), "f")?, (5, 3));
+    chownr(dir.path())?.args(["-h", "+6", "link"]).assert().success();
+    assert_eq!(owner(dir.path(), "f")?, (5, 3));
+    assert_eq!(owner(dir.path(), "link")?.0, 6);
+
+    chownr(dir.path())?.args(["--reference=f", "d"]).assert().success();
+    assert_eq!(owner(dir.path(), "d")?, (5, 3));
+    assert_ne!(owner(dir.path(), "d/sub")?, (5, 3));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+#[cfg(unix)]
+fn recursive() -> TestResult {
+    use std::os::unix::fs::symlink;
+
+    if users::get_current_uid() != 0 {
+        return Ok(());
+    }
+    let dir = setup()?;
+    symlink("../../f", dir.path().join("d/sub/link"))?;
+    chownr(dir.path())?.args(["-R", "+7:+8", "d"]).assert().success();
+    for path in ["d", "d/sub", "d/sub/x", "d/sub/link"] {
+        assert_eq!(owner(dir.path(), path)?, (7, 8), "{path}");
+    }
+    // Links are changed, not followed
+    assert_ne!(owner(dir.path(), "f")?, (7, 8));
+    Ok(())
+}
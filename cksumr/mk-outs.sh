@@ -0,0 +1,19 @@
+#!/usr/bin/env bash
+
+# Writes the expected output of each test with GNU cksum and sum
+
+OUTDIR="tests/expected"
+[[ ! -d "$OUTDIR" ]] && mkdir -p "$OUTDIR"
+
+BUSTLE="tests/inputs/the-bustle.txt"
+FOX="tests/inputs/fox.txt"
+EMPTY="tests/inputs/empty.txt"
+
+cksum $FOX $EMPTY $BUSTLE > $OUTDIR/all.cksum.out
+cksum < $FOX > $OUTDIR/fox.txt.stdin.cksum.out
+for ALG in bsd sysv; do
+    cksum -a $ALG $FOX $EMPTY $BUSTLE > $OUTDIR/all.$ALG.cksum.out
+done
+sum $FOX $EMPTY $BUSTLE > $OUTDIR/all.sum.out
+sum -s $FOX $EMPTY $BUSTLE > $OUTDIR/all.sysv.sum.out
+sum < $FOX > $OUTDIR/fox.txt.stdin.sum.out
@@ -0,0 +1,12 @@
+use cksumr::cksum;
+
+fn main() {
+    coreutils_common::reset_sigpipe();
+    match cksum::get_args().and_then(cksum::run) {
+        Ok(status) => std::process::exit(status),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(e.exit_code());
+        }
+    }
+}
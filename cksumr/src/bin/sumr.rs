@@ -0,0 +1,12 @@
+use cksumr::sum;
+
+fn main() {
+    coreutils_common::reset_sigpipe();
+    match sum::get_args().and_then(sum::run) {
+        Ok(status) => std::process::exit(status),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(e.exit_code());
+        }
+    }
+}
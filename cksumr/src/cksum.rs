@@ -0,0 +1,54 @@
+//! cksumr: the CRC and size of each file.
+
+use clap::{Parser, ValueEnum};
+
+use crate::{
+    print_sums,
+    sums::{Bsd, Crc, SysV},
+};
+use coreutils_common::{parse_args, MyResult};
+
+#[derive(Debug)]
+pub struct Config {
+    files: Vec<String>,
+    algorithm: Algorithm,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Algorithm {
+    /// The CRC of POSIX cksum
+    Crc,
+    /// The BSD sum, as of sumr -r
+    Bsd,
+    /// The System V sum, as of sumr -s
+    Sysv,
+}
+
+#[derive(Debug, Parser)]
+#[command(name = "cksumr", author = "OFFBLACK", version = "0.1.0", about = "Rust cksum")]
+struct Args {
+    /// Input file(s) [default: stdin]
+    #[arg(value_name = "FILE")]
+    files: Vec<String>,
+
+    /// The sum to print
+    #[arg(short = 'a', long, value_enum, default_value = "crc")]
+    algorithm: Algorithm,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let args: Args = parse_args();
+
+    Ok(Config {
+        files: args.files,
+        algorithm: args.algorithm,
+    })
+}
+
+pub fn run(config: Config) -> MyResult<i32> {
+    match config.algorithm {
+        Algorithm::Crc => print_sums::<Crc>(&config.files),
+        Algorithm::Bsd => print_sums::<Bsd>(&config.files),
+        Algorithm::Sysv => print_sums::<SysV>(&config.files),
+    }
+}
@@ -0,0 +1,37 @@
+//! `cksumr` and `sumr`, which print the CRC of POSIX cksum and the older
+//! BSD and System V sums. Both sum their input with the [`Checksum`]
+//! framework of hashr.
+
+use hashr::{checksum, Checksum};
+use std::io::Write;
+
+use coreutils_common::{open, CoreError, MyResult, Output};
+
+pub mod cksum;
+mod sums;
+pub mod sum;
+
+/// Prints the sum by `C` of each file and then its name, or with no files,
+/// of stdin without a name, and returns the exit status: 1 if any file
+/// couldn't be read.
+fn print_sums<C: Checksum>(files: &[String]) -> MyResult<i32> {
+    let mut out = Output::stdout();
+    let mut status = 0;
+    let stdin = [String::from("-")];
+    let named = !files.is_empty();
+    for filename in if named { files } else { &stdin } {
+        match open(filename).and_then(|mut file| {
+            checksum::<C>(&mut file).map_err(|e| CoreError::io(filename, e))
+        }) {
+            Ok(sum) if named => writeln!(out, "{sum} {filename}")?,
+            Ok(sum) => writeln!(out, "{sum}")?,
+            Err(e) => {
+                out.flush()?;
+                eprintln!("{e}");
+                status = 1;
+            }
+        }
+    }
+    out.flush()?;
+    Ok(status)
+}
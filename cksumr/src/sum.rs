@@ -0,0 +1,48 @@
+//! sumr: the BSD or System V sum and size of each file.
+
+use clap::Parser;
+
+use crate::{
+    print_sums,
+    sums::{Bsd, SysV},
+};
+use coreutils_common::{parse_args, MyResult};
+
+#[derive(Debug)]
+pub struct Config {
+    files: Vec<String>,
+    sysv: bool,
+}
+
+#[derive(Debug, Parser)]
+#[command(name = "sumr", author = "OFFBLACK", version = "0.1.0", about = "Rust sum")]
+struct Args {
+    /// Input file(s) [default: stdin]
+    #[arg(value_name = "FILE")]
+    files: Vec<String>,
+
+    /// Print the BSD sum and 1024-byte blocks (the default)
+    #[arg(short = 'r', overrides_with = "sysv")]
+    bsd: bool,
+
+    /// Print the System V sum and 512-byte blocks
+    #[arg(short = 's', long, overrides_with = "bsd")]
+    sysv: bool,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let args: Args = parse_args();
+
+    Ok(Config {
+        files: args.files,
+        sysv: args.sysv && !args.bsd,
+    })
+}
+
+pub fn run(config: Config) -> MyResult<i32> {
+    if config.sysv {
+        print_sums::<SysV>(&config.files)
+    } else {
+        print_sums::<Bsd>(&config.files)
+    }
+}
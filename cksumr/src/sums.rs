@@ -0,0 +1,137 @@
+//! The sums of cksum and sum, each printed with the size of its input.
+
+use hashr::Checksum;
+
+/// The table for the CRC of POSIX cksum, a byte at a time: polynomial
+/// 0x04C11DB7, most significant bit first.
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut byte = 0;
+    while byte < 256 {
+        let mut crc = (byte as u32) << 24;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x8000_0000 != 0 { (crc << 1) ^ 0x04C1_1DB7 } else { crc << 1 };
+            bit += 1;
+        }
+        table[byte] = crc;
+        byte += 1;
+    }
+    table
+};
+
+/// The CRC of POSIX cksum, and the number of bytes
+#[derive(Debug, Default)]
+pub struct Crc {
+    crc: u32,
+    len: u64,
+}
+
+impl Crc {
+    fn add(&mut self, byte: u8) {
+        self.crc = (self.crc << 8) ^ CRC_TABLE[((self.crc >> 24) as u8 ^ byte) as usize];
+    }
+}
+
+impl Checksum for Crc {
+    fn update(&mut self, bytes: &[u8]) {
+        bytes.iter().for_each(|&byte| self.add(byte));
+        self.len += bytes.len() as u64;
+    }
+
+    /// The length goes into the CRC too, least significant byte first and
+    /// with no zeros after the last other byte.
+    fn finish(mut self) -> String {
+        let mut len = self.len;
+        while len > 0 {
+            self.add(len as u8);
+            len >>= 8;
+        }
+        format!("{} {}", !self.crc, self.len)
+    }
+}
+
+/// The BSD sum, a 16-bit sum rotated right before each byte is added, and
+/// the number of 1024-byte blocks
+#[derive(Debug, Default)]
+pub struct Bsd {
+    sum: u16,
+    len: u64,
+}
+
+impl Checksum for Bsd {
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.sum = self.sum.rotate_right(1).wrapping_add(byte.into());
+        }
+        self.len += bytes.len() as u64;
+    }
+
+    fn finish(self) -> String {
+        format!("{:05} {:5}", self.sum, self.len.div_ceil(1024))
+    }
+}
+
+/// The System V sum, of every byte folded into 16 bits, and the number of
+/// 512-byte blocks
+#[derive(Debug, Default)]
+pub struct SysV {
+    sum: u64,
+    len: u64,
+}
+
+impl Checksum for SysV {
+    fn update(&mut self, bytes: &[u8]) {
+        self.sum = bytes.iter().fold(self.sum, |sum, &byte| sum.wrapping_add(byte.into()));
+        self.len += bytes.len() as u64;
+    }
+
+    fn finish(self) -> String {
+        let folded = (self.sum & 0xffff) + ((self.sum & 0xffff_ffff) >> 16);
+        let folded = (folded & 0xffff) + (folded >> 16);
+        format!("{folded} {}", self.len.div_ceil(512))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bsd, Crc, SysV};
+    use hashr::{checksum, Checksum};
+
+    fn sum<C: Checksum>(input: &[u8]) -> String {
+        checksum::<C>(&mut &input[..]).unwrap()
+    }
+
+    #[test]
+    fn test_crc() {
+        assert_eq!(sum::<Crc>(b""), "4294967295 0");
+        assert_eq!(sum::<Crc>(b"a"), "1220704766 1");
+        assert_eq!(sum::<Crc>(b"123456789"), "930766865 9");
+        // The length goes in as all the bytes it takes
+        assert_eq!(sum::<Crc>(&[0; 256]), "4215202376 256");
+    }
+
+    #[test]
+    fn test_bsd() {
+        assert_eq!(sum::<Bsd>(b""), "00000     0");
+        assert_eq!(sum::<Bsd>(b"a"), "00097     1");
+        assert_eq!(sum::<Bsd>(b"123456789"), "53615     1");
+        assert_eq!(sum::<Bsd>(&[0xff; 1025]), "49916     2");
+    }
+
+    #[test]
+    fn test_sysv() {
+        assert_eq!(sum::<SysV>(b""), "0 0");
+        assert_eq!(sum::<SysV>(b"123456789"), "477 1");
+        assert_eq!(sum::<SysV>(&[0xff; 513]), "65280 2");
+    }
+
+    #[test]
+    fn test_chunks() {
+        // Summing in pieces is the same as all at once
+        let input: Vec<u8> = (0..=255).cycle().take(5000).collect();
+        let mut crc = Crc::default();
+        input.chunks(7).for_each(|chunk| crc.update(chunk));
+        assert_eq!(crc.finish(), sum::<Crc>(&input));
+    }
+}
@@ -0,0 +1,114 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const CKSUM: &str = "cksumr";
+const SUM: &str = "sumr";
+const BUSTLE: &str = "tests/inputs/the-bustle.txt";
+const FOX: &str = "tests/inputs/fox.txt";
+const EMPTY: &str = "tests/inputs/empty.txt";
+
+// --------------------------------------------------
+fn run(prg: &str, args: &[&str], expected_file: &str) -> TestResult {
+    let expected = fs::read_to_string(expected_file)?;
+    Command::cargo_bin(prg)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run_stdin(prg: &str, input_file: &str, expected_file: &str) -> TestResult {
+    let input = fs::read_to_string(input_file)?;
+    let expected = fs::read_to_string(expected_file)?;
+    Command::cargo_bin(prg)?
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn skips_bad_file() -> TestResult {
+    for prg in [CKSUM, SUM] {
+        Command::cargo_bin(prg)?
+            .args(["tests/inputs/does-not-exist", EMPTY])
+            .assert()
+            .code(1)
+            .stdout(predicate::str::ends_with(format!(" {EMPTY}\n")))
+            .stderr(predicate::str::starts_with("tests/inputs/does-not-exist: "));
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_algorithm() -> TestResult {
+    Command::cargo_bin(CKSUM)?
+        .args(["-a", "md5", FOX])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("invalid value 'md5'"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn cksum() -> TestResult {
+    run(CKSUM, &[FOX, EMPTY, BUSTLE], "tests/expected/all.cksum.out")?;
+    run(CKSUM, &["-a", "crc", FOX, EMPTY, BUSTLE], "tests/expected/all.cksum.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn cksum_algorithms() -> TestResult {
+    for algorithm in ["bsd", "sysv"] {
+        run(
+            CKSUM,
+            &["-a", algorithm, FOX, EMPTY, BUSTLE],
+            &format!("tests/expected/all.{algorithm}.cksum.out"),
+        )?;
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn cksum_stdin() -> TestResult {
+    run_stdin(CKSUM, FOX, "tests/expected/fox.txt.stdin.cksum.out")?;
+    // Named, stdin is printed with its name
+    let expected = fs::read_to_string("tests/expected/fox.txt.stdin.cksum.out")?;
+    Command::cargo_bin(CKSUM)?
+        .arg("-")
+        .write_stdin(fs::read_to_string(FOX)?)
+        .assert()
+        .success()
+        .stdout(expected.replace('\n', " -\n"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn sum() -> TestResult {
+    run(SUM, &[FOX, EMPTY, BUSTLE], "tests/expected/all.sum.out")?;
+    run(SUM, &["-s", "-r", FOX, EMPTY, BUSTLE], "tests/expected/all.sum.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn sum_sysv() -> TestResult {
+    run(SUM, &["-s", FOX, EMPTY, BUSTLE], "tests/expected/all.sysv.sum.out")?;
+    run(SUM, &["--sysv", FOX, EMPTY, BUSTLE], "tests/expected/all.sysv.sum.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn sum_stdin() -> TestResult {
+    run_stdin(SUM, FOX, "tests/expected/fox.txt.stdin.sum.out")
+}
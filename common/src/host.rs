@@ -0,0 +1,129 @@
+//! The name of this host, and what the resolver says about a host name:
+//! its canonical name and its addresses, as hostname -f and -i print them.
+
+use std::{io, net::IpAddr};
+
+/// What looking up a host name found.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Host {
+    /// The full name, such as host.example.com
+    pub canonical_name: Option<String>,
+    /// Each address once, in the order the resolver gave them
+    pub addresses: Vec<IpAddr>,
+}
+
+/// The name this host was given, as `hostname` prints it.
+#[cfg(unix)]
+pub fn hostname() -> io::Result<String> {
+    let mut name = [0u8; 256];
+    // SAFETY: gethostname writes at most `name.len()` bytes
+    if unsafe { libc::gethostname(name.as_mut_ptr().cast(), name.len()) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // POSIX doesn't promise a NUL if the name had to be cut short
+    let len = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+    Ok(String::from_utf8_lossy(&name[..len]).into_owned())
+}
+
+/// Windows keeps the name in the environment.
+#[cfg(not(unix))]
+pub fn hostname() -> io::Result<String> {
+    std::env::var("COMPUTERNAME").map_err(|_| io::ErrorKind::NotFound.into())
+}
+
+/// Looks up `name` with getaddrinfo(3). The error is the resolver's, like
+/// "Name or service not known".
+#[cfg(unix)]
+pub fn lookup(name: &str) -> io::Result<Host> {
+    use std::{
+        ffi::{CStr, CString},
+        mem,
+        net::{Ipv4Addr, Ipv6Addr},
+        ptr,
+    };
+
+    let cname = CString::new(name).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    // SAFETY: addrinfo is plain data, for which zero is valid and means
+    // any family and type
+    let mut hints: libc::addrinfo = unsafe { mem::zeroed() };
+    hints.ai_flags = libc::AI_CANONNAME;
+    hints.ai_socktype = libc::SOCK_STREAM;
+    let mut found = ptr::null_mut();
+    // SAFETY: the name and hints outlive the call, which sets `found` to a
+    // list freed below
+    let code = unsafe { libc::getaddrinfo(cname.as_ptr(), ptr::null(), &hints, &mut found) };
+    if code != 0 {
+        if code == libc::EAI_SYSTEM {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: gai_strerror returns a static string for any code
+        let message = unsafe { CStr::from_ptr(libc::gai_strerror(code)) };
+        return Err(io::Error::other(message.to_string_lossy().into_owned()));
+    }
+
+    let mut host = Host::default();
+    let mut next = found;
+    // SAFETY: each entry, and what it points to, is valid until freed
+    while let Some(info) = unsafe { next.as_ref() } {
+        if host.canonical_name.is_none() && !info.ai_canonname.is_null() {
+            // SAFETY: a name getaddrinfo set ends in a NUL
+            let canonical = unsafe { CStr::from_ptr(info.ai_canonname) };
+            host.canonical_name = Some(canonical.to_string_lossy().into_owned());
+        }
+        let address = match info.ai_family {
+            // SAFETY: the family says which kind of address it is
+            libc::AF_INET => {
+                let addr = unsafe { &*info.ai_addr.cast::<libc::sockaddr_in>() };
+                Some(IpAddr::V4(Ipv4Addr::from(u32::from_be(addr.sin_addr.s_addr))))
+            }
+            // SAFETY: as for AF_INET
+            libc::AF_INET6 => {
+                let addr = unsafe { &*info.ai_addr.cast::<libc::sockaddr_in6>() };
+                Some(IpAddr::V6(Ipv6Addr::from(addr.sin6_addr.s6_addr)))
+            }
+            _ => None,
+        };
+        if let Some(address) = address.filter(|address| !host.addresses.contains(address)) {
+            host.addresses.push(address);
+        }
+        next = info.ai_next;
+    }
+    // SAFETY: `found` came from getaddrinfo and isn't used after
+    unsafe { libc::freeaddrinfo(found) };
+    Ok(host)
+}
+
+/// Without getaddrinfo(3), the addresses std can find, and no canonical
+/// name.
+#[cfg(not(unix))]
+pub fn lookup(name: &str) -> io::Result<Host> {
+    use std::net::ToSocketAddrs;
+
+    let mut host = Host::default();
+    for address in (name, 0).to_socket_addrs()? {
+        if !host.addresses.contains(&address.ip()) {
+            host.addresses.push(address.ip());
+        }
+    }
+    Ok(host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hostname, lookup};
+
+    #[test]
+    fn test_hostname() {
+        let name = hostname().unwrap();
+        assert!(!name.is_empty() && !name.contains('\0'));
+    }
+
+    #[test]
+    fn test_lookup() {
+        let host = lookup("localhost").unwrap();
+        assert!(host.addresses.iter().all(|address| address.is_loopback()));
+        assert!(!host.addresses.is_empty());
+        // The reserved .invalid domain never resolves
+        assert!(lookup("no-such-host.invalid").is_err());
+    }
+}
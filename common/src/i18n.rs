@@ -0,0 +1,241 @@
+//! A small gettext-style message catalog. Every message is looked up by its
+//! English text, so untranslated messages, and anything under the C locale,
+//! come out in English as before.
+//!
+//! The language is taken from LC_ALL, LC_MESSAGES or LANG, the first that is
+//! set and not empty, like GNU tools do.
+
+use std::{env, fmt::Display, io, sync::OnceLock};
+
+/// The languages with a catalog.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lang {
+    En,
+    De,
+    Es,
+}
+
+impl Lang {
+    /// The language of the current locale, looked up once per process.
+    pub fn current() -> Self {
+        static CURRENT: OnceLock<Lang> = OnceLock::new();
+        *CURRENT.get_or_init(|| {
+            let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+                .into_iter()
+                .filter_map(|var| env::var(var).ok())
+                .find(|val| !val.is_empty());
+            locale.as_deref().map_or(Lang::En, Lang::from_locale)
+        })
+    }
+
+    /// Picks the language out of a locale name like "de_DE.UTF-8".
+    pub fn from_locale(locale: &str) -> Self {
+        let lang = locale.split(['_', '.', '@']).next().unwrap_or_default();
+        match lang {
+            "de" => Lang::De,
+            "es" => Lang::Es,
+            _ => Lang::En,
+        }
+    }
+
+    fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Lang::En => &[],
+            Lang::De => DE,
+            Lang::Es => ES,
+        }
+    }
+
+    /// `msgid` in this language, or `msgid` itself if it isn't translated.
+    pub fn tr(self, msgid: &'static str) -> &'static str {
+        self.catalog()
+            .iter()
+            .find(|(id, _)| *id == msgid)
+            .map_or(msgid, |(_, msg)| msg)
+    }
+}
+
+/// `msgid` in the current language.
+pub fn tr(msgid: &'static str) -> &'static str {
+    Lang::current().tr(msgid)
+}
+
+/// `msgid` in the current language, with each `{name}` in it replaced by its
+/// value in `args`. Translations may move the placeholders around.
+pub fn tr_fmt(msgid: &'static str, args: &[(&str, &dyn Display)]) -> String {
+    fill(tr(msgid), args)
+}
+
+fn fill(msg: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut msg = msg.to_string();
+    for (name, value) in args {
+        msg = msg.replace(&format!("{{{name}}}"), &value.to_string());
+    }
+    msg
+}
+
+/// Describes an I/O error to the user. The system's text is always English,
+/// so the common kinds are translated here; anything else is left as it is.
+pub fn io_error(err: &io::Error) -> String {
+    let lang = Lang::current();
+    let msgid = match err.kind() {
+        io::ErrorKind::NotFound => "No such file or directory",
+        io::ErrorKind::PermissionDenied => "Permission denied",
+        io::ErrorKind::IsADirectory => "Is a directory",
+        _ => return err.to_string(),
+    };
+    match lang {
+        Lang::En => err.to_string(),
+        _ => lang.tr(msgid).to_string(),
+    }
+}
+
+/// The month names, January first.
+pub const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+static DE: &[(&str, &str)] = &[
+    ("No such file or directory", "Datei oder Verzeichnis nicht gefunden"),
+    ("Permission denied", "Keine Berechtigung"),
+    ("Is a directory", "Ist ein Verzeichnis"),
+    ("{path} is a directory", "{path} ist ein Verzeichnis"),
+    ("{path}: input file is output file", "{path}: Eingabedatei ist Ausgabedatei"),
+    ("file {num} is not in sorted order", "Datei {num} ist nicht sortiert"),
+    ("input is not in sorted order", "Eingabe ist nicht sortiert"),
+    ("standard input", "Standardeingabe"),
+    ("{file}: file truncated", "{file}: Datei wurde gekürzt"),
+    ("'{file}' has become inaccessible: {error}", "'{file}' ist unzugänglich geworden: {error}"),
+    (
+        "cannot open '{file}' for reading: {error}",
+        "'{file}' kann nicht zum Lesen geöffnet werden: {error}",
+    ),
+    ("error reading '{file}': {error}", "Fehler beim Lesen von '{file}': {error}"),
+    ("'{file}' has appeared; following new file", "'{file}' ist erschienen; folge neuer Datei"),
+    ("'{file}' has been replaced; following new file", "'{file}' wurde ersetzt; folge neuer Datei"),
+    (
+        "Both input files cannot be STDIN (\"-\")",
+        "Es können nicht beide Eingabedateien STDIN (\"-\") sein",
+    ),
+    (
+        "Must have --fields, --bytes, or --chars",
+        "--fields, --bytes oder --chars ist erforderlich",
+    ),
+    ("January", "Januar"),
+    ("February", "Februar"),
+    ("March", "März"),
+    ("April", "April"),
+    ("May", "Mai"),
+    ("June", "Juni"),
+    ("July", "Juli"),
+    ("August", "August"),
+    ("September", "September"),
+    ("October", "Oktober"),
+    ("November", "November"),
+    ("December", "Dezember"),
+    ("Su Mo Tu We Th Fr Sa", "So Mo Di Mi Do Fr Sa"),
+];
+
+static ES: &[(&str, &str)] = &[
+    ("No such file or directory", "No existe el archivo o el directorio"),
+    ("Permission denied", "Permiso denegado"),
+    ("Is a directory", "Es un directorio"),
+    ("{path} is a directory", "{path} es un directorio"),
+    ("{path}: input file is output file", "{path}: el archivo de entrada es el de salida"),
+    ("file {num} is not in sorted order", "el archivo {num} no está ordenado"),
+    ("input is not in sorted order", "la entrada no está ordenada"),
+    ("standard input", "entrada estándar"),
+    ("{file}: file truncated", "{file}: archivo truncado"),
+    ("'{file}' has become inaccessible: {error}", "'{file}' ya no es accesible: {error}"),
+    (
+        "cannot open '{file}' for reading: {error}",
+        "no se puede abrir '{file}' para lectura: {error}",
+    ),
+    ("error reading '{file}': {error}", "error al leer '{file}': {error}"),
+    (
+        "'{file}' has appeared; following new file",
+        "'{file}' ha aparecido; se sigue el nuevo archivo",
+    ),
+    (
+        "'{file}' has been replaced; following new file",
+        "'{file}' ha sido reemplazado; se sigue el nuevo archivo",
+    ),
+    (
+        "Both input files cannot be STDIN (\"-\")",
+        "Las dos entradas no pueden ser STDIN (\"-\")",
+    ),
+    (
+        "Must have --fields, --bytes, or --chars",
+        "Se requiere --fields, --bytes o --chars",
+    ),
+    ("January", "enero"),
+    ("February", "febrero"),
+    ("March", "marzo"),
+    ("April", "abril"),
+    ("May", "mayo"),
+    ("June", "junio"),
+    ("July", "julio"),
+    ("August", "agosto"),
+    ("September", "septiembre"),
+    ("October", "octubre"),
+    ("November", "noviembre"),
+    ("December", "diciembre"),
+    ("Su Mo Tu We Th Fr Sa", "do lu ma mi ju vi sá"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::{fill, Lang, MONTHS};
+
+    #[test]
+    fn test_from_locale() {
+        assert_eq!(Lang::from_locale("de_DE.UTF-8"), Lang::De);
+        assert_eq!(Lang::from_locale("es"), Lang::Es);
+        assert_eq!(Lang::from_locale("es_MX@euro"), Lang::Es);
+        assert_eq!(Lang::from_locale("C"), Lang::En);
+        assert_eq!(Lang::from_locale("POSIX"), Lang::En);
+        assert_eq!(Lang::from_locale("fr_FR.UTF-8"), Lang::En);
+    }
+
+    #[test]
+    fn test_tr() {
+        assert_eq!(Lang::De.tr("March"), "März");
+        assert_eq!(Lang::Es.tr("March"), "marzo");
+        assert_eq!(Lang::En.tr("March"), "March");
+        // Untranslated messages fall back to English
+        assert_eq!(Lang::De.tr("==> {file} <=="), "==> {file} <==");
+
+        let msg = fill(Lang::Es.tr("file {num} is not in sorted order"), &[("num", &2)]);
+        assert_eq!(msg, "el archivo 2 no está ordenado");
+    }
+
+    #[test]
+    fn test_catalogs_are_complete() {
+        for lang in [Lang::De, Lang::Es] {
+            for month in MONTHS {
+                assert!(lang.catalog().iter().any(|(id, _)| *id == month), "{lang:?} {month}");
+            }
+            for (id, msg) in lang.catalog() {
+                // A translation must keep every placeholder it was given
+                for name in ["{path}", "{num}", "{file}"] {
+                    assert_eq!(id.contains(name), msg.contains(name), "{lang:?} {id}");
+                }
+                // calr lays out the weekday header in fixed columns
+                if id.starts_with("Su Mo") {
+                    assert_eq!(msg.chars().count(), id.chars().count());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,293 @@
+//! Running another command as a process group of its own, so it and all it
+//! starts can be signalled at once, and catching the signals that should
+//! be passed on to it, for the tools that run commands, like timeoutr.
+//!
+//! A tool calls [`catch`] first, then starts the command with
+//! [`Job::spawn`] and waits on it with [`Job::wait`], which returns early
+//! whenever one of the caught signals arrives so the tool can act on it.
+
+/// A signal, by number.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Signal(pub i32);
+
+/// How a command ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Exit {
+    Code(i32),
+    Signal(Signal),
+}
+
+#[cfg(unix)]
+pub use unix::*;
+
+#[cfg(unix)]
+mod unix {
+    use super::{Exit, Signal};
+    use std::{
+        io,
+        mem::MaybeUninit,
+        os::unix::process::CommandExt,
+        process::{Child, Command},
+        ptr,
+        sync::{
+            atomic::{AtomicI32, Ordering},
+            OnceLock,
+        },
+        time::Duration,
+    };
+
+    impl Signal {
+        pub const HUP: Signal = Signal(libc::SIGHUP);
+        pub const INT: Signal = Signal(libc::SIGINT);
+        pub const QUIT: Signal = Signal(libc::SIGQUIT);
+        pub const KILL: Signal = Signal(libc::SIGKILL);
+        pub const ALRM: Signal = Signal(libc::SIGALRM);
+        pub const TERM: Signal = Signal(libc::SIGTERM);
+        pub const CONT: Signal = Signal(libc::SIGCONT);
+
+        /// The signal called `name`, in any case and with or without SIG
+        /// in front, as in `TERM` or `sigkill`, or numbered `name`.
+        pub fn parse(name: &str) -> Option<Signal> {
+            if let Ok(number) = name.parse() {
+                return (0..NSIG).contains(&number).then_some(Signal(number));
+            }
+            let name = name.to_ascii_uppercase();
+            let name = name.strip_prefix("SIG").unwrap_or(&name);
+            NAMES.iter().find(|(n, _)| *n == name).map(|&(_, number)| Signal(number))
+        }
+    }
+
+    /// One more than the highest signal number, as on Linux
+    const NSIG: i32 = 65;
+
+    const NAMES: &[(&str, i32)] = &[
+        ("HUP", libc::SIGHUP),
+        ("INT", libc::SIGINT),
+        ("QUIT", libc::SIGQUIT),
+        ("ILL", libc::SIGILL),
+        ("TRAP", libc::SIGTRAP),
+        ("ABRT", libc::SIGABRT),
+        ("IOT", libc::SIGIOT),
+        ("BUS", libc::SIGBUS),
+        ("FPE", libc::SIGFPE),
+        ("KILL", libc::SIGKILL),
+        ("USR1", libc::SIGUSR1),
+        ("SEGV", libc::SIGSEGV),
+        ("USR2", libc::SIGUSR2),
+        ("PIPE", libc::SIGPIPE),
+        ("ALRM", libc::SIGALRM),
+        ("TERM", libc::SIGTERM),
+        ("CHLD", libc::SIGCHLD),
+        ("CONT", libc::SIGCONT),
+        ("STOP", libc::SIGSTOP),
+        ("TSTP", libc::SIGTSTP),
+        ("TTIN", libc::SIGTTIN),
+        ("TTOU", libc::SIGTTOU),
+        ("URG", libc::SIGURG),
+        ("XCPU", libc::SIGXCPU),
+        ("XFSZ", libc::SIGXFSZ),
+        ("VTALRM", libc::SIGVTALRM),
+        ("PROF", libc::SIGPROF),
+        ("WINCH", libc::SIGWINCH),
+        ("IO", libc::SIGIO),
+        ("SYS", libc::SIGSYS),
+    ];
+
+    /// A command running as the leader of its own process group.
+    #[derive(Debug)]
+    pub struct Job {
+        child: Child,
+    }
+
+    impl Job {
+        /// Starts `command` with the signal mask from before [`catch`], so
+        /// it can be signalled. A signal that arrives meanwhile is still
+        /// caught.
+        pub fn spawn(command: &mut Command) -> io::Result<Job> {
+            let blocked = UNBLOCKED.get().map(set_mask).transpose()?;
+            let child = command.process_group(0).spawn();
+            if let Some(blocked) = blocked {
+                set_mask(&blocked)?;
+            }
+            Ok(Job { child: child? })
+        }
+
+        /// Sends `signal` to every process in the group, and SIGCONT after
+        /// it, so one that is stopped wakes up to act on it.
+        pub fn signal(&self, signal: Signal) -> io::Result<()> {
+            let group = self.child.id() as libc::pid_t;
+            for signal in [signal, Signal::CONT] {
+                // SAFETY: killpg only reads its arguments
+                if unsafe { libc::killpg(group, signal.0) } == -1 {
+                    return Err(io::Error::last_os_error());
+                }
+                if signal == Signal::KILL || signal == Signal::CONT {
+                    break;
+                }
+            }
+            Ok(())
+        }
+
+        /// Waits for the command to end, or returns None as soon as one of
+        /// the signals passed to [`catch`] arrives; [`caught`] tells which.
+        pub fn wait(&mut self) -> io::Result<Option<Exit>> {
+            let pid = self.child.id() as libc::pid_t;
+            loop {
+                let mut status = 0;
+                // SAFETY: status outlives the call
+                match unsafe { libc::waitpid(pid, &mut status, libc::WNOHANG) } {
+                    -1 => return Err(io::Error::last_os_error()),
+                    0 => {}
+                    _ if libc::WIFSIGNALED(status) => {
+                        return Ok(Some(Exit::Signal(Signal(libc::WTERMSIG(status)))))
+                    }
+                    _ => return Ok(Some(Exit::Code(libc::WEXITSTATUS(status)))),
+                }
+                if CAUGHT.load(Ordering::Relaxed) != 0 {
+                    return Ok(None);
+                }
+                // The caught signals and SIGCHLD are blocked but for the
+                // sleep, so none can slip in between the checks above and
+                // it
+                let Some(mask) = UNBLOCKED.get() else {
+                    return Err(io::Error::other("no signals are caught"));
+                };
+                // SAFETY: mask is a valid signal set; the call returns
+                // after any handler has run
+                unsafe { libc::sigsuspend(mask) };
+            }
+        }
+    }
+
+    /// The last signal caught and not yet taken, or 0
+    static CAUGHT: AtomicI32 = AtomicI32::new(0);
+
+    /// The signal mask from before [`catch`] blocked the caught signals
+    static UNBLOCKED: OnceLock<libc::sigset_t> = OnceLock::new();
+
+    extern "C" fn record(signal: libc::c_int) {
+        if signal != libc::SIGCHLD {
+            CAUGHT.store(signal, Ordering::Relaxed);
+        }
+    }
+
+    /// Sets the signal mask to `mask`, and returns the one before.
+    fn set_mask(mask: &libc::sigset_t) -> io::Result<libc::sigset_t> {
+        let mut old = MaybeUninit::uninit();
+        // SAFETY: the old mask is filled in before it is read
+        unsafe {
+            match libc::pthread_sigmask(libc::SIG_SETMASK, mask, old.as_mut_ptr()) {
+                0 => Ok(old.assume_init()),
+                e => Err(io::Error::from_raw_os_error(e)),
+            }
+        }
+    }
+
+    /// Catches `signals`: rather than the process acting on one, it is
+    /// kept for [`caught`], and a [`Job::wait`] returns. KILL and STOP
+    /// can't be caught, and are left out. Call it before starting the
+    /// command, so none is missed, and before starting any thread, which
+    /// could take the signals instead. The command itself starts with the
+    /// usual actions.
+    pub fn catch(signals: &[Signal]) -> io::Result<()> {
+        // SIGCHLD too, to wake Job::wait when the command ends
+        let signals: Vec<Signal> = signals
+            .iter()
+            .copied()
+            .filter(|signal| ![0, libc::SIGKILL, libc::SIGSTOP].contains(&signal.0))
+            .chain([Signal(libc::SIGCHLD)])
+            .collect();
+        // SAFETY: the handler only stores to an atomic, which is safe in a
+        // signal handler, and each set is initialised before it is read
+        unsafe {
+            let mut set = MaybeUninit::uninit();
+            libc::sigemptyset(set.as_mut_ptr());
+            let mut set = set.assume_init();
+            for signal in &signals {
+                let mut action: libc::sigaction = std::mem::zeroed();
+                action.sa_sigaction = record as extern "C" fn(libc::c_int) as usize;
+                libc::sigemptyset(&mut action.sa_mask);
+                if libc::sigaction(signal.0, &action, ptr::null_mut()) == -1 {
+                    return Err(io::Error::last_os_error());
+                }
+                libc::sigaddset(&mut set, signal.0);
+            }
+            let mut old = MaybeUninit::uninit();
+            match libc::pthread_sigmask(libc::SIG_BLOCK, &set, old.as_mut_ptr()) {
+                0 => {
+                    let _ = UNBLOCKED.set(old.assume_init());
+                    Ok(())
+                }
+                e => Err(io::Error::from_raw_os_error(e)),
+            }
+        }
+    }
+
+    /// The signal caught since the last call, if any.
+    pub fn caught() -> Option<Signal> {
+        match CAUGHT.swap(0, Ordering::Relaxed) {
+            0 => None,
+            signal => Some(Signal(signal)),
+        }
+    }
+
+    /// Has SIGALRM sent to the process once `duration` has passed. A zero
+    /// duration cancels the last one.
+    pub fn alarm_after(duration: Duration) -> io::Result<()> {
+        let micros = match (duration.as_secs(), duration.subsec_micros()) {
+            // setitimer takes zero to mean never
+            (0, 0) if !duration.is_zero() => 1,
+            (_, micros) => micros,
+        };
+        let timer = libc::itimerval {
+            it_interval: libc::timeval { tv_sec: 0, tv_usec: 0 },
+            it_value: libc::timeval {
+                tv_sec: duration.as_secs().min(libc::time_t::MAX as u64) as libc::time_t,
+                tv_usec: micros as libc::suseconds_t,
+            },
+        };
+        // SAFETY: timer outlives the call
+        match unsafe { libc::setitimer(libc::ITIMER_REAL, &timer, ptr::null_mut()) } {
+            0 => Ok(()),
+            _ => Err(io::Error::last_os_error()),
+        }
+    }
+
+    /// Ends the process as `signal` would, so whatever started it sees the
+    /// same as if it had ended the command directly, though without a core
+    /// dump. Exits with 128 plus the signal's number if the signal doesn't
+    /// end processes.
+    pub fn die_by(signal: Signal) -> ! {
+        // SAFETY: these only change this process's own resource limit,
+        // signal action and mask
+        unsafe {
+            let no_core = libc::rlimit { rlim_cur: 0, rlim_max: 0 };
+            libc::setrlimit(libc::RLIMIT_CORE, &no_core);
+            libc::signal(signal.0, libc::SIG_DFL);
+            let mut set = MaybeUninit::uninit();
+            libc::sigemptyset(set.as_mut_ptr());
+            libc::sigaddset(set.as_mut_ptr(), signal.0);
+            libc::pthread_sigmask(libc::SIG_UNBLOCK, set.as_ptr(), ptr::null_mut());
+            libc::raise(signal.0);
+        }
+        std::process::exit(128 + signal.0)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::Signal;
+
+        #[test]
+        fn test_parse() {
+            assert_eq!(Signal::parse("TERM"), Some(Signal::TERM));
+            assert_eq!(Signal::parse("sigkill"), Some(Signal::KILL));
+            assert_eq!(Signal::parse("SigHup"), Some(Signal::HUP));
+            assert_eq!(Signal::parse("9"), Some(Signal::KILL));
+            assert_eq!(Signal::parse("0"), Some(Signal(0)));
+            assert_eq!(Signal::parse("FOO"), None);
+            assert_eq!(Signal::parse("SIG"), None);
+            assert_eq!(Signal::parse("-1"), None);
+            assert_eq!(Signal::parse("1000"), None);
+        }
+    }
+}
@@ -0,0 +1,548 @@
+//! Helpers shared by all of the tools, so they read input and report
+//! errors the same way.
+
+pub mod host;
+pub mod i18n;
+pub mod job;
+pub mod term;
+pub mod units;
+
+use std::{
+    env,
+    error::Error,
+    ffi::{OsStr, OsString},
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, ErrorKind, IsTerminal, Read, StdoutLock, Write},
+    process,
+};
+
+use clap::{Parser, ValueEnum};
+use serde::Serialize;
+use thiserror::Error;
+
+pub type MyResult<T> = Result<T, CoreError>;
+
+/// Everything that can make a tool fail, grouped so callers can tell the kinds
+/// apart instead of matching on message text.
+#[derive(Debug, Error)]
+pub enum CoreError {
+    /// A named file couldn't be opened, read or written
+    #[error("{path}: {}", i18n::io_error(.source))]
+    Io {
+        path: String,
+        #[source]
+        source: io::Error,
+    },
+    /// Reading stdin or writing stdout failed
+    #[error(transparent)]
+    Stream(#[from] io::Error),
+    /// An option or argument has an invalid value
+    #[error("{0}")]
+    Parse(String),
+    /// Options that don't make sense together, or a required one is missing
+    #[error("{0}")]
+    Usage(String),
+    /// The input can't be processed, e.g. unsorted input to commr
+    #[error("{0}")]
+    Input(String),
+    /// An error from another library
+    #[error(transparent)]
+    Other(Box<dyn Error + Send + Sync>),
+}
+
+impl CoreError {
+    /// An I/O error on the file at `path`.
+    pub fn io(path: impl Into<String>, source: io::Error) -> Self {
+        CoreError::Io { path: path.into(), source }
+    }
+
+    pub fn other(err: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        CoreError::Other(err.into())
+    }
+
+    /// The status to exit with. GNU tools use 1 for every kind of failure;
+    /// tools with their own convention, like grep's 2, don't call this.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CoreError::Io { .. }
+            | CoreError::Stream(_)
+            | CoreError::Parse(_)
+            | CoreError::Usage(_)
+            | CoreError::Input(_)
+            | CoreError::Other(_) => 1,
+        }
+    }
+}
+
+/// The filename that means standard input.
+pub const STDIN: &str = "-";
+
+/// Opens `filename` for buffered reading, or stdin for "-". The error names
+/// the file, e.g. "foo.txt: No such file or directory (os error 2)". Stdin
+/// keeps its one buffer, so a later "-" reads what an earlier one left.
+pub fn open(filename: &str) -> MyResult<Box<dyn BufRead>> {
+    match filename {
+        STDIN => Ok(Box::new(io::stdin().lock())),
+        _ => Ok(Box::new(BufReader::new(
+            File::open(filename).map_err(|e| CoreError::io(filename, e))?,
+        ))),
+    }
+}
+
+/// Parses the command line like `Parser::parse`, but exits with 1 rather than
+/// clap's 2 on a usage error, as GNU tools do. Help and version still exit 0.
+pub fn parse_args<T: Parser>() -> T {
+    try_parse_args().unwrap_or_else(|e| {
+        if e.use_stderr() {
+            let _ = e.print();
+            process::exit(1);
+        }
+        e.exit()
+    })
+}
+
+/// Parses the command line, reading default options first from the tool's
+/// variable (LSR_OPTS for lsr, see [`opts_var`]). Options given on the
+/// command line override the defaults.
+pub fn try_parse_args<T: Parser>() -> Result<T, clap::Error> {
+    let defaults = env::var_os(opts_var(T::command().get_name()));
+    parse_with_defaults(env::args_os(), defaults.as_deref())
+}
+
+/// The variable holding default options for the tool `name`, e.g. LSR_OPTS.
+pub fn opts_var(name: &str) -> String {
+    format!("{}_OPTS", name.to_uppercase())
+}
+
+/// Parses `args` with the whitespace-separated `defaults` inserted after the
+/// program name. A repeated option takes its last value, so the command line
+/// wins over the defaults.
+fn parse_with_defaults<T: Parser>(
+    args: impl IntoIterator<Item = OsString>,
+    defaults: Option<&OsStr>,
+) -> Result<T, clap::Error> {
+    let command = T::command();
+    let var = opts_var(command.get_name());
+    let command = command
+        .args_override_self(true)
+        .after_help(format!("Default options are read from ${var}."));
+
+    let mut args = args.into_iter();
+    let mut full_args: Vec<OsString> = args.next().into_iter().collect();
+    if let Some(defaults) = defaults {
+        full_args.extend(defaults.to_string_lossy().split_whitespace().map(OsString::from));
+    }
+    full_args.extend(args);
+
+    let matches = command.try_get_matches_from(full_args)?;
+    T::from_arg_matches(&matches)
+}
+
+/// The value of a `--color=WHEN` option, shared by every tool that styles its
+/// output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    /// Color when stdout is a terminal and NO_COLOR isn't set
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether output written to stdout should be styled.
+    pub fn enabled(self) -> bool {
+        self.enabled_for(env::var_os("NO_COLOR").as_deref(), io::stdout().is_terminal())
+    }
+
+    /// Follows https://no-color.org: a non-empty NO_COLOR turns off automatic
+    /// color, but an explicit --color=always still wins.
+    fn enabled_for(self, no_color: Option<&OsStr>, is_terminal: bool) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => no_color.is_none_or(OsStr::is_empty) && is_terminal,
+        }
+    }
+}
+
+/// What ends each record, on input and output: a newline, or a NUL for
+/// pipelines of arbitrary file names, as with `find -print0 | xargs -0`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Delimiter {
+    #[default]
+    Newline,
+    Nul,
+}
+
+impl Delimiter {
+    pub fn byte(self) -> u8 {
+        match self {
+            Delimiter::Newline => b'\n',
+            Delimiter::Nul => b'\0',
+        }
+    }
+
+    pub fn as_char(self) -> char {
+        self.byte().into()
+    }
+
+    /// Like [`BufRead::read_line`], but the record ends at this delimiter,
+    /// which is kept.
+    pub fn read_record<R: BufRead + ?Sized>(
+        self,
+        reader: &mut R,
+        buf: &mut String,
+    ) -> io::Result<usize> {
+        if self == Delimiter::Newline {
+            return reader.read_line(buf);
+        }
+        let mut bytes = Vec::new();
+        let len = reader.read_until(self.byte(), &mut bytes)?;
+        // The same error read_line gives
+        let record = String::from_utf8(bytes).map_err(|_| {
+            io::Error::new(ErrorKind::InvalidData, "stream did not contain valid UTF-8")
+        })?;
+        buf.push_str(&record);
+        Ok(len)
+    }
+
+    /// Like [`BufRead::lines`], but split at this delimiter.
+    pub fn records<R: BufRead>(self, reader: R) -> Records<R> {
+        Records { reader, delimiter: self }
+    }
+
+    /// Removes the delimiter from the end of `record`, along with a CR before
+    /// a newline.
+    pub fn strip(self, record: &str) -> &str {
+        match self {
+            Delimiter::Newline => {
+                let record = record.strip_suffix('\n').unwrap_or(record);
+                record.strip_suffix('\r').unwrap_or(record)
+            }
+            Delimiter::Nul => record.strip_suffix('\0').unwrap_or(record),
+        }
+    }
+}
+
+/// The records of a reader, from [`Delimiter::records`].
+#[derive(Debug)]
+pub struct Records<R> {
+    reader: R,
+    delimiter: Delimiter,
+}
+
+impl<R: BufRead> Iterator for Records<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut record = String::new();
+        match self.delimiter.read_record(&mut self.reader, &mut record) {
+            Ok(0) => None,
+            Ok(_) => Some(Ok(self.delimiter.strip(&record).to_string())),
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// The -z flag, flattened into the `Args` of every tool that reads or writes
+/// records, so the name is the same everywhere.
+#[derive(Debug, clap::Args)]
+pub struct DelimiterArgs {
+    /// Records end in NUL, not newline
+    #[arg(short = 'z', long)]
+    zero_terminated: bool,
+}
+
+impl DelimiterArgs {
+    pub fn delimiter(&self) -> Delimiter {
+        if self.zero_terminated {
+            Delimiter::Nul
+        } else {
+            Delimiter::Newline
+        }
+    }
+}
+
+/// The --files-from option of the tools that take many inputs, for file
+/// lists computed by another tool, e.g. `findr -z -t f src | wcr
+/// --files-from=-`. Flatten it into `Args` and make the file operands
+/// conflict with `files_from`.
+#[derive(Debug, clap::Args)]
+pub struct FilesFromArgs {
+    /// Read the input file names from FILE ("-" for stdin), one per line or NUL-separated
+    #[arg(long, value_name = "FILE")]
+    files_from: Option<String>,
+}
+
+impl FilesFromArgs {
+    /// The inputs: those named in the --files-from list if it was given,
+    /// otherwise `files` from the command line.
+    pub fn files(&self, files: Vec<String>) -> MyResult<Vec<String>> {
+        match &self.files_from {
+            Some(list) => read_file_list(list),
+            None => Ok(files),
+        }
+    }
+}
+
+/// Reads the file names in `list`. They are NUL-separated if there is a NUL
+/// anywhere, so names with newlines still work, and one per line otherwise.
+pub fn read_file_list(list: &str) -> MyResult<Vec<String>> {
+    let mut names = String::new();
+    open(list)?
+        .read_to_string(&mut names)
+        .map_err(|e| CoreError::io(list, e))?;
+    let delimiter = if names.contains('\0') { '\0' } else { '\n' };
+    names
+        .split_terminator(delimiter)
+        .map(|name| match name {
+            "" => Err(CoreError::Input(format!("{list}: invalid zero-length file name"))),
+            // Stdin is already taken by the list
+            STDIN if list == STDIN => Err(CoreError::Input(format!(
+                "when reading file names from stdin, no file name of '{STDIN}' allowed"
+            ))),
+            name => Ok(name.to_string()),
+        })
+        .collect()
+}
+
+/// Stdout for a tool's results: locked once and buffered, instead of paying
+/// for a lock and a write with every `println!`. Records end in a newline, or
+/// a NUL with [`Output::delimiter`].
+///
+/// A reader that goes away (`grepr foo big.txt | head -1`) normally stops the
+/// tool with SIGPIPE; where that can't happen, the broken pipe ends it
+/// quietly with status 1 instead of surfacing as an error.
+pub struct Output {
+    out: BufWriter<StdoutLock<'static>>,
+    delimiter: Delimiter,
+}
+
+impl Output {
+    pub fn stdout() -> Self {
+        Output {
+            out: BufWriter::new(io::stdout().lock()),
+            delimiter: Delimiter::Newline,
+        }
+    }
+
+    /// Ends records written with [`Output::write_record`] with `delimiter`.
+    pub fn delimiter(mut self, delimiter: Delimiter) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Writes `record` followed by the delimiter.
+    pub fn write_record(&mut self, record: impl AsRef<[u8]>) -> io::Result<()> {
+        self.write_all(record.as_ref())?;
+        self.write_all(&[self.delimiter.byte()])
+    }
+}
+
+/// Exits quietly if `res` failed because stdout was closed.
+fn check_pipe<T>(res: io::Result<T>) -> io::Result<T> {
+    match res {
+        Err(e) if e.kind() == ErrorKind::BrokenPipe => process::exit(1),
+        res => res,
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        check_pipe(self.out.write(buf))
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        check_pipe(self.out.write_all(buf))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        check_pipe(self.out.flush())
+    }
+}
+
+/// Writes `value` to `out` as one line of newline-delimited JSON, the format
+/// of every tool's `--json` mode.
+pub fn write_json(out: &mut impl Write, value: &impl Serialize) -> MyResult<()> {
+    serde_json::to_writer(&mut *out, value).map_err(CoreError::other)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Restores the default SIGPIPE action, which Rust sets to "ignore", so a tool
+/// writing into a closed pipe (`catr big.txt | head`) is stopped quietly like
+/// its GNU counterpart instead of reporting a broken-pipe error. Call it first
+/// thing in `main`.
+pub fn reset_sigpipe() {
+    #[cfg(unix)]
+    // SAFETY: no other threads are running yet to race on the signal table
+    unsafe {
+        libc::signal(libc::SIGPIPE, libc::SIG_DFL);
+    }
+}
+
+/// Ignores SIGINT, so a tool can finish its part of a pipeline after ^C has
+/// stopped the rest, like `tee -i`. Does nothing where there are no signals.
+pub fn ignore_interrupts() {
+    #[cfg(unix)]
+    // SAFETY: as for reset_sigpipe, called before any other thread starts
+    unsafe {
+        libc::signal(libc::SIGINT, libc::SIG_IGN);
+    }
+}
+
+/// Blocks SIGPIPE in the calling thread only, so its writes into a closed
+/// pipe fail with EPIPE rather than stop the whole tool, as a thread feeding
+/// a child that may quit early needs. Does nothing where there are no signals.
+pub fn block_sigpipe() {
+    #[cfg(unix)]
+    // SAFETY: the set is emptied before use, and only this thread's mask changes
+    unsafe {
+        let mut set: libc::sigset_t = std::mem::zeroed();
+        libc::sigemptyset(&mut set);
+        libc::sigaddset(&mut set, libc::SIGPIPE);
+        libc::pthread_sigmask(libc::SIG_BLOCK, &set, std::ptr::null_mut());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        open, parse_with_defaults, read_file_list, write_json, ColorChoice, CoreError, Delimiter,
+    };
+    use clap::Parser;
+    use std::ffi::{OsStr, OsString};
+    use std::io::{self, BufRead};
+
+    #[test]
+    fn test_open() {
+        let mut contents = String::new();
+        open("Cargo.toml").unwrap().read_line(&mut contents).unwrap();
+        assert_eq!(contents, "[package]\n");
+
+        let err = open("/path/does/not/exist").err().unwrap();
+        assert!(matches!(&err, CoreError::Io { path, .. } if path == "/path/does/not/exist"));
+        assert!(err.to_string().starts_with("/path/does/not/exist: "));
+        assert!(err.to_string().ends_with("(os error 2)"));
+    }
+
+    #[test]
+    fn test_core_error() {
+        let err = CoreError::io("foo.txt", io::Error::other("oops"));
+        assert_eq!(err.to_string(), "foo.txt: oops");
+        assert_eq!(err.exit_code(), 1);
+
+        let err = CoreError::from(io::Error::other("oops"));
+        assert_eq!(err.to_string(), "oops");
+        assert!(matches!(err, CoreError::Stream(_)));
+    }
+
+    #[derive(Debug, Parser)]
+    #[command(name = "testr")]
+    struct Args {
+        #[arg(short = 'a')]
+        all: bool,
+
+        #[arg(short = 'n', default_value = "10")]
+        lines: String,
+
+        files: Vec<String>,
+    }
+
+    fn parse(args: &str, defaults: Option<&str>) -> Args {
+        let args = args.split_whitespace().map(OsString::from);
+        parse_with_defaults(args, defaults.map(OsStr::new)).unwrap()
+    }
+
+    #[test]
+    fn test_parse_with_defaults() {
+        let args = parse("testr foo", None);
+        assert!(!args.all);
+        assert_eq!(args.files, ["foo"]);
+
+        let args = parse("testr foo", Some(" -a  -n 5 "));
+        assert!(args.all);
+        assert_eq!(args.lines, "5");
+        assert_eq!(args.files, ["foo"]);
+
+        // The command line overrides the defaults, and may repeat them
+        let args = parse("testr -n 3 -a foo", Some("-a -n 5"));
+        assert!(args.all);
+        assert_eq!(args.lines, "3");
+
+        let res = parse_with_defaults::<Args>(
+            ["testr"].map(OsString::from),
+            Some(OsStr::new("--bogus")),
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_write_json() {
+        let mut out = Vec::new();
+        write_json(&mut out, &serde_json::json!({"path": "a\nb", "size": 3})).unwrap();
+        write_json(&mut out, &serde_json::json!({"path": "c"})).unwrap();
+        // A newline in a value is escaped, so there is one record per line
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "{\"path\":\"a\\nb\",\"size\":3}\n{\"path\":\"c\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_delimiter() {
+        let split = |delimiter: Delimiter| {
+            delimiter
+                .records("a\r\nb\0c\nd".as_bytes())
+                .collect::<io::Result<Vec<_>>>()
+                .unwrap()
+        };
+        assert_eq!(split(Delimiter::Newline), ["a", "b\0c", "d"]);
+        assert_eq!(split(Delimiter::Nul), ["a\r\nb", "c\nd"]);
+
+        let mut reader = "x\0y".as_bytes();
+        let mut buf = String::new();
+        assert_eq!(Delimiter::Nul.read_record(&mut reader, &mut buf).unwrap(), 2);
+        assert_eq!(buf, "x\0");
+        assert_eq!(Delimiter::Nul.read_record(&mut reader, &mut buf).unwrap(), 1);
+        assert_eq!(buf, "x\0y");
+
+        let mut reader: &[u8] = b"\xff\0";
+        let err = Delimiter::Nul.read_record(&mut reader, &mut buf).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_read_file_list() {
+        let path = std::env::temp_dir().join(format!("file-list-{}", std::process::id()));
+        let list = path.to_str().unwrap();
+        let read = |contents: &str| {
+            std::fs::write(&path, contents).unwrap();
+            read_file_list(list)
+        };
+
+        assert_eq!(read("a.txt\nb c.txt\n").unwrap(), ["a.txt", "b c.txt"]);
+        assert_eq!(read("a\nb\0c\0").unwrap(), ["a\nb", "c"]);
+        assert_eq!(read("").unwrap(), Vec::<String>::new());
+        assert_eq!(read("-\n").unwrap(), ["-"]);
+        let err = read("a\n\nb\n").unwrap_err();
+        assert_eq!(err.to_string(), format!("{list}: invalid zero-length file name"));
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(read_file_list("/path/does/not/exist"), Err(CoreError::Io { .. })));
+    }
+
+    #[test]
+    fn test_color_choice() {
+        use ColorChoice::*;
+
+        assert!(Auto.enabled_for(None, true));
+        assert!(Auto.enabled_for(Some(OsStr::new("")), true));
+        assert!(!Auto.enabled_for(None, false));
+        assert!(!Auto.enabled_for(Some(OsStr::new("1")), true));
+
+        assert!(Always.enabled_for(Some(OsStr::new("1")), false));
+        assert!(!Never.enabled_for(None, true));
+    }
+}
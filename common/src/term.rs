@@ -0,0 +1,118 @@
+//! The size of the terminal, and its keys one at a time, for the tools that
+//! fill the screen rather than stream lines, like watchr and morer.
+
+use std::env;
+#[cfg(unix)]
+use std::io;
+
+/// The escape codes that clear the screen and go to its top left corner.
+pub const CLEAR: &str = "\x1b[H\x1b[2J";
+
+/// How many characters fit across the terminal, and how many lines down.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Size {
+    pub columns: usize,
+    pub rows: usize,
+}
+
+/// The size of the terminal that stdout is, or if it isn't one, what the
+/// COLUMNS and LINES variables say, as for a shell's `$COLUMNS`.
+pub fn size() -> Option<Size> {
+    let var = |name| env::var(name).ok();
+    window_size().or_else(|| from_vars(var("COLUMNS").as_deref(), var("LINES").as_deref()))
+}
+
+/// The size in `columns` and `lines`, if both are positive numbers.
+fn from_vars(columns: Option<&str>, lines: Option<&str>) -> Option<Size> {
+    let parse = |n: Option<&str>| n?.trim().parse().ok().filter(|&n| n > 0);
+    Some(Size { columns: parse(columns)?, rows: parse(lines)? })
+}
+
+#[cfg(unix)]
+fn window_size() -> Option<Size> {
+    // SAFETY: winsize is plain numbers, for which zero is valid
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    // SAFETY: TIOCGWINSZ only fills in `size`
+    if unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } != 0 {
+        return None;
+    }
+    // Some terminals, like a serial console, don't know their size
+    match (size.ws_col, size.ws_row) {
+        (0, _) | (_, 0) => None,
+        (columns, rows) => Some(Size { columns: columns.into(), rows: rows.into() }),
+    }
+}
+
+#[cfg(not(unix))]
+fn window_size() -> Option<Size> {
+    None
+}
+
+/// The terminal on stderr, with line editing, echo and signals off so each
+/// key arrives as it is pressed, ^C included, until this is dropped. Pagers
+/// read keys from stderr because stdin may be what they are showing.
+#[cfg(unix)]
+pub struct Keys {
+    saved: libc::termios,
+}
+
+#[cfg(unix)]
+impl Keys {
+    pub fn new() -> io::Result<Self> {
+        // SAFETY: termios is plain numbers, for which zero is valid
+        let mut saved: libc::termios = unsafe { std::mem::zeroed() };
+        // SAFETY: tcgetattr only fills in `saved`
+        if unsafe { libc::tcgetattr(libc::STDERR_FILENO, &mut saved) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut raw = saved;
+        raw.c_lflag &= !(libc::ICANON | libc::ECHO | libc::ISIG);
+        raw.c_cc[libc::VMIN] = 1;
+        raw.c_cc[libc::VTIME] = 0;
+        // SAFETY: as for tcgetattr, with a termios it filled in
+        if unsafe { libc::tcsetattr(libc::STDERR_FILENO, libc::TCSANOW, &raw) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Keys { saved })
+    }
+
+    /// The next key's byte, or None once the terminal is gone.
+    pub fn read(&mut self) -> io::Result<Option<u8>> {
+        let mut key = 0u8;
+        loop {
+            // SAFETY: read writes at most the one byte of `key`
+            match unsafe { libc::read(libc::STDERR_FILENO, (&mut key as *mut u8).cast(), 1) } {
+                1 => return Ok(Some(key)),
+                0 => return Ok(None),
+                _ => {
+                    let e = io::Error::last_os_error();
+                    if e.kind() != io::ErrorKind::Interrupted {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Keys {
+    fn drop(&mut self) {
+        // SAFETY: `saved` is the termios tcgetattr gave in new
+        unsafe { libc::tcsetattr(libc::STDERR_FILENO, libc::TCSANOW, &self.saved) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_vars, Size};
+
+    #[test]
+    fn test_from_vars() {
+        assert_eq!(from_vars(Some("80"), Some("24")), Some(Size { columns: 80, rows: 24 }));
+        assert_eq!(from_vars(Some(" 132 "), Some("50")), Some(Size { columns: 132, rows: 50 }));
+        assert_eq!(from_vars(Some("80"), None), None);
+        assert_eq!(from_vars(Some("0"), Some("24")), None);
+        assert_eq!(from_vars(Some("wide"), Some("24")), None);
+    }
+}
@@ -0,0 +1,236 @@
+//! Numbers with unit suffixes, like 1.5K and 10M, in powers of 1000 or
+//! 1024: what dur -h prints, and what numfmtr reads and writes.
+
+use clap::ValueEnum;
+
+/// The units after K, each the base times the one before
+const UNITS: [char; 8] = ['K', 'M', 'G', 'T', 'P', 'E', 'Z', 'Y'];
+
+/// What a unit is a power of, and how it is written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Scale {
+    /// Powers of 1000: K, M, G, ...
+    Si,
+    /// Powers of 1024: K, M, G, ...
+    Iec,
+    /// Powers of 1024, written Ki, Mi, Gi, ...
+    #[value(name = "iec-i")]
+    IecI,
+}
+
+impl Scale {
+    pub fn base(self) -> f64 {
+        match self {
+            Scale::Si => 1000.0,
+            Scale::Iec | Scale::IecI => 1024.0,
+        }
+    }
+
+    /// The largest number with a unit, past which the units run out.
+    pub fn max(self) -> f64 {
+        self.base().powi(UNITS.len() as i32 + 1)
+    }
+
+    fn suffix(self, power: usize) -> String {
+        match self {
+            Scale::IecI => format!("{}i", UNITS[power - 1]),
+            _ => UNITS[power - 1].to_string(),
+        }
+    }
+}
+
+/// Which way to round what doesn't fit in the digits printed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Round {
+    /// Toward +infinity
+    Up,
+    /// Toward -infinity
+    Down,
+    /// Away from zero
+    #[default]
+    FromZero,
+    /// Toward zero
+    TowardsZero,
+    /// To the nearest, half away from zero
+    Nearest,
+}
+
+impl Round {
+    pub fn round(self, value: f64) -> f64 {
+        match self {
+            Round::Up => value.ceil(),
+            Round::Down => value.floor(),
+            Round::FromZero if value < 0.0 => value.floor(),
+            Round::FromZero => value.ceil(),
+            Round::TowardsZero => value.trunc(),
+            Round::Nearest => value.round(),
+        }
+    }
+}
+
+/// `value` in the largest unit of `scale` that it is at least one of, as
+/// GNU du -h and numfmt --to print it: with one decimal below 10, e.g.
+/// 1.5K, 10K, 2.0G. Less than one K is a whole number with no unit.
+/// Values past the last unit are printed in it, however many there are.
+pub fn to_human(value: f64, scale: Scale, round: Round) -> String {
+    let base = scale.base();
+    let whole = round.round(value);
+    if whole.abs() < base {
+        // Adding zero makes -0 plain 0
+        return format!("{}", whole + 0.0);
+    }
+    let mut divisor = 1.0;
+    for power in 1..=UNITS.len() {
+        divisor *= base;
+        let scaled = value / divisor;
+        let tenths = round.round(scaled * 10.0);
+        if tenths.abs() < 100.0 {
+            let sign = if tenths < 0.0 { "-" } else { "" };
+            let tenths = tenths.abs() as u64;
+            return format!("{sign}{}.{}{}", tenths / 10, tenths % 10, scale.suffix(power));
+        }
+        let whole = round.round(scaled);
+        // Rounding may make it the base of this unit, so 1.0 of the next
+        if whole.abs() < base || power == UNITS.len() {
+            return format!("{whole}{}", scale.suffix(power));
+        }
+    }
+    unreachable!("the last unit takes any value")
+}
+
+/// A number's unit, if it may have one, as numfmt --from reads them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum FromUnit {
+    /// No unit is allowed
+    #[default]
+    None,
+    /// K, M, ... for powers of 1000, and Ki, Mi, ... for powers of 1024
+    Auto,
+    /// K, M, ... for powers of 1000
+    Si,
+    /// K, M, ... for powers of 1024
+    Iec,
+    /// Ki, Mi, ... for powers of 1024
+    #[value(name = "iec-i")]
+    IecI,
+}
+
+/// The number `text` stands for, like `12`, `-0.5` or with `from` allowing
+/// it, `1.5M`. The error says what is wrong, as GNU numfmt does.
+pub fn parse_human(text: &str, from: FromUnit) -> Result<f64, String> {
+    let digits = text.strip_prefix('-').unwrap_or(text);
+    let int_len = digits.bytes().take_while(u8::is_ascii_digit).count();
+    let frac_len = match digits[int_len..].strip_prefix('.') {
+        Some(frac) => 1 + frac.bytes().take_while(u8::is_ascii_digit).count(),
+        None => 0,
+    };
+    let number_len = text.len() - digits.len() + int_len + frac_len;
+    // Digits on at least one side of the point, and after it if there is one
+    if int_len + frac_len == 0 || frac_len == 1 {
+        return Err(format!("invalid number: '{text}'"));
+    }
+    let (number, suffix) = text.split_at(number_len);
+    let value: f64 = number.parse().map_err(|_| format!("invalid number: '{text}'"))?;
+
+    let mut rest = suffix.chars();
+    let Some(unit) = rest.next() else {
+        return Ok(value);
+    };
+    let Some(power) = UNITS.iter().position(|&u| u == unit) else {
+        return Err(format!("invalid suffix in input: '{text}'"));
+    };
+    let binary = rest.as_str().starts_with('i');
+    if binary {
+        rest.next();
+    }
+    let base = match (from, binary) {
+        (FromUnit::None, _) => {
+            return Err(format!("rejecting suffix in input: '{text}' (consider using --from)"))
+        }
+        (FromUnit::Si | FromUnit::Iec, true) => {
+            return Err(format!("invalid suffix in input '{text}': 'i'"))
+        }
+        (FromUnit::IecI, false) => {
+            return Err(format!("missing 'i' suffix in input: '{text}' (e.g Ki/Mi/Gi)"))
+        }
+        (FromUnit::Si | FromUnit::Auto, false) => 1000.0,
+        _ => 1024.0,
+    };
+    if let Some(extra) = rest.next() {
+        return Err(format!("invalid suffix in input '{text}': '{extra}'"));
+    }
+    Ok(value * f64::powi(base, power as i32 + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_human, to_human, FromUnit, Round, Scale};
+
+    #[test]
+    fn test_to_human() {
+        let iec = |value: f64| to_human(value, Scale::Iec, Round::FromZero);
+        assert_eq!(iec(0.0), "0");
+        assert_eq!(iec(1023.0), "1023");
+        assert_eq!(iec(1024.0), "1.0K");
+        assert_eq!(iec(1025.0), "1.1K");
+        assert_eq!(iec(1536.0), "1.5K");
+        assert_eq!(iec(10.0 * 1024.0), "10K");
+        assert_eq!(iec(10.0 * 1024.0 + 1.0), "11K");
+        assert_eq!(iec(1024.0 * 1024.0 - 1.0), "1.0M");
+        assert_eq!(iec(5.0 * (1u64 << 30) as f64), "5.0G");
+        assert_eq!(iec(u64::MAX as f64), "16E");
+        assert_eq!(iec(-1536.0), "-1.5K");
+        assert_eq!(iec(1e26), "83Y");
+        // Rounding to a whole number can make it a K
+        assert_eq!(iec(1023.4), "1.0K");
+        assert_eq!(iec(1.5), "2");
+
+        let si = |value: f64| to_human(value, Scale::Si, Round::FromZero);
+        assert_eq!(si(999.0), "999");
+        assert_eq!(si(1000.0), "1.0K");
+        assert_eq!(si(9999.0), "10K");
+        assert_eq!(si(999_999.0), "1.0M");
+        assert_eq!(to_human(2048.0, Scale::IecI, Round::FromZero), "2.0Ki");
+    }
+
+    #[test]
+    fn test_round() {
+        let iec = |value: f64, round| to_human(value, Scale::Iec, round);
+        assert_eq!(iec(1025.0, Round::Down), "1.0K");
+        assert_eq!(iec(1075.0, Round::Nearest), "1.0K");
+        assert_eq!(iec(1076.0, Round::Nearest), "1.1K");
+        assert_eq!(iec(-1025.0, Round::Up), "-1.0K");
+        assert_eq!(iec(-1025.0, Round::Down), "-1.1K");
+        assert_eq!(iec(-1536.0, Round::TowardsZero), "-1.5K");
+        assert_eq!(iec(1.9, Round::Down), "1");
+        assert_eq!(iec(-0.4, Round::TowardsZero), "0");
+    }
+
+    #[test]
+    fn test_parse_human() {
+        assert_eq!(parse_human("12", FromUnit::None), Ok(12.0));
+        assert_eq!(parse_human("-0.5", FromUnit::None), Ok(-0.5));
+        assert_eq!(parse_human(".5", FromUnit::None), Ok(0.5));
+        assert_eq!(parse_human("1K", FromUnit::Si), Ok(1000.0));
+        assert_eq!(parse_human("1.5M", FromUnit::Iec), Ok(1_572_864.0));
+        assert_eq!(parse_human("1Ki", FromUnit::IecI), Ok(1024.0));
+        assert_eq!(parse_human("1K", FromUnit::Auto), Ok(1000.0));
+        assert_eq!(parse_human("1Ki", FromUnit::Auto), Ok(1024.0));
+        assert_eq!(parse_human("-1G", FromUnit::Si), Ok(-1e9));
+
+        let err = |text, from| parse_human(text, from).unwrap_err();
+        for text in ["", "x", "-", "+5", "1.", ".", "1.K", "K"] {
+            assert_eq!(err(text, FromUnit::Auto), format!("invalid number: '{text}'"));
+        }
+        assert_eq!(err("1e3x", FromUnit::Auto), "invalid suffix in input: '1e3x'");
+        assert_eq!(err("12abc", FromUnit::Si), "invalid suffix in input: '12abc'");
+        assert_eq!(err("1k", FromUnit::Si), "invalid suffix in input: '1k'");
+        assert_eq!(err("1KB", FromUnit::Si), "invalid suffix in input '1KB': 'B'");
+        assert_eq!(err("1Ki", FromUnit::Iec), "invalid suffix in input '1Ki': 'i'");
+        assert_eq!(err("1K", FromUnit::IecI), "missing 'i' suffix in input: '1K' (e.g Ki/Mi/Gi)");
+        assert_eq!(
+            err("1K", FromUnit::None),
+            "rejecting suffix in input: '1K' (consider using --from)"
+        );
+    }
+}
@@ -1,12 +1,17 @@
 use std::{
-    cmp::Ordering::*, error::Error, fs::File, 
-    io::{self, BufRead, BufReader}
+    cmp::Ordering::*,
+    io::{self, Write}
 };
 use Col::*;
+use OrderCheck::*;
 
-use clap::{Arg, App};
+use clap::Parser;
+use serde::Serialize;
 
-type MyResult<T> = Result<T, Box<dyn Error>>;
+use coreutils_common::{
+    i18n::{tr, tr_fmt},
+    open, parse_args, write_json, CoreError, Delimiter, DelimiterArgs, MyResult, Output,
+};
 
 #[derive(Debug)]
 pub struct Config {
@@ -17,170 +22,495 @@ pub struct Config {
     show_col3: bool,
     insensitive: bool,
     delimiter: String,
+    total: bool,
+    check_order: OrderCheck,
+    align: bool,
+    align_width: Option<usize>,
+    json: bool,
+    quiet: bool,
+    record_delimiter: Delimiter,
 }
 
-fn open(filename: &str) -> MyResult<Box<dyn BufRead>> {
-    match filename {
-        "-" => Ok(Box::new(BufReader::new(io::stdin()))),
-        _ => Ok(Box::new(BufReader::new(File::open(filename)
-            .map_err(|e| format!("{filename}: {e}"))?)))
-    }
+#[derive(Debug, PartialEq)]
+enum OrderCheck {
+    Warn,
+    Fatal,
+    Skip,
+}
+
+
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust comm")]
+struct Args {
+    /// Input file 1
+    #[arg(value_name = "FILE1")]
+    file1: String,
+
+    /// Input file 2
+    #[arg(value_name = "FILE2")]
+    file2: String,
+
+    /// Suppress printing of column 1
+    #[arg(short = '1')]
+    suppress_col1: bool,
+
+    /// Suppress printing of column 2
+    #[arg(short = '2')]
+    suppress_col2: bool,
+
+    /// Suppress printing of column 3
+    #[arg(short = '3')]
+    suppress_col3: bool,
+
+    /// Case-insensitive comparison of lines
+    #[arg(short, long)]
+    insensitive: bool,
+
+    /// Output delimiter
+    #[arg(short, long = "output-delimiter", value_name = "DELIM", default_value = "\t")]
+    delimiter: String,
+
+    /// Fail if the input is not correctly sorted
+    #[arg(long)]
+    check_order: bool,
+
+    /// Do not check that the input is correctly sorted
+    #[arg(long, conflicts_with = "check_order")]
+    nocheck_order: bool,
+
+    /// Pad columns to WIDTH (default: widest line) instead of delimiting them
+    #[arg(long, value_name = "WIDTH", num_args = 0..=1, require_equals = true)]
+    align: Option<Option<String>>,
+
+    /// Print the columns as a JSON object
+    #[arg(long, conflicts_with_all = ["align", "total"])]
+    json: bool,
+
+    /// Print nothing; exit 0 if no line is unique to either file, 1 otherwise
+    #[arg(short, long, alias = "status", conflicts_with_all = ["align", "json", "total"])]
+    quiet: bool,
+
+    /// Output a summary of the line counts per column
+    #[arg(long)]
+    total: bool,
+
+    #[command(flatten)]
+    records: DelimiterArgs,
 }
 
 pub fn get_args() -> MyResult<Config> {
-    let matches = App::new("commr")
-        .about("Rust comm")
-        .version("0.1.0")
-        .author("OFFBLACK")
-        .arg(
-            Arg::with_name("file1")
-                .value_name("FILE1")
-                .help("Input file 1")
-                .required(true)
-        )
-        .arg(
-            Arg::with_name("file2")
-                .value_name("FILE2")
-                .help("Input file 2")
-                .required(true)
-        )
-        .arg(
-            Arg::with_name("suppress_col1")
-                .short("1")
-                .help("Suppress printing of column 1")
-        )
-        .arg(
-            Arg::with_name("suppress_col2")
-                .short("2")
-                .help("Suppress printing of column 2")
-        )
-        .arg(
-            Arg::with_name("suppress_col3")
-                .short("3")
-                .help("Suppress printing of column 3")
-        )
-        .arg(
-            Arg::with_name("insensitive")
-                .short("i")
-                .long("insensitive")
-                .help("Case-insensitive comparison of lines") 
-        )
-        .arg(
-            Arg::with_name("delimiter")
-                .short("d")
-                .long("output-delimiter")
-                .help("Output delimiter")
-                .value_name("DELIM")
-                .default_value("\t")
-                .takes_value(true)
-        )
-        .get_matches();
+    let args: Args = parse_args();
+
+    let align_width = args
+        .align
+        .as_ref()
+        .and_then(Option::as_deref)
+        .map(|val| {
+            val.parse::<usize>()
+                .map_err(|_| CoreError::Parse(format!("illegal align width -- {val}")))
+        })
+        .transpose()?;
 
     Ok(Config {
-        file1: matches.value_of("file1").unwrap().to_string(),
-        file2: matches.value_of("file2").unwrap().to_string(),
-        show_col1: !matches.is_present("suppress_col1"),
-        show_col2: !matches.is_present("suppress_col2"),
-        show_col3: !matches.is_present("suppress_col3"),
-        insensitive: matches.is_present("insensitive"),
-        delimiter: matches.value_of("delimiter").unwrap().to_string(),
+        file1: args.file1,
+        file2: args.file2,
+        show_col1: !args.suppress_col1,
+        show_col2: !args.suppress_col2,
+        show_col3: !args.suppress_col3,
+        insensitive: args.insensitive,
+        delimiter: args.delimiter,
+        total: args.total,
+        check_order: if args.check_order {
+            Fatal
+        } else if args.nocheck_order || args.quiet {
+            Skip
+        } else {
+            Warn
+        },
+        align: args.align.is_some(),
+        align_width,
+        json: args.json,
+        quiet: args.quiet,
+        record_delimiter: args.records.delimiter(),
     })
 }
 
-enum Col<'a> {
-    Col1(&'a str),
-    Col2(&'a str),
-    Col3(&'a str),
+/// A line of input, plus its lowercased form when comparing with -i.
+struct Line {
+    text: String,
+    folded: Option<String>,
 }
 
-pub fn run(config: Config) -> MyResult<()> {
-    if &config.file1 == "-" && &config.file2 == "-" {
-        return Err("Both input files cannot be STDIN (\"-\")".into())
+impl Line {
+    /// The key the line is sorted and compared by.
+    fn key(&self) -> &str {
+        self.folded.as_deref().unwrap_or(&self.text)
     }
+}
 
-    let case = |line: String| {
-        if config.insensitive {
-            line.to_lowercase()
-        } else {
-            line
+/// Advances `lines` to the next `line`, checking that it does not sort before
+/// the one it replaces. Disorder is reported once per file, after `print` has
+/// flushed what came before, and is an error with --check-order. So is a
+/// line that can't be read.
+fn next_line(
+    lines: &mut impl Iterator<Item = MyResult<Line>>,
+    line: &mut Option<Line>,
+    file_num: usize,
+    check_order: &OrderCheck,
+    disordered: &mut bool,
+    print: &mut impl FnMut(Option<Col>) -> io::Result<()>,
+) -> MyResult<()> {
+    let next = match lines.next().transpose() {
+        Ok(next) => next,
+        Err(e) => {
+            print(None)?;
+            return Err(e);
         }
     };
-
-    let mut lines1 = open(&config.file1)?
-        .lines()
-        .filter_map(Result::ok)
-        .map(case);
-
-    let mut lines2 = open(&config.file2)?
-        .lines()
-        .filter_map(Result::ok)
-        .map(case);
-    
-    let mut line1 = lines1.next();
-    let mut line2 = lines2.next();
-
-    let print = |col: Col| {
-        let mut cols = Vec::new();
-        match col {
-            Col1(val) => {
-                if config.show_col1 {
-                    cols.push(val);
-                }
-            },
-            Col2(val) => {
-                if config.show_col2 {
-                    if config.show_col1 {
-                        cols.push("")
-                    }
-                    cols.push(val);
-                }
-            },
-            Col3(val) => {
-                if config.show_col3 {
-                    if config.show_col1 {
-                        cols.push("");
-                    }
-                    if config.show_col2 {
-                        cols.push("");
-                    }
-                    cols.push(val);
-                }
+    let prev = std::mem::replace(line, next);
+    if let (Some(prev), Some(line)) = (&prev, &line) {
+        if *check_order != Skip && line.key() < prev.key() && !*disordered {
+            let msg = format!(
+                "commr: {}",
+                tr_fmt("file {num} is not in sorted order", &[("num", &file_num)])
+            );
+            if *check_order == Fatal {
+                return Err(CoreError::Input(msg));
             }
+            print(None)?;
+            eprintln!("{msg}");
+            *disordered = true;
         }
-        
-        if !cols.is_empty() {
-            println!("{}", cols.join(&config.delimiter));
+    }
+    Ok(())
+}
+
+/// Pads every column but the last to `width` plus a two-space gutter.
+fn align_row(cols: &[impl AsRef<str>], width: usize) -> String {
+    let mut row = String::new();
+    for (i, col) in cols.iter().enumerate() {
+        if i + 1 < cols.len() {
+            row.push_str(&format!("{:<width$}  ", col.as_ref()));
+        } else {
+            row.push_str(col.as_ref());
         }
-    };
+    }
+    row
+}
 
+enum Col<'a> {
+    Col1(&'a str),
+    Col2(&'a str),
+    Col3(&'a str),
+}
+
+/// The lines of each column shown, for --json.
+#[derive(Debug, Serialize)]
+struct Columns {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    only1: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    only2: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    both: Option<Vec<String>>,
+}
+
+/// How many lines went to each column, and whether either input was found
+/// out of order.
+#[derive(Debug, Default, PartialEq)]
+struct Totals {
+    only1: usize,
+    only2: usize,
+    both: usize,
+    disordered: bool,
+}
+
+/// Walks both inputs in step, passing every line to `print` in the column it
+/// belongs to, or None when what it printed must be flushed before a warning.
+/// With `quiet`, stops at the first line unique to either input.
+fn merge(
+    mut lines1: impl Iterator<Item = MyResult<Line>>,
+    mut lines2: impl Iterator<Item = MyResult<Line>>,
+    check: &OrderCheck,
+    quiet: bool,
+    mut print: impl FnMut(Option<Col>) -> io::Result<()>,
+) -> MyResult<Totals> {
+    let mut line1 = lines1.next().transpose()?;
+    let mut line2 = lines2.next().transpose()?;
+    let mut totals = Totals::default();
+    let (mut disordered1, mut disordered2) = (false, false);
     while line1.is_some() || line2.is_some() {
+        if quiet && totals.only1 + totals.only2 > 0 {
+            break;
+        }
         match (&line1, &line2) {
-            (Some(val1), Some(val2)) => match val1.cmp(val2) {
+            (Some(val1), Some(val2)) => match val1.key().cmp(val2.key()) {
                 Equal => {
-                    print(Col3(val1));
-                    line1 = lines1.next();
-                    line2 = lines2.next();
+                    print(Some(Col3(&val1.text)))?;
+                    totals.both += 1;
+                    next_line(&mut lines1, &mut line1, 1, check, &mut disordered1, &mut print)?;
+                    next_line(&mut lines2, &mut line2, 2, check, &mut disordered2, &mut print)?;
                 },
                 Less => {
-                    print(Col1(val1));
-                    line1 = lines1.next();
+                    print(Some(Col1(&val1.text)))?;
+                    totals.only1 += 1;
+                    next_line(&mut lines1, &mut line1, 1, check, &mut disordered1, &mut print)?;
                 },
                 Greater => {
-                    print(Col2(val2));
-                    line2 = lines2.next();
+                    print(Some(Col2(&val2.text)))?;
+                    totals.only2 += 1;
+                    next_line(&mut lines2, &mut line2, 2, check, &mut disordered2, &mut print)?;
                 },
             },
             (Some(val1), None) => {
-                print(Col1(val1));
-                line1 = lines1.next();
+                print(Some(Col1(&val1.text)))?;
+                totals.only1 += 1;
+                next_line(&mut lines1, &mut line1, 1, check, &mut disordered1, &mut print)?;
             },
             (None, Some(val2)) => {
-                print(Col2(val2));
-                line2 = lines2.next();
+                print(Some(Col2(&val2.text)))?;
+                totals.only2 += 1;
+                next_line(&mut lines2, &mut line2, 2, check, &mut disordered2, &mut print)?;
             }
             _ => {},
         }
     }
+    totals.disordered = disordered1 || disordered2;
+    Ok(totals)
+}
 
-    Ok(())
+/// Compares the files and returns the exit status, which is only nonzero
+/// when --quiet finds a line unique to either file.
+pub fn run(config: Config) -> MyResult<i32> {
+    if &config.file1 == "-" && &config.file2 == "-" {
+        return Err(CoreError::Usage(tr("Both input files cannot be STDIN (\"-\")").to_string()))
+    }
+
+    let case = |text: String| Line {
+        folded: if config.insensitive {
+            Some(text.to_lowercase())
+        } else {
+            None
+        },
+        text,
+    };
+
+    // A read error, or a line that isn't UTF-8, ends the comparison
+    let records = config.record_delimiter;
+    let lines = |filename: &str| -> MyResult<_> {
+        let filename = filename.to_string();
+        Ok(records
+            .records(open(&filename)?)
+            .map(move |record| record.map(case).map_err(|e| CoreError::io(&filename, e))))
+    };
+    let lines1 = lines(&config.file1)?;
+    let lines2 = lines(&config.file2)?;
+
+    let mut out = Output::stdout().delimiter(records);
+    let mut rows = Vec::new();
+    let (mut only1, mut only2, mut both) = (Vec::new(), Vec::new(), Vec::new());
+    let mut print = |col: Option<Col>| -> io::Result<()> {
+        let Some(col) = col else {
+            return out.flush();
+        };
+        if config.quiet {
+            return Ok(());
+        }
+        if config.json {
+            match col {
+                Col1(val) => only1.push(val.to_string()),
+                Col2(val) => only2.push(val.to_string()),
+                Col3(val) => both.push(val.to_string()),
+            }
+            return Ok(());
+        }
+
+        // The number of empty columns to the left of the value
+        let (val, show, skip) = match col {
+            Col1(val) => (val, config.show_col1, 0),
+            Col2(val) => (val, config.show_col2, config.show_col1 as usize),
+            Col3(val) => (
+                val,
+                config.show_col3,
+                config.show_col1 as usize + config.show_col2 as usize,
+            ),
+        };
+        if !show {
+            return Ok(());
+        }
+
+        if config.align {
+            let mut cols = vec![""; skip];
+            cols.push(val);
+            match config.align_width {
+                Some(width) => out.write_record(align_row(&cols, width))?,
+                // The width isn't known until every row has been seen
+                None => rows.push(cols.iter().map(|col| col.to_string()).collect::<Vec<_>>()),
+            }
+        } else {
+            for _ in 0..skip {
+                out.write_all(config.delimiter.as_bytes())?;
+            }
+            out.write_record(val)?;
+        }
+        Ok(())
+    };
+
+    let totals = merge(lines1, lines2, &config.check_order, config.quiet, &mut print)?;
+
+    if config.json {
+        let columns = Columns {
+            only1: config.show_col1.then_some(only1),
+            only2: config.show_col2.then_some(only2),
+            both: config.show_col3.then_some(both),
+        };
+        write_json(&mut out, &columns)?;
+    }
+
+    let width = config.align_width.unwrap_or_else(|| {
+        rows.iter()
+            .flatten()
+            .map(|col| col.chars().count())
+            .max()
+            .unwrap_or(0)
+    });
+    for row in &rows {
+        out.write_record(align_row(row, width))?;
+    }
+
+    if config.total {
+        let counts = [totals.only1.to_string(), totals.only2.to_string(), totals.both.to_string()];
+        let cols = [&counts[0], &counts[1], &counts[2], "total"];
+        if config.align {
+            out.write_record(align_row(&cols, width))?;
+        } else {
+            out.write_record(cols.join(&config.delimiter))?;
+        }
+    }
+
+    out.flush()?;
+    if totals.disordered {
+        return Err(CoreError::Input(format!("commr: {}", tr("input is not in sorted order"))));
+    }
+
+    if config.quiet && totals.only1 + totals.only2 > 0 {
+        return Ok(1);
+    }
+
+    Ok(0)
+}
+
+/// Entry points for the cargo-fuzz targets in `../fuzz`, which can't reach
+/// private items. None of them may panic, whatever the input.
+#[cfg(feature = "fuzzing")]
+#[doc(hidden)]
+pub mod fuzzing {
+    use super::{merge, Line, OrderCheck::*};
+
+    pub fn merge_lines(file1: &str, file2: &str, check_order: bool, quiet: bool) {
+        let lines = |file: &str| {
+            file.lines()
+                .map(|text| Ok(Line { folded: Some(text.to_lowercase()), text: text.to_string() }))
+                .collect::<Vec<_>>()
+        };
+        let check = if check_order { Fatal } else { Skip };
+        let _ = merge(
+            lines(file1).into_iter(),
+            lines(file2).into_iter(),
+            &check,
+            quiet,
+            |_| Ok(()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{merge, Col::*, Line, MyResult, OrderCheck, OrderCheck::*, Totals};
+    use proptest::prelude::*;
+
+    /// Merges the inputs and returns the totals and every printed line with
+    /// its column number.
+    fn run_merge(
+        file1: &[String],
+        file2: &[String],
+        check: OrderCheck,
+        quiet: bool,
+    ) -> MyResult<(Totals, Vec<(u8, String)>)> {
+        let lines = |file: &[String]| {
+            file.iter()
+                .map(|text| Ok(Line { text: text.clone(), folded: None }))
+                .collect::<Vec<_>>()
+        };
+        let mut printed = Vec::new();
+        let totals = merge(
+            lines(file1).into_iter(),
+            lines(file2).into_iter(),
+            &check,
+            quiet,
+            |col| {
+                let Some(col) = col else {
+                    return Ok(());
+                };
+                printed.push(match col {
+                    Col1(val) => (1, val.to_string()),
+                    Col2(val) => (2, val.to_string()),
+                    Col3(val) => (3, val.to_string()),
+                });
+                Ok(())
+            },
+        )?;
+        Ok((totals, printed))
+    }
+
+    /// The lines printed in either of the given columns, in order.
+    fn in_cols(printed: &[(u8, String)], cols: [u8; 2]) -> Vec<String> {
+        printed
+            .iter()
+            .filter(|(col, _)| cols.contains(col))
+            .map(|(_, val)| val.clone())
+            .collect()
+    }
+
+    fn file() -> impl Strategy<Value = Vec<String>> {
+        prop::collection::vec("[abc]{0,3}", 0..12)
+    }
+
+    proptest! {
+        #[test]
+        fn merge_prints_every_line_once(file1 in file(), file2 in file()) {
+            // Holds whether or not the inputs are sorted
+            let (totals, printed) = run_merge(&file1, &file2, Skip, false).unwrap();
+            prop_assert_eq!(in_cols(&printed, [1, 3]), file1.clone());
+            prop_assert_eq!(in_cols(&printed, [2, 3]), file2.clone());
+            prop_assert_eq!(totals.only1 + totals.both, file1.len());
+            prop_assert_eq!(totals.only2 + totals.both, file2.len());
+        }
+
+        #[test]
+        fn merge_of_sorted_files_is_sorted(mut file1 in file(), mut file2 in file()) {
+            file1.sort();
+            file2.sort();
+            let (totals, printed) = run_merge(&file1, &file2, Fatal, false).unwrap();
+            prop_assert!(!totals.disordered);
+            prop_assert!(printed.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        }
+
+        #[test]
+        fn merge_fails_on_unsorted_file(file1 in file(), file2 in file()) {
+            let sorted = |file: &[String]| file.windows(2).all(|pair| pair[0] <= pair[1]);
+            if run_merge(&file1, &file2, Fatal, false).is_err() {
+                prop_assert!(!sorted(&file1) || !sorted(&file2));
+            }
+        }
+
+        #[test]
+        fn quiet_merge_stops_at_first_difference(mut file1 in file(), mut file2 in file()) {
+            file1.sort();
+            file2.sort();
+            let (totals, _) = run_merge(&file1, &file2, Skip, true).unwrap();
+            prop_assert!(totals.only1 + totals.only2 <= 1);
+            prop_assert_eq!(totals.only1 + totals.only2 == 0, file1 == file2);
+        }
+    }
 }
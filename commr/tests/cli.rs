@@ -1,13 +1,19 @@
 use assert_cmd::Command;
 use predicates::prelude::*;
 use rand::{distributions::Alphanumeric, Rng};
-use std::fs;
+use std::{
+    fs,
+    io::{Read, Seek, SeekFrom},
+};
 
 const PRG: &str = "commr";
 const EMPTY: &str = "tests/inputs/empty.txt";
 const FILE1: &str = "tests/inputs/file1.txt";
 const FILE2: &str = "tests/inputs/file2.txt";
 const BLANK: &str = "tests/inputs/blank.txt";
+const FILE3: &str = "tests/inputs/file3.txt";
+const CITIES1: &str = "tests/inputs/cities1.txt";
+const CITIES2: &str = "tests/inputs/cities2.txt";
 
 type TestResult = Result<(), Box<dyn std::error::Error>>;
 
@@ -17,7 +23,7 @@ fn dies_no_args() -> TestResult {
     Command::cargo_bin(PRG)?
         .assert()
         .failure()
-        .stderr(predicate::str::contains("USAGE"));
+        .stderr(predicate::str::contains("Usage"));
     Ok(())
 }
 
@@ -42,7 +48,7 @@ fn dies_bad_file1() -> TestResult {
     let bad = gen_bad_file();
     let expected = format!("{}: .* [(]os error 2[)]", bad);
     Command::cargo_bin(PRG)?
-        .args(&[&bad, FILE1])
+        .args([&bad, FILE1])
         .assert()
         .failure()
         .stderr(predicate::str::is_match(expected)?);
@@ -55,7 +61,7 @@ fn dies_bad_file2() -> TestResult {
     let bad = gen_bad_file();
     let expected = format!("{}: .* [(]os error 2[)]", bad);
     Command::cargo_bin(PRG)?
-        .args(&[FILE1, &bad])
+        .args([FILE1, &bad])
         .assert()
         .failure()
         .stderr(predicate::str::is_match(expected)?);
@@ -67,7 +73,7 @@ fn dies_bad_file2() -> TestResult {
 fn dies_both_stdin() -> TestResult {
     let expected = "Both input files cannot be STDIN (\"-\")";
     Command::cargo_bin(PRG)?
-        .args(&["-", "-"])
+        .args(["-", "-"])
         .assert()
         .failure()
         .stderr(predicate::str::contains(expected));
@@ -340,8 +346,282 @@ fn blank_file1() -> TestResult {
     run(&[BLANK, FILE1], "tests/expected/blank_file1.out")
 }
 
-//// --------------------------------------------------
+// --------------------------------------------------
 //#[test]
 //fn file1_blanks() -> TestResult {
 //    run(&[FILE1, BLANKS], "tests/expected/file1_blanks.out")
 //}
+
+// --------------------------------------------------
+#[test]
+fn file1_file2_total() -> TestResult {
+    run(
+        &["--total", FILE1, FILE2],
+        "tests/expected/file1_file2.total.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn file1_file2_total_delim() -> TestResult {
+    run(
+        &["--total", "-d", ":", FILE1, FILE2],
+        "tests/expected/file1_file2.total.delim.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn file1_file3_12_total() -> TestResult {
+    run(
+        &["--total", "-12", FILE1, FILE3],
+        "tests/expected/file1_file3.12.total.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn unsorted_warns() -> TestResult {
+    let expected = fs::read_to_string("tests/expected/cities1_cities2.out")?;
+    Command::cargo_bin(PRG)?
+        .args([CITIES1, CITIES2])
+        .assert()
+        .code(1)
+        .stdout(expected)
+        .stderr(
+            "commr: file 1 is not in sorted order\n\
+             commr: file 2 is not in sorted order\n\
+             commr: input is not in sorted order\n",
+        );
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn unsorted_warns_in_order() -> TestResult {
+    // Into one file, the warnings come right after the lines that led to them
+    let out = tempfile::tempfile()?;
+    let status = std::process::Command::new(assert_cmd::cargo::cargo_bin(PRG))
+        .args([CITIES1, CITIES2])
+        .stdout(out.try_clone()?)
+        .stderr(out.try_clone()?)
+        .status()?;
+    assert_eq!(status.code(), Some(1));
+    let mut combined = String::new();
+    (&out).seek(SeekFrom::Start(0))?;
+    (&out).read_to_string(&mut combined)?;
+    assert!(combined.starts_with(
+        "Jackson\n\
+         commr: file 1 is not in sorted order\n\
+         Denton\n\
+         Cincinnati\n\
+         Boston\n\
+         \tSan Francisco\n\
+         commr: file 2 is not in sorted order\n\
+         \tDenver\n"
+    ));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn unsorted_check_order_dies() -> TestResult {
+    let expected = fs::read_to_string("tests/expected/cities2_file1.check.out")?;
+    Command::cargo_bin(PRG)?
+        .args(["--check-order", CITIES2, FILE1])
+        .assert()
+        .code(1)
+        .stdout(expected)
+        .stderr("commr: file 1 is not in sorted order\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn unsorted_nocheck_order() -> TestResult {
+    run(
+        &["--nocheck-order", CITIES1, CITIES2],
+        "tests/expected/cities1_cities2.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn sorted_check_order() -> TestResult {
+    run(
+        &["--check-order", FILE1, FILE2],
+        "tests/expected/file1_file2.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn file1_file2_i_keeps_case() -> TestResult {
+    run(&["-i", FILE1, FILE2], "tests/expected/file1_file2.i.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn file2_file1_i_keeps_case() -> TestResult {
+    run(&["-i", FILE2, FILE1], "tests/expected/file2_file1.i.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn file1_file2_align() -> TestResult {
+    run(
+        &["--align", FILE1, FILE2],
+        "tests/expected/file1_file2.align.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn file1_file2_align_width_total() -> TestResult {
+    run(
+        &["--align=4", "--total", FILE1, FILE2],
+        "tests/expected/file1_file2.align4.total.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn file1_file3_1_align_total() -> TestResult {
+    run(
+        &["-1", "--align", "--total", FILE1, FILE3],
+        "tests/expected/file1_file3.1.align.total.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_align_width() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--align=wide", FILE1, FILE2])
+        .assert()
+        .failure()
+        .stderr("illegal align width -- wide\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn file1_file2_json() -> TestResult {
+    run(&["--json", FILE1, FILE2], "tests/expected/file1_file2.json.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn file1_file2_3_json() -> TestResult {
+    run(
+        &["--json", "-3", FILE1, FILE2],
+        "tests/expected/file1_file2.3.json.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn json_escapes_lines() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--json", "-", EMPTY])
+        .write_stdin("a\tb\nsay \"hi\"\n")
+        .assert()
+        .success()
+        .stdout("{\"only1\":[\"a\\tb\",\"say \\\"hi\\\"\"],\"only2\":[],\"both\":[]}\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn quiet_same() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--quiet", FILE1, FILE1])
+        .assert()
+        .code(0)
+        .stdout("")
+        .stderr("");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn quiet_different() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-q", FILE1, FILE2])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr("");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn status_alias() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--status", FILE1, FILE3])
+        .assert()
+        .code(1)
+        .stdout("");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn quiet_insensitive_same() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-q", "-i", CITIES1, "tests/inputs/cities1_lower.txt"])
+        .assert()
+        .code(0)
+        .stderr("");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_invalid_utf8() -> TestResult {
+    // What came before the bad line is still printed
+    for (args, expected) in [(["-", EMPTY], "a\n"), ([EMPTY, "-"], "\ta\n")] {
+        Command::cargo_bin(PRG)?
+            .args(args)
+            .write_stdin(&b"a\nb\xff\nc\n"[..])
+            .assert()
+            .code(1)
+            .stdout(expected)
+            .stderr("-: stream did not contain valid UTF-8\n");
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn zero_terminated() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-z", "-", "tests/inputs/nul.txt"])
+        .write_stdin("a\0b\0c\0")
+        .assert()
+        .success()
+        .stdout("a\0\t\tb\0c\0\td\0");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn messages_follow_lc_messages() -> TestResult {
+    let bad = gen_bad_file();
+    Command::cargo_bin(PRG)?
+        .env_remove("LC_ALL")
+        .env("LC_MESSAGES", "es_ES.UTF-8")
+        .args([&bad, FILE1])
+        .assert()
+        .failure()
+        .stderr(format!("{bad}: No existe el archivo o el directorio\n"));
+    Command::cargo_bin(PRG)?
+        .env("LC_ALL", "es_ES.UTF-8")
+        .args(["--check-order", "-", FILE1])
+        .write_stdin("b\na\n")
+        .assert()
+        .failure()
+        .stderr("commr: el archivo 1 no está ordenado\n");
+    Ok(())
+}
@@ -0,0 +1,559 @@
+//! Runs each tool and its GNU counterpart over the same fixtures and checks
+//! that stdout, stderr and the exit status agree. A tool's cases are skipped
+//! when the GNU version isn't installed, e.g. on macOS.
+//!
+//! The tools are found next to this test's binary, so run the whole
+//! workspace: `cargo test --workspace`.
+
+use regex::Regex;
+use std::{error::Error, path::PathBuf, process::Command};
+
+type TestResult = Result<(), Box<dyn Error>>;
+
+const FOX: &str = "tests/inputs/fox.txt";
+const SPIDERS: &str = "tests/inputs/spiders.txt";
+const BUSTLE: &str = "tests/inputs/the-bustle.txt";
+const NONPRINTING: &str = "tests/inputs/nonprinting.txt";
+const EMPTY: &str = "tests/inputs/empty.txt";
+const BLANKS: &str = "tests/inputs/blanks.txt";
+const BOOKS: &str = "tests/inputs/books.tsv";
+const CITIES1: &str = "tests/inputs/cities1.txt";
+const CITIES2: &str = "tests/inputs/cities2.txt";
+const T1: &str = "tests/inputs/t1.txt";
+const T2: &str = "tests/inputs/t2.txt";
+const FIELDS: &str = "tests/inputs/fields.txt";
+const TEN: &str = "tests/inputs/ten.txt";
+const NOBODY: &str = "tests/inputs/nobody.txt";
+const TABS: &str = "tests/inputs/tabs.txt";
+const SPACES: &str = "tests/inputs/spaces.txt";
+const BUSTLE_B64: &str = "tests/inputs/the-bustle.b64";
+const GARBAGE: &str = "tests/inputs/garbage.txt";
+const GOOD_SUMS: &str = "tests/inputs/good.sha256";
+const BAD_SUMS: &str = "tests/inputs/bad.md5";
+const MISSING: &str = "tests/inputs/does-not-exist";
+
+// --------------------------------------------------
+/// The GNU tool called `name`, if it's on the PATH.
+fn gnu_tool(name: &str) -> Option<&str> {
+    let out = Command::new(name).arg("--version").output().ok()?;
+    String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .next()
+        .filter(|line| line.contains("GNU"))
+        .map(|_| name)
+}
+
+// --------------------------------------------------
+fn our_tool(name: &str) -> PathBuf {
+    let path = assert_cmd::cargo::cargo_bin(name);
+    assert!(
+        path.is_file(),
+        "{} isn't built; run `cargo test --workspace`",
+        path.display()
+    );
+    path
+}
+
+// --------------------------------------------------
+/// Removes what is expected to differ in error messages: the program name
+/// in front and Rust's "(os error N)" at the end.
+fn normalize_stderr(prg: &str, stderr: &[u8]) -> String {
+    let prefix = Regex::new(&format!(r"(?m)^{}: ", regex::escape(prg))).unwrap();
+    let os_error = Regex::new(r"(?m) \(os error \d+\)$").unwrap();
+    let stderr = String::from_utf8_lossy(stderr);
+    os_error
+        .replace_all(&prefix.replace_all(&stderr, ""), "")
+        .into_owned()
+}
+
+// --------------------------------------------------
+/// Runs `gnu gnu_args` and `ours our_args` and checks they behave the same.
+fn compare_with(gnu: &str, gnu_args: &[&str], ours: &str, our_args: &[&str]) -> TestResult {
+    let run = |mut cmd: Command| cmd.env("LC_ALL", "C").output();
+    let mut gnu_cmd = Command::new(gnu);
+    gnu_cmd.args(gnu_args);
+    let mut our_cmd = Command::new(our_tool(ours));
+    our_cmd.args(our_args);
+    let (expected, actual) = (run(gnu_cmd)?, run(our_cmd)?);
+
+    let context = format!("{gnu} {gnu_args:?} vs {ours} {our_args:?}");
+    assert_eq!(
+        String::from_utf8_lossy(&actual.stdout),
+        String::from_utf8_lossy(&expected.stdout),
+        "stdout differs: {context}"
+    );
+    assert_eq!(
+        normalize_stderr(ours, &actual.stderr),
+        normalize_stderr(gnu, &expected.stderr),
+        "stderr differs: {context}"
+    );
+    assert_eq!(
+        actual.status.code(),
+        expected.status.code(),
+        "exit status differs: {context}"
+    );
+    Ok(())
+}
+
+// --------------------------------------------------
+/// Runs every case with the same arguments for both tools, or skips them
+/// if the GNU tool is missing.
+fn compare(gnu: &str, ours: &str, cases: &[&[&str]]) -> TestResult {
+    let Some(gnu) = gnu_tool(gnu) else {
+        eprintln!("GNU {gnu} not found, skipping");
+        return Ok(());
+    };
+    for args in cases {
+        compare_with(gnu, args, ours, args)?;
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn cat() -> TestResult {
+    compare(
+        "cat",
+        "catr",
+        &[
+            &[FOX],
+            &[EMPTY, FOX, SPIDERS],
+            &["-n", BUSTLE],
+            &["-b", BUSTLE],
+            &["-E", "-T", NONPRINTING],
+            &["-v", NONPRINTING],
+            &["-A", NONPRINTING],
+            &["-s", "-n", BLANKS, BLANKS],
+            &["-s", "-b", BLANKS],
+            // A last line without a newline goes on in the next file
+            &["-n", TABS, FOX],
+            &["-b", TABS, BLANKS],
+            &["-s", "-n", TABS, BLANKS],
+            &[FOX, MISSING, SPIDERS],
+        ],
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn cut() -> TestResult {
+    compare(
+        "cut",
+        "cutr",
+        &[
+            &["-f", "1", BOOKS],
+            &["-f", "1,3", BOOKS],
+            &["-f", "2-3", BOOKS],
+            // GNU -c counts bytes, so stick to ASCII
+            &["-c", "1-5", FOX],
+            &["-c", "1,3,5", FOX],
+            &["-b", "1-8", BOOKS],
+            &["-f", "1", MISSING, BOOKS],
+        ],
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn comm() -> TestResult {
+    compare(
+        "comm",
+        "commr",
+        &[
+            &[CITIES1, CITIES2],
+            &["-1", CITIES1, CITIES2],
+            &["-2", CITIES1, CITIES2],
+            &["-3", CITIES1, CITIES2],
+            &["-12", CITIES1, CITIES2],
+            &["--output-delimiter", ":", CITIES1, CITIES2],
+            &[CITIES1, EMPTY],
+        ],
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn uniq() -> TestResult {
+    compare(
+        "uniq",
+        "uniqr",
+        &[
+            &[T1],
+            &[T2],
+            &["-d", T2],
+            &["-u", T2],
+            &["-f", "1", FIELDS],
+            &["-s", "2", FIELDS],
+            &["-w", "3", FIELDS],
+        ],
+    )?;
+
+    // GNU pads counts to 7 columns where uniqr defaults to 4
+    if let Some(gnu) = gnu_tool("uniq") {
+        compare_with(gnu, &["-c", T2], "uniqr", &["-c", "--count-width", "7", T2])?;
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn tail() -> TestResult {
+    compare(
+        "tail",
+        "tailr",
+        &[
+            &[TEN],
+            &["-n", "3", TEN],
+            &["-n", "+3", TEN],
+            &["-n", "0", TEN],
+            &["-n", "0", TEN, FOX],
+            &["-v", "-c", "0", TEN],
+            &["-c", "8", TEN],
+            &["-c", "+20", TEN],
+            &["-n", "2", TEN, FOX],
+            &["-q", "-n", "2", TEN, FOX],
+            &["-v", "-n", "2", TEN],
+            &["-q", "-v", "-n", "1", TEN, FOX],
+            &["-v", "-q", "-n", "1", TEN, FOX],
+            &["-n", "1", MISSING, TEN, FOX],
+        ],
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn grep() -> TestResult {
+    compare(
+        "grep",
+        "grepr",
+        &[
+            &["The", BUSTLE],
+            &["-i", "the", BUSTLE],
+            &["-v", "the", BUSTLE],
+            &["-c", "the", BUSTLE, FOX, NOBODY],
+            &["-ic", "the", BUSTLE, FOX, NOBODY],
+            &["nomatch", FOX],
+            &["fox", FOX, MISSING],
+        ],
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn echo() -> TestResult {
+    compare(
+        "echo",
+        "echor",
+        &[
+            &["Hello", "there"],
+            &["-n", "Hello  there"],
+            &["-e", "a\\tb\\nc\\\\ \\0101\\x42 \\q"],
+            &["-e", "stop\\c here"],
+            &["-E", "a\\tb"],
+            &[],
+            &["-n"],
+        ],
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn nl() -> TestResult {
+    compare(
+        "nl",
+        "nlr",
+        &[
+            &[BUSTLE],
+            &["-b", "a", BUSTLE],
+            &["-b", "n", BUSTLE],
+            &["-b", "p^[AT]", BUSTLE],
+            &["-n", "ln", "-s", " | ", BUSTLE],
+            &["-n", "rz", "-w", "3", BUSTLE],
+            &[FOX, BUSTLE, EMPTY],
+            &[FOX, MISSING, FOX],
+        ],
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn tac() -> TestResult {
+    // GNU tac words a missing file as "failed to open ... for reading", so
+    // only the files that open are compared
+    compare(
+        "tac",
+        "tacr",
+        &[
+            &[BUSTLE],
+            &["-b", BUSTLE],
+            &["-s", "e", BUSTLE],
+            &["-b", "-s", "the", BUSTLE],
+            &[FOX, BUSTLE, EMPTY],
+        ],
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn expand() -> TestResult {
+    // GNU expand counts bytes, not characters, so only ASCII is compared
+    compare(
+        "expand",
+        "expandr",
+        &[
+            &[TABS],
+            &["-t", "4", TABS, FIELDS],
+            &["-t", "2,7", TABS],
+            &["-t", "3,/5", TABS],
+            &["-i", "-t", "1,+3", TABS],
+            &["-t", "4,2", TABS],
+            &[TABS, MISSING, FIELDS],
+        ],
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn unexpand() -> TestResult {
+    compare(
+        "unexpand",
+        "unexpandr",
+        &[
+            &[SPACES],
+            &["-a", SPACES, TABS],
+            &["-t", "4", SPACES],
+            &["-t", "3,9", SPACES],
+            &["--first-only", "-t", "2", SPACES],
+            &["-t", "0", SPACES],
+            &[SPACES, MISSING, FOX],
+        ],
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn base64() -> TestResult {
+    compare(
+        "base64",
+        "base64r",
+        &[
+            &[FOX],
+            &[NONPRINTING],
+            &["-w", "0", BUSTLE],
+            &["-w", "7", SPIDERS],
+            &["-d", BUSTLE_B64],
+            &["-d", "-i", GARBAGE],
+            &["-d", GARBAGE],
+            &["-w", "x", FOX],
+            &[MISSING],
+        ],
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn hash() -> TestResult {
+    compare(
+        "sha256sum",
+        "hashr",
+        &[
+            &[FOX, EMPTY, BUSTLE],
+            &[FOX, MISSING, SPIDERS],
+            &["-c", GOOD_SUMS],
+            &["-c", "--status", GOOD_SUMS],
+            &["-c", FOX],
+        ],
+    )?;
+    let Some(gnu) = gnu_tool("md5sum") else {
+        return Ok(());
+    };
+    for args in [&[FOX, NONPRINTING][..], &["-c", BAD_SUMS], &["-c", "--quiet", BAD_SUMS]] {
+        compare_with(gnu, args, "hashr", &[&["-a", "md5"], args].concat())?;
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn seq() -> TestResult {
+    compare(
+        "seq",
+        "seqr",
+        &[
+            &["10"],
+            &["-5", "3", "10"],
+            &["0", "0.1", "1"],
+            &["1", "-0.25", "0"],
+            &["-w", "-1", "3e1", "100"],
+            &["-w", "0.1", "7", "100"],
+            &["-s", ":", "1e2", "5e2"],
+            &["-f", "%08.3f", "1", "0.5", "4"],
+            &["-f", "%g%%", "1", "3"],
+            &["-f", "%x", "3"],
+            &["5", "1"],
+        ],
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn du() -> TestResult {
+    compare(
+        "du",
+        "dur",
+        &[
+            &["tests"],
+            &["-h", "tests"],
+            &["-s", "--apparent-size", "tests"],
+            &["-d", "1", "-h", "--apparent-size", "tests"],
+            &["--apparent-size", FOX, BUSTLE, "tests/inputs"],
+            &["-x", "-s", "tests/inputs"],
+        ],
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn readlink() -> TestResult {
+    compare(
+        "readlink",
+        "readlinkr",
+        &[
+            &[FOX],
+            &["-v", FOX],
+            &["-f", "tests/inputs/../inputs/", FOX, MISSING],
+            &["-ev", FOX, MISSING],
+            &["-m", "tests/nowhere/../x/y"],
+            &["-fv", "tests/inputs/fox.txt/x"],
+            &["-fz", "tests", FOX],
+        ],
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn realpath() -> TestResult {
+    compare(
+        "realpath",
+        "realpathr",
+        &[
+            &["tests", FOX, MISSING],
+            &["-e", FOX, MISSING],
+            &["-m", "tests/nowhere/../x/y"],
+            &["-s", "tests/inputs/./fox.txt", "tests/inputs/fox.txt/x"],
+            &["--relative-to=tests", ".", FOX, "/"],
+            &["--relative-base=tests", ".", FOX],
+            &["--relative-to=tests/inputs", "--relative-base=tests", FOX, "src"],
+            &["--relative-to=tests/nowhere/x", FOX],
+            &["-q", MISSING, "tests/inputs/fox.txt/x"],
+        ],
+    )
+}
+
+// --------------------------------------------------
+#[test]
+#[cfg(unix)]
+fn timeout() -> TestResult {
+    // Usage errors aren't compared: GNU adds a hint to try --help
+    compare(
+        "timeout",
+        "timeoutr",
+        &[
+            &["0.1", "sleep", "10"],
+            &["--preserve-status", "-s", "HUP", "0.1", "sh", "-c", "trap 'exit 7' HUP; sleep 1"],
+            &["5", "echo", "-n", "-e", "a\\tb"],
+            &["5", "sh", "-c", "exit 3"],
+            &["5", "does-not-exist"],
+            &["5", "/"],
+        ],
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn cksum() -> TestResult {
+    compare(
+        "cksum",
+        "cksumr",
+        &[
+            &[FOX, EMPTY, BUSTLE, NONPRINTING],
+            &["-a", "bsd", FOX, BUSTLE],
+            &["-a", "sysv", FOX, BUSTLE],
+            &[FOX, MISSING, EMPTY],
+        ],
+    )?;
+    compare("sum", "sumr", &[&[FOX, EMPTY, BUSTLE], &["-s", FOX, BUSTLE, NONPRINTING]])
+}
+
+// --------------------------------------------------
+#[test]
+fn numfmt() -> TestResult {
+    compare(
+        "numfmt",
+        "numfmtr",
+        &[
+            &["--to=iec", "1023", "1024", "1025", "10241", "1048575"],
+            &["--to=si", "--round=nearest", "--padding=-7", "1499", "1500", "999999"],
+            &["--from=auto", "--to=iec-i", "1K", "1.5Mi", "2G"],
+            &["--from=iec", "1.7K", "12abc"],
+            &["1K"],
+        ],
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn pathchk() -> TestResult {
+    let long = "a".repeat(300);
+    compare(
+        "pathchk",
+        "pathchkr",
+        &[
+            &[FOX, MISSING, "", &long, &format!("{FOX}/x")],
+            &["-p", "a b", "abcdefghijklmno", "ok/file.txt", ""],
+            &["-P", "--", "-x", "a/-b", "a//b", ""],
+            &["--portability", "--", "-x", "a:b"],
+        ],
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn uname() -> TestResult {
+    compare("uname", "unamer", &[&[], &["-a"], &["-snrvm"], &["-m", "-n"], &["-p", "-i", "-o"]])
+}
+
+// --------------------------------------------------
+#[test]
+fn id() -> TestResult {
+    // Usage errors aren't compared: GNU adds a hint to try --help
+    compare(
+        "id",
+        "idr",
+        &[
+            &[],
+            &["-G"],
+            &["-Gn"],
+            &["-u", "-n"],
+            &["-r", "-g"],
+            &["root", "no-such-user", "daemon"],
+            &["-Gn", "root"],
+        ],
+    )?;
+    compare("whoami", "whoamir", &[&[]])
+}
+
+// --------------------------------------------------
+#[test]
+fn tty() -> TestResult {
+    compare("tty", "ttyr", &[&[], &["-s"]])
+}
+
+// --------------------------------------------------
+#[test]
+fn true_false() -> TestResult {
+    // Only the status can be compared: --help and --version differ
+    let cases: &[&[&str]] = &[&[], &["--bogus", "-x"], &["--help", "--version"]];
+    compare("true", "true", cases)?;
+    compare("false", "false", cases)
+}
@@ -0,0 +1,64 @@
+//! Copying the contents of a file as cheaply as the platform allows.
+
+use std::{
+    fs::{File, Metadata},
+    io,
+};
+
+/// Copies everything in `from` into the empty file `to`. On Linux this is
+/// a reflink where the file system can share the blocks between the two
+/// until either changes; otherwise a sparse file is copied without its
+/// holes, so the copy stays sparse.
+#[cfg(target_os = "linux")]
+pub fn copy_contents(from: &File, to: &File, metadata: &Metadata) -> io::Result<()> {
+    use std::os::unix::{fs::MetadataExt, io::AsRawFd};
+
+    // SAFETY: both descriptors stay open for the call
+    if unsafe { libc::ioctl(to.as_raw_fd(), libc::FICLONE, from.as_raw_fd()) } == 0 {
+        return Ok(());
+    }
+    if metadata.blocks() * 512 < metadata.len() {
+        return copy_sparse(from, to, metadata.len());
+    }
+    io::copy(&mut &*from, &mut &*to).map(|_| ())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn copy_contents(from: &File, to: &File, _metadata: &Metadata) -> io::Result<()> {
+    io::copy(&mut &*from, &mut &*to).map(|_| ())
+}
+
+/// Copies the parts of `from` that hold data, found with SEEK_DATA and
+/// SEEK_HOLE, to the same offsets in `to`, and leaves the rest as holes.
+#[cfg(target_os = "linux")]
+fn copy_sparse(mut from: &File, mut to: &File, len: u64) -> io::Result<()> {
+    use std::{
+        io::{Read, Seek, SeekFrom},
+        os::unix::io::AsRawFd,
+    };
+
+    // The next offset from `offset` of the kind `whence`, or None past the
+    // last data
+    let fd = from.as_raw_fd();
+    let next = |whence, offset: u64| -> io::Result<Option<u64>> {
+        // SAFETY: the descriptor stays open for the call
+        match unsafe { libc::lseek(fd, offset as libc::off_t, whence) } {
+            -1 => match io::Error::last_os_error() {
+                e if e.raw_os_error() == Some(libc::ENXIO) => Ok(None),
+                e => Err(e),
+            },
+            pos => Ok(Some(pos as u64)),
+        }
+    };
+
+    let mut pos = 0;
+    while let Some(data) = next(libc::SEEK_DATA, pos)? {
+        let hole = next(libc::SEEK_HOLE, data)?.unwrap_or(len);
+        from.seek(SeekFrom::Start(data))?;
+        to.seek(SeekFrom::Start(data))?;
+        io::copy(&mut from.take(hole - data), &mut to)?;
+        pos = hole;
+    }
+    // A hole at the end still counts toward the length
+    to.set_len(len)
+}
@@ -0,0 +1,530 @@
+use clap::Parser;
+use filetime::FileTime;
+use std::{
+    collections::HashMap,
+    fs::{self, File, Metadata, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use coreutils_common::{parse_args, CoreError, MyResult};
+
+mod fast;
+
+#[derive(Debug)]
+pub struct Config {
+    sources: Vec<String>,
+    dest: String,
+    recursive: bool,
+    preserve: bool,
+    overwrite: Overwrite,
+    verbose: bool,
+    dereference: Dereference,
+    preserve_links: bool,
+}
+
+/// What to do when the destination file already exists.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Overwrite {
+    Always,
+    /// -n
+    Never,
+    /// -i
+    Ask,
+}
+
+/// Which symbolic links are followed, rather than copied as links.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Dereference {
+    /// -L
+    Always,
+    /// -P, -d and -a
+    Never,
+    /// The sources named on the command line, unless copying recursively
+    CommandLine,
+}
+
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust cp")]
+struct Args {
+    /// SOURCE... DEST: copy SOURCE to DEST, or each SOURCE into the directory DEST
+    #[arg(value_name = "PATH", required = true, num_args = 2..)]
+    paths: Vec<String>,
+
+    /// Copy directories and everything in them
+    #[arg(short = 'r', long, short_alias = 'R')]
+    recursive: bool,
+
+    /// Keep the mode, ownership and timestamps
+    #[arg(short = 'p', long)]
+    preserve: bool,
+
+    /// Same as -dRp: copy a tree exactly as it is
+    #[arg(short = 'a', long)]
+    archive: bool,
+
+    /// Don't overwrite existing files
+    #[arg(short = 'n', long, overrides_with = "interactive")]
+    no_clobber: bool,
+
+    /// Ask before overwriting existing files
+    #[arg(short = 'i', long, overrides_with = "no_clobber")]
+    interactive: bool,
+
+    /// Print each file as it is copied
+    #[arg(short = 'v', long)]
+    verbose: bool,
+
+    /// Copy symbolic links as links, never following them
+    #[arg(short = 'P', long, overrides_with = "dereference")]
+    no_dereference: bool,
+
+    /// Same as -P, and keep the hard links between the files copied
+    #[arg(short = 'd', overrides_with = "dereference")]
+    links: bool,
+
+    /// Always follow symbolic links
+    #[arg(short = 'L', long, overrides_with_all = ["no_dereference", "links"])]
+    dereference: bool,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let args: Args = parse_args();
+
+    let mut sources = args.paths;
+    let dest = sources.pop().unwrap_or_default();
+    let recursive = args.recursive || args.archive;
+    let dereference = if args.dereference {
+        Dereference::Always
+    } else if args.no_dereference || args.links || args.archive || recursive {
+        // Like GNU cp, a recursive copy doesn't follow links in the tree
+        Dereference::Never
+    } else {
+        Dereference::CommandLine
+    };
+
+    Ok(Config {
+        sources,
+        dest,
+        recursive,
+        preserve: args.preserve || args.archive,
+        overwrite: if args.no_clobber {
+            Overwrite::Never
+        } else if args.interactive {
+            Overwrite::Ask
+        } else {
+            Overwrite::Always
+        },
+        verbose: args.verbose,
+        dereference,
+        preserve_links: args.links || args.archive,
+    })
+}
+
+/// Asks on stderr whether to overwrite `path`, and reads the answer.
+fn confirm_overwrite(path: &Path) -> MyResult<bool> {
+    io::stdout().flush()?;
+    eprint!("overwrite '{}'? ", path.display());
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(answer.starts_with(['y', 'Y']))
+}
+
+/// Whether the existing `dest` may be replaced, asking with -i.
+fn may_overwrite(dest: &Path, config: &Config) -> MyResult<bool> {
+    match config.overwrite {
+        Overwrite::Always => Ok(true),
+        Overwrite::Never => Ok(false),
+        Overwrite::Ask => confirm_overwrite(dest),
+    }
+}
+
+/// Where each source with other hard links was copied to, by its device
+/// and inode, so that -a and -d link the copies together as well.
+type Links = HashMap<(u64, u64), PathBuf>;
+
+/// The device and inode of a file that has other hard links.
+#[cfg(unix)]
+fn linked_id(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+
+    (metadata.nlink() > 1 && !metadata.is_dir()).then(|| (metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn linked_id(_metadata: &Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// Whether the two are one file, perhaps through different names.
+#[cfg(unix)]
+fn same_file(a: &Metadata, b: &Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    a.dev() == b.dev() && a.ino() == b.ino()
+}
+
+#[cfg(not(unix))]
+fn same_file(_a: &Metadata, _b: &Metadata) -> bool {
+    false
+}
+
+/// `path` made absolute with no links or dots, even if its last part
+/// doesn't exist yet.
+fn resolve(path: &Path) -> io::Result<PathBuf> {
+    match path.canonicalize() {
+        Ok(path) => Ok(path),
+        Err(e) => match (path.parent(), path.file_name()) {
+            (Some(parent), Some(name)) => {
+                let parent = if parent.as_os_str().is_empty() { Path::new(".") } else { parent };
+                Ok(parent.canonicalize()?.join(name))
+            }
+            _ => Err(e),
+        },
+    }
+}
+
+/// Gives `dest` the owner, mode and timestamps of the source. As in GNU cp,
+/// not being allowed to change the owner isn't an error.
+fn preserve(dest: &Path, metadata: &Metadata) -> MyResult<()> {
+    let is_link = metadata.file_type().is_symlink();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{chown, lchown, MetadataExt};
+
+        let (uid, gid) = (Some(metadata.uid()), Some(metadata.gid()));
+        let _ = if is_link { lchown(dest, uid, gid) } else { chown(dest, uid, gid) };
+    }
+    let err = |e| CoreError::io(format!("preserving permissions for '{}'", dest.display()), e);
+    if !is_link {
+        fs::set_permissions(dest, metadata.permissions()).map_err(err)?;
+    }
+
+    let atime = FileTime::from_last_access_time(metadata);
+    let mtime = FileTime::from_last_modification_time(metadata);
+    // Set through the path without following or opening it, which would hang
+    // on a FIFO and touch the device behind a special file
+    filetime::set_symlink_file_times(dest, atime, mtime)
+        .map_err(|e| CoreError::io(format!("preserving times for '{}'", dest.display()), e))
+}
+
+fn print_copied(source: &Path, dest: &Path, config: &Config) {
+    if config.verbose {
+        println!("'{}' -> '{}'", source.display(), dest.display());
+    }
+}
+
+/// Copies the regular file `source` over or to `dest`. Returns false if it
+/// was left as it is, by -n or an answer to -i.
+fn copy_file(source: &Path, dest: &Path, metadata: &Metadata, config: &Config) -> MyResult<bool> {
+    if let Ok(existing) = fs::metadata(dest) {
+        if same_file(metadata, &existing) {
+            return Err(CoreError::Input(format!(
+                "'{}' and '{}' are the same file",
+                source.display(),
+                dest.display()
+            )));
+        }
+        if existing.is_dir() {
+            return Err(CoreError::Input(format!(
+                "cannot overwrite directory '{}' with non-directory",
+                dest.display()
+            )));
+        }
+        if !may_overwrite(dest, config)? {
+            return Ok(false);
+        }
+    }
+
+    let from = File::open(source)
+        .map_err(|e| CoreError::io(format!("cannot open '{}' for reading", source.display()), e))?;
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+        // A new file starts with the mode of the source, less the umask
+        options.mode(metadata.permissions().mode());
+    }
+    let to = options
+        .open(dest)
+        .map_err(|e| CoreError::io(format!("cannot create regular file '{}'", dest.display()), e))?;
+    fast::copy_contents(&from, &to, metadata).map_err(|e| {
+        CoreError::io(format!("error copying '{}' to '{}'", source.display(), dest.display()), e)
+    })?;
+    print_copied(source, dest, config);
+    Ok(true)
+}
+
+/// Makes `dest` a symbolic link with the same target as `source`. Returns
+/// false if an existing `dest` was left as it is.
+fn copy_link(source: &Path, dest: &Path, config: &Config) -> MyResult<bool> {
+    let target = fs::read_link(source)
+        .map_err(|e| CoreError::io(format!("cannot read symbolic link '{}'", source.display()), e))?;
+    if fs::symlink_metadata(dest).is_ok() {
+        if !may_overwrite(dest, config)? {
+            return Ok(false);
+        }
+        fs::remove_file(dest)
+            .map_err(|e| CoreError::io(format!("cannot remove '{}'", dest.display()), e))?;
+    }
+
+    #[cfg(unix)]
+    let created = std::os::unix::fs::symlink(&target, dest);
+    #[cfg(windows)]
+    let created = if source.is_dir() {
+        std::os::windows::fs::symlink_dir(&target, dest)
+    } else {
+        std::os::windows::fs::symlink_file(&target, dest)
+    };
+    created.map_err(|e| {
+        CoreError::io(format!("cannot create symbolic link '{}'", dest.display()), e)
+    })?;
+    print_copied(source, dest, config);
+    Ok(true)
+}
+
+/// Whether `metadata` is of a FIFO, socket or device, which a recursive
+/// copy makes anew rather than reading, as GNU cp -R does.
+#[cfg(unix)]
+fn is_special(metadata: &Metadata) -> bool {
+    use std::os::unix::fs::FileTypeExt;
+
+    let file_type = metadata.file_type();
+    file_type.is_fifo()
+        || file_type.is_socket()
+        || file_type.is_block_device()
+        || file_type.is_char_device()
+}
+
+#[cfg(not(unix))]
+fn is_special(_metadata: &Metadata) -> bool {
+    false
+}
+
+/// Makes `dest` a special file of the same kind, mode and device as
+/// `source`. Returns false if an existing `dest` was left as it is.
+#[cfg(unix)]
+fn copy_special(
+    source: &Path,
+    dest: &Path,
+    metadata: &Metadata,
+    config: &Config,
+) -> MyResult<bool> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt, os::unix::fs::MetadataExt};
+
+    if fs::symlink_metadata(dest).is_ok() {
+        if !may_overwrite(dest, config)? {
+            return Ok(false);
+        }
+        fs::remove_file(dest)
+            .map_err(|e| CoreError::io(format!("cannot remove '{}'", dest.display()), e))?;
+    }
+    let err = |e| CoreError::io(format!("cannot create special file '{}'", dest.display()), e);
+    let path = CString::new(dest.as_os_str().as_bytes())
+        .map_err(|e| err(io::Error::new(io::ErrorKind::InvalidInput, e)))?;
+    let (mode, device) = (metadata.mode() as libc::mode_t, metadata.rdev() as libc::dev_t);
+    // SAFETY: `path` is a NUL-terminated string that outlives the call
+    let made = unsafe { libc::mknod(path.as_ptr(), mode, device) };
+    if made != 0 {
+        return Err(err(io::Error::last_os_error()));
+    }
+    print_copied(source, dest, config);
+    Ok(true)
+}
+
+/// Elsewhere nothing is special, so this is never called.
+#[cfg(not(unix))]
+fn copy_special(
+    source: &Path,
+    dest: &Path,
+    metadata: &Metadata,
+    config: &Config,
+) -> MyResult<bool> {
+    copy_file(source, dest, metadata, config)
+}
+
+/// Makes `dest` another hard link to `first`, the copy of another link to
+/// `source`. Returns false if an existing `dest` was left as it is.
+fn copy_hard_link(first: &Path, source: &Path, dest: &Path, config: &Config) -> MyResult<bool> {
+    if let Ok(existing) = fs::symlink_metadata(dest) {
+        if fs::symlink_metadata(first).is_ok_and(|first| same_file(&first, &existing)) {
+            return Ok(true);
+        }
+        if !may_overwrite(dest, config)? {
+            return Ok(false);
+        }
+        fs::remove_file(dest)
+            .map_err(|e| CoreError::io(format!("cannot remove '{}'", dest.display()), e))?;
+    }
+    fs::hard_link(first, dest).map_err(|e| {
+        let (dest, first) = (dest.display(), first.display());
+        CoreError::io(format!("cannot create hard link '{dest}' to '{first}'"), e)
+    })?;
+    print_copied(source, dest, config);
+    Ok(true)
+}
+
+/// Copies the directory `source` and everything in it to `dest`, creating
+/// it if need be. Returns false if anything in it couldn't be copied; those
+/// errors are reported as they happen.
+fn copy_dir(source: &Path, dest: &Path, config: &Config, links: &mut Links) -> MyResult<bool> {
+    match fs::metadata(dest) {
+        Ok(existing) if !existing.is_dir() => {
+            return Err(CoreError::Input(format!(
+                "cannot overwrite non-directory '{}' with directory '{}'",
+                dest.display(),
+                source.display()
+            )));
+        }
+        Ok(_) => {}
+        Err(_) => {
+            fs::create_dir(dest).map_err(|e| {
+                CoreError::io(format!("cannot create directory '{}'", dest.display()), e)
+            })?;
+            print_copied(source, dest, config);
+        }
+    }
+
+    let read_err = |e| CoreError::io(format!("cannot open directory '{}'", source.display()), e);
+    let mut entries = fs::read_dir(source)
+        .and_then(|entries| entries.collect::<io::Result<Vec<_>>>())
+        .map_err(read_err)?;
+    entries.sort_by_key(|entry| entry.file_name());
+    let mut ok = true;
+    for entry in entries {
+        let name = entry.file_name();
+        match copy(&source.join(&name), &dest.join(&name), config, false, links) {
+            Ok(copied) => ok &= copied,
+            Err(e) => {
+                io::stdout().flush()?;
+                eprintln!("{e}");
+                ok = false;
+            }
+        }
+    }
+    Ok(ok)
+}
+
+/// Copies `source` to `dest`, whatever kind of file it is. `top` is set for
+/// the sources named on the command line.
+fn copy(
+    source: &Path,
+    dest: &Path,
+    config: &Config,
+    top: bool,
+    links: &mut Links,
+) -> MyResult<bool> {
+    let follow = match config.dereference {
+        Dereference::Always => true,
+        Dereference::Never => false,
+        Dereference::CommandLine => top,
+    };
+    let metadata = if follow { fs::metadata(source) } else { fs::symlink_metadata(source) }
+        .map_err(|e| CoreError::io(format!("cannot stat '{}'", source.display()), e))?;
+
+    let id = linked_id(&metadata).filter(|_| config.preserve_links);
+    if let Some(first) = id.and_then(|id| links.get(&id)) {
+        return copy_hard_link(first, source, dest, config);
+    }
+
+    let (copied, ok) = if metadata.is_dir() {
+        if !config.recursive {
+            return Err(CoreError::Usage(format!(
+                "-r not specified; omitting directory '{}'",
+                source.display()
+            )));
+        }
+        if top {
+            check_not_into_itself(source, dest, &metadata)?;
+        }
+        (true, copy_dir(source, dest, config, links)?)
+    } else if metadata.file_type().is_symlink() {
+        (copy_link(source, dest, config)?, true)
+    } else if config.recursive && is_special(&metadata) {
+        (copy_special(source, dest, &metadata, config)?, true)
+    } else {
+        (copy_file(source, dest, &metadata, config)?, true)
+    };
+
+    // Copying into a directory changes its times, so they're set last
+    if config.preserve && copied {
+        preserve(dest, &metadata)?;
+    }
+    if let (Some(id), true) = (id, copied) {
+        links.insert(id, dest.to_path_buf());
+    }
+    Ok(ok)
+}
+
+/// Fails if `dest` is `source` or inside it, where copying would never end.
+fn check_not_into_itself(source: &Path, dest: &Path, metadata: &Metadata) -> MyResult<()> {
+    if fs::metadata(dest).is_ok_and(|existing| same_file(metadata, &existing)) {
+        return Err(CoreError::Input(format!(
+            "'{}' and '{}' are the same file",
+            source.display(),
+            dest.display()
+        )));
+    }
+    let (Ok(abs_source), Ok(abs_dest)) = (resolve(source), resolve(dest)) else {
+        return Ok(());
+    };
+    if abs_dest.starts_with(&abs_source) {
+        return Err(CoreError::Input(format!(
+            "cannot copy a directory, '{}', into itself, '{}'",
+            source.display(),
+            dest.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Copies every source and returns the exit status: 1 if anything couldn't
+/// be copied.
+pub fn run(config: Config) -> MyResult<i32> {
+    let dest = Path::new(&config.dest);
+    let into_dir = fs::metadata(dest).is_ok_and(|metadata| metadata.is_dir());
+    if config.sources.len() > 1 && !into_dir {
+        return Err(match fs::metadata(dest) {
+            Ok(_) => CoreError::Usage(format!("target '{}' is not a directory", config.dest)),
+            Err(e) => CoreError::io(format!("target '{}'", config.dest), e),
+        });
+    }
+
+    let mut status = 0;
+    let mut links = Links::new();
+    for source in &config.sources {
+        let source = Path::new(source);
+        let target = if into_dir {
+            dest.join(source.file_name().unwrap_or(source.as_os_str()))
+        } else {
+            dest.to_path_buf()
+        };
+        match copy(source, &target, &config, true, &mut links) {
+            Ok(true) => {}
+            Ok(false) => status = 1,
+            Err(e) => {
+                io::stdout().flush()?;
+                eprintln!("{e}");
+                status = 1;
+            }
+        }
+    }
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::resolve;
+    use std::path::Path;
+
+    #[test]
+    fn test_resolve() {
+        let cwd = std::env::current_dir().unwrap();
+        assert_eq!(resolve(Path::new("src")).unwrap(), cwd.join("src"));
+        assert_eq!(resolve(Path::new("src/../new")).unwrap(), cwd.join("new"));
+        assert_eq!(resolve(Path::new("new")).unwrap(), cwd.join("new"));
+        assert!(resolve(Path::new("no/such/dir")).is_err());
+    }
+}
@@ -0,0 +1,10 @@
+fn main() {
+    coreutils_common::reset_sigpipe();
+    match cpr::get_args().and_then(cpr::run) {
+        Ok(status) => std::process::exit(status),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(e.exit_code());
+        }
+    }
+}
@@ -0,0 +1,312 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::{fs, path::Path};
+use tempfile::TempDir;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "cpr";
+
+// --------------------------------------------------
+/// A directory to copy in: a file `f`, and a tree with `d/top` and `d/sub/x`.
+fn setup() -> std::io::Result<TempDir> {
+    let dir = tempfile::tempdir()?;
+    fs::write(dir.path().join("f"), "file\n")?;
+    fs::create_dir_all(dir.path().join("d/sub"))?;
+    fs::write(dir.path().join("d/top"), "top\n")?;
+    fs::write(dir.path().join("d/sub/x"), "deep\n")?;
+    Ok(dir)
+}
+
+// --------------------------------------------------
+fn cpr(dir: &Path) -> Result<Command, Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(PRG)?;
+    cmd.current_dir(dir);
+    Ok(cmd)
+}
+
+// --------------------------------------------------
+fn read(dir: &Path, path: &str) -> std::io::Result<String> {
+    fs::read_to_string(dir.join(path))
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_no_dest() -> TestResult {
+    let dir = setup()?;
+    cpr(dir.path())?
+        .arg("f")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("2 values required"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_dir_without_recursive() -> TestResult {
+    let dir = setup()?;
+    cpr(dir.path())?
+        .args(["d", "f", "copy"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::starts_with("target 'copy': "));
+    cpr(dir.path())?
+        .args(["d", "copy"])
+        .assert()
+        .code(1)
+        .stderr("-r not specified; omitting directory 'd'\n");
+    assert!(!dir.path().join("copy").exists());
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_target_not_dir() -> TestResult {
+    let dir = setup()?;
+    cpr(dir.path())?
+        .args(["f", "d/top", "f"])
+        .assert()
+        .code(1)
+        .stderr("target 'f' is not a directory\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_same_file() -> TestResult {
+    let dir = setup()?;
+    cpr(dir.path())?
+        .args(["f", "./f"])
+        .assert()
+        .code(1)
+        .stderr("'f' and './f' are the same file\n");
+    assert_eq!(read(dir.path(), "f")?, "file\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_into_itself() -> TestResult {
+    let dir = setup()?;
+    cpr(dir.path())?
+        .args(["-r", "d", "d/sub/copy"])
+        .assert()
+        .code(1)
+        .stderr("cannot copy a directory, 'd', into itself, 'd/sub/copy'\n");
+    assert!(!dir.path().join("d/sub/copy").exists());
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn skips_bad_source() -> TestResult {
+    let dir = setup()?;
+    fs::create_dir(dir.path().join("out"))?;
+    cpr(dir.path())?
+        .args(["does-not-exist", "f", "out"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::starts_with("cannot stat 'does-not-exist': "));
+    assert_eq!(read(dir.path(), "out/f")?, "file\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn file() -> TestResult {
+    let dir = setup()?;
+    cpr(dir.path())?.args(["f", "copy"]).assert().success().stdout("");
+    assert_eq!(read(dir.path(), "copy")?, "file\n");
+
+    // Over an existing file
+    cpr(dir.path())?.args(["d/top", "copy"]).assert().success();
+    assert_eq!(read(dir.path(), "copy")?, "top\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn into_dir() -> TestResult {
+    let dir = setup()?;
+    cpr(dir.path())?
+        .args(["-v", "f", "d/top", "d/sub"])
+        .assert()
+        .success()
+        .stdout("'f' -> 'd/sub/f'\n'd/top' -> 'd/sub/top'\n");
+    assert_eq!(read(dir.path(), "d/sub/f")?, "file\n");
+    assert_eq!(read(dir.path(), "d/sub/top")?, "top\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn recursive() -> TestResult {
+    let dir = setup()?;
+    let expected = ["'d' -> 'copy'", "'d/sub' -> 'copy/sub'", "'d/sub/x' -> 'copy/sub/x'"];
+    cpr(dir.path())?
+        .args(["-rv", "d", "copy"])
+        .assert()
+        .success()
+        .stdout(format!("{}\n'd/top' -> 'copy/top'\n", expected.join("\n")));
+    assert_eq!(read(dir.path(), "copy/top")?, "top\n");
+    assert_eq!(read(dir.path(), "copy/sub/x")?, "deep\n");
+
+    // Into the directory that now exists
+    cpr(dir.path())?.args(["-R", "d", "copy"]).assert().success();
+    assert_eq!(read(dir.path(), "copy/d/sub/x")?, "deep\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn no_clobber() -> TestResult {
+    let dir = setup()?;
+    cpr(dir.path())?.args(["-n", "f", "d/top"]).assert().success();
+    assert_eq!(read(dir.path(), "d/top")?, "top\n");
+    // The last of -n and -i wins
+    cpr(dir.path())?.args(["-n", "-i", "f", "d/top"]).write_stdin("y\n").assert().success();
+    assert_eq!(read(dir.path(), "d/top")?, "file\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn interactive() -> TestResult {
+    let dir = setup()?;
+    cpr(dir.path())?
+        .args(["-i", "f", "d/top"])
+        .write_stdin("n\n")
+        .assert()
+        .success()
+        .stderr("overwrite 'd/top'? ");
+    assert_eq!(read(dir.path(), "d/top")?, "top\n");
+
+    cpr(dir.path())?.args(["-i", "f", "d/top"]).write_stdin("yes\n").assert().success();
+    assert_eq!(read(dir.path(), "d/top")?, "file\n");
+
+    // Nothing to ask about
+    cpr(dir.path())?.args(["-i", "f", "new"]).assert().success().stderr("");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn preserve() -> TestResult {
+    let dir = setup()?;
+    let mtime = filetime::FileTime::from_unix_time(1_000_000_000, 0);
+    filetime::set_file_mtime(dir.path().join("d/sub/x"), mtime)?;
+    let mut perms = fs::metadata(dir.path().join("d/sub/x"))?.permissions();
+    perms.set_readonly(true);
+    fs::set_permissions(dir.path().join("d/sub/x"), perms)?;
+
+    cpr(dir.path())?.args(["-a", "d", "copy"]).assert().success();
+    let copied = fs::metadata(dir.path().join("copy/sub/x"))?;
+    assert_eq!(filetime::FileTime::from_last_modification_time(&copied), mtime);
+    assert!(copied.permissions().readonly());
+
+    cpr(dir.path())?.args(["d/sub/x", "plain"]).assert().success();
+    let plain = fs::metadata(dir.path().join("plain"))?;
+    assert_ne!(filetime::FileTime::from_last_modification_time(&plain), mtime);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+#[cfg(unix)]
+fn symlinks() -> TestResult {
+    use std::os::unix::fs::symlink;
+
+    let dir = setup()?;
+    symlink("f", dir.path().join("link"))?;
+    symlink("../top", dir.path().join("d/sub/link"))?;
+    let is_link = |path: &str| fs::symlink_metadata(dir.path().join(path)).unwrap().is_symlink();
+
+    // A link named on the command line is followed...
+    cpr(dir.path())?.args(["link", "followed"]).assert().success();
+    assert!(!is_link("followed"));
+    assert_eq!(read(dir.path(), "followed")?, "file\n");
+    // ...except with -d
+    cpr(dir.path())?.args(["-d", "link", "kept"]).assert().success();
+    assert_eq!(fs::read_link(dir.path().join("kept"))?, Path::new("f"));
+
+    // Links in a tree are copied as links, or followed with -L
+    cpr(dir.path())?.args(["-r", "d", "tree"]).assert().success();
+    assert!(is_link("tree/sub/link"));
+    cpr(dir.path())?.args(["-rL", "d", "deref"]).assert().success();
+    assert!(!is_link("deref/sub/link"));
+    assert_eq!(read(dir.path(), "deref/sub/link")?, "top\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn sparse() -> TestResult {
+    use std::io::Write;
+
+    let dir = setup()?;
+    let file = fs::File::create(dir.path().join("sparse"))?;
+    file.set_len(10 << 20)?;
+    drop(file);
+    let mut contents = fs::read(dir.path().join("sparse"))?;
+    contents.extend(b"end\n");
+    fs::OpenOptions::new().append(true).open(dir.path().join("sparse"))?.write_all(b"end\n")?;
+
+    cpr(dir.path())?.args(["sparse", "copy"]).assert().success();
+    assert_eq!(fs::read(dir.path().join("copy"))?, contents);
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let blocks = fs::metadata(dir.path().join("copy"))?.blocks();
+        assert!(blocks * 512 < 1 << 20, "the holes were filled in: {blocks} blocks");
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+#[cfg(unix)]
+fn recursive_makes_fifos_anew() -> TestResult {
+    use std::os::unix::fs::FileTypeExt;
+
+    let dir = setup()?;
+    let made = std::process::Command::new("mkfifo").arg(dir.path().join("d/fifo")).status()?;
+    assert!(made.success());
+    // Reading the FIFO, or opening it to set its times, would wait for a
+    // writer forever
+    for (flag, copy) in [("-r", "copy"), ("-a", "archive"), ("-rp", "preserved")] {
+        cpr(dir.path())?
+            .args([flag, "d", copy])
+            .timeout(std::time::Duration::from_secs(5))
+            .assert()
+            .success();
+        let fifo = fs::symlink_metadata(dir.path().join(copy).join("fifo"))?;
+        assert!(fifo.file_type().is_fifo(), "{flag}");
+        assert_eq!(read(dir.path(), &format!("{copy}/top"))?, "top\n");
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+#[cfg(unix)]
+fn keeps_hard_links() -> TestResult {
+    use std::os::unix::fs::MetadataExt;
+
+    let dir = setup()?;
+    fs::hard_link(dir.path().join("d/top"), dir.path().join("d/sub/link"))?;
+    let ino = |path: &str| fs::metadata(dir.path().join(path)).map(|m| m.ino());
+
+    cpr(dir.path())?.args(["-a", "d", "copy"]).assert().success();
+    assert_eq!(ino("copy/top")?, ino("copy/sub/link")?);
+    assert_ne!(ino("copy/top")?, ino("d/top")?);
+
+    fs::create_dir(dir.path().join("out"))?;
+    cpr(dir.path())?.args(["-d", "d/top", "d/sub/link", "out"]).assert().success();
+    assert_eq!(ino("out/top")?, ino("out/link")?);
+
+    // Without -d or -a, every link is a copy of its own
+    cpr(dir.path())?.args(["-r", "d", "plain"]).assert().success();
+    assert_ne!(ino("plain/top")?, ino("plain/sub/link")?);
+    Ok(())
+}
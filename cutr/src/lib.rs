@@ -1,11 +1,14 @@
-use clap::{App, Arg};
-use csv::{ReaderBuilder, StringRecord, WriterBuilder};
+use clap::Parser;
+use csv::{ReaderBuilder, StringRecord, Terminator, WriterBuilder};
 use regex::Regex;
 
 use crate::Extract::*;
-use std::{error::Error, fs::File, io::{self, BufRead, BufReader}, num::NonZeroUsize, ops::Range};
+use std::{io::Write, num::NonZeroUsize, ops::Range};
 
-type MyResult<T> = Result<T, Box<dyn Error>>;
+use coreutils_common::{
+    i18n::tr, open, parse_args, CoreError, Delimiter, DelimiterArgs, FilesFromArgs, MyResult,
+    Output,
+};
 type PositionList = Vec<Range<usize>>;
 
 #[derive(Debug)]
@@ -20,33 +23,25 @@ pub struct Config {
     files: Vec<String>,
     delimiter: u8,
     extract: Extract,
+    record_delimiter: Delimiter,
 }
 
-pub fn open(filename: &str) -> MyResult<Box<dyn BufRead>> {
-    match filename {
-        "-" => Ok(Box::new(BufReader::new(io::stdin()))),
-        _ => Ok(Box::new(BufReader::new(File::open(filename)?)))
-    }
-}
 
 fn parse_index(input: &str) -> Result<usize, String> {
     let value_err = || format!("illegal list value: \"{}\"", input);
-    input
-        .starts_with('+')
-        .then(|| Err(value_err()))
-        .unwrap_or_else(|| {
+    if input
+        .starts_with('+') { Err(value_err()) } else { {
             input
                 .parse::<NonZeroUsize>()
                 .map(|n| usize::from(n) - 1)
                 .map_err(|_| value_err())
-        })
+        } }
 }
 
 fn parse_pos(range: &str) -> MyResult<PositionList> {
     let range_re = Regex::new(r"^(\d+)-(\d+)$").unwrap();
     range
         .split(',')
-        .into_iter()
         .map(|val| {
             parse_index(val).map(|n| n..n+1).or_else(|e| {
                 range_re.captures(val).ok_or(e).and_then(|captures| {
@@ -65,66 +60,53 @@ fn parse_pos(range: &str) -> MyResult<PositionList> {
             })
         })
         .collect::<Result<_, _>>()
-        .map_err(From::from)
+        .map_err(CoreError::Parse)
 
 }
 
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust cut")]
+struct Args {
+    /// Input file(s)
+    #[arg(value_name = "FILE", default_value = "-", conflicts_with = "files_from")]
+    files: Vec<String>,
+
+    #[command(flatten)]
+    files_from: FilesFromArgs,
+
+    /// Field delimiter
+    #[arg(short, long = "delim", value_name = "DELIMITER", default_value = "\t")]
+    delimiter: String,
+
+    /// Selected bytes
+    #[arg(short, long, value_name = "BYTES", conflicts_with_all = ["chars", "fields"])]
+    bytes: Option<String>,
+
+    /// Selected characters
+    #[arg(short, long, value_name = "CHARS", conflicts_with_all = ["bytes", "fields"])]
+    chars: Option<String>,
+
+    /// Selected fields
+    #[arg(short, long, value_name = "FIELDS", conflicts_with_all = ["chars", "bytes"])]
+    fields: Option<String>,
+
+    #[command(flatten)]
+    records: DelimiterArgs,
+}
+
 pub fn get_args() -> MyResult<Config> {
-    let matches = App::new("cutr")
-        .about("Rust cut")
-        .author("OFFBLACK")
-        .version("0.1.0")
-        .arg(
-            Arg::with_name("files")
-                .value_name("FILE")
-                .help("Input file(s)")
-                .multiple(true)
-                .default_value("-")
-        )
-        .arg(
-            Arg::with_name("delimiter")
-                .short("d")
-                .long("delim")
-                .value_name("DELIMITER")
-                .help("Field delimiter")
-                .default_value("\t")
-        )
-        .arg(
-            Arg::with_name("bytes")
-                .short("b")
-                .long("bytes")
-                .value_name("BYTES")
-                .help("Selected bytes")
-                .conflicts_with_all(&["chars", "fields"])
-        )
-        .arg(
-            Arg::with_name("chars")
-                .short("c")
-                .long("chars")
-                .value_name("CHARS")
-                .help("Selected characters")
-                .conflicts_with_all(&["bytes", "fields"])
-        )
-        .arg(
-            Arg::with_name("fields")
-                .short("f")
-                .long("fields")
-                .value_name("FIELDS")
-                .help("Selected fields")
-                .conflicts_with_all(&["chars", "bytes"])
-        )
-        .get_matches();
-
-    let delimiter = matches.value_of("delimiter").unwrap();
+    let args: Args = parse_args();
+
+    let delimiter = &args.delimiter;
     let delim_bytes = delimiter.as_bytes();
     if delim_bytes.len() != 1 {
         return Err(
-            From::from(format!("--delim \"{delimiter}\" must be a single byte"))
+            CoreError::Parse(format!("--delim \"{delimiter}\" must be a single byte"))
         );
     }
-    let fields = matches.value_of("fields").map(parse_pos).transpose()?;
-    let bytes = matches.value_of("bytes").map(parse_pos).transpose()?;
-    let chars = matches.value_of("chars").map(parse_pos).transpose()?;
+    let fields = args.fields.as_deref().map(parse_pos).transpose()?;
+    let bytes = args.bytes.as_deref().map(parse_pos).transpose()?;
+    let chars = args.chars.as_deref().map(parse_pos).transpose()?;
     let extract = if let Some(fields_pos) = fields {
         Fields(fields_pos)
     } else if let Some(bytes_pos) = bytes {
@@ -132,13 +114,14 @@ pub fn get_args() -> MyResult<Config> {
     } else if let Some(chars_pos) = chars {
         Chars(chars_pos)
     } else {
-        return Err(From::from("Must have --fields, --bytes, or --chars"));
+        return Err(CoreError::Usage(tr("Must have --fields, --bytes, or --chars").to_string()));
     };
     Ok({
         Config { 
-            files: matches.values_of_lossy("files").unwrap(), 
+            files: args.files_from.files(args.files)?,
             delimiter: *delim_bytes.first().unwrap(), 
             extract,
+            record_delimiter: args.records.delimiter(),
         }
     })
 }
@@ -174,7 +157,21 @@ fn extract_fields<'a>(
         .collect()
 }
 
-pub fn run(config: Config) -> MyResult<()> {
+/// Where the csv reader ends a record.
+fn read_terminator(records: Delimiter) -> Terminator {
+    match records {
+        // The csv default, which takes \r\n too
+        Delimiter::Newline => Terminator::CRLF,
+        Delimiter::Nul => Terminator::Any(b'\0'),
+    }
+}
+
+/// Prints the selection from every input and returns the exit status: 1 if
+/// any file failed to open.
+pub fn run(config: Config) -> MyResult<i32> {
+    let records = config.record_delimiter;
+    let mut out = Output::stdout().delimiter(records);
+    let mut status = 0;
     for filename in &config.files {
         match open(filename) {
             Ok(file) => match &config.extract {
@@ -182,32 +179,88 @@ pub fn run(config: Config) -> MyResult<()> {
                     let mut reader = ReaderBuilder::new()
                         .delimiter(config.delimiter)
                         .has_headers(false)
+                        .terminator(read_terminator(records))
                         .from_reader(file);
 
                     let mut writer = WriterBuilder::new()
                         .delimiter(config.delimiter)
-                        .from_writer(io::stdout());
-                        
+                        .terminator(Terminator::Any(records.byte()))
+                        .from_writer(&mut out);
+
                     for record in reader.records() {
-                        let record = record?;
+                        let record = record.map_err(CoreError::other)?;
                         writer.write_record(extract_fields(
                             &record, field_pos,
-                        ))?;
+                        )).map_err(CoreError::other)?;
                     }
+                    writer.flush()?;
                 },
                 Chars(char_pos) => {
-                    for line in file.lines() {
-                        println!("{}", extract_chars(&line?, char_pos));
+                    for line in records.records(file) {
+                        out.write_record(extract_chars(&line?, char_pos))?;
                     }
                 },
                 Bytes(byte_pos) => {
-                    for line in file.lines() {
-                        println!("{}", extract_bytes(&line?, byte_pos));
+                    for line in records.records(file) {
+                        out.write_record(extract_bytes(&line?, byte_pos))?;
                     }
                 }
             },
-            Err(e) => eprintln!("{}: {e}", filename),
+            Err(e) => {
+                // Keep the error in order with the output before it
+                out.flush()?;
+                eprintln!("{e}");
+                status = 1;
+            }
+        }
+    }
+    out.flush()?;
+    Ok(status)
+}
+
+/// Entry points for the cargo-fuzz targets in `../fuzz`, which can't reach
+/// private items. None of them may panic, whatever the input.
+#[cfg(feature = "fuzzing")]
+#[doc(hidden)]
+pub mod fuzzing {
+    pub fn parse_pos(list: &str) {
+        let _ = super::parse_pos(list);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_pos;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn parse_pos_never_panics(list in "[0-9+,-]{0,40}|\\PC*") {
+            let _ = parse_pos(&list);
+        }
+
+        #[test]
+        fn parse_pos_accepts_lists(ranges in prop::collection::vec((1..=usize::MAX, 0..=3usize), 1..6)) {
+            let list = ranges
+                .iter()
+                .map(|&(start, len)| {
+                    let end = start.saturating_add(len);
+                    if end == start { start.to_string() } else { format!("{start}-{end}") }
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+            let expected = ranges
+                .iter()
+                .map(|&(start, len)| start - 1..start.saturating_add(len))
+                .collect::<Vec<_>>();
+            prop_assert_eq!(parse_pos(&list).unwrap(), expected);
+        }
+
+        #[test]
+        fn parse_pos_rejects_backwards_ranges(start in 2..=usize::MAX, back in 1..=10usize) {
+            let end = start.saturating_sub(back).max(1);
+            let range = format!("{start}-{end}");
+            prop_assert!(parse_pos(&range).is_err());
         }
     }
-    Ok(())
 }
@@ -0,0 +1,193 @@
+use clap::{ArgAction, Parser};
+use std::{collections::HashSet, fs::Metadata, io::Write};
+use walkdir::WalkDir;
+
+use coreutils_common::{
+    parse_args,
+    units::{to_human, Round, Scale},
+    CoreError, MyResult, Output,
+};
+
+#[derive(Debug)]
+pub struct Config {
+    paths: Vec<String>,
+    human_readable: bool,
+    max_depth: Option<usize>,
+    one_file_system: bool,
+    apparent_size: bool,
+}
+
+#[derive(Debug, Parser)]
+#[command(
+    author = "OFFBLACK",
+    version = "0.1.0",
+    about = "Rust du",
+    // -h is for human-readable sizes, as in GNU du
+    disable_help_flag = true
+)]
+struct Args {
+    /// Files or directories to measure
+    #[arg(value_name = "PATH", default_value = ".")]
+    paths: Vec<String>,
+
+    /// Print sizes like 1.5K, 234M and 2.0G instead of 1024-byte blocks
+    #[arg(short = 'h', long)]
+    human_readable: bool,
+
+    /// Print only the total of each PATH
+    #[arg(short = 's', long, conflicts_with = "max_depth")]
+    summarize: bool,
+
+    /// Print the total of a directory only if it is at most N levels below PATH
+    #[arg(short = 'd', long, value_name = "N")]
+    max_depth: Option<String>,
+
+    /// Skip directories on other file systems
+    #[arg(short = 'x', long)]
+    one_file_system: bool,
+
+    /// Count the length of files rather than the disk space they take
+    #[arg(long)]
+    apparent_size: bool,
+
+    /// Print help
+    #[arg(long, action = ArgAction::Help)]
+    help: Option<bool>,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let args: Args = parse_args();
+
+    let max_depth = match (args.summarize, &args.max_depth) {
+        (true, _) => Some(0),
+        (_, Some(val)) => Some(
+            val.parse()
+                .map_err(|_| CoreError::Parse(format!("invalid maximum depth '{val}'")))?,
+        ),
+        _ => None,
+    };
+
+    Ok(Config {
+        paths: args.paths,
+        human_readable: args.human_readable,
+        max_depth,
+        one_file_system: args.one_file_system,
+        apparent_size: args.apparent_size,
+    })
+}
+
+/// The space the entry takes on disk, in bytes.
+#[cfg(unix)]
+fn disk_usage(metadata: &Metadata) -> u64 {
+    std::os::unix::fs::MetadataExt::blocks(metadata) * 512
+}
+
+/// Windows doesn't report allocated blocks, so count the length instead.
+#[cfg(not(unix))]
+fn disk_usage(metadata: &Metadata) -> u64 {
+    metadata.len()
+}
+
+/// The device and inode of the entry, which tell hard links to one file
+/// apart from separate files.
+#[cfg(unix)]
+fn file_id(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+#[cfg(unix)]
+fn link_count(metadata: &Metadata) -> u64 {
+    std::os::unix::fs::MetadataExt::nlink(metadata)
+}
+
+#[cfg(not(unix))]
+fn link_count(_metadata: &Metadata) -> u64 {
+    1
+}
+
+/// The error for a path that couldn't be read, naming it like the errors
+/// of the other tools.
+fn walk_error(e: walkdir::Error) -> CoreError {
+    let path = e.path().map(|path| path.display().to_string()).unwrap_or_default();
+    let message = e.to_string();
+    match e.into_io_error() {
+        Some(source) => CoreError::io(path, source),
+        // A loop of links, which can't happen without following them
+        None => CoreError::Input(message),
+    }
+}
+
+/// Prints the size of every directory under each path, after the
+/// directories in it, and returns the exit status: 1 if anything couldn't
+/// be read. As in GNU du, the size of a directory includes everything in
+/// it, and a file with several hard links counts only once. With several
+/// paths, so does anything that more than one of them leads to.
+pub fn run(config: Config) -> MyResult<i32> {
+    let mut out = Output::stdout();
+    let mut status = 0;
+    let mut seen = HashSet::new();
+    let track_all = config.paths.len() > 1;
+    let format = |bytes: u64| {
+        if config.human_readable {
+            // Rounded up, as in GNU du -h
+            to_human(bytes as f64, Scale::Iec, Round::Up)
+        } else {
+            bytes.div_ceil(1024).to_string()
+        }
+    };
+
+    for path in &config.paths {
+        // totals[depth] is the size of what has been seen so far in the
+        // directory at that depth that is being walked
+        let mut totals: Vec<u64> = vec![0];
+        let walker = WalkDir::new(path)
+            .contents_first(true)
+            .follow_root_links(false)
+            .same_file_system(config.one_file_system);
+        for entry in walker {
+            let (entry, metadata) = match entry.and_then(|entry| Ok((entry.metadata()?, entry))) {
+                Ok((metadata, entry)) => (entry, metadata),
+                Err(e) => {
+                    out.flush()?;
+                    eprintln!("{}", walk_error(e));
+                    status = 1;
+                    continue;
+                }
+            };
+
+            let depth = entry.depth();
+            if totals.len() < depth + 2 {
+                totals.resize(depth + 2, 0);
+            }
+            let tracked = track_all || (!metadata.is_dir() && link_count(&metadata) > 1);
+            // What was counted before is neither counted nor shown again
+            let repeat = tracked && file_id(&metadata).is_some_and(|id| !seen.insert(id));
+            let mut size = if repeat {
+                0
+            } else if config.apparent_size {
+                metadata.len()
+            } else {
+                disk_usage(&metadata)
+            };
+            if entry.file_type().is_dir() {
+                // Everything in it has been walked, so its total is final
+                size += std::mem::take(&mut totals[depth + 1]);
+            }
+            totals[depth] += size;
+
+            let shown = (entry.file_type().is_dir() || depth == 0) && !repeat;
+            if shown && config.max_depth.is_none_or(|max| depth <= max) {
+                writeln!(out, "{}\t{}", format(size), entry.path().display())?;
+            }
+        }
+    }
+    out.flush()?;
+    Ok(status)
+}
@@ -0,0 +1,151 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::{fs, path::Path};
+use tempfile::TempDir;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "dur";
+
+// --------------------------------------------------
+/// A tree to measure: top/a/b/five, of 5000 bytes, and top/one, of 1000.
+fn make_tree() -> std::io::Result<TempDir> {
+    let dir = tempfile::tempdir()?;
+    fs::create_dir_all(dir.path().join("top/a/b"))?;
+    fs::write(dir.path().join("top/a/b/five"), [b'5'; 5000])?;
+    fs::write(dir.path().join("top/one"), [b'1'; 1000])?;
+    Ok(dir)
+}
+
+// --------------------------------------------------
+/// The output of dur with `args`, each line split into size and path.
+fn run(args: &[&str], dir: &Path) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
+    let output = Command::cargo_bin(PRG)?.current_dir(dir).args(args).output()?;
+    assert!(output.status.success());
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .map(|line| {
+            let (size, path) = line.split_once('\t').unwrap();
+            (size.to_string(), path.replace('\\', "/"))
+        })
+        .collect())
+}
+
+// --------------------------------------------------
+/// The sizes of the directories at `paths` themselves, without what's in them.
+fn dir_sizes(dir: &Path, paths: &[&str]) -> std::io::Result<u64> {
+    paths.iter().map(|path| Ok(fs::metadata(dir.join(path))?.len())).sum()
+}
+
+// --------------------------------------------------
+#[test]
+fn skips_bad_path() -> TestResult {
+    let dir = make_tree()?;
+    Command::cargo_bin(PRG)?
+        .current_dir(dir.path())
+        .args(["--apparent-size", "does-not-exist", "top/one"])
+        .assert()
+        .code(1)
+        .stdout("1\ttop/one\n")
+        .stderr(predicate::str::starts_with("does-not-exist: "));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_depth() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-d", "x"])
+        .assert()
+        .code(1)
+        .stderr("invalid maximum depth 'x'\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_summarize_with_depth() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-s", "-d", "1"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn file() -> TestResult {
+    let dir = make_tree()?;
+    // Sizes are in 1024-byte blocks, rounded up
+    let sizes = run(&["--apparent-size", "top/a/b/five", "top/one"], dir.path())?;
+    assert_eq!(sizes, [("5".into(), "top/a/b/five".into()), ("1".into(), "top/one".into())]);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn human_readable() -> TestResult {
+    let dir = make_tree()?;
+    let sizes = run(&["-h", "--apparent-size", "top/a/b/five", "top/one"], dir.path())?;
+    assert_eq!(
+        sizes,
+        [("4.9K".into(), "top/a/b/five".into()), ("1000".into(), "top/one".into())]
+    );
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn tree() -> TestResult {
+    let dir = make_tree()?;
+    let sizes = run(&["top"], dir.path())?;
+    let paths: Vec<&str> = sizes.iter().map(|(_, path)| path.as_str()).collect();
+    // Only directories, each after the ones in it
+    assert_eq!(paths, ["top/a/b", "top/a", "top"]);
+
+    let total = dir_sizes(dir.path(), &["top", "top/a", "top/a/b"])? + 6000;
+    let sizes = run(&["--apparent-size", "-s", "top"], dir.path())?;
+    assert_eq!(sizes, [(total.div_ceil(1024).to_string(), "top".into())]);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn max_depth() -> TestResult {
+    let dir = make_tree()?;
+    let paths = |args: &[&str]| -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        Ok(run(args, dir.path())?.into_iter().map(|(_, path)| path).collect())
+    };
+    assert_eq!(paths(&["-d", "0", "top"])?, ["top"]);
+    assert_eq!(paths(&["-d", "1", "top"])?, ["top/a", "top"]);
+    assert_eq!(paths(&["-d", "5", "top"])?, ["top/a/b", "top/a", "top"]);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+#[cfg(unix)]
+fn hard_links_count_once() -> TestResult {
+    let dir = make_tree()?;
+    fs::hard_link(dir.path().join("top/a/b/five"), dir.path().join("top/five"))?;
+    let total = dir_sizes(dir.path(), &["top", "top/a", "top/a/b"])? + 6000;
+    let sizes = run(&["--apparent-size", "-s", "top"], dir.path())?;
+    assert_eq!(sizes, [(total.div_ceil(1024).to_string(), "top".into())]);
+
+    // Even across paths
+    let sizes = run(&["--apparent-size", "top/five", "top/a/b/five"], dir.path())?;
+    assert_eq!(sizes, [("5".into(), "top/five".into())]);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+#[cfg(unix)]
+fn symlinks_not_followed() -> TestResult {
+    let dir = make_tree()?;
+    std::os::unix::fs::symlink("a", dir.path().join("top/link"))?;
+    let paths: Vec<String> = run(&["top/link"], dir.path())?.into_iter().map(|(_, p)| p).collect();
+    assert_eq!(paths, ["top/link"]);
+    Ok(())
+}
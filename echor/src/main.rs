@@ -1,26 +1,119 @@
-use clap::{App, Arg};
+use clap::Parser;
+use std::{
+    io::{self, Write},
+    iter::Peekable,
+    str::Bytes,
+};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "echor",
+    version = "0.0.1",
+    author = "OFFBLACK <offblack.group@gmail.com>",
+    about = "Rust echo"
+)]
+struct Args {
+    /// Input text
+    #[arg(value_name = "TEXT")]
+    text: Vec<String>,
+
+    /// Do not print newline
+    #[arg(short = 'n')]
+    omit_newline: bool,
+
+    /// Interpret backslash escapes
+    #[arg(short = 'e', overrides_with = "no_escapes")]
+    escapes: bool,
+
+    /// Do not interpret backslash escapes (the default)
+    #[arg(short = 'E', overrides_with = "escapes")]
+    no_escapes: bool,
+}
+
+/// Expands the escapes of GNU echo -e in `text`. Returns false as well if
+/// a \c said to stop, so nothing after it, not even the newline, is printed.
+fn unescape(text: &str) -> (Vec<u8>, bool) {
+    let mut out = Vec::with_capacity(text.len());
+    let mut bytes = text.bytes().peekable();
+    while let Some(byte) = bytes.next() {
+        if byte != b'\\' {
+            out.push(byte);
+            continue;
+        }
+        let escaped = match bytes.next() {
+            Some(b'\\') => b'\\',
+            Some(b'a') => 0x07,
+            Some(b'b') => 0x08,
+            Some(b'c') => return (out, false),
+            Some(b'e') => 0x1b,
+            Some(b'f') => 0x0c,
+            Some(b'n') => b'\n',
+            Some(b'r') => b'\r',
+            Some(b't') => b'\t',
+            Some(b'v') => 0x0b,
+            Some(b'0') => take_number(&mut bytes, 8, 3),
+            Some(b'x') if bytes.peek().is_some_and(u8::is_ascii_hexdigit) => {
+                take_number(&mut bytes, 16, 2)
+            }
+            // Anything else is printed as it is
+            Some(other) => {
+                out.push(b'\\');
+                other
+            }
+            None => b'\\',
+        };
+        out.push(escaped);
+    }
+    (out, true)
+}
+
+/// Reads a number of up to `max` digits in `radix`, as the value of a byte.
+fn take_number(bytes: &mut Peekable<Bytes>, radix: u32, max: usize) -> u8 {
+    let mut value = 0u32;
+    for _ in 0..max {
+        match bytes.peek().and_then(|&b| char::from(b).to_digit(radix)) {
+            Some(digit) => {
+                value = value * radix + digit;
+                bytes.next();
+            }
+            None => break,
+        }
+    }
+    // Like GNU, \0777 wraps around
+    value as u8
+}
 
 fn main() {
-    let matches = App::new("echor")
-        .version("0.0.1")
-        .author("OFFBLACK <offblack.group@gmail.com>")
-        .about("Rust echo")
-        .arg(
-            Arg::with_name("text")
-                .value_name("TEXT")
-                .help("Input text")
-                .required(true)
-                .min_values(1)
-        )
-        .arg(
-            Arg::with_name("omit_newline")
-                .short("n")
-                .help("Do not print newline")
-                .takes_value(false)
-        )
-        .get_matches();
-
-    let text = matches.values_of_lossy("text").unwrap();
-    let omit_newline = matches.is_present("omit_newline");
-    print!("{}{}", text.join(" "), if omit_newline {""} else {"\n"});
+    coreutils_common::reset_sigpipe();
+    let args = Args::parse();
+    let text = args.text.join(" ");
+    let (mut out, newline) = if args.escapes {
+        unescape(&text)
+    } else {
+        (text.into_bytes(), true)
+    };
+    if newline && !args.omit_newline {
+        out.push(b'\n');
+    }
+    if let Err(e) = io::stdout().write_all(&out) {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::unescape;
+
+    #[test]
+    fn test_unescape() {
+        assert_eq!(unescape("a\\tb\\nc\\\\"), (b"a\tb\nc\\".to_vec(), true));
+        assert_eq!(unescape("\\0101\\01\\0"), (b"A\x01\0".to_vec(), true));
+        // At most three octal digits follow the 0
+        assert_eq!(unescape("\\01234"), (b"\x534".to_vec(), true));
+        assert_eq!(unescape("\\x41\\xe9\\x4"), (b"A\xe9\x04".to_vec(), true));
+        assert_eq!(unescape("\\xg"), (b"\\xg".to_vec(), true));
+        assert_eq!(unescape("\\q\\"), (b"\\q\\".to_vec(), true));
+        assert_eq!(unescape("ab\\cde"), (b"ab".to_vec(), false));
+    }
 }
@@ -1,5 +1,4 @@
 use assert_cmd::Command;
-use predicates::prelude::*;
 use std::fs;
 
 type TestResult = Result<(), Box<dyn std::error::Error>>;
@@ -15,11 +14,10 @@ fn run(args: &[&str], expected_file: &str) -> TestResult {
 }
  
 #[test]
-fn dies_no_args() -> TestResult {
-    let mut cmd = Command::cargo_bin("echor")?;
-    cmd.assert()
-        .failure()
-        .stderr(predicate::str::contains("USAGE"));
+fn no_args_prints_newline() -> TestResult {
+    // Like echo, with nothing to print there's still the newline
+    Command::cargo_bin("echor")?.assert().success().stdout("\n");
+    Command::cargo_bin("echor")?.arg("-n").assert().success().stdout("");
     Ok(())
 }
 
@@ -42,3 +40,29 @@ fn hello1_no_newline() -> TestResult {
 fn hello2_no_newline() -> TestResult {
     run(&["-n", "Hello", "there"], "tests/expected/hello2.n.txt")
 }
+
+#[test]
+fn escapes() -> TestResult {
+    Command::cargo_bin("echor")?
+        .args(["-e", "a\\tb", "c\\x41\\0102\\\\"])
+        .assert()
+        .success()
+        .stdout("a\tb cAB\\\n");
+    // Without -e, or when -E comes last, backslashes are left alone
+    Command::cargo_bin("echor")?
+        .args(["-e", "-E", "a\\tb"])
+        .assert()
+        .success()
+        .stdout("a\\tb\n");
+    Ok(())
+}
+
+#[test]
+fn escape_c_stops_output() -> TestResult {
+    Command::cargo_bin("echor")?
+        .args(["-e", "Hello\\c", "there"])
+        .assert()
+        .success()
+        .stdout("Hello");
+    Ok(())
+}
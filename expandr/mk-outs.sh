@@ -0,0 +1,23 @@
+#!/usr/bin/env bash
+
+# Writes the expected output of each test with GNU expand and unexpand
+
+OUTDIR="tests/expected"
+[[ ! -d "$OUTDIR" ]] && mkdir -p "$OUTDIR"
+
+TABS="tests/inputs/tabs.txt"
+SPACES="tests/inputs/spaces.txt"
+EMPTY="tests/inputs/empty.txt"
+
+expand $EMPTY > $OUTDIR/empty.txt.expand.out
+expand $TABS > $OUTDIR/tabs.txt.expand.out
+expand -t 4 $TABS > $OUTDIR/tabs.txt.expand.t4.out
+expand -t 3,10,+4 $TABS > $OUTDIR/tabs.txt.expand.tlist.out
+expand -i $TABS > $OUTDIR/tabs.txt.expand.i.out
+expand $TABS $SPACES > $OUTDIR/all.expand.out
+
+unexpand $SPACES > $OUTDIR/spaces.txt.unexpand.out
+unexpand -a $SPACES > $OUTDIR/spaces.txt.unexpand.a.out
+unexpand -t 4 $SPACES > $OUTDIR/spaces.txt.unexpand.t4.out
+unexpand --first-only -t 4 $SPACES > $OUTDIR/spaces.txt.unexpand.first.out
+unexpand -a $TABS > $OUTDIR/tabs.txt.unexpand.a.out
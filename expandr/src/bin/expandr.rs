@@ -0,0 +1,12 @@
+use expandr::expand;
+
+fn main() {
+    coreutils_common::reset_sigpipe();
+    match expand::get_args().and_then(expand::run) {
+        Ok(status) => std::process::exit(status),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(e.exit_code());
+        }
+    }
+}
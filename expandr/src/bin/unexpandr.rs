@@ -0,0 +1,12 @@
+use expandr::unexpand;
+
+fn main() {
+    coreutils_common::reset_sigpipe();
+    match unexpand::get_args().and_then(unexpand::run) {
+        Ok(status) => std::process::exit(status),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(e.exit_code());
+        }
+    }
+}
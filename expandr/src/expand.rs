@@ -0,0 +1,87 @@
+//! expandr: tabs to spaces.
+
+use clap::Parser;
+
+use crate::{convert_lines, tabs::TabStops};
+use coreutils_common::{parse_args, MyResult};
+
+#[derive(Debug)]
+pub struct Config {
+    files: Vec<String>,
+    tabs: TabStops,
+    initial: bool,
+}
+
+#[derive(Debug, Parser)]
+#[command(name = "expandr", author = "OFFBLACK", version = "0.1.0", about = "Rust expand")]
+struct Args {
+    /// Input file(s)
+    #[arg(value_name = "FILE", default_value = "-")]
+    files: Vec<String>,
+
+    /// Put tab stops every N columns, or at the columns in LIST
+    #[arg(short = 't', long, value_name = "LIST")]
+    tabs: Option<String>,
+
+    /// Only convert the tabs before the first non-blank of each line
+    #[arg(short = 'i', long)]
+    initial: bool,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let args: Args = parse_args();
+
+    Ok(Config {
+        files: args.files,
+        tabs: args.tabs.as_deref().map(TabStops::parse).transpose()?.unwrap_or_default(),
+        initial: args.initial,
+    })
+}
+
+/// `line` with its tabs replaced by spaces up to the next tab stop, or by
+/// one space past the last stop. A backspace moves back a column.
+fn expand(line: &str, tabs: &TabStops, initial: bool) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut column = 0;
+    let mut convert = true;
+    for c in line.chars() {
+        if convert {
+            match c {
+                '\t' => {
+                    let next = tabs.next(column).unwrap_or(column + 1);
+                    out.extend(std::iter::repeat_n(' ', next - column));
+                    column = next;
+                    continue;
+                }
+                '\x08' => column = column.saturating_sub(1),
+                _ => column += 1,
+            }
+            convert = !initial || c == ' ';
+        }
+        out.push(c);
+    }
+    out
+}
+
+pub fn run(config: Config) -> MyResult<i32> {
+    convert_lines(&config.files, |line| expand(line, &config.tabs, config.initial))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expand, TabStops};
+
+    #[test]
+    fn test_expand() {
+        let every = |size: &str| TabStops::parse(size).unwrap();
+        assert_eq!(expand("a\tbc\td", &TabStops::default(), false), "a       bc      d");
+        assert_eq!(expand("\tab\tc", &every("4"), false), "    ab  c");
+        assert_eq!(expand("\tab\tc", &every("4"), true), "    ab\tc");
+        assert_eq!(expand(" \tx", &every("4"), true), "    x");
+        assert_eq!(expand("ab\x08\tc", &every("4"), false), "ab\x08   c");
+        // Columns are characters, not bytes
+        assert_eq!(expand("é\tx", &every("4"), false), "é   x");
+        // Past the last stop, a tab is one space
+        assert_eq!(expand("a\tb\tc\td", &every("3,6"), false), "a  b  c d");
+    }
+}
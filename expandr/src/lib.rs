@@ -0,0 +1,43 @@
+//! `expandr` and `unexpandr`, which turn tabs into spaces and back. Both
+//! work out where the tab stops are with the `tabs` module.
+
+use std::io::{BufRead, Write};
+
+use coreutils_common::{open, MyResult, Output};
+
+pub mod expand;
+mod tabs;
+pub mod unexpand;
+
+/// Prints every line of `files` as `convert` rewrites it, and returns the
+/// exit status: 1 if any file failed to open.
+fn convert_lines(files: &[String], convert: impl Fn(&str) -> String) -> MyResult<i32> {
+    let mut out = Output::stdout();
+    let mut status = 0;
+    let mut line = String::new();
+    for filename in files {
+        let mut file = match open(filename) {
+            Ok(file) => file,
+            Err(e) => {
+                out.flush()?;
+                eprintln!("{e}");
+                status = 1;
+                continue;
+            }
+        };
+        loop {
+            line.clear();
+            if file.read_line(&mut line)? == 0 {
+                break;
+            }
+            // The tab stops start over on each line
+            let (content, newline) = match line.strip_suffix('\n') {
+                Some(content) => (content, "\n"),
+                None => (line.as_str(), ""),
+            };
+            write!(out, "{}{newline}", convert(content))?;
+        }
+    }
+    out.flush()?;
+    Ok(status)
+}
@@ -0,0 +1,165 @@
+//! Tab stops as given to -t: one size for a stop every N columns, or a list
+//! of columns, the last of which may say how the stops carry on.
+
+use coreutils_common::{CoreError, MyResult};
+
+#[derive(Debug, PartialEq)]
+pub struct TabStops {
+    /// The columns listed, ascending
+    stops: Vec<usize>,
+    /// Where the stops are past the last one listed
+    rest: Rest,
+}
+
+#[derive(Debug, PartialEq)]
+enum Rest {
+    /// Nowhere
+    None,
+    /// At every multiple of N, from "/N" or a lone size
+    Every(usize),
+    /// Every N columns from the last stop, from "+N"
+    Increment(usize),
+}
+
+impl Default for TabStops {
+    fn default() -> Self {
+        TabStops { stops: vec![], rest: Rest::Every(8) }
+    }
+}
+
+impl TabStops {
+    /// Parses a -t LIST of sizes or columns, separated by commas or blanks.
+    pub fn parse(list: &str) -> MyResult<Self> {
+        let err = |msg: String| CoreError::Parse(msg);
+        // Each value with its '/' or '+' prefix, if any
+        let mut values: Vec<(Option<char>, usize)> = vec![];
+        let mut current: Option<(Option<char>, usize)> = None;
+        let mut start = 0;
+        for (i, c) in list.char_indices() {
+            match c {
+                ',' | ' ' | '\t' => values.extend(current.take()),
+                '/' | '+' if current.is_none() => {
+                    current = Some((Some(c), 0));
+                    start = i + 1;
+                }
+                '/' | '+' => {
+                    return Err(err(format!(
+                        "'{c}' specifier not at start of number: '{}'",
+                        &list[i..]
+                    )))
+                }
+                '0'..='9' => {
+                    if current.is_none() {
+                        start = i;
+                    }
+                    let (_, value) = current.get_or_insert((None, 0));
+                    *value = value
+                        .checked_mul(10)
+                        .and_then(|value| value.checked_add(c as usize - '0' as usize))
+                        .ok_or_else(|| {
+                            let digits = list[start..].split(|c: char| !c.is_ascii_digit());
+                            let digits = digits.into_iter().next().unwrap_or_default();
+                            err(format!("tab stop is too large '{digits}'"))
+                        })?;
+                }
+                _ => {
+                    return Err(err(format!(
+                        "tab size contains invalid character(s): '{}'",
+                        &list[i..]
+                    )))
+                }
+            }
+        }
+        values.extend(current);
+
+        let mut rest = None;
+        if let Some(&(Some(prefix), size)) = values.last() {
+            values.pop();
+            rest = Some(match (prefix, size) {
+                (_, 0) => Rest::None,
+                ('/', size) => Rest::Every(size),
+                (_, size) => Rest::Increment(size),
+            });
+        }
+        let mut stops = Vec::with_capacity(values.len());
+        for (prefix, stop) in values {
+            if let Some(prefix) = prefix {
+                return Err(err(format!("'{prefix}' specifier only allowed with the last value")));
+            }
+            if stop == 0 {
+                return Err(err("tab size cannot be 0".to_string()));
+            }
+            if stops.last().is_some_and(|&last| last >= stop) {
+                return Err(err("tab sizes must be ascending".to_string()));
+            }
+            stops.push(stop);
+        }
+
+        Ok(match (stops.as_slice(), rest) {
+            ([], None) => TabStops::default(),
+            // A lone size is the distance between stops
+            (&[size], None) => TabStops { stops: vec![], rest: Rest::Every(size) },
+            (_, rest) => TabStops { stops, rest: rest.unwrap_or(Rest::None) },
+        })
+    }
+
+    /// The first stop after `column`, or None if it is past the last one.
+    pub fn next(&self, column: usize) -> Option<usize> {
+        if let Some(&stop) = self.stops.iter().find(|&&stop| stop > column) {
+            return Some(stop);
+        }
+        let last = self.stops.last().copied().unwrap_or(0);
+        match self.rest {
+            Rest::None => None,
+            Rest::Every(size) => Some(column + size - column % size),
+            Rest::Increment(size) => Some(last + ((column - last) / size + 1) * size),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TabStops;
+
+    fn stops(list: &str, upto: usize) -> Vec<usize> {
+        let tabs = TabStops::parse(list).unwrap();
+        let mut stops = vec![];
+        let mut column = 0;
+        while let Some(stop) = tabs.next(column).filter(|&stop| stop <= upto) {
+            stops.push(stop);
+            column = stop;
+        }
+        stops
+    }
+
+    #[test]
+    fn test_next() {
+        assert_eq!(stops("", 24), [8, 16, 24]);
+        assert_eq!(stops("4", 12), [4, 8, 12]);
+        assert_eq!(stops(" ,4", 12), [4, 8, 12]);
+        assert_eq!(stops("2,5 9", 20), [2, 5, 9]);
+        assert_eq!(stops("2,/4", 12), [2, 4, 8, 12]);
+        assert_eq!(stops("3,+4", 12), [3, 7, 11]);
+        assert_eq!(stops("2,/0", 12), [2]);
+        assert_eq!(stops("/3", 9), [3, 6, 9]);
+
+        let tabs = TabStops::parse("3,+4").unwrap();
+        assert_eq!(tabs.next(8), Some(11));
+        assert_eq!(TabStops::parse("2,5").unwrap().next(5), None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = |list| TabStops::parse(list).unwrap_err().to_string();
+        assert_eq!(err("0"), "tab size cannot be 0");
+        assert_eq!(err("4,2"), "tab sizes must be ascending");
+        assert_eq!(err("4,4"), "tab sizes must be ascending");
+        assert_eq!(err("4x,8"), "tab size contains invalid character(s): 'x,8'");
+        assert_eq!(err("/4,8"), "'/' specifier only allowed with the last value");
+        assert_eq!(err("4+"), "'+' specifier not at start of number: '+'");
+        assert_eq!(
+            err("2,99999999999999999999999"),
+            "tab stop is too large '99999999999999999999999'"
+        );
+    }
+}
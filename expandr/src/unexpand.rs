@@ -0,0 +1,128 @@
+//! unexpandr: spaces to tabs.
+
+use clap::Parser;
+
+use crate::{convert_lines, tabs::TabStops};
+use coreutils_common::{parse_args, MyResult};
+
+#[derive(Debug)]
+pub struct Config {
+    files: Vec<String>,
+    tabs: TabStops,
+    all: bool,
+}
+
+#[derive(Debug, Parser)]
+#[command(name = "unexpandr", author = "OFFBLACK", version = "0.1.0", about = "Rust unexpand")]
+struct Args {
+    /// Input file(s)
+    #[arg(value_name = "FILE", default_value = "-")]
+    files: Vec<String>,
+
+    /// Convert all blanks, not just the ones at the start of each line
+    #[arg(short = 'a', long)]
+    all: bool,
+
+    /// Only convert the blanks at the start of each line, even with -t
+    #[arg(long)]
+    first_only: bool,
+
+    /// Put tab stops every N columns, or at the columns in LIST (implies -a)
+    #[arg(short = 't', long, value_name = "LIST")]
+    tabs: Option<String>,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let args: Args = parse_args();
+
+    Ok(Config {
+        files: args.files,
+        all: !args.first_only && (args.all || args.tabs.is_some()),
+        tabs: args.tabs.as_deref().map(TabStops::parse).transpose()?.unwrap_or_default(),
+    })
+}
+
+/// `line` with each run of blanks that reaches a tab stop replaced by a tab,
+/// as GNU unexpand does. A lone space just before a stop is only replaced if
+/// more blanks follow it, and nothing past the last stop is converted.
+fn unexpand(line: &str, tabs: &TabStops, all: bool) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut column = 0;
+    let mut convert = true;
+    // Blanks that may yet become a tab
+    let mut pending = String::new();
+    let mut one_blank_before_stop = false;
+    let mut prev_blank = true;
+    for c in line.chars().map(Some).chain([None]) {
+        let blank = matches!(c, Some(' ' | '\t'));
+        let mut c = c;
+        if blank && convert {
+            match tabs.next(column) {
+                None => convert = false,
+                Some(next) => {
+                    if c == Some(' ') {
+                        column += 1;
+                        if !(prev_blank && column == next) {
+                            one_blank_before_stop |= column == next;
+                            pending.push(' ');
+                            prev_blank = true;
+                            continue;
+                        }
+                    } else {
+                        column = next;
+                    }
+                    // The blanks up to the stop become one tab; a lone
+                    // space before the previous stop becomes another
+                    pending.clear();
+                    if one_blank_before_stop {
+                        pending.push('\t');
+                    }
+                    c = Some('\t');
+                }
+            }
+        } else if c == Some('\x08') {
+            column = column.saturating_sub(1);
+        } else if !blank {
+            column += 1;
+        }
+
+        if pending.len() > 1 && one_blank_before_stop {
+            pending.replace_range(..1, "\t");
+        }
+        out.push_str(&pending);
+        pending.clear();
+        one_blank_before_stop = false;
+
+        prev_blank = blank;
+        convert &= all || blank;
+        out.extend(c);
+    }
+    out
+}
+
+pub fn run(config: Config) -> MyResult<i32> {
+    convert_lines(&config.files, |line| unexpand(line, &config.tabs, config.all))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{unexpand, TabStops};
+
+    #[test]
+    fn test_unexpand() {
+        let tabs = TabStops::default();
+        let every = |size: &str| TabStops::parse(size).unwrap();
+        assert_eq!(unexpand("        x  y        z", &tabs, false), "\tx  y        z");
+        assert_eq!(unexpand("        x  y        z", &tabs, true), "\tx  y\t    z");
+        assert_eq!(unexpand("1234567 x", &tabs, true), "1234567 x");
+        assert_eq!(unexpand("1234567  x", &tabs, true), "1234567\t x");
+        assert_eq!(unexpand("1234567         x", &tabs, true), "1234567\t\tx");
+        assert_eq!(unexpand("1234567 \tx", &tabs, true), "1234567\t\tx");
+        assert_eq!(unexpand("1234567 ", &tabs, true), "1234567 ");
+        assert_eq!(unexpand("  \t x", &tabs, true), "\t x");
+        assert_eq!(unexpand(" x", &every("1"), true), "\tx");
+        assert_eq!(unexpand("a\x08  x", &every("2"), true), "a\x08\tx");
+        // Nothing is converted past the last stop
+        assert_eq!(unexpand("x      y", &every("3,5"), true), "x\t\t  y");
+    }
+}
@@ -0,0 +1,155 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const EXPAND: &str = "expandr";
+const UNEXPAND: &str = "unexpandr";
+const TABS: &str = "tests/inputs/tabs.txt";
+const SPACES: &str = "tests/inputs/spaces.txt";
+const EMPTY: &str = "tests/inputs/empty.txt";
+
+// --------------------------------------------------
+fn run(prg: &str, args: &[&str], expected_file: &str) -> TestResult {
+    let expected = fs::read_to_string(expected_file)?;
+    Command::cargo_bin(prg)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn skips_bad_file() -> TestResult {
+    Command::cargo_bin(EXPAND)?
+        .args([EMPTY, "tests/inputs/does-not-exist", TABS])
+        .assert()
+        .code(1)
+        .stdout(fs::read_to_string("tests/expected/tabs.txt.expand.out")?)
+        .stderr(predicate::str::starts_with("tests/inputs/does-not-exist: "));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_tabs() -> TestResult {
+    for (tabs, msg) in [
+        ("0", "tab size cannot be 0"),
+        ("8,4", "tab sizes must be ascending"),
+        ("4x", "tab size contains invalid character(s): 'x'"),
+    ] {
+        for prg in [EXPAND, UNEXPAND] {
+            Command::cargo_bin(prg)?
+                .args(["-t", tabs, TABS])
+                .assert()
+                .code(1)
+                .stdout("")
+                .stderr(format!("{msg}\n"));
+        }
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn expand_empty() -> TestResult {
+    run(EXPAND, &[EMPTY], "tests/expected/empty.txt.expand.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn expand_tabs() -> TestResult {
+    run(EXPAND, &[TABS], "tests/expected/tabs.txt.expand.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn expand_tabs_stdin() -> TestResult {
+    let input = fs::read_to_string(TABS)?;
+    let expected = fs::read_to_string("tests/expected/tabs.txt.expand.out")?;
+    Command::cargo_bin(EXPAND)?
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn expand_tab_size() -> TestResult {
+    run(EXPAND, &["-t", "4", TABS], "tests/expected/tabs.txt.expand.t4.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn expand_tab_list() -> TestResult {
+    run(
+        EXPAND,
+        &["--tabs", "3,10,+4", TABS],
+        "tests/expected/tabs.txt.expand.tlist.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn expand_initial() -> TestResult {
+    run(EXPAND, &["-i", TABS], "tests/expected/tabs.txt.expand.i.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn expand_all() -> TestResult {
+    run(EXPAND, &[TABS, SPACES], "tests/expected/all.expand.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn unexpand_spaces() -> TestResult {
+    run(UNEXPAND, &[SPACES], "tests/expected/spaces.txt.unexpand.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn unexpand_all() -> TestResult {
+    run(UNEXPAND, &["-a", SPACES], "tests/expected/spaces.txt.unexpand.a.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn unexpand_tab_size() -> TestResult {
+    // -t implies -a
+    run(UNEXPAND, &["-t", "4", SPACES], "tests/expected/spaces.txt.unexpand.t4.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn unexpand_first_only() -> TestResult {
+    run(
+        UNEXPAND,
+        &["--first-only", "-t", "4", SPACES],
+        "tests/expected/spaces.txt.unexpand.first.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn unexpand_tabs() -> TestResult {
+    run(UNEXPAND, &["-a", TABS], "tests/expected/tabs.txt.unexpand.a.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn round_trip() -> TestResult {
+    // Expanding what unexpandr made gives back the spaces
+    let tabbed = Command::cargo_bin(UNEXPAND)?.args(["-a", SPACES]).output()?;
+    Command::cargo_bin(EXPAND)?
+        .write_stdin(tabbed.stdout)
+        .assert()
+        .success()
+        .stdout(fs::read_to_string(SPACES)?);
+    Ok(())
+}
@@ -1,13 +1,15 @@
 use crate::EntryType::*;
-use clap::{Arg, App};
+use clap::Parser;
 use regex::Regex;
+use serde::Serialize;
+use std::io::Write;
 use walkdir::{DirEntry, WalkDir};
-use std::error::Error;
+use coreutils_common::{
+    parse_args, write_json, CoreError, Delimiter, DelimiterArgs, MyResult, Output,
+};
 
-
-type MyResult<T> = Result<T, Box<dyn Error>>;
-
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
 enum EntryType {
     Dir,
     File,
@@ -19,74 +21,100 @@ pub struct Config {
     paths: Vec<String>,
     names: Vec<Regex>,
     entry_types: Vec<EntryType>,
+    json: bool,
+    delimiter: Delimiter,
+}
+
+/// One line of --json output. The type is null for entries like sockets that
+/// --type can't select.
+#[derive(Debug, Serialize)]
+struct Match {
+    path: String,
+    #[serde(rename = "type")]
+    entry_type: Option<EntryType>,
+}
+
+impl Match {
+    fn new(entry: &DirEntry) -> Self {
+        let file_type = entry.file_type();
+        Match {
+            path: entry.path().display().to_string(),
+            entry_type: if file_type.is_symlink() {
+                Some(Link)
+            } else if file_type.is_dir() {
+                Some(Dir)
+            } else if file_type.is_file() {
+                Some(File)
+            } else {
+                None
+            },
+        }
+    }
+}
+
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust find")]
+struct Args {
+    /// Search paths
+    #[arg(value_name = "PATH", default_value = ".")]
+    paths: Vec<String>,
+
+    /// Name
+    #[arg(short = 'n', long = "name", value_name = "NAME", num_args = 1..)]
+    names: Vec<String>,
+
+    /// Entry type
+    #[arg(
+        short = 't',
+        long = "type",
+        value_name = "TYPE",
+        num_args = 1..,
+        value_parser = ["f", "d", "l"]
+    )]
+    entry_types: Vec<String>,
+
+    /// Print each match as a line of JSON
+    #[arg(long)]
+    json: bool,
+
+    #[command(flatten)]
+    delimiter: DelimiterArgs,
 }
 
 pub fn get_args() -> MyResult<Config> {
-    let matches = App::new("findr")
-        .about("Rust find")
-        .author("OFFBLACK")
-        .version("0.1.0")
-        .arg(
-            Arg::with_name("name")
-                .multiple(true)
-                .short("n")
-                .long("name")
-                .help("Name")
-                .value_name("NAME")
-                .takes_value(true)
-        )
-        .arg(
-            Arg::with_name("type")
-                .multiple(true)
-                .short("t")
-                .long("type")
-                .possible_values(&["f", "d", "l"])
-                .help("Entry type")
-                .value_name("TYPE")
-                .takes_value(true)
-        )
-        .arg(
-            Arg::with_name("paths")
-                .default_value(".")
-                .multiple(true)
-                .value_name("PATH")
-                .help("Search paths")
-        )
-       .get_matches();
-
-    let names = matches.values_of_lossy("name")
-        .map(|vals| {
-            vals.into_iter()
-                .map(|name| {
-                    Regex::new(&name)
-                        .map_err(|_| format!("Invalid --name \"{}\"", name))
-                })
-                .collect::<Result<Vec<_>, _>>()
+    let args: Args = parse_args();
+
+    let names = args.names
+        .iter()
+        .map(|name| {
+            Regex::new(name)
+                .map_err(|_| CoreError::Parse(format!("Invalid --name \"{}\"", name)))
         })
-        .transpose()?
-        .unwrap_or_default();
-    
-    let entry_types = matches.values_of_lossy("type")
-        .map(|vals|
-            vals.iter()
-                .map(|val| match val.as_str() {
-                    "d" => Dir,
-                    "f" => File,
-                    "l" => Link,
-                    _ => unreachable!("Invalid type")
-                })
-                .collect()
-        )
-        .unwrap_or_default();
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let entry_types = args.entry_types
+        .iter()
+        .map(|val| match val.as_str() {
+            "d" => Dir,
+            "f" => File,
+            "l" => Link,
+            _ => unreachable!("Invalid type")
+        })
+        .collect();
 
     Ok(Config {
-        paths: matches.values_of_lossy("paths").unwrap(),
+        paths: args.paths,
         names,
         entry_types,
+        json: args.json,
+        delimiter: args.delimiter.delimiter(),
     })
 }
 
-pub fn run(config: Config) -> MyResult<()> {
+/// Prints the matching entries and returns the exit status: 1 if any path
+/// couldn't be searched.
+pub fn run(config: Config) -> MyResult<i32> {
+    let mut status = 0;
     let type_filter = |entry: &DirEntry| {
         config.entry_types.is_empty()
             || config
@@ -107,24 +135,27 @@ pub fn run(config: Config) -> MyResult<()> {
                 .any(|re| re.is_match(&entry.file_name().to_string_lossy()))
     };
 
+    let mut out = Output::stdout().delimiter(config.delimiter);
     for path in &config.paths {
-        let entries = WalkDir::new(path)
-            .into_iter()
-            .filter_map(|entry| {
-                match entry {
-                    Ok(entry) => Some(entry),
-                    Err(e) => {
-                        eprintln!("{e}");
-                        None
+        for entry in WalkDir::new(path) {
+            match entry {
+                Err(e) => {
+                    // Keep the error in order with the output before it
+                    out.flush()?;
+                    eprintln!("{e}");
+                    status = 1;
+                }
+                Ok(entry) if type_filter(&entry) && name_filter(&entry) => {
+                    if config.json {
+                        write_json(&mut out, &Match::new(&entry))?;
+                    } else {
+                        out.write_record(entry.path().display().to_string())?;
                     }
                 }
-            })
-            .filter(type_filter)
-            .filter(name_filter)
-            .map(|entry| entry.path().display().to_string())
-            .collect::<Vec<_>>();
-
-        println!("{}", entries.join("\n"))
+                Ok(_) => {}
+            }
+        }
     }
-    Ok(())
+    out.flush()?;
+    Ok(status)
 }
@@ -1,7 +1,7 @@
 use assert_cmd::Command;
 use predicates::prelude::*;
 use rand::{distributions::Alphanumeric, Rng};
-use std::{borrow::Cow, fs, path::Path};
+use std::{borrow::Cow, fs};
 
 type TestResult = Result<(), Box<dyn std::error::Error>>;
 
@@ -30,7 +30,7 @@ fn skips_bad_dir() -> TestResult {
     Command::cargo_bin(PRG)?
         .arg(&bad)
         .assert()
-        .success()
+        .code(1)
         .stderr(predicate::str::is_match(expected)?);
     Ok(())
 }
@@ -39,7 +39,7 @@ fn skips_bad_dir() -> TestResult {
 #[test]
 fn dies_bad_name() -> TestResult {
     Command::cargo_bin(PRG)?
-        .args(&["--name", "*.csv"])
+        .args(["--name", "*.csv"])
         .assert()
         .failure()
         .stderr(predicate::str::contains("Invalid --name \"*.csv\""));
@@ -49,9 +49,9 @@ fn dies_bad_name() -> TestResult {
 // --------------------------------------------------
 #[test]
 fn dies_bad_type() -> TestResult {
-    let expected = "error: 'x' isn't a valid value for '--type <TYPE>...'";
+    let expected = "error: invalid value 'x' for '--type <TYPE>...'";
     Command::cargo_bin(PRG)?
-        .args(&["--type", "x"])
+        .args(["--type", "x"])
         .assert()
         .failure()
         .stderr(predicate::str::contains(expected));
@@ -60,14 +60,14 @@ fn dies_bad_type() -> TestResult {
 
 // --------------------------------------------------
 #[cfg(windows)]
-fn format_file_name(expected_file: &str) -> Cow<str> {
+fn format_file_name(expected_file: &str) -> Cow<'_, str> {
     // Equivalent to: Cow::Owned(format!("{}.windows", expected_file))
     format!("{}.windows", expected_file).into()
 }
 
 // --------------------------------------------------
 #[cfg(not(windows))]
-fn format_file_name(expected_file: &str) -> Cow<str> {
+fn format_file_name(expected_file: &str) -> Cow<'_, str> {
     // Equivalent to: Cow::Borrowed(expected_file)
     expected_file.into()
 }
@@ -125,6 +125,28 @@ fn path_a_b_d() -> TestResult {
     )
 }
 
+// --------------------------------------------------
+#[test]
+fn path_a_zero_terminated() -> TestResult {
+    let file = format_file_name("tests/expected/path_a.txt");
+    let contents = fs::read_to_string(file.as_ref())?;
+    let mut expected: Vec<&str> =
+        contents.split("\n").filter(|s| !s.is_empty()).collect();
+    expected.sort();
+
+    let cmd = Command::cargo_bin(PRG)?
+        .args(["-z", "tests/inputs/a"])
+        .assert()
+        .success();
+    let stdout = String::from_utf8(cmd.get_output().stdout.clone())?;
+    assert!(stdout.ends_with('\0') && !stdout.contains('\n'));
+    let mut paths: Vec<&str> = stdout.split_terminator('\0').collect();
+    paths.sort();
+
+    assert_eq!(paths, expected);
+    Ok(())
+}
+
 // --------------------------------------------------
 #[test]
 fn type_f() -> TestResult {
@@ -215,6 +237,18 @@ fn type_l() -> TestResult {
     run(&["tests/inputs", "-t", "l"], "tests/expected/type_l.txt")
 }
 
+// --------------------------------------------------
+#[test]
+fn path_a_json() -> TestResult {
+    run(&["--json", "tests/inputs/a"], "tests/expected/path_a_json.txt")
+}
+
+// --------------------------------------------------
+#[test]
+fn type_l_json() -> TestResult {
+    run(&["--json", "tests/inputs", "-t", "l"], "tests/expected/type_l_json.txt")
+}
+
 // --------------------------------------------------
 #[test]
 fn type_f_l() -> TestResult {
@@ -286,7 +320,7 @@ fn path_g() -> TestResult {
 #[cfg(not(windows))]
 fn unreadable_dir() -> TestResult {
     let dirname = "tests/inputs/cant-touch-this";
-    if !Path::new(dirname).exists() {
+    if !std::path::Path::new(dirname).exists() {
         fs::create_dir(dirname)?;
     }
 
@@ -295,7 +329,7 @@ fn unreadable_dir() -> TestResult {
     //permissions.set_mode(0o000);
 
     std::process::Command::new("chmod")
-        .args(&["000", dirname])
+        .args(["000", dirname])
         .status()
         .expect("failed");
 
@@ -1,17 +1,32 @@
-use std::{error::Error, fs::{self, File}, io::{BufRead, BufReader}, path::{Path, PathBuf}};
+use std::{
+    env,
+    fs::{self, File},
+    io::{BufRead, BufReader, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
 
-use clap::{App, Arg};
-use rand::{rngs::{StdRng, ThreadRng}, seq::SliceRandom, SeedableRng};
+use clap::Parser;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use regex::{Regex, RegexBuilder};
 use walkdir::WalkDir;
 
-type MyResult<T> = Result<T, Box<dyn Error>>;
+use coreutils_common::{parse_args, CoreError, MyResult};
+
+/// Searched when no sources are given and FORTUNE_PATH is unset.
+const DEFAULT_FORTUNE_DIR: &str = "/usr/share/games/fortunes";
 
 #[derive(Debug)]
 pub struct Config {
     sources: Vec<String>,
     pattern: Option<Regex>,
     seed: Option<u64>,
+    length: usize,
+    short_only: bool,
+    long_only: bool,
+    all: bool,
+    offensive: bool,
+    equal: bool,
+    weights: Vec<(String, u32)>,
 }
 
 #[derive(Debug)]
@@ -20,74 +35,141 @@ pub struct Fortune {
     text: String,
 }
 
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust fortune")]
+struct Args {
+    /// Input file(s) [default: $FORTUNE_PATH or /usr/share/games/fortunes]
+    #[arg(value_name = "FILE")]
+    sources: Vec<String>,
+
+    /// Ignore case for -m patterns
+    #[arg(short = 'i', long)]
+    insensitive: bool,
+
+    /// Pattern
+    #[arg(short = 'm', long, value_name = "PATTERN")]
+    pattern: Option<String>,
+
+    /// Print every fortune instead of a random one
+    #[arg(short = 'a', long)]
+    all: bool,
+
+    /// Also choose from potentially offensive fortunes
+    #[arg(short = 'o', long)]
+    offensive: bool,
+
+    /// Make every source file equally likely
+    #[arg(short = 'e')]
+    equal: bool,
+
+    /// Give the source FILE a relative weight of N
+    #[arg(long = "weight", value_name = "FILE:N")]
+    weights: Vec<String>,
+
+    /// Longest fortune length considered short
+    #[arg(short = 'n', value_name = "CHARS", default_value = "160")]
+    length: String,
+
+    /// Only show short fortunes
+    #[arg(short = 's')]
+    short: bool,
+
+    /// Only show long fortunes
+    #[arg(short = 'l', conflicts_with = "short")]
+    long: bool,
+
+    /// Random seed
+    #[arg(long, value_name = "SEED")]
+    seed: Option<String>,
+}
+
 pub fn get_args() -> MyResult<Config> {
-    let matches = App::new("fortuner")
-        .about("Rust fortune")
-        .author("OFFBLACK")
-        .version("0.1.0")
-        .arg(
-            Arg::with_name("sources")
-                .multiple(true)
-                .value_name("FILE")
-                .help("Input file(s)")
-                .required(true)
-        )
-        .arg(
-            Arg::with_name("insensitive")
-                .short("i")
-                .long("insensitive")
-                .help("Ignore case for -m patterns")
-        )
-        .arg(
-            Arg::with_name("pattern")
-                .short("m")
-                .long("pattern")
-                .value_name("PATTERN")
-                .help("Pattern")
-        )
-        .arg(
-            Arg::with_name("seed")
-                .short("s")
-                .long("seed")
-                .help("Random seed")
-                .value_name("SEED")
-        )
-        .get_matches();
+    let args: Args = parse_args();
 
-    let pattern = matches
-        .value_of("pattern")
+    let pattern = args
+        .pattern
         .map(|p| {
-            RegexBuilder::new(p)
-                .case_insensitive(matches.is_present("insensitive"))
+            RegexBuilder::new(&p)
+                .case_insensitive(args.insensitive)
                 .build()
-            .map_err(|_| format!("Invalid --pattern \"{p}\""))
+                .map_err(|_| CoreError::Parse(format!("Invalid --pattern \"{p}\"")))
         })
         .transpose()?;
 
-    let seed = matches.value_of("seed")
-        .map(|s| s.parse().map_err(|_| format!("\"{s}\" not a valid integer")))
+    let seed = args
+        .seed
+        .map(|s| {
+            s.parse()
+                .map_err(|_| CoreError::Parse(format!("\"{s}\" not a valid integer")))
+        })
         .transpose()?;
 
+    let length = args
+        .length
+        .parse()
+        .map_err(|_| CoreError::Parse(format!("\"{}\" not a valid length", args.length)))?;
+
+    let weights = args
+        .weights
+        .iter()
+        .map(|val| {
+            val.rsplit_once(':')
+                .and_then(|(file, n)| Some((file.to_string(), n.parse().ok()?)))
+                .ok_or_else(|| CoreError::Parse(format!("\"{val}\" not a valid weight")))
+        })
+        .collect::<Result<_, _>>()?;
 
     Ok(Config {
-        sources: matches.values_of_lossy("sources").unwrap(),
+        sources: args.sources,
         pattern,
-        seed    
+        seed,
+        length,
+        short_only: args.short,
+        long_only: args.long,
+        all: args.all,
+        offensive: args.offensive,
+        equal: args.equal,
+        weights,
     })
 }
 
-pub fn find_files(paths: &[String]) -> MyResult<Vec<PathBuf>> {
+/// Sources to search when no sources are given: the colon-separated
+/// FORTUNE_PATH if set, otherwise the compiled-in default. Missing entries
+/// are skipped rather than treated as errors.
+fn default_sources() -> MyResult<Vec<String>> {
+    let dirs: Vec<PathBuf> = match env::var_os("FORTUNE_PATH") {
+        Some(path) if !path.is_empty() => env::split_paths(&path).collect(),
+        _ => vec![PathBuf::from(DEFAULT_FORTUNE_DIR)],
+    };
+    let sources: Vec<String> = dirs
+        .iter()
+        .filter(|dir| dir.exists())
+        .map(|dir| dir.to_string_lossy().into_owned())
+        .collect();
+    if sources.is_empty() {
+        return Err(CoreError::Usage(format!(
+            "No fortune sources given and none found in FORTUNE_PATH or {DEFAULT_FORTUNE_DIR}"
+        )));
+    }
+    Ok(sources)
+}
+
+/// Finds the cookie files under `paths`. Offensive files inside a searched
+/// directory are skipped unless `offensive` is set; files named directly
+/// are always kept.
+pub fn find_files(paths: &[String], offensive: bool) -> MyResult<Vec<PathBuf>> {
     let mut files: Vec<PathBuf> = Vec::new();
-        
+
     for path in paths {
         match fs::metadata(path) {
-            Err(e) => return Err(format!("{path}: {e}").into()),
+            Err(e) => return Err(CoreError::io(path, e)),
             Ok(_) => files.extend(
                 WalkDir::new(path)
                     .into_iter()
                     .filter_map(Result::ok)
-                    .filter(|e| e.file_type().is_file() )
-                    .map(|e| e.path().into())
+                    .filter(|e| e.file_type().is_file() && !is_index(e.path()))
+                    .filter(|e| offensive || e.depth() == 0 || !is_offensive(e.path()))
+                    .map(|e| e.path().into()),
             ),
         }
     }
@@ -98,110 +180,401 @@ pub fn find_files(paths: &[String]) -> MyResult<Vec<PathBuf>> {
     Ok(files)
 }
 
-fn read_fortunes(paths: &[PathBuf]) -> MyResult<Vec<Fortune>> {
+/// Skips the ".dat" index files strfile writes next to each cookie file.
+fn is_index(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "dat")
+}
+
+/// Follows the classic convention of marking offensive cookie files with a
+/// "-o" suffix or keeping them in an "off" directory.
+fn is_offensive(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().ends_with("-o"))
+        || path.parent().is_some_and(|dir| dir.ends_with("off"))
+}
+
+/// Offensive fortunes are stored rot13-encoded so they aren't read by accident.
+fn rot13(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            'a'..='m' | 'A'..='M' => (c as u8 + 13) as char,
+            'n'..='z' | 'N'..='Z' => (c as u8 - 13) as char,
+            _ => c,
+        })
+        .collect()
+}
+
+fn open_cookie(path: &Path) -> MyResult<BufReader<File>> {
+    let file = File::open(path).map_err(|e| CoreError::io(path.to_string_lossy(), e))?;
+    Ok(BufReader::new(file))
+}
+
+/// The lines of a cookie file, as `lines()` gives them, but with anything
+/// that isn't UTF-8 replaced rather than ending the file there.
+fn cookie_lines(
+    reader: BufReader<File>,
+    path: &Path,
+) -> impl Iterator<Item = MyResult<String>> + '_ {
+    reader.split(b'\n').map(move |line| {
+        let line = line.map_err(|e| CoreError::io(path.to_string_lossy(), e))?;
+        let line = line.strip_suffix(b"\r").unwrap_or(&line);
+        Ok(String::from_utf8_lossy(line).into_owned())
+    })
+}
+
+fn read_fortunes(path: &Path) -> MyResult<Vec<Fortune>> {
     let mut fortunes = Vec::new();
     let mut buffer = Vec::new();
-    
-    for path in paths {
-        let basename = path.file_name().unwrap().to_string_lossy().into_owned();
-        let file = File::open(path).map_err(|e| {
-            format!("{}: {}", path.to_string_lossy().into_owned(), e)
-        })?;
-
-        for line in BufReader::new(file).lines().filter_map(Result::ok) {
-            if line == "%" {
-                if !buffer.is_empty() {
-                    fortunes.push(Fortune {
-                        source: basename.clone(),
-                        text: buffer.join("\n")
-                    });
-                    buffer.clear();
-                }
-            } else {
-                buffer.push(line.to_string());
+    let basename = path.file_name().unwrap().to_string_lossy().into_owned();
+    let encoded = is_offensive(path);
+
+    for line in cookie_lines(open_cookie(path)?, path) {
+        let line = line?;
+        if line == "%" {
+            if !buffer.is_empty() {
+                fortunes.push(Fortune {
+                    source: basename.clone(),
+                    text: if encoded {
+                        rot13(&buffer.join("\n"))
+                    } else {
+                        buffer.join("\n")
+                    },
+                });
+                buffer.clear();
             }
+        } else {
+            buffer.push(line.to_string());
         }
     }
     Ok(fortunes)
 }
 
-fn pick_fortune(fortunes: &[Fortune], seed: Option<u64>) -> Option<String> {
-    if let Some(seed) = seed {
-        let mut seed = StdRng::seed_from_u64(seed);
-        fortunes.choose(&mut seed).map(|f| f.text.clone())
+/// Where a fortune starts in its cookie file, and how long it is.
+#[derive(Debug)]
+struct FortuneRef {
+    offset: u64,
+    len: usize,
+}
+
+/// Records the position and length of every fortune in `path` without
+/// keeping their text, so only the chosen one has to be read back.
+fn index_fortunes(path: &Path) -> MyResult<Vec<FortuneRef>> {
+    let mut refs = Vec::new();
+    let mut line = Vec::new();
+    let mut reader = open_cookie(path)?;
+    let mut pos = 0;
+    let mut start = None;
+    let mut len = 0;
+    loop {
+        line.clear();
+        let bytes = reader.read_until(b'\n', &mut line)?;
+        if bytes == 0 {
+            break;
+        }
+        let text = line.strip_suffix(b"\n").unwrap_or(&line);
+        let text = text.strip_suffix(b"\r").unwrap_or(text);
+        if text == b"%" {
+            if let Some(offset) = start.take() {
+                // Lines are joined with a newline, so drop the last one
+                refs.push(FortuneRef {
+                    offset,
+                    len: len - 1,
+                });
+                len = 0;
+            }
+        } else {
+            start.get_or_insert(pos);
+            len += String::from_utf8_lossy(text).chars().count() + 1;
+        }
+        pos += bytes as u64;
+    }
+    Ok(refs)
+}
+
+/// Reads back the fortune starting at `offset`. strfile leaves the offset
+/// after an empty fortune at its delimiter, which is skipped.
+fn read_fortune_at(path: &Path, offset: u64) -> MyResult<String> {
+    let mut reader = open_cookie(path)?;
+    reader.seek(SeekFrom::Start(offset))?;
+    let is_delimiter = |line: &MyResult<String>| matches!(line, Ok(line) if line == "%");
+    let lines = cookie_lines(reader, path)
+        .skip_while(is_delimiter)
+        .take_while(|line| !is_delimiter(line))
+        .collect::<MyResult<Vec<_>>>()?;
+    let text = lines.join("\n");
+    Ok(if is_offensive(path) {
+        rot13(&text)
     } else {
-        let mut seed = rand::thread_rng();
-        fortunes.choose(&mut seed).map(|f| f.text.clone())
+        text
+    })
+}
+
+/// The offsets strfile recorded in the ".dat" index next to `path`, where
+/// each of its fortunes starts, or None if it has no index that can be used.
+fn read_dat(path: &Path) -> Option<Vec<u64>> {
+    let mut dat = path.as_os_str().to_owned();
+    dat.push(".dat");
+    let bytes = fs::read(dat).ok()?;
+    let word = |i: usize| {
+        let word = bytes.get(i * 4..i * 4 + 4)?;
+        Some(u32::from_be_bytes(word.try_into().ok()?))
+    };
+    // The header: version, count, longest and shortest length, flags and the
+    // delimiter, all big-endian, then an offset for each fortune and the end
+    let (version, count) = (word(0)?, word(1)?);
+    if !(1..=2).contains(&version) || bytes.get(20) != Some(&b'%') {
+        return None;
     }
+    let offsets: Vec<u64> = (0..count as usize)
+        .map(|i| word(6 + i).map(u64::from))
+        .collect::<Option<_>>()?;
+    // An index the cookie file has outgrown is no use
+    let len = fs::metadata(path).ok()?.len();
+    offsets
+        .iter()
+        .all(|&offset| offset < len)
+        .then_some(offsets)
 }
 
-pub fn run(config: Config) -> MyResult<()> {
-    let files = find_files(&config.sources)?;
-    let fortunes = read_fortunes(&files)?;
-    if fortunes.is_empty() { 
-        println!("No fortunes found") 
+/// How big each file is, to weigh it by without reading it: how many
+/// fortunes it holds if every file has a strfile index to say, otherwise
+/// how many bytes long it is.
+fn file_sizes(files: &[PathBuf], dats: &[Option<Vec<u64>>]) -> MyResult<Vec<u64>> {
+    if dats.iter().all(Option::is_some) {
+        return Ok(dats
+            .iter()
+            .flatten()
+            .map(|offsets| offsets.len() as u64)
+            .collect());
+    }
+    files
+        .iter()
+        .map(|file| {
+            let metadata =
+                fs::metadata(file).map_err(|e| CoreError::io(file.to_string_lossy(), e))?;
+            Ok(metadata.len())
+        })
+        .collect()
+}
+
+/// Chance of picking each file: its size, 1 for every file with -e, or the
+/// N given by a matching --weight FILE:N. An empty file is never picked.
+fn file_weights(
+    files: &[PathBuf],
+    sizes: &[u64],
+    overrides: &[(String, u32)],
+    equal: bool,
+) -> MyResult<Vec<u32>> {
+    for (name, _) in overrides {
+        if !files.iter().any(|file| is_named(file, name)) {
+            return Err(CoreError::Usage(format!(
+                "--weight {name}: not one of the sources"
+            )));
+        }
+    }
+    Ok(files
+        .iter()
+        .zip(sizes)
+        .map(
+            |(file, &size)| match overrides.iter().find(|(name, _)| is_named(file, name)) {
+                _ if size == 0 => 0,
+                Some((_, weight)) => *weight,
+                None if equal => 1,
+                None => u32::try_from(size).unwrap_or(u32::MAX),
+            },
+        )
+        .collect())
+}
+
+/// Whether `file` is the one a --weight option refers to, by path or basename.
+fn is_named(file: &Path, name: &str) -> bool {
+    file == Path::new(name) || file.file_name().is_some_and(|base| base == name)
+}
+
+/// A random number below `total`, which mustn't be 0. It's drawn as a u32
+/// whenever the total fits, which keeps the picks --seed gives the same as
+/// they have always been.
+fn draw(total: u64, rng: &mut impl Rng) -> u64 {
+    match u32::try_from(total) {
+        Ok(total) => u64::from(rng.gen_range(0..total)),
+        Err(_) => rng.gen_range(0..total),
+    }
+}
+
+/// Picks a file by weight, returning its index.
+fn pick_file(weights: &[u32], rng: &mut impl Rng) -> Option<usize> {
+    // Summed wider than the weights, which can each be up to u32::MAX
+    let total: u64 = weights.iter().map(|&weight| u64::from(weight)).sum();
+    if total == 0 {
+        return None;
+    }
+    // With only one file to pick, its fortune gets the first draw
+    let mut picked = weights.iter().enumerate().filter(|(_, &weight)| weight > 0);
+    if let (Some((file, _)), None) = (picked.next(), picked.next()) {
+        return Some(file);
+    }
+    let mut draw = draw(total, rng);
+    weights
+        .iter()
+        .map(|&weight| u64::from(weight))
+        .position(|weight| {
+            if draw < weight {
+                true
+            } else {
+                draw -= weight;
+                false
+            }
+        })
+}
+
+/// Picks a file by weight and then one of its fortunes that `wanted` keeps by
+/// length, reading only the file picked, and returns the fortune's text. A
+/// file without any such fortune is put aside and another one picked.
+fn pick_fortune(
+    files: &[PathBuf],
+    dats: &[Option<Vec<u64>>],
+    mut weights: Vec<u32>,
+    wanted: Option<&dyn Fn(usize) -> bool>,
+    rng: &mut impl Rng,
+) -> MyResult<Option<String>> {
+    while let Some(file) = pick_file(&weights, rng) {
+        let indexed;
+        let offsets = match (&dats[file], wanted) {
+            // A strfile index has no lengths, so it's only of use without -s or -l
+            (Some(offsets), None) => offsets,
+            (_, wanted) => {
+                indexed = index_fortunes(&files[file])?
+                    .into_iter()
+                    .filter(|fortune| wanted.is_none_or(|wanted| wanted(fortune.len)))
+                    .map(|fortune| fortune.offset)
+                    .collect::<Vec<_>>();
+                &indexed
+            }
+        };
+        if offsets.is_empty() {
+            weights[file] = 0;
+            continue;
+        }
+        let fortune = draw(offsets.len() as u64, rng) as usize;
+        return read_fortune_at(&files[file], offsets[fortune]).map(Some);
+    }
+    Ok(None)
+}
+
+/// Prints one random fortune, or with -m/-a every selected one, and returns
+/// the exit status: 1 if -m matched nothing.
+pub fn run(config: Config) -> MyResult<i32> {
+    let sources = if config.sources.is_empty() {
+        default_sources()?
     } else {
-        if let Some(pattern) = config.pattern {
-            let mut prev_source = None;
-            for fortune in fortunes {
-                pattern.captures(&fortune.text)
-                    .map(|_| {
-                        if prev_source.as_ref()
-                            .map_or(true, |s| s != &fortune.source) {
-                            eprintln!("({})\n%", fortune.source);
-                            prev_source = Some(fortune.source.clone());
-                        }
-                        println!("{}\n%", fortune.text)
-                    });
+        config.sources
+    };
+    let files = find_files(&sources, config.offensive)?;
+    let wanted = |len: usize| {
+        !((config.short_only && len > config.length) || (config.long_only && len <= config.length))
+    };
+
+    if config.all || config.pattern.is_some() {
+        // Like fortune -m: the "(source)" headers go to stderr so stdout is a
+        // valid cookie file on its own
+        let mut found = false;
+        for file in &files {
+            let mut header = true;
+            for fortune in read_fortunes(file)? {
+                if !wanted(fortune.text.chars().count())
+                    || !config
+                        .pattern
+                        .as_ref()
+                        .is_none_or(|p| p.is_match(&fortune.text))
+                {
+                    continue;
+                }
+                if header {
+                    eprintln!("({})\n%", fortune.source);
+                    header = false;
+                }
+                println!("{}\n%", fortune.text);
+                found = true;
             }
+        }
+        Ok(if found || config.pattern.is_none() {
+            0
         } else {
-            pick_fortune(&fortunes, config.seed)
-                    .map(|f| println!("{}", f));
+            1
+        })
+    } else {
+        let dats: Vec<_> = files.iter().map(|file| read_dat(file)).collect();
+        let sizes = file_sizes(&files, &dats)?;
+        let weights = file_weights(&files, &sizes, &config.weights, config.equal)?;
+        let wanted: Option<&dyn Fn(usize) -> bool> =
+            (config.short_only || config.long_only).then_some(&wanted);
+        let picked = match config.seed {
+            Some(seed) => {
+                let mut rng = StdRng::seed_from_u64(seed);
+                pick_fortune(&files, &dats, weights, wanted, &mut rng)?
+            }
+            None => pick_fortune(&files, &dats, weights, wanted, &mut rand::thread_rng())?,
+        };
+        match picked {
+            Some(fortune) => println!("{fortune}"),
+            None => println!("No fortunes found"),
         }
+        Ok(0)
     }
-    Ok(())
 }
 
-
 #[cfg(test)]
 mod tests {
-    use super::find_files;
+    use super::{
+        find_files, index_fortunes, pick_file, read_dat, read_fortune_at, read_fortunes, rot13,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::path::Path;
 
     #[test]
     fn test_find_files() {
         // Verify that the function finds a file known to exist
-        let res = find_files(&["./tests/inputs/jokes".to_string()]);
+        let res = find_files(&["./tests/inputs/jokes".to_string()], false);
         assert!(res.is_ok());
 
         let files = res.unwrap();
         assert_eq!(files.len(), 1);
         assert_eq!(
-            files.get(0).unwrap().to_string_lossy(),
+            files.first().unwrap().to_string_lossy(),
             "./tests/inputs/jokes"
         );
 
         // Fails to find a bad file
-        let res = find_files(&["/path/does/not/exist".to_string()]);
+        let res = find_files(&["/path/does/not/exist".to_string()], false);
         assert!(res.is_err());
 
         // Finds all the input files, excludes ".dat"
-        let res = find_files(&["./tests/inputs".to_string()]);
+        let res = find_files(&["./tests/inputs".to_string()], false);
         assert!(res.is_ok());
 
         // Check number and order of files
         let files = res.unwrap();
         assert_eq!(files.len(), 5);
-        let first = files.get(0).unwrap().display().to_string();
+        let first = files.first().unwrap().display().to_string();
         assert!(first.contains("ascii-art"));
         let last = files.last().unwrap().display().to_string();
         assert!(last.contains("quotes"));
 
+        // Offensive files are only found with the flag, or when named
+        let res = find_files(&["./tests/inputs".to_string()], true);
+        assert_eq!(res.unwrap().len(), 6);
+        let res = find_files(&["./tests/inputs/puns-o".to_string()], false);
+        assert_eq!(res.unwrap().len(), 1);
+
         // Test for multiple sources, path must be unique and sorted
-        let res = find_files(&[
-            "./tests/inputs/jokes".to_string(),
-            "./tests/inputs/ascii-art".to_string(),
-            "./tests/inputs/jokes".to_string(),
-        ]);
+        let res = find_files(
+            &[
+                "./tests/inputs/jokes".to_string(),
+                "./tests/inputs/ascii-art".to_string(),
+                "./tests/inputs/jokes".to_string(),
+            ],
+            false,
+        );
         assert!(res.is_ok());
         let files = res.unwrap();
         assert_eq!(files.len(), 2);
@@ -212,4 +585,55 @@ mod tests {
             assert_eq!(filename.to_string_lossy(), "jokes".to_string())
         }
     }
+
+    #[test]
+    fn test_rot13() {
+        assert_eq!(rot13("Uryyb, Jbeyq!"), "Hello, World!");
+        assert_eq!(rot13(&rot13("abc XYZ 123")), "abc XYZ 123");
+    }
+
+    #[test]
+    fn test_index_fortunes() {
+        // The index must agree with reading every fortune in full
+        let files = find_files(&["./tests/inputs".to_string()], true).unwrap();
+        for file in &files {
+            let refs = index_fortunes(file).unwrap();
+            let fortunes = read_fortunes(file).unwrap();
+            assert_eq!(refs.len(), fortunes.len());
+            for (fortune_ref, fortune) in refs.iter().zip(&fortunes) {
+                assert_eq!(fortune_ref.len, fortune.text.chars().count());
+                let text = read_fortune_at(file, fortune_ref.offset).unwrap();
+                assert_eq!(text, fortune.text);
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_dat() {
+        // strfile's offsets are where the index finds the fortunes too
+        let jokes = Path::new("./tests/inputs/jokes");
+        let offsets: Vec<_> = index_fortunes(jokes)
+            .unwrap()
+            .iter()
+            .map(|f| f.offset)
+            .collect();
+        assert_eq!(read_dat(jokes), Some(offsets));
+        assert_eq!(read_dat(Path::new("./tests/inputs/quotes")), None);
+    }
+
+    #[test]
+    fn test_pick_file() {
+        let mut rng = StdRng::seed_from_u64(1);
+
+        // Nothing to pick from
+        assert_eq!(pick_file(&[0, 0, 0], &mut rng), None);
+
+        // Only files with a weight are chosen
+        for _ in 0..100 {
+            assert_eq!(pick_file(&[0, 5, 0], &mut rng), Some(1));
+            assert!(pick_file(&[3, 0, 4], &mut rng).is_some_and(|file| file != 1));
+        }
+        // Even when they can't all be added up as a u32
+        assert!(pick_file(&[u32::MAX, u32::MAX], &mut rng).is_some());
+    }
 }
@@ -37,7 +37,7 @@ fn dies_bad_file() -> TestResult {
     let bad = gen_bad_file();
     let expected = format!("{}: .* [(]os error 2[)]", bad);
     Command::cargo_bin(PRG)?
-        .args(&[LITERATURE, &bad])
+        .args([LITERATURE, &bad])
         .assert()
         .failure()
         .stderr(predicate::str::is_match(expected)?);
@@ -50,7 +50,7 @@ fn dies_bad_seed() -> TestResult {
     let bad = random_string();
     let expected = format!("\"{}\" not a valid integer", &bad);
     Command::cargo_bin(PRG)?
-        .args(&[LITERATURE, "--seed", &bad])
+        .args([LITERATURE, "--seed", &bad])
         .assert()
         .failure()
         .stderr(predicate::str::contains(expected));
@@ -77,7 +77,7 @@ fn no_fortunes_found() -> TestResult {
 #[test]
 fn quotes_seed_1() -> TestResult {
     run(
-        &[QUOTES, "-s", "1"],
+        &[QUOTES, "--seed", "1"],
         "You can observe a lot just by watching.\n-- Yogi Berra\n",
     )
 }
@@ -86,7 +86,7 @@ fn quotes_seed_1() -> TestResult {
 #[test]
 fn jokes_seed_1() -> TestResult {
     run(
-        &[JOKES, "-s", "1"],
+        &[JOKES, "--seed", "1"],
         "Q: What happens when frogs park illegally?\nA: They get toad.\n",
     )
 }
@@ -94,11 +94,13 @@ fn jokes_seed_1() -> TestResult {
 // --------------------------------------------------
 #[test]
 fn dir_seed_10() -> TestResult {
-    run(
-        &[FORTUNE_DIR, "-s", "10"],
-        "Q: Why did the fungus and the alga marry?\n\
-        A: Because they took a lichen to each other!\n",
-    )
+    // The files are weighed by size, which makes the long ascii-art likeliest
+    Command::cargo_bin(PRG)?
+        .args([FORTUNE_DIR, "--seed", "10"])
+        .assert()
+        .success()
+        .stdout(fs::read_to_string("tests/expected/dir_seed_10.out")?);
+    Ok(())
 }
 
 // --------------------------------------------------
@@ -114,6 +116,17 @@ fn run_outfiles(args: &[&str], out_file: &str, err_file: &str) -> TestResult {
     Ok(())
 }
 
+// --------------------------------------------------
+fn run_no_match(args: &[&str]) -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .code(1)
+        .stderr("")
+        .stdout("");
+    Ok(())
+}
+
 // --------------------------------------------------
 #[test]
 fn yogi_berra_cap() -> TestResult {
@@ -137,21 +150,13 @@ fn mark_twain_cap() -> TestResult {
 // --------------------------------------------------
 #[test]
 fn yogi_berra_lower() -> TestResult {
-    run_outfiles(
-        &["--pattern", "yogi berra", FORTUNE_DIR],
-        "tests/expected/berra_lower.out",
-        "tests/expected/berra_lower.err",
-    )
+    run_no_match(&["--pattern", "yogi berra", FORTUNE_DIR])
 }
 
 // --------------------------------------------------
 #[test]
 fn mark_twain_lower() -> TestResult {
-    run_outfiles(
-        &["-m", "will twain", FORTUNE_DIR],
-        "tests/expected/twain_lower.out",
-        "tests/expected/twain_lower.err",
-    )
+    run_no_match(&["-m", "will twain", FORTUNE_DIR])
 }
 
 // --------------------------------------------------
@@ -173,3 +178,234 @@ fn mark_twain_lower_i() -> TestResult {
         "tests/expected/twain_lower_i.err",
     )
 }
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_length() -> TestResult {
+    let bad = random_string();
+    let expected = format!("\"{}\" not a valid length", &bad);
+    Command::cargo_bin(PRG)?
+        .args([LITERATURE, "-n", &bad])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(expected));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_short_and_long() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args([LITERATURE, "-s", "-l"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn quotes_short() -> TestResult {
+    run_outfiles(
+        &["-m", ".", "-s", QUOTES],
+        "tests/expected/quotes_short.out",
+        "tests/expected/quotes_short.err",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn quotes_long() -> TestResult {
+    run_outfiles(
+        &["-m", ".", "-l", QUOTES],
+        "tests/expected/quotes_long.out",
+        "tests/expected/quotes_long.err",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn jokes_short_n60() -> TestResult {
+    run_outfiles(
+        &["-m", ".", "-s", "-n", "60", JOKES],
+        "tests/expected/jokes_short_n60.out",
+        "tests/expected/jokes_short_n60.err",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn literature_long_none_found() -> TestResult {
+    run(&[LITERATURE, "-l"], "No fortunes found\n")
+}
+
+// --------------------------------------------------
+#[test]
+fn jokes_all() -> TestResult {
+    run_outfiles(
+        &["-a", JOKES],
+        "tests/expected/jokes_all.out",
+        "tests/expected/jokes_all.err",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn twain_all() -> TestResult {
+    run_outfiles(
+        &["--all", "-m", "Twain", LITERATURE, QUOTES],
+        "tests/expected/twain_all.out",
+        "tests/expected/twain_all.err",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn fortune_path_seed_1() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .env("FORTUNE_PATH", format!("{JOKES}:{QUOTES}:{}", gen_bad_file()))
+        .args(["--seed", "1"])
+        .assert()
+        .success()
+        .stdout("It's like deja vu all over again.\n-- Yogi Berra\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_no_default_sources() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .env("FORTUNE_PATH", gen_bad_file())
+        .assert()
+        .failure()
+        .stderr("No fortune sources given and none found in FORTUNE_PATH or \
+            /usr/share/games/fortunes\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn banker_skips_offensive() -> TestResult {
+    run_outfiles(
+        &["-m", "banker", FORTUNE_DIR],
+        "tests/expected/banker.out",
+        "tests/expected/banker.err",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn banker_offensive() -> TestResult {
+    run_outfiles(
+        &["-o", "-m", "banker", FORTUNE_DIR],
+        "tests/expected/banker_o.out",
+        "tests/expected/banker_o.err",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn named_offensive_file_is_decoded() -> TestResult {
+    run(
+        &["./tests/inputs/puns-o", "-m", "Velcro"],
+        "Velcro: what a rip-off!\n%\n",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn no_match_in_empty_dir() -> TestResult {
+    run_no_match(&["-m", "anything", EMPTY_DIR])
+}
+
+// --------------------------------------------------
+#[test]
+fn all_in_empty_dir() -> TestResult {
+    run(&["-a", EMPTY_DIR], "")
+}
+
+// --------------------------------------------------
+#[test]
+fn quotes_long_seed_1() -> TestResult {
+    run(
+        &[QUOTES, "-l", "--seed", "1"],
+        "Keep away from people who try to belittle your ambitions. Small people \
+        always do that, but the really great make you feel that you, too, can \
+        become great.\n-- Mark Twain\n",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn weight_only_quotes() -> TestResult {
+    run(
+        &[
+            FORTUNE_DIR,
+            "--weight",
+            "jokes:0",
+            "--weight",
+            "literature:0",
+            "--weight",
+            "ascii-art:0",
+            "--seed",
+            "3",
+        ],
+        "It's like deja vu all over again.\n-- Yogi Berra\n",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn weights_too_big_to_add() -> TestResult {
+    // Together the weights are more than a u32 holds
+    Command::cargo_bin(PRG)?
+        .args([FORTUNE_DIR, "--weight", "jokes:4294967295", "--weight", "quotes:4294967295"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty().not());
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_weight() -> TestResult {
+    for bad in ["jokes", "jokes:x", "jokes:-1"] {
+        Command::cargo_bin(PRG)?
+            .args([FORTUNE_DIR, "--weight", bad])
+            .assert()
+            .failure()
+            .stderr(format!("\"{bad}\" not a valid weight\n"));
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_weight_unknown_source() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args([JOKES, "--weight", "quotes:2"])
+        .assert()
+        .failure()
+        .stderr("--weight quotes: not one of the sources\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn reads_past_invalid_utf8() -> TestResult {
+    let dir = tempfile::tempdir()?;
+    let cookies = dir.path().join("cookies");
+    fs::write(&cookies, b"caf\xe9\nau lait\n%\n")?;
+    Command::cargo_bin(PRG)?
+        .arg(&cookies)
+        .assert()
+        .success()
+        .stdout("caf\u{fffd}\nau lait\n");
+    Command::cargo_bin(PRG)?
+        .args(["-m", "lait"])
+        .arg(&cookies)
+        .assert()
+        .success()
+        .stdout("caf\u{fffd}\nau lait\n%\n");
+    Ok(())
+}
@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: (&str, &str, bool, bool)| {
+    let (file1, file2, check_order, quiet) = input;
+    commr::fuzzing::merge_lines(file1, file2, check_order, quiet);
+});
@@ -0,0 +1,7 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|list: &str| {
+    cutr::fuzzing::parse_pos(list);
+});
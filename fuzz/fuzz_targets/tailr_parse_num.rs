@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: (&str, i64)| {
+    let (val, total) = input;
+    tailr::fuzzing::parse_num(val, total);
+});
@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Raw bytes, so invalid UTF-8 reaches the line reader
+fuzz_target!(|input: (&[u8], u8, u8, Option<u8>, bool)| {
+    let (data, skip_fields, skip_chars, check_chars, global) = input;
+    uniqr::fuzzing::dedup(
+        data,
+        skip_fields.into(),
+        skip_chars.into(),
+        check_chars.map(Into::into),
+        global,
+    );
+});
@@ -1,10 +1,14 @@
-use std::{error::Error, fs::{self, File}, io::{self, BufRead, BufReader}, mem};
+use std::{fs, io::{BufRead, Write}, mem};
 
-use clap::{App, Arg};
+use clap::Parser;
 use regex::{Regex, RegexBuilder};
+use serde::Serialize;
 use walkdir::WalkDir;
 
-type MyResult<T> = Result<T, Box<dyn Error>>;
+use coreutils_common::{
+    i18n::tr_fmt, open, try_parse_args, write_json, CoreError, Delimiter, DelimiterArgs,
+    FilesFromArgs, MyResult, Output,
+};
 
 #[derive(Debug)]
 pub struct Config {
@@ -13,18 +17,35 @@ pub struct Config {
     recursive: bool,
     count: bool,
     invert_match: bool,
+    json: bool,
+    delimiter: Delimiter,
+}
+
+/// A selected line in --json output, without its delimiter.
+#[derive(Debug, Serialize)]
+struct Hit<'a> {
+    file: &'a str,
+    line: &'a str,
+}
+
+/// The --json output of --count for one file.
+#[derive(Debug, Serialize)]
+struct HitCount<'a> {
+    file: &'a str,
+    count: usize,
 }
 
 fn find_lines<T: BufRead>(
     mut file: T, 
     pattern: &Regex,
-    invert_match: bool
+    invert_match: bool,
+    delimiter: Delimiter,
 ) -> MyResult<Vec<String>> {
     let mut matches = Vec::new();
     let mut line = String::new();
 
     loop {
-        let bytes = file.read_line(&mut line)?;
+        let bytes = delimiter.read_record(&mut file, &mut line)?;
         if bytes == 0 {
             break;
         }
@@ -36,12 +57,6 @@ fn find_lines<T: BufRead>(
     Ok(matches)
 }
 
-fn open(filename: &str) -> MyResult<Box<dyn BufRead>> {
-    match filename {
-        "-" => Ok(Box::new(BufReader::new(io::stdin()))),
-        _ => Ok(Box::new(BufReader::new(File::open(filename)?))),
-    }
-}
 
 fn find_files(paths: &[String], recursive: bool) -> Vec<MyResult<String>> {
     let mut results = Vec::new();
@@ -65,106 +80,144 @@ fn find_files(paths: &[String], recursive: bool) -> Vec<MyResult<String>> {
                             }
                         } else {
                             results.push(
-                                Err(format!("{path} is a directory").into())
+                                Err(CoreError::Input(tr_fmt(
+                                    "{path} is a directory",
+                                    &[("path", path)],
+                                )))
                             );
                         }
                     } else if metadata.is_file() {
                         results.push(Ok(path.to_string()));
                     }
                 },
-                Err(e) => results.push(Err(format!("{path}: {e}").into())),
+                Err(e) => results.push(Err(CoreError::io(path, e))),
             }
         }
     }
     results
 }
 
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust grep")]
+struct Args {
+    /// Search pattern
+    #[arg(value_name = "PATTERN")]
+    pattern: String,
+
+    /// Count occurences
+    #[arg(short = 'c', long)]
+    count: bool,
+
+    /// Case-insensitive
+    #[arg(short = 'i', long)]
+    insensitive: bool,
+
+    /// Invert match
+    #[arg(short = 'v', long = "invert-match")]
+    invert: bool,
+
+    /// Recursive search
+    #[arg(short = 'r', long)]
+    recursive: bool,
+
+    /// Print each selected line as a line of JSON
+    #[arg(long)]
+    json: bool,
+
+    #[command(flatten)]
+    delimiter: DelimiterArgs,
+
+    /// Input file(s)
+    #[arg(value_name = "FILE", default_value = "-", conflicts_with = "files_from")]
+    files: Vec<String>,
+
+    #[command(flatten)]
+    files_from: FilesFromArgs,
+}
+
 pub fn get_args() -> MyResult<Config> {
-    let matches = App::new("grepr")
-        .about("Rust grep")
-        .version("0.1.0")
-        .author("OFFBLACK")
-        .arg(
-            Arg::with_name("pattern")
-                .help("Search pattern")
-                .value_name("PATTERN")
-                .required(true)
-        )
-        .arg(
-            Arg::with_name("count")
-                .short("c")
-                .long("count")
-                .help("Count occurences")
-        )
-        .arg(
-            Arg::with_name("insensitive")
-                .short("i")
-                .long("insensitive")
-                .help("Case-insensitive")
-        )
-        .arg(
-            Arg::with_name("invert")
-                .short("v")
-                .long("invert-match")
-                .help("Invert match")
-        )
-        .arg(
-            Arg::with_name("recursive")
-                .short("r")
-                .long("recursive")
-                .help("Recursive search")
-        )
-        .arg(
-            Arg::with_name("files")
-                .multiple(true)
-                .value_name("FILE")
-                .help("Input file(s)")
-                .default_value("-")
-        )
-        .get_matches();
-
-    let pattern = matches.value_of("pattern").unwrap();
-    let pattern = RegexBuilder::new(pattern)
-        .case_insensitive(matches.is_present("insensitive"))
+    // Usage errors keep clap's exit status, which is grep's 2
+    let args: Args = try_parse_args().unwrap_or_else(|e| e.exit());
+
+    let pattern = RegexBuilder::new(&args.pattern)
+        .case_insensitive(args.insensitive)
         .build()
-        .map_err(|_| format!("Invalid pattern \"{pattern}\""))?;
+        .map_err(|_| CoreError::Parse(format!("Invalid pattern \"{}\"", args.pattern)))?;
 
     Ok(Config {
         pattern,
-        recursive: matches.is_present("recursive"),
-        count: matches.is_present("count"),
-        invert_match: matches.is_present("invert"),
-        files: matches.values_of_lossy("files").unwrap(),
+        recursive: args.recursive,
+        count: args.count,
+        invert_match: args.invert,
+        files: args.files_from.files(args.files)?,
+        json: args.json,
+        delimiter: args.delimiter.delimiter(),
     }) 
 }
 
-pub fn run(config: Config) -> MyResult<()> {
+/// Prints the matching lines and returns grep's exit status: 0 if any line
+/// was selected, 1 if none were, and 2 if any input couldn't be read.
+pub fn run(config: Config) -> MyResult<i32> {
+    let mut selected = false;
+    let mut failed = false;
     let entries = find_files(&config.files, config.recursive);
     let num_files = entries.len();
-    let print = |fname: &str, content: &str| {
-        if num_files > 1 { print!("{fname}:{content}") }
-        else { print!("{content}") }
+    let mut out = Output::stdout();
+    let print = |out: &mut Output, fname: &str, content: &str| {
+        if num_files > 1 { write!(out, "{fname}:{content}") }
+        else { write!(out, "{content}") }
     };
     for entry in entries {
         match entry {
-            Err(e) => eprintln!("{e}"),
+            Err(e) => {
+                // Keep the error in order with the output before it
+                out.flush()?;
+                eprintln!("{e}");
+                failed = true;
+            }
             Ok(filename) => match open(&filename) {
-                Err(e) => eprintln!("{filename}: {e}"),
+                Err(e) => {
+                    out.flush()?;
+                    eprintln!("{e}");
+                    failed = true;
+                }
                 Ok(file) => {
                     match find_lines(
                         file, &config.pattern, 
-                        config.invert_match
+                        config.invert_match, config.delimiter
                     ) {
-                        Err(e) => eprintln!("{e}"),
+                        Err(e) => {
+                            out.flush()?;
+                            eprintln!("{e}");
+                            failed = true;
+                        }
                         Ok(matches) => {
-                            if config.count {
+                            selected |= !matches.is_empty();
+                            if config.json {
+                                if config.count {
+                                    write_json(&mut out, &HitCount {
+                                        file: &filename,
+                                        count: matches.len(),
+                                    })?;
+                                } else {
+                                    for line in &matches {
+                                        write_json(&mut out, &Hit {
+                                            file: &filename,
+                                            line: line
+                                                .strip_suffix(config.delimiter.as_char())
+                                                .unwrap_or(line),
+                                        })?;
+                                    }
+                                }
+                            } else if config.count {
                                 print(
+                                    &mut out,
                                     &filename, 
                                     &format!("{}\n", matches.len())
-                                );
+                                )?;
                             } else {
                                 for line in &matches {
-                                    print(&filename, line);
+                                    print(&mut out, &filename, line)?;
                                 }
                             }
                         }
@@ -173,5 +226,10 @@ pub fn run(config: Config) -> MyResult<()> {
             },
         }
     }
-    Ok(())
+    out.flush()?;
+    Ok(match (failed, selected) {
+        (true, _) => 2,
+        (false, true) => 0,
+        (false, false) => 1,
+    })
 }
@@ -1,6 +1,10 @@
 fn main() {
-    if let Err(e) = grepr::get_args().and_then(grepr::run) {
-        eprintln!("{e}");
-        std::process::exit(1);
+    coreutils_common::reset_sigpipe();
+    match grepr::get_args().and_then(grepr::run) {
+        Ok(status) => std::process::exit(status),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(2);
+        }
     }
 }
@@ -2,7 +2,6 @@ use assert_cmd::Command;
 use predicates::prelude::*;
 use rand::{distributions::Alphanumeric, Rng};
 use std::{fs, path::Path};
-use sys_info::os_type;
 
 type TestResult = Result<(), Box<dyn std::error::Error>>;
 
@@ -34,7 +33,7 @@ fn dies_no_args() -> TestResult {
     Command::cargo_bin(PRG)?
         .assert()
         .failure()
-        .stderr(predicate::str::contains("USAGE"));
+        .stderr(predicate::str::contains("Usage"));
     Ok(())
 }
 
@@ -42,7 +41,7 @@ fn dies_no_args() -> TestResult {
 #[test]
 fn dies_bad_pattern() -> TestResult {
     Command::cargo_bin(PRG)?
-        .args(&["*foo", FOX])
+        .args(["*foo", FOX])
         .assert()
         .failure()
         .stderr(predicate::str::contains("Invalid pattern \"*foo\""));
@@ -55,7 +54,7 @@ fn warns_bad_file() -> TestResult {
     let bad = gen_bad_file();
     let expected = format!("{}: .* [(]os error 2[)]", bad);
     Command::cargo_bin(PRG)?
-        .args(&["foo", &bad])
+        .args(["foo", &bad])
         .assert()
         .stderr(predicate::str::is_match(expected)?);
     Ok(())
@@ -64,15 +63,14 @@ fn warns_bad_file() -> TestResult {
 // --------------------------------------------------
 fn run(args: &[&str], expected_file: &str) -> TestResult {
     let windows_file = format!("{}.windows", expected_file);
-    let expected_file = if os_type().unwrap() == "Windows"
-        && Path::new(&windows_file).is_file()
+    let expected_file = if cfg!(windows) && Path::new(&windows_file).is_file()
     {
         &windows_file
     } else {
         expected_file
     };
 
-    let expected = fs::read_to_string(&expected_file)?;
+    let expected = fs::read_to_string(expected_file)?;
 
     Command::cargo_bin(PRG)?
         .args(args)
@@ -117,6 +115,31 @@ fn bustle_insensitive() -> TestResult {
     )
 }
 
+// --------------------------------------------------
+#[test]
+fn insensitive_from_env() -> TestResult {
+    let expected = fs::read_to_string("tests/expected/bustle.txt.the.lowercase.insensitive")?;
+    Command::cargo_bin(PRG)?
+        .env("GREPR_OPTS", "--insensitive")
+        .args(["the", BUSTLE])
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_env_option() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .env("GREPR_OPTS", "--bogus")
+        .args(["the", BUSTLE])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("unexpected argument '--bogus'"));
+    Ok(())
+}
+
 // --------------------------------------------------
 #[test]
 fn nobody() -> TestResult {
@@ -219,6 +242,24 @@ fn sensitive_count_multiple() -> TestResult {
     )
 }
 
+// --------------------------------------------------
+#[test]
+fn multiple_files_json() -> TestResult {
+    run(
+        &["--json", "The", BUSTLE, EMPTY, FOX, NOBODY],
+        "tests/expected/all.the.capitalized.json",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn count_multiple_json() -> TestResult {
+    run(
+        &["--json", "-c", "The", BUSTLE, EMPTY, FOX, NOBODY],
+        "tests/expected/all.the.capitalized.count.json",
+    )
+}
+
 // --------------------------------------------------
 #[test]
 fn insensitive_count_multiple() -> TestResult {
@@ -234,7 +275,7 @@ fn warns_dir_not_recursive() -> TestResult {
     let stdout = "tests/inputs/fox.txt:\
         The quick brown fox jumps over the lazy dog.";
     Command::cargo_bin(PRG)?
-        .args(&["fox", INPUTS_DIR, FOX])
+        .args(["fox", INPUTS_DIR, FOX])
         .assert()
         .stderr(predicate::str::contains("tests/inputs is a directory"))
         .stdout(predicate::str::contains(stdout));
@@ -271,9 +312,74 @@ fn stdin_insensitive_count() -> TestResult {
     let expected = fs::read_to_string(expected_file)?;
 
     Command::cargo_bin(PRG)?
-        .args(&["-ci", "the", "-"])
+        .args(["-ci", "the", "-"])
         .write_stdin(input)
         .assert()
         .stdout(expected);
     Ok(())
 }
+
+// --------------------------------------------------
+#[test]
+fn exits_0_on_match() -> TestResult {
+    Command::cargo_bin(PRG)?.args(["fox", FOX]).assert().code(0);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn exits_1_on_no_match() -> TestResult {
+    Command::cargo_bin(PRG)?.args(["nobody", FOX]).assert().code(1);
+    Command::cargo_bin(PRG)?.args(["-c", "nobody", FOX]).assert().code(1);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn exits_2_on_error() -> TestResult {
+    // An unreadable input is an error even when another file matched
+    Command::cargo_bin(PRG)?
+        .args(["fox", FOX, &gen_bad_file()])
+        .assert()
+        .code(2);
+    Command::cargo_bin(PRG)?.args(["*foo", FOX]).assert().code(2);
+    Command::cargo_bin(PRG)?.assert().code(2);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn zero_terminated() -> TestResult {
+    // A record may contain newlines, and keeps its NUL on output
+    Command::cargo_bin(PRG)?
+        .args(["-z", "bar"])
+        .write_stdin("foo\nbar\0baz\0foo bar\0")
+        .assert()
+        .success()
+        .stdout("foo\nbar\0foo bar\0");
+    Command::cargo_bin(PRG)?
+        .args(["-zc", "bar"])
+        .write_stdin("foo\nbar\0baz\0foo bar\0")
+        .assert()
+        .success()
+        .stdout("2\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn files_from() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-c", "the", "--files-from=-"])
+        .write_stdin(format!("{BUSTLE}\n{FOX}\n"))
+        .assert()
+        .success()
+        .stdout(format!("{BUSTLE}:1\n{FOX}:1\n"));
+    Command::cargo_bin(PRG)?
+        .args(["the", "--files-from=-"])
+        .write_stdin("-\n")
+        .assert()
+        .code(2)
+        .stderr("when reading file names from stdin, no file name of '-' allowed\n");
+    Ok(())
+}
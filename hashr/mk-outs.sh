@@ -0,0 +1,17 @@
+#!/usr/bin/env bash
+
+# Writes the expected output of each test with GNU md5sum and sha*sum
+
+OUTDIR="tests/expected"
+[[ ! -d "$OUTDIR" ]] && mkdir -p "$OUTDIR"
+
+BUSTLE="tests/inputs/the-bustle.txt"
+FOX="tests/inputs/fox.txt"
+EMPTY="tests/inputs/empty.txt"
+
+for ALG in md5 sha1 sha256 sha512; do
+    ${ALG}sum $FOX $EMPTY $BUSTLE > $OUTDIR/all.$ALG.out
+done
+sha256sum -c tests/inputs/good.sha256 > $OUTDIR/good.sha256.check.out
+md5sum -c tests/inputs/bad.md5 > $OUTDIR/bad.md5.check.out 2>/dev/null
+md5sum -c --quiet tests/inputs/bad.md5 > $OUTDIR/bad.md5.check.quiet.out 2>/dev/null
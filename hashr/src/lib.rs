@@ -0,0 +1,390 @@
+use clap::{Parser, ValueEnum};
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+use std::{
+    borrow::Cow,
+    env,
+    io::{self, BufRead, Read, Write},
+    path::Path,
+};
+
+use coreutils_common::{open, parse_args, CoreError, MyResult};
+
+#[derive(Debug)]
+pub struct Config {
+    files: Vec<String>,
+    algorithm: Option<Algorithm>,
+    check: bool,
+    quiet: bool,
+    status: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Algorithm {
+    Md5,
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust md5sum and sha*sum")]
+struct Args {
+    /// Input file(s), or checksum files with -c
+    #[arg(value_name = "FILE", default_value = "-")]
+    files: Vec<String>,
+
+    /// Digest algorithm [default: from the name run as, e.g. md5sum, or sha256]
+    #[arg(short = 'a', long, value_enum)]
+    algorithm: Option<Algorithm>,
+
+    /// Read checksums from the FILEs and check them
+    #[arg(short = 'c', long)]
+    check: bool,
+
+    /// Don't print OK for each file that checks out
+    #[arg(long, requires = "check")]
+    quiet: bool,
+
+    /// Print nothing, only set the exit status
+    #[arg(long, requires = "check")]
+    status: bool,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let args: Args = parse_args();
+
+    let program = env::args_os().next().unwrap_or_default();
+    let program = Path::new(&program).file_stem().unwrap_or_default();
+
+    Ok(Config {
+        files: args.files,
+        algorithm: args
+            .algorithm
+            .or_else(|| Algorithm::from_program(&program.to_string_lossy())),
+        check: args.check,
+        quiet: args.quiet,
+        status: args.status,
+    })
+}
+
+impl Algorithm {
+    /// The algorithm a link to hashr named like a GNU tool stands for, with
+    /// or without the trailing r.
+    pub fn from_program(name: &str) -> Option<Self> {
+        match name.strip_suffix('r').unwrap_or(name) {
+            "md5sum" => Some(Algorithm::Md5),
+            "sha1sum" => Some(Algorithm::Sha1),
+            "sha256sum" => Some(Algorithm::Sha256),
+            "sha512sum" => Some(Algorithm::Sha512),
+            _ => None,
+        }
+    }
+
+    /// How many hex digits a digest has.
+    fn hex_len(self) -> usize {
+        match self {
+            Algorithm::Md5 => 32,
+            Algorithm::Sha1 => 40,
+            Algorithm::Sha256 => 64,
+            Algorithm::Sha512 => 128,
+        }
+    }
+
+    /// The digest of everything in `input`, in lowercase hex.
+    pub fn digest(self, input: &mut impl Read) -> io::Result<String> {
+        match self {
+            Algorithm::Md5 => checksum::<Md5>(input),
+            Algorithm::Sha1 => checksum::<Sha1>(input),
+            Algorithm::Sha256 => checksum::<Sha256>(input),
+            Algorithm::Sha512 => checksum::<Sha512>(input),
+        }
+    }
+
+    /// A writer that sums what is written to it, for tools that sum their
+    /// output as they write it.
+    pub fn writer(self) -> DigestWriter {
+        DigestWriter(match self {
+            Algorithm::Md5 => Box::new(Md5::default()),
+            Algorithm::Sha1 => Box::new(Sha1::default()),
+            Algorithm::Sha256 => Box::new(Sha256::default()),
+            Algorithm::Sha512 => Box::new(Sha512::default()),
+        })
+    }
+}
+
+/// A sum worked out a block at a time as its input is read, so inputs of
+/// any size can be summed without holding them in memory. The digests
+/// here are sums, as are the simpler ones of cksumr.
+pub trait Checksum: Default {
+    /// Adds `bytes` to what has been summed.
+    fn update(&mut self, bytes: &[u8]);
+
+    /// The sum of everything added, as it is printed.
+    fn finish(self) -> String;
+}
+
+impl<D: Digest + Default> Checksum for D {
+    fn update(&mut self, bytes: &[u8]) {
+        Digest::update(self, bytes);
+    }
+
+    fn finish(self) -> String {
+        self.finalize().iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+}
+
+/// A Checksum whose type was chosen at run time.
+trait AnySum {
+    fn add(&mut self, bytes: &[u8]);
+    fn finish_boxed(self: Box<Self>) -> String;
+}
+
+impl<C: Checksum> AnySum for C {
+    fn add(&mut self, bytes: &[u8]) {
+        self.update(bytes);
+    }
+
+    fn finish_boxed(self: Box<Self>) -> String {
+        (*self).finish()
+    }
+}
+
+/// Sums everything written to it by an [`Algorithm`], see
+/// [`Algorithm::writer`].
+pub struct DigestWriter(Box<dyn AnySum>);
+
+impl DigestWriter {
+    /// The digest of everything written, in lowercase hex.
+    pub fn finish(self) -> String {
+        self.0.finish_boxed()
+    }
+}
+
+impl Write for DigestWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.add(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The sum of everything in `input`, by `C`.
+pub fn checksum<C: Checksum>(input: &mut impl Read) -> io::Result<String> {
+    let mut sum = C::default();
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        match input.read(&mut buf) {
+            Ok(0) => return Ok(sum.finish()),
+            Ok(len) => sum.update(&buf[..len]),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// The digest of the file called `filename`.
+fn digest_file(filename: &str, algorithm: Algorithm) -> MyResult<String> {
+    let mut file = open(filename)?;
+    algorithm.digest(&mut file).map_err(|e| CoreError::io(filename, e))
+}
+
+/// `filename` as GNU writes it after a digest: with each backslash, newline
+/// and carriage return escaped if it has any, which a backslash starting the line then
+/// says. Returns that prefix and the name.
+fn escape(filename: &str) -> (&'static str, Cow<'_, str>) {
+    if !filename.contains(['\\', '\n', '\r']) {
+        return ("", filename.into());
+    }
+    let escaped = filename.replace('\\', "\\\\").replace('\n', "\\n").replace('\r', "\\r");
+    ("\\", escaped.into())
+}
+
+/// The name that [`escape`] wrote as `escaped`, or None if it has an
+/// escape that it doesn't write.
+fn unescape(escaped: &str) -> Option<String> {
+    let mut filename = String::new();
+    let mut chars = escaped.chars();
+    while let Some(c) = chars.next() {
+        filename.push(match c {
+            '\\' => match chars.next()? {
+                '\\' => '\\',
+                'n' => '\n',
+                'r' => '\r',
+                _ => return None,
+            },
+            c => c,
+        });
+    }
+    Some(filename)
+}
+
+/// Splits a line of a checksum file into its digest and file name. The two
+/// are separated by a space and then another space, or a '*' for files that
+/// were read in binary mode. A line starting with a backslash has the name
+/// escaped.
+fn parse_check_line(line: &str) -> Option<(&str, Cow<'_, str>)> {
+    let (escaped, line) = match line.strip_prefix('\\') {
+        Some(line) => (true, line),
+        None => (false, line),
+    };
+    let (digest, rest) = line.split_once(' ')?;
+    let filename = rest.strip_prefix([' ', '*'])?;
+    let valid = !digest.is_empty() && digest.bytes().all(|b| b.is_ascii_hexdigit());
+    if !valid || filename.is_empty() {
+        return None;
+    }
+    let filename = if escaped { unescape(filename)?.into() } else { filename.into() };
+    Some((digest, filename))
+}
+
+/// Prints "WARNING: ..." for `count` lines or files, if there are any.
+fn warn(count: usize, one: &str, many: &str) {
+    match count {
+        0 => {}
+        1 => eprintln!("WARNING: 1 {one}"),
+        _ => eprintln!("WARNING: {count} {many}"),
+    }
+}
+
+/// Checks every file listed in the checksum file `sums`, and returns
+/// whether all of them could be read and matched.
+fn check(sums: &str, config: &Config) -> MyResult<bool> {
+    let mut input = open(sums)?;
+    let (mut checked, mut improper, mut unreadable, mut mismatched) = (0, 0, 0, 0);
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if input.read_line(&mut line).map_err(|e| CoreError::io(sums, e))? == 0 {
+            break;
+        }
+        let parsed = parse_check_line(line.strip_suffix('\n').unwrap_or(&line));
+        // Without -a, the length of the digest tells the algorithm
+        let algorithm = parsed.as_ref().and_then(|(digest, _)| match config.algorithm {
+            Some(algorithm) => (digest.len() == algorithm.hex_len()).then_some(algorithm),
+            None => Algorithm::value_variants()
+                .iter()
+                .copied()
+                .find(|algorithm| algorithm.hex_len() == digest.len()),
+        });
+        let (Some((expected, filename)), Some(algorithm)) = (parsed, algorithm) else {
+            improper += 1;
+            continue;
+        };
+        checked += 1;
+
+        let result = match digest_file(&filename, algorithm) {
+            Ok(digest) if digest.eq_ignore_ascii_case(expected) => "OK",
+            Ok(_) => {
+                mismatched += 1;
+                "FAILED"
+            }
+            Err(e) => {
+                io::stdout().flush()?;
+                eprintln!("{e}");
+                unreadable += 1;
+                "FAILED open or read"
+            }
+        };
+        let silent = config.status || (config.quiet && result == "OK");
+        if !silent {
+            // Only a name with a newline is escaped here, as GNU does
+            let (prefix, filename) =
+                if filename.contains('\n') { escape(&filename) } else { ("", filename) };
+            println!("{prefix}{filename}: {result}");
+        }
+    }
+
+    if checked == 0 {
+        eprintln!("{sums}: no properly formatted checksum lines found");
+        return Ok(false);
+    }
+    if !config.status {
+        io::stdout().flush()?;
+        warn(improper, "line is improperly formatted", "lines are improperly formatted");
+        warn(unreadable, "listed file could not be read", "listed files could not be read");
+        warn(mismatched, "computed checksum did NOT match", "computed checksums did NOT match");
+    }
+    Ok(unreadable == 0 && mismatched == 0)
+}
+
+/// Prints the digest of every file, or checks them with -c, and returns the
+/// exit status: 1 if any file couldn't be read or didn't match.
+pub fn run(config: Config) -> MyResult<i32> {
+    let mut status = 0;
+    for filename in &config.files {
+        let result = if config.check {
+            check(filename, &config)
+        } else {
+            digest_file(filename, config.algorithm.unwrap_or(Algorithm::Sha256)).map(|digest| {
+                let (prefix, filename) = escape(filename);
+                println!("{prefix}{digest}  {filename}");
+                true
+            })
+        };
+        match result {
+            Ok(true) => {}
+            Ok(false) => status = 1,
+            Err(e) => {
+                io::stdout().flush()?;
+                eprintln!("{e}");
+                status = 1;
+            }
+        }
+    }
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_check_line, Algorithm};
+    use std::io::Write;
+
+    #[test]
+    fn test_digest() {
+        let digest = |algorithm: Algorithm| algorithm.digest(&mut &b"abc"[..]).unwrap();
+        assert_eq!(digest(Algorithm::Md5), "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(digest(Algorithm::Sha1), "a9993e364706816aba3e25717850c26c9cd0d89d");
+        assert_eq!(
+            digest(Algorithm::Sha256),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert!(digest(Algorithm::Sha512).starts_with("ddaf35a193617aba"));
+
+        // Written in pieces, the sum is the same
+        for algorithm in [Algorithm::Md5, Algorithm::Sha1, Algorithm::Sha256, Algorithm::Sha512] {
+            let mut writer = algorithm.writer();
+            writer.write_all(b"a").unwrap();
+            writer.write_all(b"bc").unwrap();
+            assert_eq!(writer.finish(), digest(algorithm));
+        }
+        for algorithm in [Algorithm::Md5, Algorithm::Sha1, Algorithm::Sha256, Algorithm::Sha512] {
+            assert_eq!(digest(algorithm).len(), algorithm.hex_len());
+        }
+    }
+
+    #[test]
+    fn test_from_program() {
+        assert_eq!(Algorithm::from_program("md5sum"), Some(Algorithm::Md5));
+        assert_eq!(Algorithm::from_program("sha512sumr"), Some(Algorithm::Sha512));
+        assert_eq!(Algorithm::from_program("hashr"), None);
+    }
+
+    #[test]
+    fn test_parse_check_line() {
+        assert_eq!(parse_check_line("abc123  foo bar"), Some(("abc123", "foo bar".into())));
+        assert_eq!(parse_check_line("ABC123 *foo"), Some(("ABC123", "foo".into())));
+        assert_eq!(parse_check_line("\\abc123  a\\\\b\\nc"), Some(("abc123", "a\\b\nc".into())));
+        assert_eq!(parse_check_line("abc123  a\\nb"), Some(("abc123", "a\\nb".into())));
+        assert_eq!(parse_check_line("\\abc123  a\\rb"), Some(("abc123", "a\rb".into())));
+        assert_eq!(parse_check_line("\\abc123  a\\tb"), None);
+        assert_eq!(parse_check_line("abc123 foo"), None);
+        assert_eq!(parse_check_line("xyz  foo"), None);
+        assert_eq!(parse_check_line("abc123  "), None);
+        assert_eq!(parse_check_line(""), None);
+    }
+}
@@ -0,0 +1,193 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "hashr";
+const BUSTLE: &str = "tests/inputs/the-bustle.txt";
+const FOX: &str = "tests/inputs/fox.txt";
+const EMPTY: &str = "tests/inputs/empty.txt";
+const GOOD: &str = "tests/inputs/good.sha256";
+const BAD: &str = "tests/inputs/bad.md5";
+
+// --------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> TestResult {
+    let expected = fs::read_to_string(expected_file)?;
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn skips_bad_file() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["tests/inputs/does-not-exist", EMPTY])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::ends_with(format!("  {EMPTY}\n")))
+        .stderr(predicate::str::starts_with("tests/inputs/does-not-exist: "));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn algorithms() -> TestResult {
+    for algorithm in ["md5", "sha1", "sha256", "sha512"] {
+        run(
+            &["-a", algorithm, FOX, EMPTY, BUSTLE],
+            &format!("tests/expected/all.{algorithm}.out"),
+        )?;
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn sha256_by_default() -> TestResult {
+    run(&[FOX, EMPTY, BUSTLE], "tests/expected/all.sha256.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn stdin() -> TestResult {
+    let expected = fs::read_to_string("tests/expected/all.md5.out")?;
+    let fox = expected.lines().next().unwrap().replace(FOX, "-");
+    Command::cargo_bin(PRG)?
+        .args(["--algorithm", "md5"])
+        .write_stdin(fs::read(FOX)?)
+        .assert()
+        .success()
+        .stdout(format!("{fox}\n"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[cfg(unix)]
+#[test]
+fn algorithm_from_name() -> TestResult {
+    let dir = tempfile::tempdir()?;
+    for algorithm in ["md5", "sha1", "sha512"] {
+        let link = dir.path().join(format!("{algorithm}sum"));
+        std::os::unix::fs::symlink(assert_cmd::cargo::cargo_bin(PRG), &link)?;
+        Command::new(&link)
+            .args([FOX, EMPTY, BUSTLE])
+            .assert()
+            .success()
+            .stdout(fs::read_to_string(format!("tests/expected/all.{algorithm}.out"))?);
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn check_good() -> TestResult {
+    run(&["-c", GOOD], "tests/expected/good.sha256.check.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn check_bad() -> TestResult {
+    // The digest length tells the algorithm
+    Command::cargo_bin(PRG)?
+        .args(["--check", BAD])
+        .assert()
+        .code(1)
+        .stdout(fs::read_to_string("tests/expected/bad.md5.check.out")?)
+        .stderr(
+            predicate::str::starts_with("tests/inputs/does-not-exist: ").and(
+                predicate::str::ends_with(
+                    "\nWARNING: 1 line is improperly formatted\n\
+                     WARNING: 1 listed file could not be read\n\
+                     WARNING: 1 computed checksum did NOT match\n",
+                ),
+            ),
+        );
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn check_quiet() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-c", "--quiet", BAD])
+        .assert()
+        .code(1)
+        .stdout(fs::read_to_string("tests/expected/bad.md5.check.quiet.out")?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn check_status() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-c", "--status", GOOD])
+        .assert()
+        .success()
+        .stdout("");
+    Command::cargo_bin(PRG)?
+        .args(["-c", "--status", BAD])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(predicate::str::starts_with("tests/inputs/does-not-exist: "))
+        .stderr(predicate::str::contains("WARNING").not());
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn check_wrong_algorithm() -> TestResult {
+    // SHA-256 digests aren't MD5 ones
+    Command::cargo_bin(PRG)?
+        .args(["-c", "-a", "md5", GOOD])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(format!("{GOOD}: no properly formatted checksum lines found\n"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[cfg(unix)]
+#[test]
+fn escapes_names() -> TestResult {
+    // Names are run in the directory, so they are the ones written
+    let dir = tempfile::tempdir()?;
+    for name in ["a\\b", "c\rd", "e\nf", "plain"] {
+        fs::write(dir.path().join(name), name)?;
+    }
+    let output = Command::cargo_bin(PRG)?
+        .current_dir(&dir)
+        .args(["a\\b", "c\rd", "e\nf", "plain"])
+        .output()?;
+    assert!(output.status.success());
+    let sums = String::from_utf8(output.stdout)?;
+    let names: Vec<_> = sums
+        .lines()
+        .map(|line| line.split_once("  ").map(|(digest, name)| (digest.starts_with('\\'), name)))
+        .collect();
+    assert_eq!(
+        names,
+        [
+            Some((true, "a\\\\b")),
+            Some((true, "c\\rd")),
+            Some((true, "e\\nf")),
+            Some((false, "plain"))
+        ]
+    );
+
+    // Read back, each is found and matches, and only a newline is escaped
+    fs::write(dir.path().join("sums"), sums)?;
+    Command::cargo_bin(PRG)?
+        .current_dir(&dir)
+        .args(["-c", "sums"])
+        .assert()
+        .success()
+        .stdout("a\\b: OK\nc\rd: OK\n\\e\\nf: OK\nplain: OK\n");
+    Ok(())
+}
@@ -1,9 +1,7 @@
-use clap::{App, Arg};
-use std::error::Error;
-use std::fs::File;
-use std::io::{self, BufRead, BufReader, Read};
+use clap::Parser;
+use std::io::{BufRead, Read};
 
-type MyResult<T> = Result<T, Box<dyn Error>>;
+use coreutils_common::{i18n::tr_fmt, open, parse_args, CoreError, MyResult};
 
 #[derive(Debug)]
 pub struct Config {
@@ -12,68 +10,64 @@ pub struct Config {
     bytes: Option<usize>,
 }
 
+#[derive(Debug, Parser)]
+#[command(
+    author = "Ken Youens-Clark <kyclark@gmail.com>",
+    version = "0.1.0",
+    about = "Rust head"
+)]
+struct Args {
+    /// Number of lines
+    #[arg(short = 'n', long, value_name = "LINES", default_value = "10")]
+    lines: String,
+
+    /// Number of bytes
+    #[arg(short = 'c', long, value_name = "BYTES", conflicts_with = "lines")]
+    bytes: Option<String>,
+
+    /// Input file(s)
+    #[arg(value_name = "FILE", default_value = "-")]
+    files: Vec<String>,
+}
+
 pub fn get_args() -> MyResult<Config> {
-    let matches = App::new("headr")
-        .version("0.1.0")
-        .author("Ken Youens-Clark <kyclark@gmail.com>")
-        .about("Rust head")
-        .arg(
-            Arg::with_name("lines")
-                .short("n")
-                .long("lines")
-                .value_name("LINES")
-                .help("Number of lines")
-                .default_value("10"),
-        )
-        .arg(
-            Arg::with_name("bytes")
-                .short("c")
-                .long("bytes")
-                .value_name("BYTES")
-                .takes_value(true)
-                .conflicts_with("lines")
-                .help("Number of bytes"),
-        )
-        .arg(
-            Arg::with_name("files")
-                .value_name("FILE")
-                .help("Input file(s)")
-                .multiple(true)
-                .default_value("-"),
-        )
-        .get_matches();
-
-    let lines = matches
-        .value_of("lines")
-        .map(parse_positive_int)
-        .transpose()
-        .map_err(|e| format!("illegal line count -- {}", e))?;
+    let args: Args = parse_args();
+
+    let lines = parse_positive_int(&args.lines)
+        .map_err(|e| CoreError::Parse(format!("illegal line count -- {}", e)))?;
 
-    let bytes = matches
-        .value_of("bytes")
+    let bytes = args
+        .bytes
+        .as_deref()
         .map(parse_positive_int)
         .transpose()
-        .map_err(|e| format!("illegal byte count -- {}", e))?;
+        .map_err(|e| CoreError::Parse(format!("illegal byte count -- {}", e)))?;
 
     Ok(Config {
-        files: matches.values_of_lossy("files").unwrap(),
-        lines: lines.unwrap(),
+        files: args.files,
+        lines,
         bytes,
     })
 }
 
-pub fn run(config: Config) -> MyResult<()> {
+/// Prints the head of every input and returns the exit status: 1 if any
+/// file failed to open.
+pub fn run(config: Config) -> MyResult<i32> {
+    let mut status = 0;
     let num_files = config.files.len();
 
     for (file_num, filename) in config.files.iter().enumerate() {
         match open(filename) {
-            Err(err) => eprintln!("{}: {}", filename, err),
+            Err(err) => {
+                eprintln!("{}", err);
+                status = 1;
+            }
             Ok(mut file) => {
                 if num_files > 1 {
                     println!(
-                        "{}==> {} <==",
+                        "{}{}",
                         if file_num > 0 { "\n" } else { "" },
-                        &filename
+                        tr_fmt("==> {file} <==", &[("file", &filename)])
                     );
                 }
 
@@ -96,20 +90,14 @@ pub fn run(config: Config) -> MyResult<()> {
             }
         }
     }
-    Ok(())
+    Ok(status)
 }
 
-fn open(filename: &str) -> MyResult<Box<dyn BufRead>> {
-    match filename {
-        "-" => Ok(Box::new(BufReader::new(io::stdin()))),
-        _ => Ok(Box::new(BufReader::new(File::open(filename)?))),
-    }
-}
 
 fn parse_positive_int(val: &str) -> MyResult<usize> {
     match val.parse() {
         Ok(n) if n > 0 => Ok(n),
-        _ => Err(From::from(val)),
+        _ => Err(CoreError::Parse(val.to_string())),
     }
 }
 
@@ -120,7 +108,7 @@ fn test_parse_positive_int() {
     assert_eq!(res.unwrap(), 3);
 
     let res = parse_positive_int("foo");
-    assert!(res.is_err());
+    assert!(matches!(res, Err(CoreError::Parse(_))));
     assert_eq!(res.unwrap_err().to_string(), "foo".to_string());
 
     let res = parse_positive_int("0");
@@ -1,6 +1,10 @@
 fn main() {
-    if let Err(e) = headr::get_args().and_then(headr::run) {
-        eprintln!("{}", e);
-        std::process::exit(1);
+    coreutils_common::reset_sigpipe();
+    match headr::get_args().and_then(headr::run) {
+        Ok(status) => std::process::exit(status),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(e.exit_code());
+        }
     }
 }
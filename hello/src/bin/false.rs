@@ -1,3 +1,3 @@
 fn main() {
-    std::process::exit(1);
+    std::process::exit(hello::run("false", 1, "Exit with a status code indicating failure."));
 }
@@ -1,3 +1,3 @@
 fn main() {
-    std::process::exit(0);
+    std::process::exit(hello::run("true", 0, "Exit with a status code indicating success."));
 }
@@ -0,0 +1,34 @@
+//! `true` and `false`, which do nothing but exit with a fixed status.
+
+use std::{
+    env,
+    ffi::OsString,
+    io::{self, Write},
+};
+
+/// Exits with `status` whatever the arguments, like GNU: only a lone --help
+/// or --version is recognised, and the status stays the same after either.
+/// Returns 1 if that output can't be written.
+pub fn run(name: &str, status: i32, about: &str) -> i32 {
+    let args: Vec<OsString> = env::args_os().skip(1).collect();
+    let text = match args.as_slice() {
+        [arg] if arg == "--help" => format!(
+            "Usage: {name} [ignored command line arguments]\n  \
+             or:  {name} OPTION\n\
+             {about}\n\n      \
+             --help        display this help and exit\n      \
+             --version     output version information and exit\n"
+        ),
+        [arg] if arg == "--version" => format!("{name} {}\n", env!("CARGO_PKG_VERSION")),
+        _ => return status,
+    };
+
+    let mut out = io::stdout().lock();
+    match out.write_all(text.as_bytes()).and_then(|_| out.flush()) {
+        Ok(()) => status,
+        Err(e) => {
+            eprintln!("{name}: write error: {e}");
+            1
+        }
+    }
+}
@@ -17,3 +17,50 @@ fn is_false() {
     let mut cmd = Command::cargo_bin("false").expect("should find false");
     cmd.assert().failure();
 }
+
+#[test]
+fn ignores_arguments() {
+    for args in [&["-x", "foo"][..], &["--help", "--version"], &["--bogus"]] {
+        Command::cargo_bin("true").unwrap().args(args).assert().success().stdout("");
+        Command::cargo_bin("false").unwrap().args(args).assert().code(1).stdout("");
+    }
+}
+
+#[test]
+fn help() {
+    Command::cargo_bin("true")
+        .unwrap()
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with(
+            "Usage: true [ignored command line arguments]\n  or:  true OPTION\n\
+             Exit with a status code indicating success.\n",
+        ));
+    // Like GNU, false fails even when asked for help
+    Command::cargo_bin("false")
+        .unwrap()
+        .arg("--help")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("indicating failure"));
+}
+
+#[test]
+fn version() {
+    Command::cargo_bin("true").unwrap().arg("--version").assert().success().stdout("true 0.1.0\n");
+    Command::cargo_bin("false").unwrap().arg("--version").assert().code(1).stdout("false 0.1.0\n");
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn dies_write_error() {
+    let full = std::fs::OpenOptions::new().write(true).open("/dev/full").unwrap();
+    let output = std::process::Command::new(assert_cmd::cargo::cargo_bin("true"))
+        .arg("--help")
+        .stdout(full)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("true: write error: "));
+}
@@ -0,0 +1,19 @@
+#!/usr/bin/env bash
+
+# Writes the expected output of each test with xxd
+
+OUTDIR="tests/expected"
+[[ ! -d "$OUTDIR" ]] && mkdir -p "$OUTDIR"
+
+BYTES="tests/inputs/bytes.bin"
+FOX="tests/inputs/fox.txt"
+EMPTY="tests/inputs/empty.txt"
+
+xxd $EMPTY > $OUTDIR/empty.txt.out
+xxd $FOX > $OUTDIR/fox.txt.out
+xxd $BYTES > $OUTDIR/bytes.bin.out
+xxd -g 1 -c 8 $BYTES > $OUTDIR/bytes.bin.g1.c8.out
+xxd -g 0 -c 10 $BYTES > $OUTDIR/bytes.bin.g0.c10.out
+xxd -g 3 $BYTES > $OUTDIR/bytes.bin.g3.out
+xxd -s 250 -l 10 $BYTES > $OUTDIR/bytes.bin.s250.n10.out
+xxd -s 0x10 $FOX > $OUTDIR/fox.txt.s0x10.out
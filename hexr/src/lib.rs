@@ -0,0 +1,216 @@
+use clap::Parser;
+use std::{
+    fmt::Write as _,
+    io::{self, BufRead, ErrorKind, Read, Write},
+};
+
+use coreutils_common::{open, parse_args, CoreError, MyResult, Output};
+
+#[derive(Debug)]
+pub struct Config {
+    file: String,
+    group_size: usize,
+    cols: usize,
+    seek: u64,
+    length: Option<u64>,
+    reverse: bool,
+}
+
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust hex dump, like xxd")]
+struct Args {
+    /// Input file
+    #[arg(value_name = "FILE", default_value = "-")]
+    file: String,
+
+    /// Put N bytes in each group of hex digits, or 0 for one group per line
+    #[arg(short = 'g', long = "groupsize", value_name = "N", default_value = "2")]
+    group_size: String,
+
+    /// Dump N bytes per line
+    #[arg(short = 'c', long, value_name = "N", default_value = "16")]
+    cols: String,
+
+    /// Start at OFFSET into the input, decimal or 0x hex
+    #[arg(short = 's', long, value_name = "OFFSET")]
+    seek: Option<String>,
+
+    /// Stop after LEN bytes
+    #[arg(short = 'n', long, value_name = "LEN")]
+    length: Option<String>,
+
+    /// Convert a dump back to binary
+    #[arg(short = 'r', long, conflicts_with_all = ["seek", "length"])]
+    reverse: bool,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let args: Args = parse_args();
+
+    let invalid = |what: &str, val: &str| CoreError::Parse(format!("invalid {what}: \"{val}\""));
+    let parse = |what: &str, val: &str| parse_num(val).ok_or_else(|| invalid(what, val));
+    let cols = parse_num(&args.cols)
+        .filter(|&cols| cols > 0)
+        .ok_or_else(|| invalid("number of columns", &args.cols))? as usize;
+    let group_size = parse("group size", &args.group_size)? as usize;
+
+    Ok(Config {
+        file: args.file,
+        // Like xxd, a group never spans lines
+        group_size: if group_size == 0 { cols } else { group_size.min(cols) },
+        cols,
+        seek: args.seek.map(|val| parse("offset", &val)).transpose()?.unwrap_or(0),
+        length: args.length.map(|val| parse("length", &val)).transpose()?,
+        reverse: args.reverse,
+    })
+}
+
+/// A count or offset, in decimal or with a 0x prefix in hex.
+fn parse_num(val: &str) -> Option<u64> {
+    match val.strip_prefix("0x").or_else(|| val.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => val.parse().ok(),
+    }
+}
+
+/// Reads into `buf` until it is full or the input ends, returning how much
+/// was read.
+fn read_full(input: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+    while len < buf.len() {
+        match input.read(&mut buf[len..]) {
+            Ok(0) => break,
+            Ok(n) => len += n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(len)
+}
+
+/// One line of the dump: the offset, the bytes in hex and then as text,
+/// with anything unprintable as a dot. A short last line is padded so its
+/// text lines up with the lines above.
+fn format_line(offset: u64, bytes: &[u8], cols: usize, group_size: usize) -> String {
+    let width = 2 * cols + cols.div_ceil(group_size);
+    let mut line = format!("{offset:08x}: ");
+    let hex_end = line.len() + width;
+    for (i, byte) in bytes.iter().enumerate() {
+        let _ = write!(line, "{byte:02x}");
+        if (i + 1) % group_size == 0 {
+            line.push(' ');
+        }
+    }
+    line.extend(std::iter::repeat_n(' ', hex_end - line.len() + 1));
+    line.extend(bytes.iter().map(|&byte| match byte {
+        b' '..=b'~' => byte as char,
+        _ => '.',
+    }));
+    line
+}
+
+/// Dumps `input`, numbering lines from `offset`.
+fn dump(input: &mut impl Read, offset: u64, config: &Config, out: &mut impl Write) -> io::Result<()> {
+    let mut offset = offset;
+    let mut buf = vec![0; config.cols];
+    loop {
+        let len = read_full(input, &mut buf)?;
+        if len == 0 {
+            return Ok(());
+        }
+        writeln!(out, "{}", format_line(offset, &buf[..len], config.cols, config.group_size))?;
+        offset += len as u64;
+    }
+}
+
+/// The offset and bytes of one line of a dump, or None if it isn't one.
+/// The hex digits end where the text column starts, after two spaces.
+fn parse_line(line: &str) -> Option<(u64, Vec<u8>)> {
+    let (offset, rest) = line.split_once(':')?;
+    let offset = u64::from_str_radix(offset.trim(), 16).ok()?;
+    let hex = rest.split("  ").next().unwrap_or_default();
+    let mut digits = hex.chars().filter(|&c| c != ' ').map(|c| c.to_digit(16));
+    let mut bytes = vec![];
+    // Like xxd, the line ends at anything that isn't a pair of hex digits
+    while let (Some(Some(high)), Some(Some(low))) = (digits.next(), digits.next()) {
+        bytes.push((high * 16 + low) as u8);
+    }
+    Some((offset, bytes))
+}
+
+/// Writes the bytes of the dump in `input`. A gap between lines is filled
+/// with zeros; a line can't go back before what was already written.
+fn revert(input: &mut impl BufRead, out: &mut impl Write) -> MyResult<()> {
+    let mut written = 0;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        let Some((offset, bytes)) = parse_line(&line) else {
+            continue;
+        };
+        if offset < written {
+            return Err(CoreError::Input(format!("cannot seek back to offset {offset:#x}")));
+        }
+        io::copy(&mut io::repeat(0).take(offset - written), out)?;
+        out.write_all(&bytes)?;
+        written = offset + bytes.len() as u64;
+    }
+}
+
+pub fn run(config: Config) -> MyResult<i32> {
+    let mut input = open(&config.file)?;
+    let mut out = Output::stdout();
+    if config.reverse {
+        revert(&mut input, &mut out)?;
+    } else {
+        let read_err = |e| CoreError::io(&config.file, e);
+        // Skipping by reading works even when stdin can't seek
+        let skipped =
+            io::copy(&mut input.by_ref().take(config.seek), &mut io::sink()).map_err(read_err)?;
+        let mut input = input.take(config.length.unwrap_or(u64::MAX));
+        dump(&mut input, skipped, &config, &mut out).map_err(read_err)?;
+    }
+    out.flush()?;
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_line, parse_line, parse_num};
+
+    #[test]
+    fn test_parse_num() {
+        assert_eq!(parse_num("42"), Some(42));
+        assert_eq!(parse_num("0x2a"), Some(42));
+        assert_eq!(parse_num("0X2A"), Some(42));
+        assert_eq!(parse_num("-1"), None);
+        assert_eq!(parse_num("2a"), None);
+    }
+
+    #[test]
+    fn test_format_line() {
+        assert_eq!(
+            format_line(0, b"Hello, world!\n\0\xff", 16, 2),
+            "00000000: 4865 6c6c 6f2c 2077 6f72 6c64 210a 00ff  Hello, world!..."
+        );
+        assert_eq!(
+            format_line(0, b"f hexr\0", 10, 4),
+            "00000000: 66206865 787200         f hexr."
+        );
+        assert_eq!(format_line(8, b"ab", 4, 4), "00000008: 6162      ab");
+    }
+
+    #[test]
+    fn test_parse_line() {
+        let line = "00000010: 6973 2069 7320 6120 7465 7374 206f 6620  is is a test of \n";
+        assert_eq!(parse_line(line), Some((16, b"is is a test of ".to_vec())));
+        // The text column may look like hex
+        let line = "00000020: 6162                                     ab\n";
+        assert_eq!(parse_line(line), Some((32, b"ab".to_vec())));
+        assert_eq!(parse_line("0000000a: 41"), Some((10, b"A".to_vec())));
+        assert_eq!(parse_line("not a dump"), None);
+    }
+}
@@ -0,0 +1,160 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "hexr";
+const BYTES: &str = "tests/inputs/bytes.bin";
+const FOX: &str = "tests/inputs/fox.txt";
+const EMPTY: &str = "tests/inputs/empty.txt";
+
+// --------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> TestResult {
+    let expected = fs::read_to_string(expected_file)?;
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_file() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .arg("tests/inputs/does-not-exist")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::starts_with("tests/inputs/does-not-exist: "));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_numbers() -> TestResult {
+    for (args, msg) in [
+        (["-c", "0"], "invalid number of columns: \"0\""),
+        (["-g", "x"], "invalid group size: \"x\""),
+        (["-s", "1k"], "invalid offset: \"1k\""),
+        (["-n", "0xg"], "invalid length: \"0xg\""),
+    ] {
+        Command::cargo_bin(PRG)?
+            .args(args)
+            .arg(FOX)
+            .assert()
+            .code(1)
+            .stderr(format!("{msg}\n"));
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn empty() -> TestResult {
+    run(&[EMPTY], "tests/expected/empty.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fox() -> TestResult {
+    run(&[FOX], "tests/expected/fox.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fox_stdin() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .write_stdin(fs::read(FOX)?)
+        .assert()
+        .success()
+        .stdout(fs::read_to_string("tests/expected/fox.txt.out")?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn bytes() -> TestResult {
+    run(&[BYTES], "tests/expected/bytes.bin.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn bytes_groups_of_one() -> TestResult {
+    run(&["-g", "1", "-c", "8", BYTES], "tests/expected/bytes.bin.g1.c8.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn bytes_no_groups() -> TestResult {
+    run(&["-g", "0", "--cols", "10", BYTES], "tests/expected/bytes.bin.g0.c10.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn bytes_groups_of_three() -> TestResult {
+    run(&["--groupsize", "3", BYTES], "tests/expected/bytes.bin.g3.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn seek_and_length() -> TestResult {
+    run(&["-s", "250", "-n", "10", BYTES], "tests/expected/bytes.bin.s250.n10.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn seek_hex_stdin() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--seek", "0x10"])
+        .write_stdin(fs::read(FOX)?)
+        .assert()
+        .success()
+        .stdout(fs::read_to_string("tests/expected/fox.txt.s0x10.out")?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn reverse() -> TestResult {
+    for dump in [
+        "tests/expected/bytes.bin.out",
+        "tests/expected/bytes.bin.g1.c8.out",
+        "tests/expected/bytes.bin.g0.c10.out",
+        "tests/expected/bytes.bin.g3.out",
+    ] {
+        Command::cargo_bin(PRG)?
+            .args(["-r", dump])
+            .assert()
+            .success()
+            .stdout(fs::read(BYTES)?);
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn reverse_fills_gaps() -> TestResult {
+    // A dump that starts part way in is zero-filled up to its first offset
+    let mut expected = vec![0; 0x10];
+    expected.extend(&fs::read(FOX)?[0x10..]);
+    Command::cargo_bin(PRG)?
+        .args(["-r", "tests/expected/fox.txt.s0x10.out"])
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_reverse_backwards() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .arg("-r")
+        .write_stdin("00000010: 4142  AB\n00000000: 4344  CD\n")
+        .assert()
+        .code(1)
+        .stderr("cannot seek back to offset 0x0\n");
+    Ok(())
+}
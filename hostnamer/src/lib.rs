@@ -0,0 +1,71 @@
+use clap::Parser;
+
+use coreutils_common::{host, parse_args, CoreError, MyResult};
+
+#[derive(Debug)]
+pub struct Config {
+    format: Format,
+}
+
+/// What to print about the host.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Name,
+    Short,
+    Fqdn,
+    Addresses,
+}
+
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust hostname")]
+struct Args {
+    /// Print the full name, with the domain, as the resolver knows it
+    #[arg(
+        short = 'f',
+        long,
+        visible_alias = "long",
+        overrides_with_all = ["short", "ip_address"]
+    )]
+    fqdn: bool,
+
+    /// Print the name up to the first dot
+    #[arg(short = 's', long, overrides_with_all = ["fqdn", "ip_address"])]
+    short: bool,
+
+    /// Print the addresses the name resolves to
+    #[arg(short = 'i', long, overrides_with_all = ["fqdn", "short"])]
+    ip_address: bool,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let args: Args = parse_args();
+
+    let format = if args.fqdn {
+        Format::Fqdn
+    } else if args.short {
+        Format::Short
+    } else if args.ip_address {
+        Format::Addresses
+    } else {
+        Format::Name
+    };
+    Ok(Config { format })
+}
+
+/// Prints the host's name, or what the resolver says about it.
+pub fn run(config: Config) -> MyResult<i32> {
+    let name = host::hostname()?;
+    let lookup = || host::lookup(&name).map_err(|e| CoreError::Input(e.to_string()));
+    let line = match config.format {
+        Format::Name => name.clone(),
+        Format::Short => name.split('.').next().unwrap_or_default().to_string(),
+        Format::Fqdn => lookup()?.canonical_name.unwrap_or_else(|| name.clone()),
+        Format::Addresses => {
+            let addresses: Vec<String> =
+                lookup()?.addresses.iter().map(ToString::to_string).collect();
+            addresses.join(" ")
+        }
+    };
+    println!("{line}");
+    Ok(0)
+}
@@ -0,0 +1,77 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::net::IpAddr;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "hostnamer";
+
+// --------------------------------------------------
+/// What the program prints with `args`, less the newline.
+fn output(args: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+    let output = Command::cargo_bin(PRG)?.args(args).output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    Ok(stdout.strip_suffix('\n').ok_or("no newline")?.to_string())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_args() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .arg("-x")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("unexpected argument"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn name() -> TestResult {
+    let name = output(&[])?;
+    assert!(!name.is_empty() && !name.contains(char::is_whitespace), "{name:?}");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn short() -> TestResult {
+    let name = output(&[])?;
+    let short = output(&["-s"])?;
+    assert_eq!(short, name.split('.').next().unwrap());
+    assert_eq!(output(&["--short"])?, short);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn fqdn() -> TestResult {
+    // Hosts often don't resolve in sandboxes, so only check when it does
+    let fqdn = Command::cargo_bin(PRG)?.arg("-f").output()?;
+    if fqdn.status.success() {
+        let short = output(&["-s"])?;
+        let fqdn = String::from_utf8(fqdn.stdout)?;
+        assert!(fqdn.to_lowercase().starts_with(&short.to_lowercase()), "{fqdn}");
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn ip_address() -> TestResult {
+    let addresses = Command::cargo_bin(PRG)?.arg("-i").output()?;
+    if addresses.status.success() {
+        for address in String::from_utf8(addresses.stdout)?.split_whitespace() {
+            address.parse::<IpAddr>()?;
+        }
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn last_format_wins() -> TestResult {
+    assert_eq!(output(&["-i", "-s"])?, output(&["-s"])?);
+    Ok(())
+}
@@ -0,0 +1,18 @@
+#[cfg(unix)]
+fn main() {
+    coreutils_common::reset_sigpipe();
+    match idr::whoami::get_args().and_then(idr::whoami::run) {
+        Ok(status) => std::process::exit(status),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(e.exit_code());
+        }
+    }
+}
+
+/// Users and groups are only looked up on Unix.
+#[cfg(not(unix))]
+fn main() {
+    eprintln!("whoamir needs Unix users and groups");
+    std::process::exit(1);
+}
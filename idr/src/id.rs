@@ -0,0 +1,214 @@
+//! idr: the user and groups of a user, or of the process.
+
+use clap::Parser;
+use std::io::Write;
+
+use crate::{group_name, process_groups, user_groups, user_name};
+use coreutils_common::{parse_args, CoreError, MyResult, Output};
+
+#[derive(Debug)]
+pub struct Config {
+    users: Vec<String>,
+    only: Option<Only>,
+    names: bool,
+    real: bool,
+}
+
+/// The one thing to print instead of everything.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Only {
+    User,
+    Group,
+    Groups,
+}
+
+#[derive(Debug, Parser)]
+#[command(name = "idr", author = "OFFBLACK", version = "0.1.0", about = "Rust id")]
+struct Args {
+    /// Users to print, by name or number [default: the process]
+    #[arg(value_name = "USER")]
+    users: Vec<String>,
+
+    /// Print only the effective user ID
+    #[arg(short = 'u', long)]
+    user: bool,
+
+    /// Print only the effective group ID
+    #[arg(short = 'g', long)]
+    group: bool,
+
+    /// Print all group IDs
+    #[arg(short = 'G', long)]
+    groups: bool,
+
+    /// Print names instead of numbers, with -u, -g or -G
+    #[arg(short = 'n', long)]
+    name: bool,
+
+    /// Print the real rather than effective IDs, with -u, -g or -G
+    #[arg(short = 'r', long)]
+    real: bool,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let args: Args = parse_args();
+
+    let only = match (args.user, args.group, args.groups) {
+        (false, false, false) => None,
+        (true, false, false) => Some(Only::User),
+        (false, true, false) => Some(Only::Group),
+        (false, false, true) => Some(Only::Groups),
+        _ => {
+            return Err(CoreError::Usage(
+                "cannot print \"only\" of more than one choice".to_string(),
+            ))
+        }
+    };
+    if only.is_none() && (args.name || args.real) {
+        return Err(CoreError::Usage(
+            "cannot print only names or real IDs in default format".to_string(),
+        ));
+    }
+    Ok(Config { users: args.users, only, names: args.name, real: args.real })
+}
+
+/// Who to print: a user, or the process, whose real and effective IDs can
+/// differ.
+#[derive(Debug)]
+struct Ids {
+    uid: u32,
+    euid: u32,
+    gid: u32,
+    egid: u32,
+    /// Every group, starting with the effective one, or the real one for -r
+    groups: Vec<u32>,
+}
+
+impl Ids {
+    fn process(real: bool) -> MyResult<Self> {
+        let gid = users::get_current_gid();
+        let egid = users::get_effective_gid();
+        Ok(Ids {
+            uid: users::get_current_uid(),
+            euid: users::get_effective_uid(),
+            gid,
+            egid,
+            groups: process_groups(if real { gid } else { egid })?,
+        })
+    }
+
+    /// The user with the name, or failing that the number, `spec`.
+    fn user(spec: &str) -> MyResult<Self> {
+        let user = users::get_user_by_name(spec)
+            .or_else(|| spec.parse().ok().and_then(users::get_user_by_uid))
+            .ok_or_else(|| CoreError::Input(format!("'{spec}': no such user")))?;
+        let gid = user.primary_group_id();
+        Ok(Ids {
+            uid: user.uid(),
+            euid: user.uid(),
+            gid,
+            egid: gid,
+            groups: user_groups(&user.name().to_string_lossy(), gid),
+        })
+    }
+}
+
+/// Names users and groups for -n: by number if they have no name, which is
+/// an error kept to report once the line is printed.
+#[derive(Default)]
+struct Namer {
+    errors: Vec<String>,
+}
+
+impl Namer {
+    fn user(&mut self, uid: u32) -> String {
+        user_name(uid).unwrap_or_else(|| {
+            self.errors.push(format!("cannot find name for user ID {uid}"));
+            uid.to_string()
+        })
+    }
+
+    fn group(&mut self, gid: u32) -> String {
+        group_name(gid).unwrap_or_else(|| {
+            self.errors.push(format!("cannot find name for group ID {gid}"));
+            gid.to_string()
+        })
+    }
+}
+
+/// `id` and its name in parentheses, if there is one.
+fn with_name(id: u32, name: Option<String>) -> String {
+    match name {
+        Some(name) => format!("{id}({name})"),
+        None => id.to_string(),
+    }
+}
+
+/// Everything about `ids` on one line, as uid=0(root) gid=0(root) ...
+fn describe(ids: &Ids) -> String {
+    let mut line = format!(
+        "uid={} gid={}",
+        with_name(ids.uid, user_name(ids.uid)),
+        with_name(ids.gid, group_name(ids.gid))
+    );
+    if ids.euid != ids.uid {
+        line += &format!(" euid={}", with_name(ids.euid, user_name(ids.euid)));
+    }
+    if ids.egid != ids.gid {
+        line += &format!(" egid={}", with_name(ids.egid, group_name(ids.egid)));
+    }
+    let groups: Vec<String> =
+        ids.groups.iter().map(|&gid| with_name(gid, group_name(gid))).collect();
+    line + " groups=" + &groups.join(",")
+}
+
+/// Prints each user, or the process, and returns the exit status: 1 if a
+/// user doesn't exist or a name wasn't found.
+pub fn run(config: Config) -> MyResult<i32> {
+    let mut out = Output::stdout();
+    let mut status = 0;
+    let who = if config.users.is_empty() {
+        vec![Ids::process(config.real)]
+    } else {
+        config.users.iter().map(|spec| Ids::user(spec)).collect()
+    };
+    for ids in who {
+        let ids = match ids {
+            Ok(ids) => ids,
+            Err(e) => {
+                out.flush()?;
+                eprintln!("{e}");
+                status = 1;
+                continue;
+            }
+        };
+        let mut namer = Namer::default();
+        let line = match (config.only, config.names) {
+            (None, _) => describe(&ids),
+            (Some(Only::User), names) => {
+                let uid = if config.real { ids.uid } else { ids.euid };
+                if names { namer.user(uid) } else { uid.to_string() }
+            }
+            (Some(Only::Group), names) => {
+                let gid = if config.real { ids.gid } else { ids.egid };
+                if names { namer.group(gid) } else { gid.to_string() }
+            }
+            (Some(Only::Groups), names) => {
+                let groups: Vec<String> = ids
+                    .groups
+                    .iter()
+                    .map(|&gid| if names { namer.group(gid) } else { gid.to_string() })
+                    .collect();
+                groups.join(" ")
+            }
+        };
+        writeln!(out, "{line}")?;
+        if !namer.errors.is_empty() {
+            out.flush()?;
+            namer.errors.iter().for_each(|e| eprintln!("{e}"));
+            status = 1;
+        }
+    }
+    out.flush()?;
+    Ok(status)
+}
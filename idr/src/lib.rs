@@ -0,0 +1,80 @@
+//! `idr` and `whoamir`, which print who a process runs as: its user and
+//! groups, by number and by name. They look up names with the users crate,
+//! so are only built for Unix.
+#![cfg(unix)]
+
+use std::io;
+
+pub mod id;
+pub mod whoami;
+
+/// The name of user `uid`, if it has one.
+fn user_name(uid: u32) -> Option<String> {
+    users::get_user_by_uid(uid).map(|user| user.name().to_string_lossy().into_owned())
+}
+
+/// The name of group `gid`, if it has one.
+fn group_name(gid: u32) -> Option<String> {
+    users::get_group_by_gid(gid).map(|group| group.name().to_string_lossy().into_owned())
+}
+
+/// `first` and then the supplementary groups of this process, each once.
+fn process_groups(first: u32) -> io::Result<Vec<u32>> {
+    // SAFETY: with a size of 0, getgroups only counts the groups
+    let count = unsafe { libc::getgroups(0, std::ptr::null_mut()) };
+    if count < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let mut groups = vec![0; count as usize];
+    // SAFETY: `groups` has room for `count` of them
+    let count = unsafe { libc::getgroups(count, groups.as_mut_ptr()) };
+    if count < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    groups.truncate(count as usize);
+    Ok(with_first(first, groups))
+}
+
+/// `gid` and then the groups that the user `name` is a member of, each
+/// once.
+fn user_groups(name: &str, gid: u32) -> Vec<u32> {
+    let Ok(cname) = std::ffi::CString::new(name) else {
+        return vec![gid];
+    };
+    let mut groups: Vec<libc::gid_t> = vec![0; 64];
+    loop {
+        let mut count = groups.len() as libc::c_int;
+        // SAFETY: `groups` has room for `count` of them, and getgrouplist
+        // says how many it needs if that isn't enough
+        let found = unsafe {
+            libc::getgrouplist(cname.as_ptr(), gid as _, groups.as_mut_ptr() as _, &mut count)
+        };
+        if found >= 0 {
+            groups.truncate(count as usize);
+            return with_first(gid, groups);
+        }
+        groups.resize(groups.len().max(count as usize) * 2, 0);
+    }
+}
+
+/// `groups` with `first` moved to the front, without repeats.
+fn with_first(first: u32, groups: Vec<u32>) -> Vec<u32> {
+    let mut all = vec![first];
+    for gid in groups {
+        if !all.contains(&gid) {
+            all.push(gid);
+        }
+    }
+    all
+}
+
+#[cfg(test)]
+mod tests {
+    use super::with_first;
+
+    #[test]
+    fn test_with_first() {
+        assert_eq!(with_first(5, vec![]), [5]);
+        assert_eq!(with_first(5, vec![1, 5, 2, 1]), [5, 1, 2]);
+    }
+}
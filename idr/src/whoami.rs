@@ -0,0 +1,28 @@
+//! whoamir: the name of the user the process runs as.
+
+use clap::Parser;
+
+use crate::user_name;
+use coreutils_common::{parse_args, CoreError, MyResult};
+
+#[derive(Debug)]
+pub struct Config {}
+
+#[derive(Debug, Parser)]
+#[command(name = "whoamir", author = "OFFBLACK", version = "0.1.0", about = "Rust whoami")]
+struct Args {}
+
+pub fn get_args() -> MyResult<Config> {
+    let _args: Args = parse_args();
+    Ok(Config {})
+}
+
+/// Prints the name of the effective user, the one whose permissions
+/// the process has.
+pub fn run(_config: Config) -> MyResult<i32> {
+    let uid = users::get_effective_uid();
+    let name = user_name(uid)
+        .ok_or_else(|| CoreError::Input(format!("cannot find name for user ID {uid}")))?;
+    println!("{name}");
+    Ok(0)
+}
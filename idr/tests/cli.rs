@@ -0,0 +1,117 @@
+#![cfg(unix)]
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const ID: &str = "idr";
+const WHOAMI: &str = "whoamir";
+
+// --------------------------------------------------
+fn run(prg: &str, args: &[&str], expected: &str) -> TestResult {
+    Command::cargo_bin(prg)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(format!("{expected}\n"));
+    Ok(())
+}
+
+// --------------------------------------------------
+/// The names of the user and group the process runs as.
+fn names() -> (String, String) {
+    let user = users::get_effective_username().unwrap();
+    let group = users::get_effective_groupname().unwrap();
+    (user.to_string_lossy().into_owned(), group.to_string_lossy().into_owned())
+}
+
+// --------------------------------------------------
+#[test]
+fn whoami() -> TestResult {
+    run(WHOAMI, &[], &names().0)
+}
+
+// --------------------------------------------------
+#[test]
+fn whoami_dies_operand() -> TestResult {
+    Command::cargo_bin(WHOAMI)?
+        .arg("root")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unexpected argument 'root'"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_flags() -> TestResult {
+    for (args, msg) in [
+        (&["-u", "-g"][..], "cannot print \"only\" of more than one choice"),
+        (&["-G", "-u"], "cannot print \"only\" of more than one choice"),
+        (&["-n"], "cannot print only names or real IDs in default format"),
+        (&["-r"], "cannot print only names or real IDs in default format"),
+    ] {
+        Command::cargo_bin(ID)?
+            .args(args)
+            .assert()
+            .code(1)
+            .stdout("")
+            .stderr(format!("{msg}\n"));
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_no_such_user() -> TestResult {
+    // The users that do exist are still printed
+    Command::cargo_bin(ID)?
+        .args(["-u", "no-such-user", "root"])
+        .assert()
+        .code(1)
+        .stdout("0\n")
+        .stderr("'no-such-user': no such user\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn only() -> TestResult {
+    let (user, group) = names();
+    run(ID, &["-u"], &users::get_effective_uid().to_string())?;
+    run(ID, &["-un"], &user)?;
+    run(ID, &["-r", "-u"], &users::get_current_uid().to_string())?;
+    run(ID, &["-g"], &users::get_effective_gid().to_string())?;
+    run(ID, &["--group", "--name"], &group)
+}
+
+// --------------------------------------------------
+#[test]
+fn groups_start_with_effective() -> TestResult {
+    let output = Command::cargo_bin(ID)?.arg("-G").output()?;
+    let groups = String::from_utf8(output.stdout)?;
+    let first = groups.split_whitespace().next();
+    assert_eq!(first, Some(users::get_effective_gid().to_string().as_str()));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn root() -> TestResult {
+    // Root is user 0 everywhere, though its group's name varies
+    let root = users::get_user_by_uid(0).unwrap();
+    let gid = root.primary_group_id();
+    let group = users::get_group_by_gid(gid).unwrap();
+    let group = group.name().to_string_lossy();
+    // It may be in other groups too, but its own comes first
+    Command::cargo_bin(ID)?
+        .arg("root")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(format!(
+            "uid=0(root) gid={gid}({group}) groups={gid}({group})"
+        )));
+    run(ID, &["-u", "0"], "0")?;
+    run(ID, &["-gn", "root"], &group)
+}
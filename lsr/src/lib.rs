@@ -1,57 +1,66 @@
-use std::{error::Error, fs, os::unix::fs::MetadataExt, path::PathBuf};
+use std::{
+    fs::{self, Metadata},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
 
 use chrono::{DateTime, Local};
-use clap::{App, Arg};
+use clap::Parser;
+use serde::Serialize;
 use tabular::{Row, Table};
-use users::{get_group_by_gid, get_user_by_uid};
 
-type MyResult<T> = Result<T, Box<dyn Error>>;
+use coreutils_common::{parse_args, write_json, CoreError, MyResult};
 
 #[derive(Debug)]
 pub struct Config {
     paths: Vec<String>,
     long: bool,
     show_hidden: bool,
+    json: bool,
+}
+
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust ls")]
+struct Args {
+    /// Files and/or directories
+    #[arg(value_name = "PATH", default_value = ".")]
+    paths: Vec<String>,
+
+    /// Show all files
+    #[arg(short = 'a', long = "all")]
+    show_hidden: bool,
+
+    /// Long listing
+    #[arg(short = 'l', long)]
+    long: bool,
+
+    /// Print each entry's details as a line of JSON
+    #[arg(long, conflicts_with = "long")]
+    json: bool,
 }
 
 pub fn get_args() -> MyResult<Config> {
-    let matches = App::new("lsr")
-        .about("Rust ls")
-        .author("OFFBLACK")
-        .version("0.1.0")
-        .arg(
-            Arg::with_name("paths")
-                .help("Files and/or directories")
-                .default_value(".")
-                .multiple(true)
-                .value_name("PATH"),
-        )
-        .arg(
-            Arg::with_name("show_hidden")
-                .short("a")
-                .long("all")
-                .help("Show all files"),
-        )
-        .arg(
-            Arg::with_name("long")
-                .short("l")
-                .long("long")
-                .help("Long listing"),
-        )
-        .get_matches();
+    let args: Args = parse_args();
 
     Ok(Config {
-        paths: matches.values_of_lossy("paths").unwrap(),
-        show_hidden: matches.is_present("show_hidden"),
-        long: matches.is_present("long"),
+        paths: args.paths,
+        show_hidden: args.show_hidden,
+        long: args.long,
+        json: args.json,
     })
 }
 
-fn find_files(paths: &[String], show_hidden: bool) -> MyResult<Vec<PathBuf>> {
+/// Lists the given files and the contents of the given directories. Paths
+/// that don't exist are reported on stderr and make the status 1.
+fn find_files(paths: &[String], show_hidden: bool) -> MyResult<(Vec<PathBuf>, i32)> {
     let mut results = Vec::new();
+    let mut status = 0;
     for path in paths {
         match fs::metadata(path) {
-            Err(e) => eprintln!("{path}: {e}"),
+            Err(e) => {
+                eprintln!("{}", CoreError::io(path, e));
+                status = 1;
+            }
             Ok(file) if file.is_file() => {
                 results.push(PathBuf::from(path));
             }
@@ -59,14 +68,92 @@ fn find_files(paths: &[String], show_hidden: bool) -> MyResult<Vec<PathBuf>> {
                 for file in fs::read_dir(path)? {
                     let file = file?;
                     if show_hidden || !file.file_name().to_string_lossy().starts_with(".") {
-                        results.push(PathBuf::from(file.path()));
+                        results.push(file.path());
                     }
                 }
             }
             _ => {}
         }
     }
-    Ok(results)
+    Ok((results, status))
+}
+
+/// The details of one entry, as shown by -l and printed by --json.
+#[derive(Debug, Serialize)]
+struct Entry {
+    path: String,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    mode: String,
+    links: u64,
+    user: String,
+    group: String,
+    size: u64,
+    modified: DateTime<Local>,
+}
+
+fn describe(path: &Path) -> MyResult<Entry> {
+    let metadata = path.metadata()?;
+    let (user, group) = owner(&metadata);
+
+    Ok(Entry {
+        path: path.display().to_string(),
+        kind: if metadata.is_dir() { "dir" } else { "file" },
+        mode: format_mode(mode_bits(&metadata)),
+        links: link_count(&metadata),
+        user,
+        group,
+        size: metadata.len(),
+        modified: DateTime::from(metadata.modified()?),
+    })
+}
+
+#[cfg(unix)]
+fn mode_bits(metadata: &Metadata) -> u32 {
+    std::os::unix::fs::MetadataExt::mode(metadata)
+}
+
+/// Windows has no permission bits, so derive them from the read-only
+/// attribute: everyone may read, and write unless it's set. Directories are
+/// always searchable.
+#[cfg(not(unix))]
+fn mode_bits(metadata: &Metadata) -> u32 {
+    let write = if metadata.permissions().readonly() { 0 } else { 0o222 };
+    let search = if metadata.is_dir() { 0o111 } else { 0 };
+    0o444 | write | search
+}
+
+#[cfg(unix)]
+fn link_count(metadata: &Metadata) -> u64 {
+    std::os::unix::fs::MetadataExt::nlink(metadata)
+}
+
+#[cfg(not(unix))]
+fn link_count(_metadata: &Metadata) -> u64 {
+    1
+}
+
+/// The names of the owning user and group, or their ids if they have none.
+#[cfg(unix)]
+fn owner(metadata: &Metadata) -> (String, String) {
+    use std::os::unix::fs::MetadataExt;
+    use users::{get_group_by_gid, get_user_by_uid};
+
+    let uid = metadata.uid();
+    let user = get_user_by_uid(uid)
+        .map(|u| u.name().to_string_lossy().into_owned())
+        .unwrap_or_else(|| uid.to_string());
+    let gid = metadata.gid();
+    let group = get_group_by_gid(gid)
+        .map(|g| g.name().to_string_lossy().into_owned())
+        .unwrap_or_else(|| gid.to_string());
+    (user, group)
+}
+
+/// Ownership isn't part of the standard metadata on other platforms.
+#[cfg(not(unix))]
+fn owner(_metadata: &Metadata) -> (String, String) {
+    ("-".to_string(), "-".to_string())
 }
 
 fn format_output(paths: &[PathBuf]) -> MyResult<String> {
@@ -74,29 +161,17 @@ fn format_output(paths: &[PathBuf]) -> MyResult<String> {
     let mut table = Table::new(fmt);
 
     for path in paths {
-        let metadata = path.metadata()?;
-
-        let uid = metadata.uid();
-        let user = get_user_by_uid(uid)
-            .map(|u| u.name().to_string_lossy().into_owned())
-            .unwrap_or_else(|| uid.to_string());
-        let gid = metadata.gid();
-        let group = get_group_by_gid(gid)
-            .map(|g| g.name().to_string_lossy().into_owned())
-            .unwrap_or_else(|| gid.to_string());
-
-        let last_modified: DateTime<Local> = DateTime::from(metadata.modified()?);
-
+        let entry = describe(path)?;
         table.add_row(
             Row::new()
-                .with_cell(if path.is_dir() { "d" } else { "-" })
-                .with_cell(format_mode(metadata.mode()))
-                .with_cell(metadata.nlink())
-                .with_cell(user)
-                .with_cell(group)
-                .with_cell(metadata.len())
-                .with_cell(last_modified.format("%b %d %y %H:%M"))
-                .with_cell(path.display()),
+                .with_cell(if entry.kind == "dir" { "d" } else { "-" })
+                .with_cell(entry.mode)
+                .with_cell(entry.links)
+                .with_cell(entry.user)
+                .with_cell(entry.group)
+                .with_cell(entry.size)
+                .with_cell(entry.modified.format("%b %d %y %H:%M"))
+                .with_cell(entry.path),
         );
     }
 
@@ -126,29 +201,37 @@ fn format_mode(mode: u32) -> String {
     result
 }
 
-pub fn run(config: Config) -> MyResult<()> {
-    let paths = find_files(&config.paths, config.show_hidden)?;
-    if config.long {
+/// Prints the listing and returns the exit status: 1 if any path was missing.
+pub fn run(config: Config) -> MyResult<i32> {
+    let (paths, status) = find_files(&config.paths, config.show_hidden)?;
+    if config.json {
+        let mut out = BufWriter::new(io::stdout().lock());
+        for path in &paths {
+            write_json(&mut out, &describe(path)?)?;
+        }
+        out.flush()?;
+    } else if config.long {
         println!("{}", format_output(&paths)?);
     } else {
         for path in paths {
             println!("{}", path.display());
         }
     }
-    Ok(())
+    Ok(status)
 }
 
 #[cfg(test)]
 mod tests {
     use super::{find_files, format_mode};
+    use std::path::PathBuf;
 
     #[test]
     fn test_find_files() {
         // Find all non-hidden entries in a directory
         let res = find_files(&["tests/inputs".to_string()], false);
-        assert!(res.is_ok());
-        let mut filenames: Vec<_> = res
-            .unwrap()
+        let (paths, status) = res.unwrap();
+        assert_eq!(status, 0);
+        let mut filenames: Vec<_> = paths
             .iter()
             .map(|entry| entry.display().to_string())
             .collect();
@@ -165,9 +248,9 @@ mod tests {
 
         // Any existing file should be found even if hidden
         let res = find_files(&["tests/inputs/.hidden".to_string()], false);
-        assert!(res.is_ok());
-        let filenames: Vec<_> = res
-            .unwrap()
+        let (paths, status) = res.unwrap();
+        assert_eq!(status, 0);
+        let filenames: Vec<_> = paths
             .iter()
             .map(|entry| entry.display().to_string())
             .collect();
@@ -181,9 +264,9 @@ mod tests {
             ],
             false,
         );
-        assert!(res.is_ok());
-        let mut filenames: Vec<_> = res
-            .unwrap()
+        let (paths, status) = res.unwrap();
+        assert_eq!(status, 0);
+        let mut filenames: Vec<_> = paths
             .iter()
             .map(|entry| entry.display().to_string())
             .collect();
@@ -194,13 +277,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_find_files_missing() {
+        // A missing path is skipped but sets the status
+        let res = find_files(
+            &[
+                "tests/inputs/fox.txt".to_string(),
+                "tests/inputs/does-not-exist".to_string(),
+            ],
+            false,
+        );
+        let (paths, status) = res.unwrap();
+        assert_eq!(paths, [PathBuf::from("tests/inputs/fox.txt")]);
+        assert_eq!(status, 1);
+    }
+
     #[test]
     fn test_find_files_hidden() {
         // Find all entries in a directory including hidden
         let res = find_files(&["tests/inputs".to_string()], true);
-        assert!(res.is_ok());
-        let mut filenames: Vec<_> = res
-            .unwrap()
+        let (paths, status) = res.unwrap();
+        assert_eq!(status, 0);
+        let mut filenames: Vec<_> = paths
             .iter()
             .map(|entry| entry.display().to_string())
             .collect();
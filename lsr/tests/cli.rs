@@ -30,13 +30,12 @@ fn gen_bad_file() -> String {
 #[test]
 fn bad_file() -> TestResult {
     let bad = gen_bad_file();
-    let expected =
-        format!("{}: No such file or directory (os error 2)", &bad);
+    let expected = format!("{}: .* [(]os error 2[)]", &bad);
     Command::cargo_bin(PRG)?
         .arg(&bad)
         .assert()
-        .success()
-        .stderr(predicate::str::contains(expected));
+        .code(1)
+        .stderr(predicate::str::is_match(expected)?);
     Ok(())
 }
 
@@ -64,12 +63,12 @@ fn run_short(arg: &str) -> TestResult {
 // --------------------------------------------------
 fn run_long(filename: &str, permissions: &str, size: &str) -> TestResult {
     let cmd = Command::cargo_bin(PRG)?
-        .args(&["--long", filename])
+        .args(["--long", filename])
         .assert()
         .success();
     let stdout = String::from_utf8(cmd.get_output().stdout.clone())?;
     let parts: Vec<_> = stdout.split_whitespace().collect();
-    assert_eq!(parts.get(0).unwrap(), &permissions);
+    assert_eq!(parts.first().unwrap(), &permissions);
     assert_eq!(parts.get(4).unwrap(), &size);
     assert_eq!(parts.last().unwrap(), &filename);
     Ok(())
@@ -82,6 +81,7 @@ fn empty() -> TestResult {
 }
 
 #[test]
+#[cfg(unix)]
 fn empty_long() -> TestResult {
     run_long(EMPTY, "-rw-r--r--", "0")
 }
@@ -93,6 +93,7 @@ fn bustle() -> TestResult {
 }
 
 #[test]
+#[cfg(unix)]
 fn bustle_long() -> TestResult {
     run_long(BUSTLE, "-rw-r--r--", "193")
 }
@@ -104,10 +105,18 @@ fn fox() -> TestResult {
 }
 
 #[test]
+#[cfg(unix)]
 fn fox_long() -> TestResult {
     run_long(FOX, "-rw-------", "45")
 }
 
+#[test]
+#[cfg(windows)]
+fn fox_long() -> TestResult {
+    // Permissions come from the read-only attribute
+    run_long(FOX, "-rw-rw-rw-", "45")
+}
+
 // --------------------------------------------------
 #[test]
 fn hidden() -> TestResult {
@@ -115,19 +124,32 @@ fn hidden() -> TestResult {
 }
 
 #[test]
+#[cfg(unix)]
 fn hidden_long() -> TestResult {
     run_long(HIDDEN, "-rw-r--r--", "0")
 }
 
 // --------------------------------------------------
-fn dir_short(args: &[&str], expected: &[&str]) -> TestResult {
+/// Lists `args` and returns stdout, with Windows path separators turned into
+/// slashes so the expected paths work everywhere.
+fn list(args: &[&str]) -> Result<String, Box<dyn Error>> {
     let cmd = Command::cargo_bin(PRG)?.args(args).assert().success();
     let stdout = String::from_utf8(cmd.get_output().stdout.clone())?;
+    Ok(if cfg!(windows) {
+        stdout.replace("\\\\", "/").replace('\\', "/")
+    } else {
+        stdout
+    })
+}
+
+// --------------------------------------------------
+fn dir_short(args: &[&str], expected: &[&str]) -> TestResult {
+    let stdout = list(args)?;
     let lines: Vec<&str> =
         stdout.split("\n").filter(|s| !s.is_empty()).collect();
     assert_eq!(lines.len(), expected.len());
     for filename in expected {
-        assert!(lines.contains(&filename));
+        assert!(lines.contains(filename));
     }
     Ok(())
 }
@@ -172,10 +194,32 @@ fn dir2_all() -> TestResult {
     )
 }
 
+#[test]
+fn dir2_all_from_env() -> TestResult {
+    let cmd = Command::cargo_bin(PRG)?
+        .env("LSR_OPTS", "--all")
+        .arg("tests/inputs/dir")
+        .assert()
+        .success();
+    let stdout = String::from_utf8(cmd.get_output().stdout.clone())?;
+    assert_eq!(stdout.lines().count(), 2);
+    assert!(stdout.contains(".gitkeep"));
+    Ok(())
+}
+
+#[test]
+fn help_names_env_var() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Default options are read from $LSR_OPTS."));
+    Ok(())
+}
+
 // --------------------------------------------------
 fn dir_long(args: &[&str], expected: &[(&str, &str, &str)]) -> TestResult {
-    let cmd = Command::cargo_bin(PRG)?.args(args).assert().success();
-    let stdout = String::from_utf8(cmd.get_output().stdout.clone())?;
+    let stdout = list(args)?;
     let lines: Vec<&str> =
         stdout.split("\n").filter(|s| !s.is_empty()).collect();
     assert_eq!(lines.len(), expected.len());
@@ -183,11 +227,11 @@ fn dir_long(args: &[&str], expected: &[(&str, &str, &str)]) -> TestResult {
     let mut check = vec![];
     for line in lines {
         let parts: Vec<_> = line.split_whitespace().collect();
-        let path = parts.last().unwrap().clone();
-        let permissions = parts.get(0).unwrap().clone();
+        let path = *parts.last().unwrap();
+        let permissions = *parts.first().unwrap();
         let size = match permissions.chars().next() {
             Some('d') => "",
-            _ => parts.get(4).unwrap().clone(),
+            _ => *parts.get(4).unwrap(),
         };
         check.push((path, permissions, size));
     }
@@ -201,6 +245,7 @@ fn dir_long(args: &[&str], expected: &[(&str, &str, &str)]) -> TestResult {
 
 // --------------------------------------------------
 #[test]
+#[cfg(unix)]
 fn dir1_long() -> TestResult {
     dir_long(
         &["-l", "tests/inputs"],
@@ -214,6 +259,7 @@ fn dir1_long() -> TestResult {
 }
 
 #[test]
+#[cfg(unix)]
 fn dir1_long_all() -> TestResult {
     dir_long(
         &["-la", "tests/inputs"],
@@ -228,6 +274,7 @@ fn dir1_long_all() -> TestResult {
 }
 
 #[test]
+#[cfg(unix)]
 fn dir2_long() -> TestResult {
     dir_long(
         &["--long", "tests/inputs/dir"],
@@ -236,6 +283,16 @@ fn dir2_long() -> TestResult {
 }
 
 #[test]
+#[cfg(windows)]
+fn dir2_long() -> TestResult {
+    dir_long(
+        &["--long", "tests/inputs/dir"],
+        &[("tests/inputs/dir/spiders.txt", "-rw-rw-rw-", "45")],
+    )
+}
+
+#[test]
+#[cfg(unix)]
 fn dir2_long_all() -> TestResult {
     dir_long(
         &["tests/inputs/dir", "--long", "--all"],
@@ -245,3 +302,29 @@ fn dir2_long_all() -> TestResult {
         ],
     )
 }
+
+// --------------------------------------------------
+#[test]
+fn fox_json() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--json", FOX])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            r#"{"path":"tests/inputs/fox.txt","type":"file","mode":"#,
+        ))
+        .stdout(predicate::str::contains(r#""size":45,"#))
+        .stdout(predicate::str::ends_with("}\n"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dir_json() -> TestResult {
+    let stdout = list(&["--json", "-a", "tests/inputs"])?;
+    // One object per entry
+    assert_eq!(stdout.lines().count(), 5);
+    assert!(stdout.lines().all(|line| line.starts_with('{') && line.ends_with('}')));
+    assert!(stdout.contains(r#"{"path":"tests/inputs/dir","type":"dir","#));
+    Ok(())
+}
@@ -0,0 +1,127 @@
+use clap::Parser;
+use std::io::{BufRead, Write};
+
+use coreutils_common::{open, parse_args, CoreError, MyResult, Output, STDIN};
+
+#[cfg(unix)]
+mod pager;
+
+/// The lines above and below each file's name when there are several.
+const RULE: &str = "::::::::::::::";
+
+#[derive(Debug)]
+pub struct Config {
+    files: Vec<String>,
+    /// The line of the first file to start at, from 0, only when paging
+    #[cfg_attr(not(unix), allow(dead_code))]
+    start: usize,
+}
+
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust more")]
+struct Args {
+    /// Files to show, and +N to start at line N of the first [default: -]
+    #[arg(value_name = "FILE")]
+    files: Vec<String>,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let args: Args = parse_args();
+
+    let (start, mut files) = split_start(args.files)?;
+    if files.is_empty() {
+        files.push(STDIN.to_string());
+    }
+    Ok(Config { files, start })
+}
+
+/// Takes each +N out of `args` as the line to start at, from 0, the last
+/// one winning.
+fn split_start(args: Vec<String>) -> MyResult<(usize, Vec<String>)> {
+    let mut start = 0;
+    let mut files = vec![];
+    for arg in args {
+        match arg.strip_prefix('+') {
+            Some(line) => {
+                let line: usize = line
+                    .parse()
+                    .map_err(|_| CoreError::Parse(format!("invalid line number '{arg}'")))?;
+                start = line.saturating_sub(1);
+            }
+            None => files.push(arg),
+        }
+    }
+    Ok((start, files))
+}
+
+/// The three lines that name a file when there are several.
+fn header(filename: &str) -> String {
+    format!("{RULE}\n{filename}\n{RULE}")
+}
+
+/// Copies `file`, which reads `filename`, to `out` a read block at a time,
+/// so a read error names the file and a write error doesn't.
+fn copy(file: &mut dyn BufRead, filename: &str, out: &mut impl Write) -> MyResult<()> {
+    loop {
+        let block = file.fill_buf().map_err(|e| CoreError::io(filename, e))?;
+        if block.is_empty() {
+            return Ok(());
+        }
+        out.write_all(block)?;
+        let len = block.len();
+        file.consume(len);
+    }
+}
+
+/// Shows the files a screen at a time when stdout and stderr, where the
+/// keys come from, are a terminal. Otherwise copies them to stdout like
+/// catr, with a header for each if there are several, and ignoring +N, as
+/// more does. Returns 1 if a file couldn't be read.
+pub fn run(config: Config) -> MyResult<i32> {
+    #[cfg(unix)]
+    {
+        use std::io::{self, IsTerminal};
+        if io::stdout().is_terminal() && io::stderr().is_terminal() {
+            return pager::run(&config);
+        }
+    }
+
+    let mut out = Output::stdout();
+    let mut status = 0;
+    for filename in &config.files {
+        let copied = open(filename).and_then(|mut file| {
+            if config.files.len() > 1 {
+                writeln!(out, "{}", header(filename))?;
+            }
+            copy(&mut file, filename, &mut out)
+        });
+        match copied {
+            Err(e @ CoreError::Io { .. }) => {
+                out.flush()?;
+                eprintln!("{e}");
+                status = 1;
+            }
+            result => result?,
+        }
+    }
+    out.flush()?;
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_start;
+
+    #[test]
+    fn test_split_start() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect();
+        assert_eq!(split_start(args(&["a", "b"])).unwrap(), (0, args(&["a", "b"])));
+        assert_eq!(split_start(args(&["+5", "a"])).unwrap(), (4, args(&["a"])));
+        assert_eq!(split_start(args(&["a", "+1", "+3"])).unwrap(), (2, args(&["a"])));
+        assert_eq!(split_start(args(&["+0"])).unwrap(), (0, vec![]));
+        assert_eq!(
+            split_start(args(&["+x"])).unwrap_err().to_string(),
+            "invalid line number '+x'"
+        );
+    }
+}
@@ -0,0 +1,374 @@
+//! The screen at a time part of morer, reading keys from the terminal.
+
+use ansi_term::Style;
+use regex::{Regex, RegexBuilder};
+use std::{
+    collections::VecDeque,
+    fs,
+    io::{self, BufRead, Write},
+    vec,
+};
+
+use crate::{header, Config};
+use coreutils_common::{
+    open,
+    term::{self, Keys, Size},
+    CoreError, MyResult, STDIN,
+};
+
+/// Erases the rest of the line, here the prompt.
+const ERASE: &str = "\r\x1b[K";
+
+const CTRL_C: u8 = 0x03;
+const BACKSPACE: u8 = 0x08;
+const ESCAPE: u8 = 0x1b;
+const DELETE: u8 = 0x7f;
+
+/// The lines of the files, each under a header if there are several, read
+/// only as they are asked for.
+struct Source {
+    files: vec::IntoIter<String>,
+    headers: bool,
+    /// The file being read, and its name
+    file: Option<(Box<dyn BufRead>, String)>,
+    /// Header lines to give before the file's own
+    pending: VecDeque<String>,
+    /// How many lines have been given
+    given: usize,
+    /// The line the first file that opened starts at
+    first: Option<usize>,
+    /// The bytes read of the files, and how many there are in all, if known
+    read: u64,
+    total: Option<u64>,
+    /// 1 once a file couldn't be read
+    status: i32,
+}
+
+impl Source {
+    fn new(files: Vec<String>) -> Self {
+        // Only regular files say how big they are; one that can't be opened
+        // adds nothing
+        let total = files
+            .iter()
+            .map(|filename| match filename.as_str() {
+                STDIN => None,
+                _ => fs::metadata(filename).map_or(Some(0), |meta| {
+                    meta.is_file().then_some(meta.len())
+                }),
+            })
+            .sum();
+        Source {
+            headers: files.len() > 1,
+            files: files.into_iter(),
+            file: None,
+            pending: VecDeque::new(),
+            given: 0,
+            first: None,
+            read: 0,
+            total,
+            status: 0,
+        }
+    }
+
+    /// Reports an error reading a file, which is then passed over.
+    fn fail(&mut self, err: CoreError) {
+        eprintln!("{err}");
+        self.status = 1;
+        self.file = None;
+    }
+
+    /// The next line, opening the next file once this one is done, or None
+    /// after the last.
+    fn next_line(&mut self) -> Option<String> {
+        loop {
+            if let Some(line) = self.pending.pop_front() {
+                self.given += 1;
+                return Some(line);
+            }
+            let Some((file, filename)) = &mut self.file else {
+                let filename = self.files.next()?;
+                match open(&filename) {
+                    Err(e) => self.fail(e),
+                    Ok(file) => {
+                        if self.headers {
+                            self.pending.extend(header(&filename).lines().map(String::from));
+                        }
+                        self.first.get_or_insert(self.given + self.pending.len());
+                        self.file = Some((file, filename));
+                    }
+                }
+                continue;
+            };
+            let mut line = vec![];
+            match file.read_until(b'\n', &mut line) {
+                Err(e) => {
+                    let err = CoreError::io(filename.as_str(), e);
+                    self.fail(err);
+                }
+                Ok(0) => self.file = None,
+                Ok(len) => {
+                    self.read += len as u64;
+                    // Without the line ending, as str::lines gives it
+                    if line.ends_with(b"\n") {
+                        line.pop();
+                        if line.ends_with(b"\r") {
+                            line.pop();
+                        }
+                    }
+                    self.pending.push_back(String::from_utf8_lossy(&line).into_owned());
+                }
+            }
+        }
+    }
+}
+
+/// The lines read so far, and what has been shown of them. Lines already
+/// read are kept, so they can be shown again.
+struct Pager {
+    lines: Vec<String>,
+    /// The bytes of the files read by the end of each line
+    ends: Vec<u64>,
+    source: Source,
+    size: Size,
+    /// The first line not yet shown
+    next: usize,
+    /// The last pattern searched for, for n
+    pattern: Option<Regex>,
+}
+
+impl Pager {
+    fn new(source: Source, size: Size) -> Self {
+        Pager { lines: vec![], ends: vec![], source, size, next: 0, pattern: None }
+    }
+
+    /// Whether there is a line `index`, reading up to it if it hasn't been.
+    fn has(&mut self, index: usize) -> bool {
+        while self.lines.len() <= index {
+            let Some(line) = self.source.next_line() else {
+                return false;
+            };
+            self.lines.push(line);
+            self.ends.push(self.source.read);
+        }
+        true
+    }
+
+    /// Where the lines from `from` that fit in `rows` rows, wrapped at the
+    /// screen's width, end. A line too long for them is shown anyway.
+    fn fit(&mut self, from: usize, rows: usize) -> usize {
+        let mut used = 0;
+        let mut end = from;
+        while self.has(end) {
+            used += self.lines[end].chars().count().div_ceil(self.size.columns).max(1);
+            if used > rows && end > from {
+                break;
+            }
+            end += 1;
+        }
+        end
+    }
+
+    /// Prints the next lines that fit in `rows` rows.
+    fn show(&mut self, out: &mut impl Write, rows: usize) -> io::Result<()> {
+        let end = self.fit(self.next, rows);
+        for line in &self.lines[self.next..end] {
+            writeln!(out, "{line}")?;
+        }
+        self.next = end;
+        Ok(())
+    }
+
+    /// How much of the files has been shown, for the prompt, if it is known
+    /// how big they are.
+    fn percent(&self) -> Option<u64> {
+        let shown = self.next.checked_sub(1).map_or(0, |last| self.ends[last]);
+        Some((shown * 100).checked_div(self.source.total?).unwrap_or(100))
+    }
+
+    /// Skips to the next line not yet shown that matches the last pattern,
+    /// showing it after two lines before it as more does, or says why not.
+    fn search(&mut self, out: &mut impl Write, rows: usize) -> io::Result<Option<String>> {
+        let Some(pattern) = self.pattern.clone() else {
+            return Ok(Some("No previous regular expression".to_string()));
+        };
+        let mut found = self.next;
+        while self.has(found) && !pattern.is_match(&self.lines[found]) {
+            found += 1;
+        }
+        if found == self.lines.len() {
+            return Ok(Some("Pattern not found".to_string()));
+        }
+        writeln!(out, "...skipping")?;
+        self.next = found.saturating_sub(2);
+        self.show(out, rows - 1)?;
+        Ok(None)
+    }
+}
+
+/// Reads what is typed after a prompt, echoing it, or None if it was given
+/// up with ^C or escape.
+fn read_line(keys: &mut Keys, out: &mut impl Write) -> MyResult<Option<String>> {
+    let mut line = vec![];
+    loop {
+        match keys.read()? {
+            None | Some(CTRL_C | ESCAPE) => return Ok(None),
+            Some(b'\r' | b'\n') => return Ok(Some(String::from_utf8_lossy(&line).into_owned())),
+            Some(BACKSPACE | DELETE) => {
+                // The whole of the last character, however many bytes it took
+                while let Some(byte) = line.pop() {
+                    if byte & 0xc0 != 0x80 {
+                        write!(out, "\x08 \x08")?;
+                        break;
+                    }
+                }
+            }
+            Some(byte) => {
+                line.push(byte);
+                out.write_all(&[byte])?;
+            }
+        }
+        out.flush()?;
+    }
+}
+
+/// Shows the files, each under a header if there are several, a screen at
+/// a time from +N: space for the next screen, enter for the next line,
+/// /pattern to skip to a line that matches, n to skip to the next, and q to
+/// quit. The prompt says how much has been shown, if the files are ones
+/// whose size is known. Lines are read only as they are needed.
+pub fn run(config: &Config) -> MyResult<i32> {
+    let size = term::size().unwrap_or(Size { columns: 80, rows: 24 });
+    // The prompt takes the last row
+    let rows = size.rows.saturating_sub(1).max(2);
+    let mut pager = Pager::new(Source::new(config.files.clone()), size);
+    // Reading the first line opens the first file that can be opened
+    pager.has(0);
+    let start = pager.source.first.unwrap_or(0) + config.start;
+    pager.has(start);
+    pager.next = start.min(pager.lines.len());
+    let mut out = io::stdout().lock();
+    pager.show(&mut out, rows)?;
+
+    let mut keys = Keys::new()?;
+    let mut notice = None;
+    while pager.has(pager.next) {
+        let prompt = notice.take().unwrap_or_else(|| match pager.percent() {
+            Some(percent) => format!("--More--({percent}%)"),
+            None => "--More--".to_string(),
+        });
+        write!(out, "{ERASE}{}", Style::new().reverse().paint(prompt))?;
+        out.flush()?;
+        let Some(key) = keys.read()? else {
+            break;
+        };
+        write!(out, "{ERASE}")?;
+        match key {
+            b' ' => pager.show(&mut out, rows)?,
+            b'\r' | b'\n' => pager.show(&mut out, 1)?,
+            b'q' | b'Q' | CTRL_C => break,
+            b'/' => {
+                write!(out, "/")?;
+                out.flush()?;
+                let Some(text) = read_line(&mut keys, &mut out)? else {
+                    continue;
+                };
+                write!(out, "{ERASE}")?;
+                // Nothing searches for the last pattern again
+                if !text.is_empty() {
+                    match RegexBuilder::new(&text).build() {
+                        Ok(pattern) => pager.pattern = Some(pattern),
+                        Err(_) => {
+                            notice = Some(format!("Invalid pattern \"{text}\""));
+                            continue;
+                        }
+                    }
+                }
+                notice = pager.search(&mut out, rows)?;
+            }
+            b'n' => notice = pager.search(&mut out, rows)?,
+            _ => {}
+        }
+    }
+    out.flush()?;
+    Ok(pager.source.status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Pager, Source};
+    use coreutils_common::term::Size;
+    use regex::Regex;
+    use std::io::Cursor;
+
+    /// A pager on one file of `lines`, none of them read yet.
+    fn pager(lines: &[&str]) -> Pager {
+        let text: String = lines.iter().map(|line| format!("{line}\n")).collect();
+        let mut source = Source::new(vec![]);
+        source.total = Some(text.len() as u64);
+        source.file = Some((Box::new(Cursor::new(text)), "test".to_string()));
+        Pager::new(source, Size { columns: 4, rows: 3 })
+    }
+
+    #[test]
+    fn test_fit() {
+        let mut pager = pager(&["a", "", "abcd", "abcde", "a"]);
+        assert_eq!(pager.fit(0, 2), 2);
+        // Read only as far as the line that didn't fit
+        assert_eq!(pager.lines.len(), 3);
+        assert_eq!(pager.fit(0, 3), 3);
+        // Wrapped, the fourth line takes two rows
+        assert_eq!(pager.fit(3, 2), 4);
+        assert_eq!(pager.fit(3, 3), 5);
+        assert_eq!(pager.fit(3, 1), 4);
+        assert_eq!(pager.fit(5, 3), 5);
+    }
+
+    #[test]
+    fn test_percent() {
+        let mut pager = pager(&["a", "b", "cc"]);
+        assert_eq!(pager.percent(), Some(0));
+        pager.has(1);
+        pager.next = 2;
+        assert_eq!(pager.percent(), Some(57));
+        pager.source.total = None;
+        assert_eq!(pager.percent(), None);
+        assert_eq!(self::pager(&[]).percent(), Some(100));
+    }
+
+    #[test]
+    fn test_source() {
+        let files = ["no-such-file", "tests/inputs/ten.txt"].map(String::from);
+        let mut source = Source::new(files.to_vec());
+        assert_eq!(source.total, Some(71));
+        assert_eq!(source.next_line().as_deref(), Some("::::::::::::::"));
+        assert_eq!(source.status, 1);
+        assert_eq!(source.next_line().as_deref(), Some("tests/inputs/ten.txt"));
+        assert_eq!(source.next_line().as_deref(), Some("::::::::::::::"));
+        assert_eq!(source.next_line().as_deref(), Some("line 1"));
+        assert_eq!((source.first, source.read), (Some(3), 7));
+        let mut rest = 0;
+        while source.next_line().is_some() {
+            rest += 1;
+        }
+        assert_eq!((rest, source.read), (9, 71));
+    }
+
+    #[test]
+    fn test_search() {
+        let mut pager = pager(&["a", "b", "c", "d", "match", "e", "f"]);
+        let mut out = vec![];
+        assert_eq!(
+            pager.search(&mut out, 3).unwrap().as_deref(),
+            Some("No previous regular expression")
+        );
+        pager.pattern = Some(Regex::new("^m").unwrap());
+        // "match" wraps onto a second row
+        assert_eq!(pager.search(&mut out, 5).unwrap(), None);
+        assert_eq!(String::from_utf8(out).unwrap(), "...skipping\nc\nd\nmatch\n");
+        assert_eq!(pager.next, 5);
+        assert_eq!(
+            pager.search(&mut vec![], 3).unwrap().as_deref(),
+            Some("Pattern not found")
+        );
+    }
+}
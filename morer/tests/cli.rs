@@ -0,0 +1,172 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "morer";
+const TEN: &str = "tests/inputs/ten.txt";
+const FOX: &str = "tests/inputs/fox.txt";
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_start() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["+x", TEN])
+        .assert()
+        .code(1)
+        .stderr("invalid line number '+x'\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn copies_when_not_a_terminal() -> TestResult {
+    // Without a terminal to page on, +N is ignored as in more
+    Command::cargo_bin(PRG)?
+        .args(["+3", TEN])
+        .assert()
+        .success()
+        .stdout(fs::read_to_string(TEN)?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn stdin() -> TestResult {
+    Command::cargo_bin(PRG)?.write_stdin("one\ntwo\n").assert().success().stdout("one\ntwo\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn headers() -> TestResult {
+    let expected = format!(
+        "::::::::::::::\n{FOX}\n::::::::::::::\n{}::::::::::::::\n-\n::::::::::::::\nin\n",
+        fs::read_to_string(FOX)?
+    );
+    Command::cargo_bin(PRG)?
+        .args([FOX, "-"])
+        .write_stdin("in\n")
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn skips_bad_file() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["no-such-file", FOX])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::ends_with(fs::read_to_string(FOX)?))
+        .stderr(predicate::str::starts_with("no-such-file: No such file or directory"));
+    Ok(())
+}
+
+/// What the pager writes to the terminal until it has written `until`.
+#[cfg(unix)]
+fn read_until(leader: &mut fs::File, until: &str) -> std::io::Result<String> {
+    use std::io::Read;
+
+    let mut output = vec![];
+    let mut buf = [0; 1024];
+    while !String::from_utf8_lossy(&output).contains(until) {
+        match leader.read(&mut buf)? {
+            0 => break,
+            n => output.extend_from_slice(&buf[..n]),
+        }
+    }
+    Ok(String::from_utf8_lossy(&output).replace("\r\n", "\n"))
+}
+
+/// A new pseudo-terminal, the near end and the far one.
+#[cfg(unix)]
+fn pty() -> std::io::Result<(fs::File, fs::File)> {
+    use std::{os::unix::io::FromRawFd, ptr};
+
+    let (mut leader, mut follower) = (0, 0);
+    let null = ptr::null_mut();
+    // SAFETY: openpty only writes the two descriptors
+    let opened = unsafe { libc::openpty(&mut leader, &mut follower, null, null as _, null as _) };
+    if opened != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    // SAFETY: openpty just opened both, and nothing else owns them
+    Ok(unsafe { (fs::File::from_raw_fd(leader), fs::File::from_raw_fd(follower)) })
+}
+
+// --------------------------------------------------
+#[test]
+#[cfg(unix)]
+fn pages() -> TestResult {
+    use assert_cmd::cargo::cargo_bin;
+    use std::{io::Write, process};
+
+    // The far end of the terminal is both stdout and stderr
+    let (mut leader, follower) = pty()?;
+
+    // Three lines and the prompt to a screen, starting at line 2
+    let mut child = process::Command::new(cargo_bin(PRG))
+        .args(["+2", TEN])
+        .env("LINES", "4")
+        .env("COLUMNS", "80")
+        .stdout(follower.try_clone()?)
+        .stderr(follower)
+        .spawn()?;
+
+    let prompt = |percent: usize| format!("\x1b[7m--More--({percent}%)\x1b[0m");
+    let screen = read_until(&mut leader, &prompt(39))?;
+    assert_eq!(screen, format!("line 2\nline 3\nline 4\n\r\x1b[K{}", prompt(39)));
+    leader.write_all(b" ")?;
+    let screen = read_until(&mut leader, &prompt(69))?;
+    assert_eq!(screen, format!("\r\x1b[Kline 5\nline 6\nline 7\n\r\x1b[K{}", prompt(69)));
+    leader.write_all(b"\n")?;
+    assert!(read_until(&mut leader, &prompt(78))?.contains("line 8\n"));
+    leader.write_all(b"/^line 1\n")?;
+    // The screen after skipping starts two lines before the match
+    let screen = read_until(&mut leader, &prompt(88))?;
+    assert!(screen.contains("...skipping\nline 8\nline 9\n"));
+    leader.write_all(b"q")?;
+    assert!(child.wait()?.success());
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+#[cfg(unix)]
+fn pages_before_input_ends() -> TestResult {
+    use assert_cmd::cargo::cargo_bin;
+    use std::{
+        io::Write,
+        process::{self, Stdio},
+        thread,
+        time::Duration,
+    };
+
+    let (mut leader, follower) = pty()?;
+    let mut child = process::Command::new(cargo_bin(PRG))
+        .env("LINES", "4")
+        .env("COLUMNS", "80")
+        .stdin(Stdio::piped())
+        .stdout(follower.try_clone()?)
+        .stderr(follower)
+        .spawn()?;
+    let mut input = child.stdin.take().unwrap();
+    input.write_all(b"a\nb\nc\nd\n")?;
+    // Ends the input after a while, so a pager that waits for all of it
+    // fails rather than hangs
+    thread::spawn(move || {
+        thread::sleep(Duration::from_secs(5));
+        drop(input);
+    });
+
+    // How big stdin is isn't known, so the prompt can't say how far through
+    let screen = read_until(&mut leader, "\x1b[0m")?;
+    assert_eq!(screen, "a\nb\nc\n\r\x1b[K\x1b[7m--More--\x1b[0m");
+    leader.write_all(b"q")?;
+    assert!(child.wait()?.success());
+    Ok(())
+}
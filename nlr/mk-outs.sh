@@ -0,0 +1,19 @@
+#!/usr/bin/env bash
+
+# Writes the expected output of each test with GNU nl
+
+OUTDIR="tests/expected"
+[[ ! -d "$OUTDIR" ]] && mkdir -p "$OUTDIR"
+
+BUSTLE="tests/inputs/the-bustle.txt"
+FOX="tests/inputs/fox.txt"
+EMPTY="tests/inputs/empty.txt"
+
+nl $EMPTY > $OUTDIR/empty.txt.out
+nl $BUSTLE > $OUTDIR/the-bustle.txt.out
+nl -b a $BUSTLE > $OUTDIR/the-bustle.txt.ba.out
+nl -b n $BUSTLE > $OUTDIR/the-bustle.txt.bn.out
+nl -b 'p^[AT]' $BUSTLE > $OUTDIR/the-bustle.txt.bp.out
+nl -n ln $BUSTLE > $OUTDIR/the-bustle.txt.nln.out
+nl -n rz -w 3 -s ': ' $BUSTLE > $OUTDIR/the-bustle.txt.nrz.w3.s.out
+nl $BUSTLE $FOX $BUSTLE > $OUTDIR/all.out
@@ -0,0 +1,212 @@
+use clap::Parser;
+use regex::Regex;
+use std::io::{BufRead, Write};
+
+use coreutils_common::{open, parse_args, CoreError, MyResult, Output};
+
+#[derive(Debug)]
+pub struct Config {
+    files: Vec<String>,
+    style: Style,
+    format: Format,
+    width: usize,
+    separator: String,
+}
+
+/// Which lines get a number, from -b.
+#[derive(Debug)]
+enum Style {
+    /// a: every line
+    All,
+    /// t: lines that aren't empty
+    NonEmpty,
+    /// n: none
+    None,
+    /// pREGEX: lines matching REGEX
+    Matching(Regex),
+}
+
+/// How numbers are laid out in their column, from -n.
+#[derive(Debug, PartialEq)]
+enum Format {
+    /// ln: left-justified
+    Left,
+    /// rn: right-justified
+    Right,
+    /// rz: right-justified with leading zeros
+    Zeros,
+}
+
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust nl")]
+struct Args {
+    /// Input file(s)
+    #[arg(value_name = "FILE", default_value = "-")]
+    files: Vec<String>,
+
+    /// Number lines by STYLE: a (all), t (nonempty), n (none) or pREGEX
+    #[arg(short = 'b', long, value_name = "STYLE", default_value = "t")]
+    body_numbering: String,
+
+    /// Lay out numbers by FORMAT: ln, rn or rz
+    #[arg(short = 'n', long, value_name = "FORMAT", default_value = "rn")]
+    number_format: String,
+
+    /// Use N columns for line numbers
+    #[arg(short = 'w', long, value_name = "N", default_value = "6")]
+    number_width: String,
+
+    /// Add STRING after line numbers
+    #[arg(
+        short = 's',
+        long,
+        value_name = "STRING",
+        default_value = "\t",
+        hide_default_value = true
+    )]
+    number_separator: String,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let args: Args = parse_args();
+
+    let width = args
+        .number_width
+        .parse()
+        .ok()
+        .filter(|&width| width > 0)
+        .ok_or_else(|| {
+            CoreError::Parse(format!(
+                "invalid line number field width: \"{}\"",
+                args.number_width
+            ))
+        })?;
+
+    Ok(Config {
+        files: args.files,
+        style: parse_style(&args.body_numbering)?,
+        format: parse_format(&args.number_format)?,
+        width,
+        separator: args.number_separator,
+    })
+}
+
+fn parse_style(val: &str) -> MyResult<Style> {
+    match val {
+        "a" => Ok(Style::All),
+        "t" => Ok(Style::NonEmpty),
+        "n" => Ok(Style::None),
+        _ => match val.strip_prefix('p') {
+            Some(pattern) => Regex::new(pattern)
+                .map(Style::Matching)
+                .map_err(|_| CoreError::Parse(format!("invalid regular expression: \"{pattern}\""))),
+            None => Err(CoreError::Parse(format!("invalid body numbering style: \"{val}\""))),
+        },
+    }
+}
+
+fn parse_format(val: &str) -> MyResult<Format> {
+    match val {
+        "ln" => Ok(Format::Left),
+        "rn" => Ok(Format::Right),
+        "rz" => Ok(Format::Zeros),
+        _ => Err(CoreError::Parse(format!("invalid line numbering format: \"{val}\""))),
+    }
+}
+
+impl Style {
+    fn numbers(&self, line: &str) -> bool {
+        match self {
+            Style::All => true,
+            Style::NonEmpty => !line.is_empty(),
+            Style::None => false,
+            Style::Matching(re) => re.is_match(line),
+        }
+    }
+}
+
+/// `num` laid out in a column of `width`, which it may overflow.
+fn format_number(num: usize, format: &Format, width: usize) -> String {
+    match format {
+        Format::Left => format!("{num:<width$}"),
+        Format::Right => format!("{num:>width$}"),
+        Format::Zeros => format!("{num:0width$}"),
+    }
+}
+
+/// Prints every input with its lines numbered, counting on from one file to
+/// the next, and returns the exit status: 1 if any file failed to open.
+pub fn run(config: Config) -> MyResult<i32> {
+    let mut out = Output::stdout();
+    // Unnumbered lines are indented to match the numbered ones
+    let blank = " ".repeat(config.width + config.separator.len());
+    let mut line_no = 1;
+    let mut status = 0;
+    let mut line = String::new();
+    for filename in &config.files {
+        let mut file = match open(filename) {
+            Ok(file) => file,
+            Err(e) => {
+                out.flush()?;
+                eprintln!("{e}");
+                status = 1;
+                continue;
+            }
+        };
+        loop {
+            line.clear();
+            if file.read_line(&mut line)? == 0 {
+                break;
+            }
+            let content = line.strip_suffix('\n').unwrap_or(&line);
+            if config.style.numbers(content) {
+                let num = format_number(line_no, &config.format, config.width);
+                write!(out, "{num}{}", config.separator)?;
+                line_no += 1;
+            } else {
+                out.write_all(blank.as_bytes())?;
+            }
+            // Like GNU nl, a last line without a newline gets one
+            out.write_record(content)?;
+        }
+    }
+    out.flush()?;
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_number, parse_format, parse_style, Format};
+
+    #[test]
+    fn test_format_number() {
+        assert_eq!(format_number(12, &Format::Left, 4), "12  ");
+        assert_eq!(format_number(12, &Format::Right, 4), "  12");
+        assert_eq!(format_number(12, &Format::Zeros, 4), "0012");
+        assert_eq!(format_number(12345, &Format::Zeros, 3), "12345");
+    }
+
+    #[test]
+    fn test_parse_style() {
+        assert!(parse_style("a").unwrap().numbers(""));
+        assert!(!parse_style("t").unwrap().numbers(""));
+        assert!(parse_style("t").unwrap().numbers(" "));
+        assert!(!parse_style("n").unwrap().numbers("foo"));
+
+        let style = parse_style("p^[0-9]").unwrap();
+        assert!(style.numbers("1 fish"));
+        assert!(!style.numbers("two fish"));
+
+        let err = parse_style("x").unwrap_err();
+        assert_eq!(err.to_string(), "invalid body numbering style: \"x\"");
+        let err = parse_style("p(").unwrap_err();
+        assert_eq!(err.to_string(), "invalid regular expression: \"(\"");
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(parse_format("rz").unwrap(), Format::Zeros);
+        let err = parse_format("xx").unwrap_err();
+        assert_eq!(err.to_string(), "invalid line numbering format: \"xx\"");
+    }
+}
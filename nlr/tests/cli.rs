@@ -0,0 +1,144 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "nlr";
+const BUSTLE: &str = "tests/inputs/the-bustle.txt";
+const FOX: &str = "tests/inputs/fox.txt";
+const EMPTY: &str = "tests/inputs/empty.txt";
+
+// --------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> TestResult {
+    let expected = fs::read_to_string(expected_file)?;
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+fn dies(args: &[&str], expected: &str) -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .failure()
+        .stderr(format!("{expected}\n"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_style() -> TestResult {
+    dies(&["-b", "x", BUSTLE], "invalid body numbering style: \"x\"")
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_regex() -> TestResult {
+    dies(&["-b", "p*", BUSTLE], "invalid regular expression: \"*\"")
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_format() -> TestResult {
+    dies(&["-n", "rr", BUSTLE], "invalid line numbering format: \"rr\"")
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_width() -> TestResult {
+    dies(&["-w", "0", BUSTLE], "invalid line number field width: \"0\"")?;
+    dies(&["-w", "x", BUSTLE], "invalid line number field width: \"x\"")
+}
+
+// --------------------------------------------------
+#[test]
+fn skips_bad_file() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args([FOX, "tests/inputs/does-not-exist", FOX])
+        .assert()
+        .code(1)
+        .stdout("     1\tThe quick brown fox jumps over the lazy dog.\n     2\tThe quick brown fox jumps over the lazy dog.\n")
+        .stderr(predicate::str::starts_with("tests/inputs/does-not-exist: "));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn empty() -> TestResult {
+    run(&[EMPTY], "tests/expected/empty.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn bustle() -> TestResult {
+    run(&[BUSTLE], "tests/expected/the-bustle.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn bustle_stdin() -> TestResult {
+    let input = fs::read_to_string(BUSTLE)?;
+    let expected = fs::read_to_string("tests/expected/the-bustle.txt.out")?;
+    Command::cargo_bin(PRG)?
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn bustle_all() -> TestResult {
+    run(&["-b", "a", BUSTLE], "tests/expected/the-bustle.txt.ba.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn bustle_none() -> TestResult {
+    run(&["-b", "n", BUSTLE], "tests/expected/the-bustle.txt.bn.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn bustle_regex() -> TestResult {
+    run(&["-b", "p^[AT]", BUSTLE], "tests/expected/the-bustle.txt.bp.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn bustle_left() -> TestResult {
+    run(&["-n", "ln", BUSTLE], "tests/expected/the-bustle.txt.nln.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn bustle_zeros_width_separator() -> TestResult {
+    run(
+        &["-n", "rz", "-w", "3", "-s", ": ", BUSTLE],
+        "tests/expected/the-bustle.txt.nrz.w3.s.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn all() -> TestResult {
+    // Numbering carries on from one file to the next
+    run(&[BUSTLE, FOX, BUSTLE], "tests/expected/all.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn adds_final_newline() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .write_stdin("a\nb")
+        .assert()
+        .success()
+        .stdout("     1\ta\n     2\tb\n");
+    Ok(())
+}
@@ -0,0 +1,22 @@
+#!/usr/bin/env bash
+
+# Writes the expected output of each test with GNU numfmt
+
+OUTDIR="tests/expected"
+[[ ! -d "$OUTDIR" ]] && mkdir -p "$OUTDIR"
+
+numfmt --to=iec 0 1023 1024 1025 1536 10241 1048575 > $OUTDIR/to_iec.out
+numfmt --to=si -- 999 1000 9999 999999 -1500 > $OUTDIR/to_si.out
+numfmt --to=iec-i 2048 5000000 > $OUTDIR/to_iec_i.out
+numfmt --from=si -- 1K 1.5M -2G > $OUTDIR/from_si.out
+numfmt --from=iec 1K 1.7K > $OUTDIR/from_iec.out
+numfmt --from=auto 1K 1Ki > $OUTDIR/from_auto.out
+numfmt --to=iec --round=down 1025 1500 > $OUTDIR/round_down.out
+numfmt --to=iec --round=nearest 1075 1076 > $OUTDIR/round_nearest.out
+numfmt --to=iec --padding=8 1024 1048576 > $OUTDIR/padding.out
+numfmt --to=iec --padding=-8 1024 1048576 > $OUTDIR/padding.left.out
+numfmt --to=si --field=2 < tests/inputs/sizes.txt > $OUTDIR/sizes.field.out 2>/dev/null
+numfmt --to=si --field=3 < tests/inputs/sizes.txt > $OUTDIR/sizes.bad.out 2>/dev/null
+numfmt --to=iec --field=2 --padding=6 < tests/inputs/sizes.txt > $OUTDIR/sizes.padding.out 2>/dev/null
+numfmt --from=auto --to=iec -d : --field=2- < tests/inputs/units.csv > $OUTDIR/units.delimiter.out
+numfmt --from=auto -d : --field=2 --padding=10 < tests/inputs/units.csv > $OUTDIR/units.padding.out
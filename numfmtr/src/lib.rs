@@ -0,0 +1,364 @@
+use clap::Parser;
+use std::{
+    io::{BufRead, Write},
+    ops::Range,
+};
+
+use coreutils_common::{
+    open, parse_args,
+    units::{parse_human, to_human, FromUnit, Round, Scale},
+    CoreError, MyResult, Output, STDIN,
+};
+
+/// Past this, a number can't be read, and is as big as the units go.
+const MAX_VALUE: f64 = 1e27;
+
+#[derive(Debug)]
+pub struct Config {
+    numbers: Vec<String>,
+    from: FromUnit,
+    to: Option<Scale>,
+    round: Round,
+    /// The width to pad to, on the left if negative
+    padding: Option<isize>,
+    /// The fields to convert, counted from 0
+    fields: Vec<Range<usize>>,
+    /// None for fields split at blanks
+    delimiter: Option<char>,
+}
+
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust numfmt")]
+struct Args {
+    /// Numbers to convert, or lines of standard input if none
+    #[arg(value_name = "NUMBER", allow_negative_numbers = true)]
+    numbers: Vec<String>,
+
+    /// Read numbers with units of this scale
+    #[arg(long, value_name = "UNIT", value_enum, default_value_t)]
+    from: FromUnit,
+
+    /// Write numbers with units of this scale
+    #[arg(long, value_name = "UNIT", value_enum)]
+    to: Option<Scale>,
+
+    /// How to round what doesn't fit
+    #[arg(long, value_name = "METHOD", value_enum, default_value_t)]
+    round: Round,
+
+    /// Pad numbers to N characters, on the right if negative
+    #[arg(long, value_name = "N", allow_negative_numbers = true)]
+    padding: Option<String>,
+
+    /// Convert these fields, e.g. 2, 1,3, 2-4, 3- or - for all
+    #[arg(long, value_name = "FIELDS", default_value = "1")]
+    field: String,
+
+    /// Split fields at X rather than at blanks
+    #[arg(short = 'd', long, value_name = "X")]
+    delimiter: Option<String>,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let args: Args = parse_args();
+
+    let padding = match args.padding {
+        Some(padding) => match padding.parse::<isize>() {
+            Ok(n) if n != 0 => Some(n),
+            _ => return Err(CoreError::Parse(format!("invalid padding value '{padding}'"))),
+        },
+        None => None,
+    };
+    let delimiter = match args.delimiter {
+        Some(delimiter) => {
+            let mut chars = delimiter.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Some(c),
+                _ => {
+                    return Err(CoreError::Usage(
+                        "the delimiter must be a single character".to_string(),
+                    ))
+                }
+            }
+        }
+        None => None,
+    };
+    Ok(Config {
+        numbers: args.numbers,
+        from: args.from,
+        to: args.to,
+        round: args.round,
+        padding,
+        fields: parse_fields(&args.field)?,
+        delimiter,
+    })
+}
+
+/// Parses a comma-separated list of field numbers and ranges as N, N-M, N-
+/// or -M, where `-` alone is every field.
+fn parse_fields(list: &str) -> MyResult<Vec<Range<usize>>> {
+    let index = |n: &str| match n.parse::<usize>() {
+        Ok(0) => Err(CoreError::Parse("fields are numbered from 1".to_string())),
+        Ok(n) => Ok(n - 1),
+        Err(_) => Err(CoreError::Parse(format!("invalid field value '{n}'"))),
+    };
+    list.split(',')
+        .map(|range| {
+            let (start, end) = match range.split_once('-') {
+                Some((start, end)) => (
+                    if start.is_empty() { 0 } else { index(start)? },
+                    if end.is_empty() { usize::MAX } else { index(end)? + 1 },
+                ),
+                None => {
+                    let n = index(range)?;
+                    (n, n + 1)
+                }
+            };
+            if start >= end {
+                return Err(CoreError::Parse("invalid decreasing range".to_string()));
+            }
+            Ok(start..end)
+        })
+        .collect()
+}
+
+impl Config {
+    /// The number `text` with the units asked for.
+    fn convert(&self, text: &str) -> Result<String, String> {
+        let value = parse_human(text, self.from)?;
+        if value.abs() >= MAX_VALUE {
+            return Err(format!("value too large to be converted: '{text}'"));
+        }
+        if let Some(scale) = self.to {
+            return Ok(to_human(value, scale, self.round));
+        }
+        // Written as it was, but whole once a unit is multiplied out
+        let has_unit = !text.ends_with(|c: char| c.is_ascii_digit());
+        if !has_unit {
+            if let Ok(n) = text.parse::<i64>() {
+                return Ok(n.to_string());
+            }
+        }
+        let value = if has_unit { self.round.round(value) } else { value };
+        if value.abs() > i64::MAX as f64 {
+            return Err(format!(
+                "value too large to be printed: '{}' (consider using --to)",
+                exponent(value)
+            ));
+        }
+        let decimals = match text.split_once('.') {
+            Some((_, frac)) if !has_unit => frac.len(),
+            _ => 0,
+        };
+        // Adding zero makes -0 plain 0
+        Ok(format!("{:.decimals$}", value + 0.0))
+    }
+
+    /// `number` padded to the width asked for, if any, or to `width`.
+    fn pad(&self, number: &str, width: usize) -> String {
+        match self.padding {
+            Some(n) if n < 0 => format!("{number:<0$}", n.unsigned_abs()),
+            Some(n) => format!("{number:>0$}", n as usize),
+            None => format!("{number:>width$}"),
+        }
+    }
+
+    /// Converts the selected fields of `line` into `out`. On an error, `out`
+    /// holds the line up to the field that couldn't be converted.
+    fn convert_line(&self, line: &str, out: &mut String) -> Result<(), String> {
+        let selected = |i: usize| self.fields.iter().any(|range| range.contains(&i));
+        if let Some(delimiter) = self.delimiter {
+            for (i, field) in line.split(delimiter).enumerate() {
+                if i > 0 {
+                    out.push(delimiter);
+                }
+                if selected(i) {
+                    let number = self.convert(field)?;
+                    out.push_str(&self.pad(&number, 0));
+                } else {
+                    out.push_str(field);
+                }
+            }
+            return Ok(());
+        }
+
+        // Each field is its leading blanks and then the rest up to a blank.
+        // The first blank of each after the first is written as a space, and
+        // a converted field that had blanks keeps the width of the rest,
+        // unless padded
+        let mut rest = line;
+        let mut i = 0;
+        loop {
+            let start = rest.len() - rest.trim_start_matches([' ', '\t']).len();
+            let end = rest[start..].find([' ', '\t']).map_or(rest.len(), |end| start + end);
+            if start == end {
+                out.push_str(rest);
+                return Ok(());
+            }
+            let (field, after) = rest.split_at(end);
+            let (blank, field) = match field.chars().next() {
+                Some(c) if i > 0 => (" ", &field[c.len_utf8()..]),
+                _ => ("", field),
+            };
+            let width = if start > 0 { field.chars().count() } else { 0 };
+            let start = start - blank.len();
+            out.push_str(blank);
+            if selected(i) {
+                out.push_str(&field[..start]);
+                let number = self.convert(&field[start..])?;
+                out.truncate(out.len() - start);
+                out.push_str(&self.pad(&number, width));
+            } else {
+                out.push_str(field);
+            }
+            rest = after;
+            i += 1;
+        }
+    }
+}
+
+/// `value` as printf's %g writes a large number, e.g. 1.84467e+19.
+fn exponent(value: f64) -> String {
+    let formatted = format!("{value:.5e}");
+    let (mantissa, exp) = formatted.split_once('e').unwrap_or((&formatted, "0"));
+    let mantissa = mantissa.trim_end_matches('0').trim_end_matches('.');
+    let exp: i32 = exp.parse().unwrap_or_default();
+    format!("{mantissa}e{}{:02}", if exp < 0 { '-' } else { '+' }, exp.abs())
+}
+
+/// Converts each number, or each line of standard input, and returns the
+/// exit status: 2 if a number couldn't be converted, which stops everything
+/// after it, as in GNU numfmt.
+pub fn run(config: Config) -> MyResult<i32> {
+    let mut out = Output::stdout();
+    let mut convert = |line: &str, newline: bool| -> MyResult<bool> {
+        let mut converted = String::new();
+        let result = config.convert_line(line, &mut converted);
+        out.write_all(converted.as_bytes())?;
+        if let Err(e) = result {
+            out.flush()?;
+            eprintln!("{e}");
+            return Ok(false);
+        }
+        if newline {
+            out.write_all(b"\n")?;
+        }
+        Ok(true)
+    };
+
+    if config.numbers.is_empty() {
+        let mut input = open(STDIN)?;
+        let mut line = String::new();
+        while input.read_line(&mut line)? > 0 {
+            let newline = line.ends_with('\n');
+            if !convert(line.strip_suffix('\n').unwrap_or(&line), newline)? {
+                return Ok(2);
+            }
+            line.clear();
+        }
+    } else {
+        for number in &config.numbers {
+            if !convert(number, true)? {
+                return Ok(2);
+            }
+        }
+    }
+    out.flush()?;
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_fields, Config};
+    use coreutils_common::units::{FromUnit, Round, Scale};
+
+    fn config(to: Option<Scale>, padding: Option<isize>, delimiter: Option<char>) -> Config {
+        Config {
+            numbers: vec![],
+            from: FromUnit::Auto,
+            to,
+            round: Round::FromZero,
+            padding,
+            fields: parse_fields("2").unwrap(),
+            delimiter,
+        }
+    }
+
+    #[test]
+    fn test_parse_fields() {
+        let fields = |list| {
+            let fields = parse_fields(list).unwrap();
+            fields.into_iter().map(|range| (range.start, range.end)).collect::<Vec<_>>()
+        };
+        assert_eq!(fields("1"), [(0, 1)]);
+        assert_eq!(fields("1,3-4"), [(0, 1), (2, 4)]);
+        assert_eq!(fields("2-"), [(1, usize::MAX)]);
+        assert_eq!(fields("-2"), [(0, 2)]);
+        assert_eq!(fields("-"), [(0, usize::MAX)]);
+
+        let err = |list| parse_fields(list).unwrap_err().to_string();
+        assert_eq!(err("0"), "fields are numbered from 1");
+        assert_eq!(err("a"), "invalid field value 'a'");
+        assert_eq!(err(""), "invalid field value ''");
+        assert_eq!(err("3-1"), "invalid decreasing range");
+    }
+
+    #[test]
+    fn test_convert() {
+        let plain = config(None, None, None);
+        assert_eq!(plain.convert("007"), Ok("7".to_string()));
+        assert_eq!(plain.convert("-0"), Ok("0".to_string()));
+        assert_eq!(plain.convert("-0.50"), Ok("-0.50".to_string()));
+        assert_eq!(plain.convert("1.5K"), Ok("1500".to_string()));
+        assert_eq!(plain.convert("1E"), Ok("1000000000000000000".to_string()));
+        assert_eq!(plain.convert("1.7Ki"), Ok("1741".to_string()));
+        assert_eq!(plain.convert("9223372036854775807"), Ok(i64::MAX.to_string()));
+        assert_eq!(
+            plain.convert("10E").unwrap_err(),
+            "value too large to be printed: '1e+19' (consider using --to)"
+        );
+        assert_eq!(
+            plain.convert("18446744073709551615").unwrap_err(),
+            "value too large to be printed: '1.84467e+19' (consider using --to)"
+        );
+        assert_eq!(
+            config(Some(Scale::Si), None, None).convert(&format!("1{}", "0".repeat(27))),
+            Err(format!("value too large to be converted: '1{}'", "0".repeat(27)))
+        );
+    }
+
+    #[test]
+    fn test_convert_line() {
+        let convert = |config: &Config, line: &str| {
+            let mut out = String::new();
+            config.convert_line(line, &mut out).map(|_| out.clone()).map_err(|e| (out, e))
+        };
+        let iec = config(Some(Scale::Iec), None, None);
+        assert_eq!(convert(&iec, "b 12345678 x"), Ok("b      12M x".to_string()));
+        assert_eq!(convert(&iec, "b\t2048 x"), Ok("b 2.0K x".to_string()));
+        assert_eq!(convert(&iec, "short"), Ok("short".to_string()));
+        assert_eq!(convert(&iec, "a\t\t2048\t\tb"), Ok("a  2.0K \tb".to_string()));
+        assert_eq!(convert(&iec, "a  "), Ok("a  ".to_string()));
+        assert_eq!(
+            convert(&iec, "b y 3"),
+            Err(("b ".to_string(), "invalid number: 'y'".to_string()))
+        );
+
+        // Without blanks before it, the first field isn't padded
+        let first = Config {
+            fields: parse_fields("1").unwrap(),
+            ..config(Some(Scale::Iec), None, None)
+        };
+        assert_eq!(convert(&first, "12345678 x"), Ok("12M x".to_string()));
+        assert_eq!(convert(&first, "  1024  "), Ok("  1.0K  ".to_string()));
+
+        let padded = config(Some(Scale::Iec), Some(-6), None);
+        assert_eq!(convert(&padded, " 1  2048 3"), Ok(" 1 2.0K   3".to_string()));
+        let delimited = config(Some(Scale::Iec), Some(6), Some(':'));
+        assert_eq!(convert(&delimited, "a:2048:3"), Ok("a:  2.0K:3".to_string()));
+        assert_eq!(
+            convert(&delimited, "a:x:3"),
+            Err(("a:".to_string(), "invalid number: 'x'".to_string()))
+        );
+    }
+}
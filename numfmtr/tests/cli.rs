@@ -0,0 +1,209 @@
+use assert_cmd::Command;
+use std::fs;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "numfmtr";
+const SIZES: &str = "tests/inputs/sizes.txt";
+const UNITS: &str = "tests/inputs/units.csv";
+
+// --------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> TestResult {
+    let expected = fs::read_to_string(expected_file)?;
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+fn run_stdin(input_file: &str, args: &[&str], expected_file: &str) -> TestResult {
+    let expected = fs::read_to_string(expected_file)?;
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .write_stdin(fs::read(input_file)?)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_args() -> TestResult {
+    for (args, msg) in [
+        (&["--padding=0", "1"][..], "invalid padding value '0'"),
+        (&["--padding=a", "1"], "invalid padding value 'a'"),
+        (&["--field=0", "1"], "fields are numbered from 1"),
+        (&["--field=a", "1"], "invalid field value 'a'"),
+        (&["--field=3-1", "1"], "invalid decreasing range"),
+        (&["-d", "ab", "1"], "the delimiter must be a single character"),
+    ] {
+        Command::cargo_bin(PRG)?
+            .args(args)
+            .assert()
+            .code(1)
+            .stdout("")
+            .stderr(format!("{msg}\n"));
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_number() -> TestResult {
+    for (args, msg) in [
+        (&["x"][..], "invalid number: 'x'"),
+        (&["12abc"], "invalid suffix in input: '12abc'"),
+        (&["1K"], "rejecting suffix in input: '1K' (consider using --from)"),
+        (&["--from=si", "1KB"], "invalid suffix in input '1KB': 'B'"),
+        (&["--from=iec-i", "1K"], "missing 'i' suffix in input: '1K' (e.g Ki/Mi/Gi)"),
+        (
+            &["--to=si", "1000000000000000000000000000"],
+            "value too large to be converted: '1000000000000000000000000000'",
+        ),
+    ] {
+        Command::cargo_bin(PRG)?
+            .args(args)
+            .assert()
+            .code(2)
+            .stdout("")
+            .stderr(format!("{msg}\n"));
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn stops_at_bad_number() -> TestResult {
+    // What came before is printed, and nothing after
+    Command::cargo_bin(PRG)?
+        .args(["--to=iec", "1024", "x", "2048"])
+        .assert()
+        .code(2)
+        .stdout("1.0K\n")
+        .stderr("invalid number: 'x'\n");
+    let expected = fs::read_to_string("tests/expected/sizes.bad.out")?;
+    Command::cargo_bin(PRG)?
+        .args(["--to=si", "--field=3"])
+        .write_stdin(fs::read(SIZES)?)
+        .assert()
+        .code(2)
+        .stdout(expected)
+        .stderr("invalid number: 'x'\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn keeps_decimals() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["007", "1.50", "-0.25", "--", "-3"])
+        .assert()
+        .success()
+        .stdout("7\n1.50\n-0.25\n-3\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn to_iec() -> TestResult {
+    run(
+        &["--to=iec", "0", "1023", "1024", "1025", "1536", "10241", "1048575"],
+        "tests/expected/to_iec.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn to_si() -> TestResult {
+    run(&["--to=si", "999", "1000", "9999", "999999", "-1500"], "tests/expected/to_si.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn to_iec_i() -> TestResult {
+    run(&["--to=iec-i", "2048", "5000000"], "tests/expected/to_iec_i.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn from_si() -> TestResult {
+    run(&["--from=si", "--", "1K", "1.5M", "-2G"], "tests/expected/from_si.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn from_iec() -> TestResult {
+    // Multiplied out, a number with a unit is rounded to a whole one
+    run(&["--from=iec", "1K", "1.7K"], "tests/expected/from_iec.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn from_auto() -> TestResult {
+    run(&["--from=auto", "1K", "1Ki"], "tests/expected/from_auto.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn round_down() -> TestResult {
+    run(&["--to=iec", "--round=down", "1025", "1500"], "tests/expected/round_down.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn round_nearest() -> TestResult {
+    run(&["--to=iec", "--round=nearest", "1075", "1076"], "tests/expected/round_nearest.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn padding() -> TestResult {
+    run(&["--to=iec", "--padding=8", "1024", "1048576"], "tests/expected/padding.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn padding_left() -> TestResult {
+    run(&["--to=iec", "--padding=-8", "1024", "1048576"], "tests/expected/padding.left.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn sizes_field() -> TestResult {
+    // Converted fields keep their width, so columns stay lined up
+    run_stdin(SIZES, &["--to=si", "--field=2"], "tests/expected/sizes.field.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn sizes_padding() -> TestResult {
+    run_stdin(
+        SIZES,
+        &["--to=iec", "--field=2", "--padding=6"],
+        "tests/expected/sizes.padding.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn units_delimiter() -> TestResult {
+    run_stdin(
+        UNITS,
+        &["--from=auto", "--to=iec", "-d", ":", "--field=2-"],
+        "tests/expected/units.delimiter.out",
+    )
+}
+
+// --------------------------------------------------
+#[test]
+fn units_padding() -> TestResult {
+    run_stdin(
+        UNITS,
+        &["--from=auto", "-d", ":", "--field=2", "--padding=10"],
+        "tests/expected/units.padding.out",
+    )
+}
@@ -0,0 +1,158 @@
+use clap::Parser;
+use std::{fs, io};
+
+use coreutils_common::{parse_args, CoreError, MyResult};
+
+/// The most bytes in a file name, with the NUL ending it, that every POSIX
+/// system allows.
+const POSIX_PATH_MAX: usize = 256;
+/// The longest part of a file name that every POSIX system allows.
+const POSIX_NAME_MAX: usize = 14;
+
+#[derive(Debug)]
+pub struct Config {
+    files: Vec<String>,
+    /// Check against the limits of every POSIX system, not this one
+    posix: bool,
+    /// Also reject empty names and parts that start with `-`
+    extra: bool,
+}
+
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust pathchk")]
+struct Args {
+    /// File names to check
+    #[arg(value_name = "NAME", required = true)]
+    files: Vec<String>,
+
+    /// Check for most POSIX systems rather than this one
+    #[arg(short = 'p')]
+    posix: bool,
+
+    /// Check for empty names and leading '-'
+    #[arg(short = 'P')]
+    extra: bool,
+
+    /// Check for all POSIX systems (the same as -p -P)
+    #[arg(long)]
+    portability: bool,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let args: Args = parse_args();
+
+    Ok(Config {
+        files: args.files,
+        posix: args.posix || args.portability,
+        extra: args.extra || args.portability,
+    })
+}
+
+/// Checks that `name` could be created: with -p on any POSIX system, using
+/// only the portable characters and within its limits, and otherwise on
+/// this one, which is what looking it up tells.
+fn check(name: &str, config: &Config) -> MyResult<()> {
+    if name.is_empty() && (config.posix || config.extra) {
+        return Err(CoreError::Input("empty file name".to_string()));
+    }
+    if config.posix {
+        let portable = |c: char| c.is_ascii_alphanumeric() || "._-/".contains(c);
+        if let Some(c) = name.chars().find(|&c| !portable(c)) {
+            return Err(CoreError::Input(format!(
+                "nonportable character '{c}' in file name '{name}'"
+            )));
+        }
+    }
+    if config.extra && name.split('/').any(|part| part.starts_with('-')) {
+        return Err(CoreError::Input(format!(
+            "leading '-' in a component of file name '{name}'"
+        )));
+    }
+
+    if !config.posix {
+        // Names that are too long, or go through a file, can't be looked up
+        return match fs::symlink_metadata(name) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound || name.is_empty() => {
+                Err(CoreError::io(if name.is_empty() { "''" } else { name }, e))
+            }
+            _ => Ok(()),
+        };
+    }
+    if name.len() >= POSIX_PATH_MAX {
+        return Err(CoreError::Input(format!(
+            "limit {} exceeded by length {} of file name '{name}'",
+            POSIX_PATH_MAX - 1,
+            name.len()
+        )));
+    }
+    match name.split('/').find(|part| part.len() > POSIX_NAME_MAX) {
+        Some(part) => Err(CoreError::Input(format!(
+            "limit {POSIX_NAME_MAX} exceeded by length {} of file name component '{part}'",
+            part.len()
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Checks each name and returns the exit status: 1 if any failed.
+pub fn run(config: Config) -> MyResult<i32> {
+    let mut status = 0;
+    for name in &config.files {
+        if let Err(e) = check(name, &config) {
+            eprintln!("{e}");
+            status = 1;
+        }
+    }
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check, Config};
+
+    fn err(name: &str, posix: bool, extra: bool) -> Option<String> {
+        let config = Config { files: vec![], posix, extra };
+        check(name, &config).err().map(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_posix() {
+        assert_eq!(err("a/b-c_d.TXT", true, false), None);
+        assert_eq!(err("-x", true, false), None);
+        assert_eq!(err("", true, false).unwrap(), "empty file name");
+        assert_eq!(
+            err("a b", true, false).unwrap(),
+            "nonportable character ' ' in file name 'a b'"
+        );
+        assert_eq!(err(&"a".repeat(14), true, false), None);
+        assert_eq!(
+            err(&format!("x/{}", "a".repeat(15)), true, false).unwrap(),
+            format!("limit 14 exceeded by length 15 of file name component '{}'", "a".repeat(15))
+        );
+        let long = "a/".repeat(128);
+        assert_eq!(
+            err(&long, true, false).unwrap(),
+            format!("limit 255 exceeded by length 256 of file name '{long}'")
+        );
+    }
+
+    #[test]
+    fn test_extra() {
+        assert_eq!(err("a//b", false, true), None);
+        assert_eq!(err("", false, true).unwrap(), "empty file name");
+        for name in ["-x", "a/-b"] {
+            assert_eq!(
+                err(name, false, true).unwrap(),
+                format!("leading '-' in a component of file name '{name}'")
+            );
+        }
+    }
+
+    #[test]
+    fn test_system() {
+        // Names that don't exist are fine, as long as they could
+        assert_eq!(err("no/such/file", false, false), None);
+        assert!(err("", false, false).unwrap().starts_with("'': "));
+        assert!(err(&"a".repeat(5000), false, false).is_some());
+    }
+}
@@ -0,0 +1,94 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "pathchkr";
+
+// --------------------------------------------------
+#[test]
+fn dies_no_args() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Usage"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn valid_names() -> TestResult {
+    // Names that don't exist yet pass, as they could be created
+    Command::cargo_bin(PRG)?
+        .args(["/etc", "no/such/file", "a b"])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("");
+    Command::cargo_bin(PRG)?
+        .args(["--portability", "--", "dir/file-1.txt", "a//b"])
+        .assert()
+        .success()
+        .stderr("");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn system_limits() -> TestResult {
+    let dir = tempfile::tempdir()?;
+    fs::write(dir.path().join("f"), "")?;
+    let long = "a".repeat(300);
+    Command::cargo_bin(PRG)?
+        .current_dir(dir.path())
+        .args(["", "f/x", &long, "f"])
+        .assert()
+        .code(1)
+        .stderr(
+            predicate::str::is_match(
+                "^'': No such file or directory.*\n\
+                f/x: Not a directory.*\n\
+                a+: File name too long.*\n$",
+            )?,
+        );
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn posix_limits() -> TestResult {
+    let name = "abcdefghijklmno";
+    Command::cargo_bin(PRG)?
+        .args(["-p", "", "a b", "ok", name, &format!("{name}/x")])
+        .assert()
+        .code(1)
+        .stderr(format!(
+            "empty file name\n\
+            nonportable character ' ' in file name 'a b'\n\
+            limit 14 exceeded by length 15 of file name component '{name}'\n\
+            limit 14 exceeded by length 15 of file name component '{name}'\n"
+        ));
+    let long = "a/".repeat(130);
+    Command::cargo_bin(PRG)?
+        .args(["-p", &long])
+        .assert()
+        .code(1)
+        .stderr(format!("limit 255 exceeded by length 260 of file name '{long}'\n"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn leading_hyphen() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-P", "--", "-x", "a/-b", "a-b", ""])
+        .assert()
+        .code(1)
+        .stderr(
+            "leading '-' in a component of file name '-x'\n\
+            leading '-' in a component of file name 'a/-b'\n\
+            empty file name\n",
+        );
+    Ok(())
+}
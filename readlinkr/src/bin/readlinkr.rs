@@ -0,0 +1,12 @@
+use readlinkr::readlink;
+
+fn main() {
+    coreutils_common::reset_sigpipe();
+    match readlink::get_args().and_then(readlink::run) {
+        Ok(status) => std::process::exit(status),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(e.exit_code());
+        }
+    }
+}
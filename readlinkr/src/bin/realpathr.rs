@@ -0,0 +1,12 @@
+use readlinkr::realpath;
+
+fn main() {
+    coreutils_common::reset_sigpipe();
+    match realpath::get_args().and_then(realpath::run) {
+        Ok(status) => std::process::exit(status),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(e.exit_code());
+        }
+    }
+}
@@ -0,0 +1,218 @@
+//! Turning a path into the absolute one it leads to, with every `.`, `..`
+//! and symbolic link worked out, as GNU readlink -f and realpath do.
+
+use std::{
+    env, fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+/// How many links may be followed for one path before it counts as a loop,
+/// as on Linux.
+const MAX_LINKS: usize = 40;
+
+/// Which parts of the path must exist.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// Every part (-e)
+    Existing,
+    /// All but the last (-f, and realpath's default)
+    AllButLast,
+    /// None (-m); a part that can't be looked up is taken as it is
+    Missing,
+}
+
+/// The absolute form of `path`. With `follow_links`, each link is replaced
+/// by where it points; without, links stay, but must still lead to
+/// something where `mode` requires the part to exist. A `..` always takes
+/// off the part before it, so after a link it goes up from the target.
+pub fn canonicalize(path: &Path, mode: Mode, follow_links: bool) -> io::Result<PathBuf> {
+    // An empty path names nothing, even where parts may be missing
+    if path.as_os_str().is_empty() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, "No such file or directory"));
+    }
+    let mut resolved = if path.is_absolute() { PathBuf::new() } else { env::current_dir()? };
+    // What's left to resolve, last first, so link targets can be pushed
+    let mut rest: Vec<PathBuf> = parts(path);
+    let mut links = 0;
+    while let Some(part) = rest.pop() {
+        let name = match part.components().next() {
+            Some(Component::Prefix(_) | Component::RootDir) => {
+                resolved.push(&part);
+                continue;
+            }
+            Some(Component::ParentDir) => {
+                resolved.pop();
+                continue;
+            }
+            Some(Component::Normal(name)) => name.to_owned(),
+            _ => continue,
+        };
+        let candidate = resolved.join(&name);
+        let last = rest.is_empty();
+        let metadata = if follow_links {
+            fs::symlink_metadata(&candidate)
+        } else {
+            fs::metadata(&candidate)
+        };
+        match metadata {
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                links += 1;
+                if links > MAX_LINKS {
+                    if mode == Mode::Missing {
+                        resolved = candidate;
+                        continue;
+                    }
+                    return Err(loop_error(&candidate));
+                }
+                let target = fs::read_link(&candidate)?;
+                if target.is_absolute() {
+                    resolved = PathBuf::new();
+                }
+                rest.extend(parts(&target));
+            }
+            Ok(metadata) => {
+                if !metadata.is_dir() && !last && mode != Mode::Missing {
+                    return Err(not_dir_error(&candidate));
+                }
+                resolved = candidate;
+            }
+            Err(e) => {
+                let allowed = match mode {
+                    Mode::Existing => false,
+                    Mode::AllButLast => last && e.kind() == io::ErrorKind::NotFound,
+                    Mode::Missing => true,
+                };
+                if !allowed {
+                    return Err(e);
+                }
+                resolved = candidate;
+            }
+        }
+    }
+    Ok(resolved)
+}
+
+/// The components of `path`, last first.
+fn parts(path: &Path) -> Vec<PathBuf> {
+    path.components().rev().map(|component| PathBuf::from(component.as_os_str())).collect()
+}
+
+/// The error the system gives for a path through the looping link at
+/// `path`, or one like it for a chain that is merely too long.
+fn loop_error(path: &Path) -> io::Error {
+    fs::metadata(path)
+        .err()
+        .unwrap_or_else(|| io::Error::other("Too many levels of symbolic links"))
+}
+
+/// The error the system gives for a path through the file at `path`, as if
+/// it were a directory.
+fn not_dir_error(path: &Path) -> io::Error {
+    fs::metadata(path.join(".")).err().unwrap_or_else(|| io::Error::other("Not a directory"))
+}
+
+/// `path` as seen from the directory `base`, both canonical: as many `..`
+/// as it takes to get from `base` to where they part, and then the rest of
+/// `path`.
+pub fn relative(path: &Path, base: &Path) -> PathBuf {
+    let path: Vec<_> = path.components().collect();
+    let base: Vec<_> = base.components().collect();
+    let common = path.iter().zip(&base).take_while(|(a, b)| a == b).count();
+    let mut relative: PathBuf = base[common..].iter().map(|_| Component::ParentDir).collect();
+    relative.extend(&path[common..]);
+    if relative.as_os_str().is_empty() {
+        relative.push(".");
+    }
+    relative
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{canonicalize, relative, Mode};
+    use std::{fs, io, path::Path};
+
+    #[test]
+    fn test_relative() {
+        let rel = |path: &str, base: &str| relative(Path::new(path), Path::new(base));
+        assert_eq!(rel("/a/b", "/a"), Path::new("b"));
+        assert_eq!(rel("/a", "/a/b/c"), Path::new("../.."));
+        assert_eq!(rel("/a/x", "/a/b"), Path::new("../x"));
+        assert_eq!(rel("/a", "/a"), Path::new("."));
+        assert_eq!(rel("/", "/usr"), Path::new(".."));
+    }
+
+    #[test]
+    fn test_dots_and_missing() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let root = dir.path().canonicalize()?;
+        fs::create_dir(root.join("a"))?;
+        fs::write(root.join("file"), "")?;
+        let canon = |path: &str, mode| canonicalize(&root.join(path), mode, true);
+
+        assert_eq!(canon("a/./../a//", Mode::Existing)?, root.join("a"));
+        assert_eq!(canon("new", Mode::AllButLast)?, root.join("new"));
+        assert!(canon("new", Mode::Existing).is_err());
+        // Only the last part may be missing, as long as nothing follows it
+        let err = canon("new/x", Mode::AllButLast).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert_eq!(canon("new/x/../y", Mode::Missing)?, root.join("new/y"));
+        // A file can't have anything in it
+        let err = canon("file/x", Mode::AllButLast).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotADirectory);
+        assert_eq!(canon("file/x", Mode::Missing)?, root.join("file/x"));
+        // An empty path is missing whatever the mode
+        for mode in [Mode::Existing, Mode::AllButLast, Mode::Missing] {
+            let err = canonicalize(Path::new(""), mode, true).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_links() -> io::Result<()> {
+        use std::os::unix::fs::symlink;
+
+        let dir = tempfile::tempdir()?;
+        let root = dir.path().canonicalize()?;
+        fs::create_dir_all(root.join("a/b"))?;
+        symlink("a/b", root.join("ab"))?;
+        symlink(root.join("a"), root.join("abs"))?;
+        symlink("nowhere", root.join("dangling"))?;
+        let canon = |path: &str, mode, follow| canonicalize(&root.join(path), mode, follow);
+
+        assert_eq!(canon("ab", Mode::Existing, true)?, root.join("a/b"));
+        assert_eq!(canon("abs/b", Mode::Existing, true)?, root.join("a/b"));
+        // .. goes up from where the link leads, not from the link
+        assert_eq!(canon("ab/..", Mode::Existing, true)?, root.join("a"));
+        assert_eq!(canon("dangling", Mode::AllButLast, true)?, root.join("nowhere"));
+        assert!(canon("dangling", Mode::Existing, true).is_err());
+
+        // Without following, links are kept but must still lead somewhere
+        assert_eq!(canon("ab", Mode::Existing, false)?, root.join("ab"));
+        assert_eq!(canon("ab/..", Mode::Existing, false)?, root.clone());
+        assert!(canon("dangling", Mode::Existing, false).is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_loops() -> io::Result<()> {
+        use std::os::unix::fs::symlink;
+
+        let dir = tempfile::tempdir()?;
+        let root = dir.path().canonicalize()?;
+        symlink("loop2", root.join("loop1"))?;
+        symlink("loop1", root.join("loop2"))?;
+        symlink("self/x", root.join("self"))?;
+        let canon = |path: &str, mode| canonicalize(&root.join(path), mode, true);
+
+        for path in ["loop1", "loop2/x", "self"] {
+            let err = canon(path, Mode::AllButLast).unwrap_err();
+            assert!(err.to_string().starts_with("Too many levels of symbolic links"), "{path}");
+        }
+        // With -m, the link just stays
+        assert_eq!(canon("loop1", Mode::Missing)?, root.join("loop1"));
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+//! `readlinkr` and `realpathr`, which print where paths lead. Both work it
+//! out with the `canon` module.
+
+use std::{io, path::Path};
+
+use coreutils_common::{CoreError, Output};
+
+mod canon;
+pub mod readlink;
+pub mod realpath;
+
+/// Writes `path` as it is, even if it isn't UTF-8.
+fn write_path(out: &mut Output, path: &Path) -> io::Result<()> {
+    out.write_record(path.as_os_str().as_encoded_bytes())
+}
+
+/// `err` for the operand `filename`, which is quoted when it's empty so it
+/// doesn't vanish from the message, as GNU does.
+fn path_error(filename: &str, err: io::Error) -> CoreError {
+    CoreError::io(if filename.is_empty() { "''" } else { filename }, err)
+}
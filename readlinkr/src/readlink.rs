@@ -0,0 +1,117 @@
+//! readlinkr: where links point, or with -f, -e or -m, where paths lead.
+
+use clap::Parser;
+use std::{fs, io::Write, path::Path};
+
+use crate::{
+    canon::{canonicalize, Mode},
+    path_error, write_path,
+};
+use coreutils_common::{parse_args, Delimiter, DelimiterArgs, MyResult, Output};
+
+#[derive(Debug)]
+pub struct Config {
+    files: Vec<String>,
+    /// None to print just the target of each link
+    mode: Option<Mode>,
+    no_newline: bool,
+    verbose: bool,
+    delimiter: Delimiter,
+}
+
+#[derive(Debug, Parser)]
+#[command(name = "readlinkr", author = "OFFBLACK", version = "0.1.0", about = "Rust readlink")]
+struct Args {
+    /// The link(s) to read
+    #[arg(value_name = "FILE", required = true)]
+    files: Vec<String>,
+
+    /// Print where each path leads; all but the last part must exist
+    #[arg(
+        short = 'f',
+        long,
+        overrides_with_all = ["canonicalize_existing", "canonicalize_missing"]
+    )]
+    canonicalize: bool,
+
+    /// Like -f, but every part must exist
+    #[arg(short = 'e', long, overrides_with_all = ["canonicalize", "canonicalize_missing"])]
+    canonicalize_existing: bool,
+
+    /// Like -f, but no part needs to exist
+    #[arg(short = 'm', long, overrides_with_all = ["canonicalize", "canonicalize_existing"])]
+    canonicalize_missing: bool,
+
+    /// Don't end the output with a newline (only with one FILE)
+    #[arg(short = 'n', long)]
+    no_newline: bool,
+
+    /// Don't report errors (the default)
+    #[arg(
+        short = 'q',
+        long,
+        visible_short_alias = 's',
+        visible_alias = "silent",
+        overrides_with = "verbose"
+    )]
+    quiet: bool,
+
+    /// Report errors
+    #[arg(short = 'v', long, overrides_with = "quiet")]
+    verbose: bool,
+
+    #[command(flatten)]
+    records: DelimiterArgs,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let args: Args = parse_args();
+
+    let mode = if args.canonicalize {
+        Some(Mode::AllButLast)
+    } else if args.canonicalize_existing {
+        Some(Mode::Existing)
+    } else if args.canonicalize_missing {
+        Some(Mode::Missing)
+    } else {
+        None
+    };
+    Ok(Config {
+        files: args.files,
+        mode,
+        no_newline: args.no_newline,
+        verbose: args.verbose && !args.quiet,
+        delimiter: args.records.delimiter(),
+    })
+}
+
+pub fn run(config: Config) -> MyResult<i32> {
+    let mut out = Output::stdout().delimiter(config.delimiter);
+    let mut status = 0;
+    let no_newline = config.no_newline && config.files.len() == 1;
+    if config.no_newline && !no_newline {
+        eprintln!("ignoring --no-newline with multiple arguments");
+    }
+    for filename in &config.files {
+        let path = Path::new(filename);
+        let resolved = match config.mode {
+            Some(mode) => canonicalize(path, mode, true),
+            None => fs::read_link(path),
+        };
+        match resolved {
+            Ok(resolved) if no_newline => {
+                out.write_all(resolved.as_os_str().as_encoded_bytes())?
+            }
+            Ok(resolved) => write_path(&mut out, &resolved)?,
+            Err(e) => {
+                if config.verbose {
+                    out.flush()?;
+                    eprintln!("{}", path_error(filename, e));
+                }
+                status = 1;
+            }
+        }
+    }
+    out.flush()?;
+    Ok(status)
+}
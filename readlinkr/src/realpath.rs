@@ -0,0 +1,129 @@
+//! realpathr: where paths lead, optionally from some other directory.
+
+use clap::Parser;
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    canon::{canonicalize, relative, Mode},
+    path_error, write_path,
+};
+use coreutils_common::{parse_args, CoreError, Delimiter, DelimiterArgs, MyResult, Output};
+
+#[derive(Debug)]
+pub struct Config {
+    files: Vec<String>,
+    mode: Mode,
+    follow_links: bool,
+    relative_to: Option<String>,
+    relative_base: Option<String>,
+    quiet: bool,
+    delimiter: Delimiter,
+}
+
+#[derive(Debug, Parser)]
+#[command(name = "realpathr", author = "OFFBLACK", version = "0.1.0", about = "Rust realpath")]
+struct Args {
+    /// The path(s) to resolve
+    #[arg(value_name = "FILE", required = true)]
+    files: Vec<String>,
+
+    /// Every part of each path must exist
+    #[arg(short = 'e', long, overrides_with = "canonicalize_missing")]
+    canonicalize_existing: bool,
+
+    /// No part of each path needs to exist
+    #[arg(short = 'm', long, overrides_with = "canonicalize_existing")]
+    canonicalize_missing: bool,
+
+    /// Leave symbolic links as they are
+    #[arg(short = 's', long = "strip", visible_alias = "no-symlinks")]
+    no_symlinks: bool,
+
+    /// Print the paths relative to DIR
+    #[arg(long, value_name = "DIR")]
+    relative_to: Option<String>,
+
+    /// Print the paths under DIR relative to it, and the rest absolute
+    #[arg(long, value_name = "DIR")]
+    relative_base: Option<String>,
+
+    /// Don't report errors
+    #[arg(short = 'q', long)]
+    quiet: bool,
+
+    #[command(flatten)]
+    records: DelimiterArgs,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let args: Args = parse_args();
+
+    let mode = if args.canonicalize_existing {
+        Mode::Existing
+    } else if args.canonicalize_missing {
+        Mode::Missing
+    } else {
+        Mode::AllButLast
+    };
+    Ok(Config {
+        files: args.files,
+        mode,
+        follow_links: !args.no_symlinks,
+        relative_to: args.relative_to,
+        relative_base: args.relative_base,
+        quiet: args.quiet,
+        delimiter: args.records.delimiter(),
+    })
+}
+
+/// The directories the paths are printed relative to: the one to start
+/// from, and the one they must be under to be printed that way. As in GNU
+/// realpath, a base alone is also where to start from, and nothing is
+/// printed relative if the start isn't under the base.
+fn relative_dirs(config: &Config) -> MyResult<(Option<PathBuf>, Option<PathBuf>)> {
+    let resolve = |dir: &Option<String>| -> MyResult<Option<PathBuf>> {
+        dir.as_deref()
+            .map(|dir| {
+                canonicalize(Path::new(dir), config.mode, config.follow_links)
+                    .map_err(|e| CoreError::io(dir, e))
+            })
+            .transpose()
+    };
+    let to = resolve(&config.relative_to)?;
+    let base = resolve(&config.relative_base)?;
+    Ok(match (to, base) {
+        (Some(to), Some(base)) if !to.starts_with(&base) => (None, None),
+        (None, base) => (base.clone(), base),
+        dirs => dirs,
+    })
+}
+
+pub fn run(config: Config) -> MyResult<i32> {
+    let (relative_to, relative_base) = relative_dirs(&config)?;
+    let mut out = Output::stdout().delimiter(config.delimiter);
+    let mut status = 0;
+    for filename in &config.files {
+        let path = match canonicalize(Path::new(filename), config.mode, config.follow_links) {
+            Ok(path) => path,
+            Err(e) => {
+                if !config.quiet {
+                    out.flush()?;
+                    eprintln!("{}", path_error(filename, e));
+                }
+                status = 1;
+                continue;
+            }
+        };
+        let under_base = relative_base.as_ref().is_none_or(|base| path.starts_with(base));
+        match &relative_to {
+            Some(to) if under_base => write_path(&mut out, &relative(&path, to))?,
+            _ => write_path(&mut out, &path)?,
+        }
+    }
+    out.flush()?;
+    Ok(status)
+}
+
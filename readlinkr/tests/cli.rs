@@ -0,0 +1,248 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tempfile::TempDir;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const READLINK: &str = "readlinkr";
+const REALPATH: &str = "realpathr";
+
+// --------------------------------------------------
+/// A directory to resolve paths in: a file `f` and a directory `a/b`, and on
+/// Unix, links `ab` to `a/b`, `dangling` to nowhere, and `loop1` and `loop2`
+/// to each other.
+fn setup() -> std::io::Result<TempDir> {
+    let dir = tempfile::tempdir()?;
+    fs::write(dir.path().join("f"), "")?;
+    fs::create_dir_all(dir.path().join("a/b"))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::symlink;
+        symlink("a/b", dir.path().join("ab"))?;
+        symlink("nowhere", dir.path().join("dangling"))?;
+        symlink("loop2", dir.path().join("loop1"))?;
+        symlink("loop1", dir.path().join("loop2"))?;
+    }
+    Ok(dir)
+}
+
+// --------------------------------------------------
+fn cmd(prg: &str, dir: &Path) -> Result<Command, Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin(prg)?;
+    cmd.current_dir(dir);
+    Ok(cmd)
+}
+
+// --------------------------------------------------
+/// The lines `paths` would print as, each under the canonical `dir`, with
+/// any `.` dropped.
+fn lines(dir: &Path, paths: &[&str]) -> std::io::Result<String> {
+    let dir = dir.canonicalize()?;
+    Ok(paths
+        .iter()
+        .map(|path| format!("{}\n", dir.join(path).components().collect::<PathBuf>().display()))
+        .collect())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_no_args() -> TestResult {
+    for prg in [READLINK, REALPATH] {
+        Command::cargo_bin(prg)?
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("required"));
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn readlink_not_link() -> TestResult {
+    let dir = setup()?;
+    // Quiet unless asked
+    cmd(READLINK, dir.path())?.args(["f", "new"]).assert().code(1).stdout("").stderr("");
+    cmd(READLINK, dir.path())?
+        .args(["-v", "new"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::starts_with("new: "));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn readlink_modes() -> TestResult {
+    let dir = setup()?;
+    cmd(READLINK, dir.path())?
+        .args(["-f", "a/./b/..", "new"])
+        .assert()
+        .success()
+        .stdout(lines(dir.path(), &["a", "new"])?);
+    cmd(READLINK, dir.path())?
+        .args(["-e", "a", "new"])
+        .assert()
+        .code(1)
+        .stdout(lines(dir.path(), &["a"])?);
+    cmd(READLINK, dir.path())?
+        .args(["-m", "new/x/../y", "f/x"])
+        .assert()
+        .success()
+        .stdout(lines(dir.path(), &["new/y", "f/x"])?);
+    // The last mode wins
+    cmd(READLINK, dir.path())?.args(["-m", "-e", "new/x"]).assert().code(1);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn readlink_no_newline() -> TestResult {
+    let dir = setup()?;
+    let a = dir.path().canonicalize()?.join("a");
+    cmd(READLINK, dir.path())?
+        .args(["-n", "-f", "a"])
+        .assert()
+        .success()
+        .stdout(a.display().to_string());
+    cmd(READLINK, dir.path())?
+        .args(["-n", "-f", "a", "a"])
+        .assert()
+        .success()
+        .stdout(format!("{0}\n{0}\n", a.display()))
+        .stderr("ignoring --no-newline with multiple arguments\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn zero_terminated() -> TestResult {
+    let dir = setup()?;
+    let expected = lines(dir.path(), &["a", "f"])?.replace('\n', "\0");
+    for prg in [READLINK, REALPATH] {
+        let mut cmd = cmd(prg, dir.path())?;
+        if prg == READLINK {
+            cmd.arg("-f");
+        }
+        cmd.args(["-z", "a", "f"]).assert().success().stdout(expected.clone());
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+#[cfg(unix)]
+fn readlink_links() -> TestResult {
+    let dir = setup()?;
+    cmd(READLINK, dir.path())?
+        .args(["ab", "dangling", "loop1"])
+        .assert()
+        .success()
+        .stdout("a/b\nnowhere\nloop2\n");
+    cmd(READLINK, dir.path())?
+        .args(["-f", "ab/..", "dangling"])
+        .assert()
+        .success()
+        .stdout(lines(dir.path(), &["a", "nowhere"])?);
+    cmd(READLINK, dir.path())?
+        .args(["-v", "-f", "loop1"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::starts_with("loop1: Too many levels of symbolic links"));
+    // With -m, a loop is left as it is
+    cmd(READLINK, dir.path())?
+        .args(["-m", "loop1"])
+        .assert()
+        .success()
+        .stdout(lines(dir.path(), &["loop1"])?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn realpath_errors() -> TestResult {
+    let dir = setup()?;
+    cmd(REALPATH, dir.path())?
+        .args(["new/x", "f"])
+        .assert()
+        .code(1)
+        .stdout(lines(dir.path(), &["f"])?)
+        .stderr(predicate::str::starts_with("new/x: "));
+    cmd(REALPATH, dir.path())?
+        .args(["-q", "-e", "new", "f/x"])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr("");
+    cmd(REALPATH, dir.path())?
+        .args(["-m", "new/x", "f/x"])
+        .assert()
+        .success()
+        .stdout(lines(dir.path(), &["new/x", "f/x"])?);
+    cmd(REALPATH, dir.path())?
+        .args(["-m", "", "f"])
+        .assert()
+        .code(1)
+        .stdout(lines(dir.path(), &["f"])?)
+        .stderr("'': No such file or directory\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+#[cfg(unix)]
+fn realpath_no_symlinks() -> TestResult {
+    let dir = setup()?;
+    cmd(REALPATH, dir.path())?
+        .args(["ab", "dangling"])
+        .assert()
+        .success()
+        .stdout(lines(dir.path(), &["a/b", "nowhere"])?);
+    // Links are kept, and .. just goes up from them
+    cmd(REALPATH, dir.path())?
+        .args(["-s", "ab", "ab/..", "dangling"])
+        .assert()
+        .success()
+        .stdout(lines(dir.path(), &["ab", ".", "dangling"])?);
+    // but must still lead somewhere with -e
+    cmd(REALPATH, dir.path())?.args(["-se", "dangling", "loop1"]).assert().code(1);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn realpath_relative() -> TestResult {
+    let dir = setup()?;
+    let sep = std::path::MAIN_SEPARATOR;
+    cmd(REALPATH, dir.path())?
+        .args(["--relative-to=a/b", ".", "a", "a/b", "f", "a/b/new"])
+        .assert()
+        .success()
+        .stdout(format!("..{sep}..\n..\n.\n..{sep}..{sep}f\nnew\n"));
+    // Only the paths under the base are relative
+    cmd(REALPATH, dir.path())?
+        .args(["--relative-base=a", "a/b", "f"])
+        .assert()
+        .success()
+        .stdout(format!("b\n{}", lines(dir.path(), &["f"])?));
+    cmd(REALPATH, dir.path())?
+        .args(["--relative-to=a/b", "--relative-base=a", "a", "f"])
+        .assert()
+        .success()
+        .stdout(format!("..\n{}", lines(dir.path(), &["f"])?));
+    // None are if the directory they'd be relative to isn't under the base
+    cmd(REALPATH, dir.path())?
+        .args(["--relative-to=.", "--relative-base=a", "a/b"])
+        .assert()
+        .success()
+        .stdout(lines(dir.path(), &["a/b"])?);
+    cmd(REALPATH, dir.path())?
+        .args(["--relative-to=new/x", "a"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::starts_with("new/x: "));
+    Ok(())
+}
@@ -0,0 +1,19 @@
+#!/usr/bin/env bash
+
+# Writes the expected output of each test with util-linux rev. It reverses
+# code points, not graphemes, so graphemes.txt.out is written by hand.
+
+# the-bustle.txt has an em dash, which rev rejects in the C locale
+export LC_ALL=C.UTF-8
+
+OUTDIR="tests/expected"
+[[ ! -d "$OUTDIR" ]] && mkdir -p "$OUTDIR"
+
+BUSTLE="tests/inputs/the-bustle.txt"
+FOX="tests/inputs/fox.txt"
+EMPTY="tests/inputs/empty.txt"
+
+rev $EMPTY > $OUTDIR/empty.txt.out
+rev $FOX > $OUTDIR/fox.txt.out
+rev $BUSTLE > $OUTDIR/the-bustle.txt.out
+rev $BUSTLE $FOX > $OUTDIR/all.out
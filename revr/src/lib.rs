@@ -0,0 +1,109 @@
+use clap::Parser;
+use std::io::{BufRead, Write};
+use unicode_segmentation::UnicodeSegmentation;
+
+use coreutils_common::{open, parse_args, CoreError, MyResult, Output};
+
+#[derive(Debug)]
+pub struct Config {
+    files: Vec<String>,
+}
+
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust rev")]
+struct Args {
+    /// Input file(s)
+    #[arg(value_name = "FILE", default_value = "-")]
+    files: Vec<String>,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let args: Args = parse_args();
+
+    Ok(Config { files: args.files })
+}
+
+/// `line` with its characters in reverse order. A character is what the
+/// reader sees as one, an extended grapheme cluster, so accents stay on
+/// their letters and emoji built of several code points stay whole. A
+/// line that isn't UTF-8 is reversed byte by byte.
+fn reverse(line: &[u8]) -> Vec<u8> {
+    match std::str::from_utf8(line) {
+        Ok(text) => text.graphemes(true).rev().collect::<String>().into_bytes(),
+        Err(_) => line.iter().rev().copied().collect(),
+    }
+}
+
+/// Splits the line ending, "\n" or "\r\n", off `line`, so it stays at the
+/// end.
+fn split_ending(line: &[u8]) -> (&[u8], &[u8]) {
+    let len = match line {
+        [.., b'\r', b'\n'] => 2,
+        [.., b'\n'] => 1,
+        _ => 0,
+    };
+    line.split_at(line.len() - len)
+}
+
+/// Prints every line of the inputs reversed and returns the exit status: 1
+/// if any file failed to open.
+pub fn run(config: Config) -> MyResult<i32> {
+    let mut out = Output::stdout();
+    let mut status = 0;
+    for filename in &config.files {
+        let mut input = match open(filename) {
+            Ok(input) => input,
+            Err(e) => {
+                out.flush()?;
+                eprintln!("{e}");
+                status = 1;
+                continue;
+            }
+        };
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            let len = input
+                .read_until(b'\n', &mut line)
+                .map_err(|e| CoreError::io(filename, e))?;
+            if len == 0 {
+                break;
+            }
+            let (text, ending) = split_ending(&line);
+            out.write_all(&reverse(text))?;
+            out.write_all(ending)?;
+        }
+    }
+    out.flush()?;
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{reverse, split_ending};
+
+    fn rev(line: &str) -> String {
+        String::from_utf8(reverse(line.as_bytes())).unwrap()
+    }
+
+    #[test]
+    fn test_reverse() {
+        assert_eq!(rev("abc"), "cba");
+        assert_eq!(rev(""), "");
+        assert_eq!(rev("naïve"), "evïan");
+        // e and a combining acute accent
+        assert_eq!(rev("e\u{301}t"), "te\u{301}");
+        // A flag is two regional indicators, reversed they'd be another flag
+        assert_eq!(rev("FR🇫🇷"), "🇫🇷RF");
+        assert_eq!(rev("👩‍💻 x"), "x 👩‍💻");
+        assert_eq!(reverse(b"a\xffb"), b"b\xffa");
+    }
+
+    #[test]
+    fn test_split_ending() {
+        assert_eq!(split_ending(b"ab\n"), (&b"ab"[..], &b"\n"[..]));
+        assert_eq!(split_ending(b"ab\r\n"), (&b"ab"[..], &b"\r\n"[..]));
+        assert_eq!(split_ending(b"ab"), (&b"ab"[..], &b""[..]));
+        assert_eq!(split_ending(b"\r"), (&b"\r"[..], &b""[..]));
+    }
+}
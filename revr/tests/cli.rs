@@ -0,0 +1,87 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "revr";
+const BUSTLE: &str = "tests/inputs/the-bustle.txt";
+const FOX: &str = "tests/inputs/fox.txt";
+const EMPTY: &str = "tests/inputs/empty.txt";
+const GRAPHEMES: &str = "tests/inputs/graphemes.txt";
+
+// --------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> TestResult {
+    let expected = fs::read_to_string(expected_file)?;
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn skips_bad_file() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args([FOX, "tests/inputs/does-not-exist", FOX])
+        .assert()
+        .code(1)
+        .stdout(fs::read_to_string("tests/expected/fox.txt.out")?.repeat(2))
+        .stderr(predicate::str::starts_with("tests/inputs/does-not-exist: "));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn empty() -> TestResult {
+    run(&[EMPTY], "tests/expected/empty.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fox() -> TestResult {
+    run(&[FOX], "tests/expected/fox.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn bustle() -> TestResult {
+    run(&[BUSTLE], "tests/expected/the-bustle.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn graphemes() -> TestResult {
+    // Accents and emoji stay whole, and a CRLF stays at the end
+    run(&[GRAPHEMES], "tests/expected/graphemes.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn all() -> TestResult {
+    run(&[BUSTLE, FOX], "tests/expected/all.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn stdin() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .write_stdin("abc\n\nxyz")
+        .assert()
+        .success()
+        .stdout("cba\n\nzyx");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn not_utf8() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .write_stdin(&b"ab\xff\n"[..])
+        .assert()
+        .success()
+        .stdout(&b"\xffba\n"[..]);
+    Ok(())
+}
@@ -0,0 +1,20 @@
+#!/usr/bin/env bash
+
+# Writes the expected output of each test with GNU seq
+
+OUTDIR="tests/expected"
+[[ ! -d "$OUTDIR" ]] && mkdir -p "$OUTDIR"
+
+seq 3 > $OUTDIR/last.out
+seq 2 5 > $OUTDIR/first.last.out
+seq 10 -3 1 > $OUTDIR/down.out
+seq 1 0.5 3 > $OUTDIR/halves.out
+seq 0 0.1 1 > $OUTDIR/tenths.out
+seq 1e2 1e2 3e2 > $OUTDIR/exponent.out
+seq -s ', ' 1 3 > $OUTDIR/separator.out
+seq -w 8 11 > $OUTDIR/equal_width.out
+seq -w -3 2 > $OUTDIR/equal_width.negative.out
+seq -w 0.5 1.5 10 > $OUTDIR/equal_width.decimals.out
+seq -f '%05.2f' 1 3 > $OUTDIR/format.out
+seq -f 'n=%g' 0.25 0.25 1 > $OUTDIR/format.text.out
+seq -f '%e' 1 1000 3000 > $OUTDIR/format.exponent.out
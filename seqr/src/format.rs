@@ -0,0 +1,205 @@
+//! The -f format: text around one printf floating-point directive, %e, %f
+//! or %g, with printf's flags, width and precision.
+
+use coreutils_common::{CoreError, MyResult};
+
+#[derive(Debug)]
+pub struct Format {
+    prefix: String,
+    spec: Spec,
+    suffix: String,
+}
+
+#[derive(Debug, Default)]
+struct Spec {
+    /// '-': pad on the right
+    left: bool,
+    /// '+': always print a sign
+    plus: bool,
+    /// ' ': a space where a plus sign would go
+    space: bool,
+    /// '0': pad with zeros after the sign
+    zero: bool,
+    /// '#': keep the trailing zeros of %g
+    alt: bool,
+    width: usize,
+    precision: Option<usize>,
+    /// e, f or g, in upper case for upper case output
+    conversion: char,
+}
+
+impl Format {
+    pub fn parse(format: &str) -> MyResult<Self> {
+        let err = |msg: &str| CoreError::Parse(format!("format '{format}' {msg}"));
+        let mut parts = [String::new(), String::new()];
+        let mut spec: Option<Spec> = None;
+        let mut chars = format.char_indices().peekable();
+        while let Some((start, c)) = chars.next() {
+            if c != '%' {
+                parts[spec.is_some() as usize].push(c);
+                continue;
+            }
+            if chars.next_if(|&(_, c)| c == '%').is_some() {
+                parts[spec.is_some() as usize].push('%');
+                continue;
+            }
+            if spec.is_some() {
+                return Err(err("has too many % directives"));
+            }
+
+            let mut new = Spec::default();
+            while let Some((_, flag)) = chars.next_if(|&(_, c)| "-+ #0'".contains(c)) {
+                match flag {
+                    '-' => new.left = true,
+                    '+' => new.plus = true,
+                    ' ' => new.space = true,
+                    '0' => new.zero = true,
+                    '#' => new.alt = true,
+                    // Thousands grouping does nothing in the C locale
+                    _ => {}
+                }
+            }
+            new.width = take_number(&mut chars);
+            if chars.next_if(|&(_, c)| c == '.').is_some() {
+                new.precision = Some(take_number(&mut chars));
+            }
+            match chars.next() {
+                Some((_, c @ ('e' | 'E' | 'f' | 'F' | 'g' | 'G'))) => {
+                    new.conversion = c;
+                    spec = Some(new);
+                }
+                Some((end, c)) => {
+                    let directive = &format[start..end + c.len_utf8()];
+                    return Err(err(&format!("has unknown {directive} directive")));
+                }
+                None => return Err(err("ends in %")),
+            }
+        }
+
+        let [prefix, suffix] = parts;
+        match spec {
+            Some(spec) => Ok(Format { prefix, spec, suffix }),
+            None => Err(err("has no % directive")),
+        }
+    }
+
+    /// `value` laid out as the format says.
+    pub fn apply(&self, value: f64) -> String {
+        let spec = &self.spec;
+        let sign = match (value.is_sign_negative(), spec.plus, spec.space) {
+            (true, _, _) => "-",
+            (_, true, _) => "+",
+            (_, _, true) => " ",
+            _ => "",
+        };
+        let abs = value.abs();
+        let precision = spec.precision.unwrap_or(6);
+        let mut body = match spec.conversion.to_ascii_lowercase() {
+            'f' => format!("{abs:.precision$}"),
+            'e' => exponential(abs, precision),
+            _ => general(abs, precision, spec.alt),
+        };
+        if spec.conversion.is_ascii_uppercase() {
+            body.make_ascii_uppercase();
+        }
+
+        let pad = spec.width.saturating_sub(sign.len() + body.len());
+        let number = if spec.left {
+            format!("{sign}{body}{}", " ".repeat(pad))
+        } else if spec.zero {
+            format!("{sign}{}{body}", "0".repeat(pad))
+        } else {
+            format!("{}{sign}{body}", " ".repeat(pad))
+        };
+        format!("{}{number}{}", self.prefix, self.suffix)
+    }
+}
+
+fn take_number(chars: &mut std::iter::Peekable<std::str::CharIndices>) -> usize {
+    let mut num = 0;
+    while let Some((_, digit)) = chars.next_if(|(_, c)| c.is_ascii_digit()) {
+        num = num * 10 + (digit as usize - '0' as usize);
+    }
+    num
+}
+
+/// `abs` as %e: one digit, `precision` decimals and a signed exponent of at
+/// least two digits.
+fn exponential(abs: f64, precision: usize) -> String {
+    let formatted = format!("{abs:.precision$e}");
+    let (mantissa, exponent) = formatted.split_once('e').unwrap_or((&formatted, "0"));
+    let exponent: i32 = exponent.parse().unwrap_or_default();
+    let sign = if exponent < 0 { '-' } else { '+' };
+    format!("{mantissa}e{sign}{:02}", exponent.abs())
+}
+
+/// `abs` as %g: %e for very large or small numbers and %f otherwise, with
+/// `precision` significant digits and, unless `alt`, no trailing zeros.
+fn general(abs: f64, precision: usize, alt: bool) -> String {
+    let precision = precision.max(1);
+    // The exponent after rounding to `precision` digits decides the style
+    let exponent: i32 = format!("{abs:.*e}", precision - 1)
+        .split_once('e')
+        .and_then(|(_, exponent)| exponent.parse().ok())
+        .unwrap_or_default();
+    let formatted = if exponent < -4 || exponent >= precision as i32 {
+        exponential(abs, precision - 1)
+    } else {
+        let decimals = (precision as i32 - 1 - exponent) as usize;
+        format!("{abs:.decimals$}")
+    };
+    if alt {
+        return formatted;
+    }
+    let (mantissa, exponent) = match formatted.find('e') {
+        Some(i) => formatted.split_at(i),
+        None => (formatted.as_str(), ""),
+    };
+    let mantissa = if mantissa.contains('.') {
+        mantissa.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        mantissa
+    };
+    format!("{mantissa}{exponent}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Format;
+
+    fn apply(format: &str, value: f64) -> String {
+        Format::parse(format).unwrap().apply(value)
+    }
+
+    #[test]
+    fn test_apply() {
+        assert_eq!(apply("%g", 1.0), "1");
+        assert_eq!(apply("%g", 1.5), "1.5");
+        assert_eq!(apply("%g", 0.0001), "0.0001");
+        assert_eq!(apply("%g", 0.00001), "1e-05");
+        assert_eq!(apply("%g", 123456.0), "123456");
+        assert_eq!(apply("%g", 1234567.0), "1.23457e+06");
+        assert_eq!(apply("%#g", 1.0), "1.00000");
+        assert_eq!(apply("%.3g", 1000.0), "1e+03");
+        assert_eq!(apply("%G", 0.00001), "1E-05");
+        assert_eq!(apply("%e", 0.0), "0.000000e+00");
+        assert_eq!(apply("%10.3e|", 1.0), " 1.000e+00|");
+        assert_eq!(apply("%05.2f", 1.0), "01.00");
+        assert_eq!(apply("%05g", -1.0), "-0001");
+        assert_eq!(apply("%-8g|", 1.0), "1       |");
+        assert_eq!(apply("%+g", 1.0), "+1");
+        assert_eq!(apply("x%gy", 2.0), "x2y");
+        assert_eq!(apply("%%%g%%", 1.0), "%1%");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = |format| Format::parse(format).unwrap_err().to_string();
+        assert_eq!(err("abc"), "format 'abc' has no % directive");
+        assert_eq!(err("%g %g"), "format '%g %g' has too many % directives");
+        assert_eq!(err("%d"), "format '%d' has unknown %d directive");
+        assert_eq!(err("%-5x"), "format '%-5x' has unknown %-5x directive");
+        assert_eq!(err("%g%"), "format '%g%' has too many % directives");
+        assert_eq!(err("%"), "format '%' ends in %");
+    }
+}
@@ -0,0 +1,237 @@
+use clap::Parser;
+use std::io::Write;
+
+use coreutils_common::{parse_args, CoreError, MyResult, Output};
+
+mod format;
+
+use format::Format;
+
+#[derive(Debug)]
+pub struct Config {
+    /// The numbers, all as multiples of 10^-`scale`
+    first: i128,
+    increment: i128,
+    last: i128,
+    scale: u32,
+    /// How many decimals to print: as many as FIRST or INCREMENT has
+    precision: u32,
+    separator: String,
+    equal_width: bool,
+    format: Option<Format>,
+}
+
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust seq")]
+struct Args {
+    /// [FIRST [INCREMENT]] LAST; FIRST and INCREMENT default to 1
+    #[arg(
+        value_name = "NUMBER",
+        required = true,
+        num_args = 1..=3,
+        allow_negative_numbers = true
+    )]
+    numbers: Vec<String>,
+
+    /// Put STRING between numbers
+    #[arg(
+        short = 's',
+        long,
+        value_name = "STRING",
+        default_value = "\n",
+        hide_default_value = true
+    )]
+    separator: String,
+
+    /// Pad with leading zeros to make all numbers the same width
+    #[arg(short = 'w', long, conflicts_with = "format")]
+    equal_width: bool,
+
+    /// Print each number with a printf-style %e, %f or %g FORMAT
+    #[arg(short = 'f', long, value_name = "FORMAT")]
+    format: Option<String>,
+}
+
+/// A number as written: `digits` × 10^-`scale`. Stepping by these is exact,
+/// so 0.1 three times is 0.3, and the last number isn't lost to rounding.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Decimal {
+    digits: i128,
+    scale: u32,
+}
+
+impl Decimal {
+    /// Parses a decimal number with an optional sign and exponent.
+    fn parse(val: &str) -> Option<Self> {
+        let (mantissa, exponent) = match val.find(['e', 'E']) {
+            Some(i) => (&val[..i], val[i + 1..].parse::<i32>().ok()?),
+            None => (val, 0),
+        };
+        let (negative, mantissa) = match mantissa.strip_prefix('-') {
+            Some(mantissa) => (true, mantissa),
+            None => (false, mantissa.strip_prefix('+').unwrap_or(mantissa)),
+        };
+        let (int, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        let all_digits = int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit());
+        if !all_digits || int.len() + frac.len() == 0 {
+            return None;
+        }
+
+        let mut digits: i128 = format!("{int}{frac}").parse().ok()?;
+        let mut scale = frac.len() as i32 - exponent;
+        if scale < 0 {
+            digits = digits.checked_mul(10i128.checked_pow(scale.unsigned_abs())?)?;
+            scale = 0;
+        }
+        Some(Decimal {
+            digits: if negative { -digits } else { digits },
+            scale: scale as u32,
+        })
+    }
+
+    /// The digits as a multiple of 10^-`scale`, which is at least as fine.
+    fn rescale(self, scale: u32) -> Option<i128> {
+        self.digits.checked_mul(10i128.checked_pow(scale - self.scale)?)
+    }
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let args: Args = parse_args();
+
+    let parse = |val: &String| {
+        Decimal::parse(val)
+            .ok_or_else(|| CoreError::Parse(format!("invalid floating point argument: '{val}'")))
+    };
+    let one = Decimal { digits: 1, scale: 0 };
+    let (first, increment, last) = match args.numbers.as_slice() {
+        [last] => (one, one, parse(last)?),
+        [first, last] => (parse(first)?, one, parse(last)?),
+        [first, increment, last] => (parse(first)?, parse(increment)?, parse(last)?),
+        _ => unreachable!("clap takes 1 to 3 numbers"),
+    };
+    if increment.digits == 0 {
+        let val = &args.numbers[1];
+        return Err(CoreError::Usage(format!("invalid Zero increment value: '{val}'")));
+    }
+
+    let scale = first.scale.max(increment.scale).max(last.scale);
+    // Digits past what an i128 holds only come from absurd arguments
+    let rescale = |decimal: Decimal| {
+        decimal
+            .rescale(scale)
+            .ok_or_else(|| CoreError::Parse("too many digits in arguments".to_string()))
+    };
+    Ok(Config {
+        first: rescale(first)?,
+        increment: rescale(increment)?,
+        last: rescale(last)?,
+        scale,
+        precision: first.scale.max(increment.scale),
+        separator: args.separator,
+        equal_width: args.equal_width,
+        format: args.format.as_deref().map(Format::parse).transpose()?,
+    })
+}
+
+/// `value`, a multiple of 10^-`scale`, with `precision` decimals. The value
+/// has no more decimals than that, so nothing is rounded.
+fn format_fixed(value: i128, scale: u32, precision: u32) -> String {
+    let value = value / 10i128.pow(scale - precision);
+    let sign = if value < 0 { "-" } else { "" };
+    let abs = value.unsigned_abs();
+    if precision == 0 {
+        return format!("{sign}{abs}");
+    }
+    let unit = 10u128.pow(precision);
+    format!("{sign}{}.{:0width$}", abs / unit, abs % unit, width = precision as usize)
+}
+
+/// `num` padded to `width` with zeros after any sign.
+fn zero_pad(num: String, width: usize) -> String {
+    let pad = "0".repeat(width.saturating_sub(num.len()));
+    match num.strip_prefix('-') {
+        Some(abs) => format!("-{pad}{abs}"),
+        None => format!("{pad}{num}"),
+    }
+}
+
+pub fn run(config: Config) -> MyResult<i32> {
+    let step = config.increment;
+    let in_range = |value: i128| {
+        if step > 0 {
+            value <= config.last
+        } else {
+            value >= config.last
+        }
+    };
+    let to_string = |value: i128| match &config.format {
+        Some(format) => format.apply(value as f64 / 10f64.powi(config.scale as i32)),
+        None => format_fixed(value, config.scale, config.precision),
+    };
+    // Like GNU seq, the width is that of FIRST or LAST, whichever is wider,
+    // with LAST cut to the printed decimals and keeping its sign
+    let width = if config.equal_width {
+        let unit = 10i128.pow(config.scale - config.precision);
+        let mut last = format_fixed(config.last / unit * unit, config.scale, config.precision);
+        if config.last < 0 && !last.starts_with('-') {
+            last.insert(0, '-');
+        }
+        to_string(config.first).len().max(last.len())
+    } else {
+        0
+    };
+
+    let mut out = Output::stdout();
+    let mut value = Some(config.first);
+    let mut printed = false;
+    while let Some(num) = value.filter(|&num| in_range(num)) {
+        if printed {
+            out.write_all(config.separator.as_bytes())?;
+        }
+        out.write_all(zero_pad(to_string(num), width).as_bytes())?;
+        printed = true;
+        value = num.checked_add(step);
+    }
+    if printed {
+        writeln!(out)?;
+    }
+    out.flush()?;
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_fixed, zero_pad, Decimal};
+
+    #[test]
+    fn test_parse_decimal() {
+        let parse = |val| Decimal::parse(val).map(|d| (d.digits, d.scale));
+        assert_eq!(parse("42"), Some((42, 0)));
+        assert_eq!(parse("-1.50"), Some((-150, 2)));
+        assert_eq!(parse("+.5"), Some((5, 1)));
+        assert_eq!(parse("1."), Some((1, 0)));
+        assert_eq!(parse("1e2"), Some((100, 0)));
+        assert_eq!(parse("1.5e1"), Some((15, 0)));
+        assert_eq!(parse("1e-1"), Some((1, 1)));
+        assert_eq!(parse("99999999999999999999"), Some((99999999999999999999, 0)));
+        for bad in ["", "-", ".", "x", "1x", "--1", "1e", "nan", "0x10"] {
+            assert_eq!(parse(bad), None, "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_format_fixed() {
+        assert_eq!(format_fixed(150, 2, 1), "1.5");
+        assert_eq!(format_fixed(-5, 1, 1), "-0.5");
+        assert_eq!(format_fixed(0, 1, 1), "0.0");
+        assert_eq!(format_fixed(300, 2, 0), "3");
+        assert_eq!(format_fixed(1035, 3, 3), "1.035");
+    }
+
+    #[test]
+    fn test_zero_pad() {
+        assert_eq!(zero_pad("5".to_string(), 3), "005");
+        assert_eq!(zero_pad("-5".to_string(), 3), "-05");
+        assert_eq!(zero_pad("10.5".to_string(), 3), "10.5");
+    }
+}
@@ -0,0 +1,148 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "seqr";
+
+// --------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> TestResult {
+    let expected = fs::read_to_string(expected_file)?;
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_no_args() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Usage"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_args() -> TestResult {
+    for (args, msg) in [
+        (&["x"][..], "invalid floating point argument: 'x'"),
+        (&["1", "0x2", "3"], "invalid floating point argument: '0x2'"),
+        (&["1", "0", "3"], "invalid Zero increment value: '0'"),
+        (&["-f", "%d", "3"], "format '%d' has unknown %d directive"),
+        (&["-f", "%g %g", "3"], "format '%g %g' has too many % directives"),
+        (&["-f", "abc", "3"], "format 'abc' has no % directive"),
+    ] {
+        Command::cargo_bin(PRG)?
+            .args(args)
+            .assert()
+            .code(1)
+            .stdout("")
+            .stderr(format!("{msg}\n"));
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_width_and_format() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-w", "-f", "%g", "3"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn empty_sequence() -> TestResult {
+    for args in [&["5", "2"][..], &["1", "-1", "2"], &["0"]] {
+        Command::cargo_bin(PRG)?.args(args).assert().success().stdout("");
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn last() -> TestResult {
+    run(&["3"], "tests/expected/last.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn first_last() -> TestResult {
+    run(&["2", "5"], "tests/expected/first.last.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn down() -> TestResult {
+    run(&["10", "-3", "1"], "tests/expected/down.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn halves() -> TestResult {
+    run(&["1", "0.5", "3"], "tests/expected/halves.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn tenths() -> TestResult {
+    // Stepping in floating point would stop short of 1.0
+    run(&["0", "0.1", "1"], "tests/expected/tenths.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn exponent() -> TestResult {
+    run(&["1e2", "1e2", "3e2"], "tests/expected/exponent.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn separator() -> TestResult {
+    run(&["-s", ", ", "1", "3"], "tests/expected/separator.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn equal_width() -> TestResult {
+    run(&["-w", "8", "11"], "tests/expected/equal_width.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn equal_width_negative() -> TestResult {
+    run(&["-w", "-3", "2"], "tests/expected/equal_width.negative.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn equal_width_decimals() -> TestResult {
+    run(&["-w", "0.5", "1.5", "10"], "tests/expected/equal_width.decimals.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn format() -> TestResult {
+    run(&["-f", "%05.2f", "1", "3"], "tests/expected/format.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn format_text() -> TestResult {
+    run(&["-f", "n=%g", "0.25", "0.25", "1"], "tests/expected/format.text.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn format_exponent() -> TestResult {
+    run(&["-f", "%e", "1", "1000", "3000"], "tests/expected/format.exponent.out")
+}
@@ -0,0 +1,18 @@
+#!/usr/bin/env bash
+
+# Writes the expected output of each test with GNU tac
+
+OUTDIR="tests/expected"
+[[ ! -d "$OUTDIR" ]] && mkdir -p "$OUTDIR"
+
+BUSTLE="tests/inputs/the-bustle.txt"
+FOX="tests/inputs/fox.txt"
+EMPTY="tests/inputs/empty.txt"
+COLONS="tests/inputs/colons.txt"
+
+tac $EMPTY > $OUTDIR/empty.txt.out
+tac $BUSTLE > $OUTDIR/the-bustle.txt.out
+tac -b $BUSTLE > $OUTDIR/the-bustle.txt.b.out
+tac -s '::' $COLONS > $OUTDIR/colons.txt.s.out
+tac -b -s '::' $COLONS > $OUTDIR/colons.txt.b.s.out
+tac $BUSTLE $FOX > $OUTDIR/all.out
@@ -0,0 +1,190 @@
+use clap::Parser;
+use std::{
+    fs::File,
+    io::{self, Read, Seek, SeekFrom, Write},
+};
+
+use coreutils_common::{parse_args, CoreError, MyResult, Output, STDIN};
+
+/// How much is read at a time, from the end of the file backwards.
+const BLOCK_SIZE: usize = 64 * 1024;
+
+#[derive(Debug)]
+pub struct Config {
+    files: Vec<String>,
+    separator: String,
+    before: bool,
+}
+
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust tac")]
+struct Args {
+    /// Input file(s)
+    #[arg(value_name = "FILE", default_value = "-")]
+    files: Vec<String>,
+
+    /// Attach the separator before each record instead of after it
+    #[arg(short = 'b', long)]
+    before: bool,
+
+    /// Use STRING as the record separator instead of newline
+    #[arg(
+        short = 's',
+        long,
+        value_name = "STRING",
+        default_value = "\n",
+        hide_default_value = true
+    )]
+    separator: String,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let args: Args = parse_args();
+
+    Ok(Config {
+        files: args.files,
+        separator: args.separator,
+        before: args.before,
+    })
+}
+
+/// Opens `filename` for reading from the end. Input that can't seek, like
+/// stdin or a pipe, is first copied to a temporary file that is removed on
+/// close, so it doesn't have to fit in memory either.
+fn open_seekable(filename: &str) -> io::Result<File> {
+    let spool = |input: &mut dyn Read| -> io::Result<File> {
+        let mut tmp = tempfile::tempfile()?;
+        io::copy(input, &mut tmp)?;
+        Ok(tmp)
+    };
+    if filename == STDIN {
+        return spool(&mut io::stdin().lock());
+    }
+    let mut file = File::open(filename)?;
+    match file.seek(SeekFrom::End(0)) {
+        Ok(_) => Ok(file),
+        Err(_) => spool(&mut file),
+    }
+}
+
+/// Writes the records of `input` to `out` last first. A record ends with
+/// `separator`, or starts with it when `before` is set, and keeps it. Reads
+/// `block_size` bytes at a time from the end, so only the current record
+/// and one block are ever held in memory.
+fn tac<R: Read + Seek>(
+    input: &mut R,
+    separator: &[u8],
+    before: bool,
+    block_size: usize,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let mut pos = input.seek(SeekFrom::End(0))?;
+    if separator.is_empty() {
+        // Like GNU tac, nothing separates the file into records
+        input.seek(SeekFrom::Start(0))?;
+        io::copy(input, out)?;
+        return Ok(());
+    }
+
+    // The bytes from `pos` that haven't been written yet; the last record
+    // ends at `end`, and no separator lies entirely before `search_end`
+    let mut buf: Vec<u8> = Vec::new();
+    let mut end = 0;
+    while pos > 0 {
+        let len = block_size.min(pos as usize);
+        pos -= len as u64;
+        let mut block = vec![0; len];
+        input.seek(SeekFrom::Start(pos))?;
+        input.read_exact(&mut block)?;
+        block.extend_from_slice(&buf[..end]);
+        buf = block;
+        end += len;
+
+        // A separator may straddle the new block and the earlier bytes
+        let mut search_end = (len + separator.len() - 1).min(end);
+        while let Some(start) = buf[..search_end]
+            .windows(separator.len())
+            .rposition(|window| window == separator)
+        {
+            let record_start = if before { start } else { start + separator.len() };
+            out.write_all(&buf[record_start..end])?;
+            end = record_start;
+            search_end = start;
+        }
+    }
+    out.write_all(&buf[..end])
+}
+
+/// Prints every input with its records reversed and returns the exit
+/// status: 1 if any file failed to open.
+pub fn run(config: Config) -> MyResult<i32> {
+    let mut out = Output::stdout();
+    let mut status = 0;
+    for filename in &config.files {
+        match open_seekable(filename) {
+            Ok(mut file) => tac(
+                &mut file,
+                config.separator.as_bytes(),
+                config.before,
+                BLOCK_SIZE,
+                &mut out,
+            )
+            .map_err(|e| CoreError::io(filename, e))?,
+            Err(e) => {
+                out.flush()?;
+                eprintln!("{}", CoreError::io(filename, e));
+                status = 1;
+            }
+        }
+    }
+    out.flush()?;
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::tac;
+    use proptest::prelude::*;
+    use std::io::Cursor;
+
+    fn run(input: &str, separator: &str, before: bool, block_size: usize) -> String {
+        let mut out = Vec::new();
+        let mut input = Cursor::new(input.as_bytes());
+        tac(&mut input, separator.as_bytes(), before, block_size, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    /// The records of `input` split in memory, for checking the block reader.
+    fn records(input: &str, separator: &str, before: bool) -> Vec<String> {
+        let mut bounds = vec![0];
+        bounds.extend(input.match_indices(separator).map(|(i, _)| {
+            if before { i } else { i + separator.len() }
+        }));
+        bounds.push(input.len());
+        bounds.windows(2).map(|w| input[w[0]..w[1]].to_string()).collect()
+    }
+
+    #[test]
+    fn test_tac() {
+        assert_eq!(run("a\nb\nc\n", "\n", false, 64), "c\nb\na\n");
+        assert_eq!(run("a\nb", "\n", false, 64), "ba\n");
+        assert_eq!(run("a\nb\n", "\n", true, 64), "\n\nba");
+        assert_eq!(run("x::y::z", "::", false, 2), "zy::x::");
+        assert_eq!(run("x::y::z::", "::", true, 1), "::::z::yx");
+        assert_eq!(run("", "\n", false, 4), "");
+        assert_eq!(run("ab\ncd", "", false, 4), "ab\ncd");
+    }
+
+    proptest! {
+        #[test]
+        fn tac_matches_records_reversed(
+            input in "[ab\n]{0,40}",
+            separator in prop::sample::select(vec!["\n", "ab", "b\n"]),
+            before in any::<bool>(),
+            block_size in 1..8usize,
+        ) {
+            let expected: String = records(&input, separator, before).into_iter().rev().collect();
+            prop_assert_eq!(run(&input, separator, before, block_size), expected);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "tacr";
+const BUSTLE: &str = "tests/inputs/the-bustle.txt";
+const FOX: &str = "tests/inputs/fox.txt";
+const EMPTY: &str = "tests/inputs/empty.txt";
+const COLONS: &str = "tests/inputs/colons.txt";
+
+// --------------------------------------------------
+fn run(args: &[&str], expected_file: &str) -> TestResult {
+    let expected = fs::read_to_string(expected_file)?;
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn skips_bad_file() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args([FOX, "tests/inputs/does-not-exist", FOX])
+        .assert()
+        .code(1)
+        .stdout(format!("{0}{0}", fs::read_to_string(FOX)?))
+        .stderr(predicate::str::starts_with("tests/inputs/does-not-exist: "));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn empty() -> TestResult {
+    run(&[EMPTY], "tests/expected/empty.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn bustle() -> TestResult {
+    run(&[BUSTLE], "tests/expected/the-bustle.txt.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn bustle_before() -> TestResult {
+    run(&["-b", BUSTLE], "tests/expected/the-bustle.txt.b.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn colons_separator() -> TestResult {
+    run(&["-s", "::", COLONS], "tests/expected/colons.txt.s.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn colons_separator_before() -> TestResult {
+    run(&["-b", "-s", "::", COLONS], "tests/expected/colons.txt.b.s.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn all() -> TestResult {
+    // Each file is reversed on its own
+    run(&[BUSTLE, FOX], "tests/expected/all.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn stdin_across_blocks() -> TestResult {
+    // Bigger than a block, so records are split between reads
+    let lines: Vec<String> = (0..50_000).map(|n| format!("line {n}\n")).collect();
+    let reversed: String = lines.iter().rev().map(String::as_str).collect();
+    Command::cargo_bin(PRG)?
+        .write_stdin(lines.concat())
+        .assert()
+        .success()
+        .stdout(reversed);
+    Ok(())
+}
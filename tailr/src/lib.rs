@@ -1,10 +1,22 @@
-use std::{error::Error, fs::File, io::{BufRead, BufReader, Read, Seek}};
+use std::{
+    collections::VecDeque,
+    fs::{self, File, Metadata},
+    io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write},
+    thread,
+    time::Duration,
+};
 use num::Zero;
 use TakeValue::*;
 
-use clap::{App, Arg};
+use clap::{Parser, ValueEnum};
 
-type MyResult<T> = Result<T, Box<dyn Error>>;
+use coreutils_common::{
+    i18n::{io_error, tr, tr_fmt},
+    parse_args, CoreError, Delimiter, DelimiterArgs, FilesFromArgs, MyResult, STDIN,
+};
+
+#[cfg(unix)]
+mod mapped;
 
 #[derive(Debug)]
 pub struct Config {
@@ -12,72 +24,109 @@ pub struct Config {
     lines: TakeValue,
     bytes: Option<TakeValue>,
     quiet: bool,
+    verbose: bool,
+    follow: Option<FollowBy>,
+    retry: bool,
+    sleep_interval: Duration,
+    #[cfg_attr(not(unix), allow(dead_code))]
+    mmap: bool,
+    head: bool,
+    delimiter: Delimiter,
+}
+
+/// What -f follows: the file that was opened, or whichever file has its name
+/// now, for logs that get rotated.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum FollowBy {
+    Descriptor,
+    Name,
 }
 
+/// How much to print: everything for +0, what follows record or byte N for
+/// a positive N, and the last N for a negative one.
 #[derive(Debug, PartialEq)]
-enum TakeValue {
+pub enum TakeValue {
     PlusZero,
     TakeNum(i64)
 }
 
-fn parse_num(val: &str) -> MyResult<TakeValue> {
+/// What a count like "3", "-3" or "+3" means, as -n and -c take it.
+pub fn parse_num(val: &str) -> MyResult<TakeValue> {
+    let parse = |val: &str| val.parse::<i64>().map_err(|_| CoreError::Parse(val.to_string()));
     if val.starts_with("+") {
-        if val.parse::<i64>()?.is_zero() {
+        if parse(val)?.is_zero() {
             return Ok(PlusZero)
         }
-        return Ok(TakeNum(val.parse()?))
+        Ok(TakeNum(parse(val)?))
     } else if val.starts_with("-") {
-        return Ok(TakeNum(val.parse()?))
+        Ok(TakeNum(parse(val)?))
     } else {
-        match val.parse::<i64>() {
-            Ok(val) => return Ok(TakeNum(val * -1)),
-            Err(_) => return Err(val.to_string().into()),
-        }
+        Ok(TakeNum(-parse(val)?))
     }
 }
 
-fn count_lines_bytes(filename: &str) -> MyResult<(i64, i64)> {
-    let mut file = BufReader::new(File::open(filename)?);
-    let mut line = String::new();
-    let mut lines = 0;
-    let mut bytes = 0i64;
-    loop { 
-        let bytes_read = file.read_line(&mut line)?;
-        if bytes_read == 0 {
-            break;
+/// How much of a file is read at a time, going back from its end.
+const CHUNK: u64 = 64 * 1024;
+
+/// Where the last `num` records of `file` start, found by reading back from
+/// its end a chunk at a time, so that only about as much as the tail is
+/// read. A delimiter that ends the file doesn't start another record.
+fn find_tail_start(
+    file: &mut (impl Read + Seek),
+    num: u64,
+    delimiter: Delimiter,
+) -> io::Result<u64> {
+    let len = file.seek(SeekFrom::End(0))?;
+    let mut end = len;
+    let mut found = 0;
+    let mut buf = vec![0; CHUNK as usize];
+    while end > 0 {
+        let start = end.saturating_sub(CHUNK);
+        let chunk = &mut buf[..(end - start) as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(chunk)?;
+        for (i, &byte) in chunk.iter().enumerate().rev() {
+            let pos = start + i as u64;
+            if byte == delimiter.byte() && pos + 1 != len {
+                found += 1;
+                if found == num {
+                    return Ok(pos + 1);
+                }
+            }
         }
-        lines += 1;
-        bytes += bytes_read as i64;
-        line.clear();
+        end = start;
     }
-    Ok((lines, bytes))
+    Ok(0)
 }
 
 fn print_lines(
-    mut file: impl BufRead,
+    file: &mut (impl Read + Seek),
     num_lines: &TakeValue,
-    total_lines: i64,
+    delimiter: Delimiter,
 ) -> MyResult<()> {
-
-    if let Some(start) = get_start_index(num_lines, total_lines) {
-        let mut line_num = 0;
-        let mut buf = String::new();
-        loop {
-            let bytes = file.read_line(&mut buf)?;
-            if bytes == 0 {
-                break;
-            }
-            if line_num >= start {
-                print!("{buf}");
+    let mut out = io::stdout();
+    match *num_lines {
+        TakeNum(0) => {}
+        TakeNum(num) if num < 0 => {
+            let start = find_tail_start(file, num.unsigned_abs(), delimiter)?;
+            file.seek(SeekFrom::Start(start))?;
+            io::copy(file, &mut out)?;
+        }
+        PlusZero | TakeNum(_) => {
+            let (skip, _) = stream_window(num_lines);
+            let mut file = BufReader::new(file);
+            let mut record = Vec::new();
+            for _ in 0..skip {
+                record.clear();
+                if file.read_until(delimiter.byte(), &mut record)? == 0 {
+                    break;
+                }
             }
-            line_num += 1;
-            buf.clear();
+            io::copy(&mut file, &mut out)?;
         }
-        return Ok(())
     }
     Ok(())
 }
-
 fn print_bytes<T: Read + Seek>(
     mut file: T,
     num_bytes: &TakeValue, 
@@ -85,15 +134,245 @@ fn print_bytes<T: Read + Seek>(
 ) -> MyResult<()> {
     if let Some(start) = get_start_index(num_bytes, total_bytes) {
         file.seek(std::io::SeekFrom::Start(start))?;
-        let mut buf = Vec::new();
-        file.read_to_end(&mut buf)?;
-        if !buf.is_empty() {
-            print!("{}", String::from_utf8_lossy(&buf));
+        // A chunk at a time, as what follows +1 can be far bigger than memory
+        io::copy(&mut file, &mut io::stdout())?;
+    }
+    Ok(())
+}
+
+/// The tail of `data`, a whole file in memory.
+#[cfg_attr(not(unix), allow(dead_code))]
+fn tail_of<'a>(data: &'a [u8], config: &Config) -> &'a [u8] {
+    let delimiter = config.delimiter.byte();
+    let delimiters = || data.iter().enumerate().filter(move |(_, &byte)| byte == delimiter);
+    let start = match (&config.bytes, &config.lines) {
+        (Some(take_val), _) => get_start_index(take_val, data.len() as i64),
+        (None, TakeNum(0)) => None,
+        (None, &TakeNum(num)) if num < 0 => {
+            let num = usize::try_from(num.unsigned_abs()).unwrap_or(usize::MAX);
+            // A delimiter that ends the data doesn't start another record
+            let found = delimiters().rev().filter(|&(i, _)| i + 1 != data.len()).nth(num - 1);
+            Some(found.map_or(0, |(i, _)| i as u64 + 1))
+        }
+        (None, take_val) => match stream_window(take_val) {
+            (0, _) => Some(0),
+            (skip, _) => {
+                let skip = usize::try_from(skip).unwrap_or(usize::MAX);
+                delimiters().nth(skip - 1).map(|(i, _)| i as u64 + 1)
+            }
+        },
+    };
+    start.map_or(&[], |start| &data[start as usize..])
+}
+
+/// Prints the first K lines or bytes of `input`, for --head, counting from
+/// the start whatever the sign of K.
+fn print_head(mut input: impl BufRead, config: &Config) -> io::Result<()> {
+    let count = |take_val: &TakeValue| match *take_val {
+        PlusZero => 0,
+        TakeNum(num) => num.unsigned_abs(),
+    };
+    let mut out = io::stdout();
+    if let Some(ref take_val) = config.bytes {
+        io::copy(&mut input.take(count(take_val)), &mut out)?;
+        return Ok(());
+    }
+    let mut record = Vec::new();
+    for _ in 0..count(&config.lines) {
+        record.clear();
+        if input.read_until(config.delimiter.byte(), &mut record)? == 0 {
+            break;
         }
+        out.write_all(&record)?;
     }
     Ok(())
 }
 
+/// Prints the tail of a regular file, found by reading back from its end or
+/// with --mmap, in memory.
+fn print_file(filename: &str, file: &mut File, config: &Config) -> MyResult<()> {
+    if config.head {
+        return Ok(print_head(BufReader::new(file), config)?);
+    }
+    #[cfg(unix)]
+    if config.mmap {
+        let mapped = mapped::Mapped::new(file).map_err(|e| CoreError::io(filename, e))?;
+        io::stdout().write_all(tail_of(&mapped, config))?;
+        return Ok(());
+    }
+    match config.bytes {
+        Some(ref take_val) => {
+            let len = file.metadata().map_err(|e| CoreError::io(filename, e))?.len();
+            print_bytes(file, take_val, len as i64)
+        }
+        None => print_lines(file, &config.lines, config.delimiter),
+    }
+}
+
+/// How many records the tail of a stream skips first, and how many of the
+/// last it keeps, or None for all that are left.
+fn stream_window(take_val: &TakeValue) -> (u64, Option<usize>) {
+    match *take_val {
+        PlusZero => (0, None),
+        TakeNum(num) if num > 0 => (num as u64 - 1, None),
+        TakeNum(num) => (0, Some(num.unsigned_abs().try_into().unwrap_or(usize::MAX))),
+    }
+}
+
+/// Prints the tail of input that can only be read once, like a pipe.
+fn print_stream(input: impl BufRead, config: &Config) -> io::Result<()> {
+    if config.head {
+        return print_head(input, config);
+    }
+    let mut out = io::stdout();
+    if let Some(ref take_val) = config.bytes {
+        return tail_bytes(input, take_val, &mut out);
+    }
+    for record in TailRecords::new(input, &config.lines, config.delimiter) {
+        out.write_all(&record?)?;
+    }
+    Ok(())
+}
+
+/// Writes the tail of what `reader` reads to `out`. What follows +N is
+/// copied as it is read; the last N bytes are kept in a ring buffer until
+/// the end, as how many there are isn't known before.
+pub fn tail_bytes(
+    mut reader: impl BufRead,
+    take_val: &TakeValue,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let (skip, keep) = stream_window(take_val);
+    io::copy(&mut reader.by_ref().take(skip), &mut io::sink())?;
+    let Some(keep) = keep else {
+        io::copy(&mut reader, out)?;
+        return Ok(());
+    };
+    let mut tail = VecDeque::new();
+    loop {
+        let chunk = reader.fill_buf()?;
+        if chunk.is_empty() {
+            break;
+        }
+        let len = chunk.len();
+        tail.extend(chunk);
+        reader.consume(len);
+        tail.drain(..tail.len().saturating_sub(keep));
+    }
+    let (front, back) = tail.as_slices();
+    out.write_all(front)?;
+    out.write_all(back)
+}
+
+/// The lines at the tail of `reader`, without their newlines, as
+/// [`BufRead::lines`] gives them. The lines after +N come as they are read,
+/// but the last N only once all of it has been.
+pub fn tail_lines<R: BufRead>(
+    reader: R,
+    take_val: &TakeValue,
+) -> impl Iterator<Item = io::Result<String>> {
+    let delimiter = Delimiter::Newline;
+    TailRecords::new(reader, take_val, delimiter).map(move |line| {
+        // The same error read_line gives
+        let line = String::from_utf8(line?).map_err(|_| {
+            io::Error::new(io::ErrorKind::InvalidData, "stream did not contain valid UTF-8")
+        })?;
+        Ok(delimiter.strip(&line).to_string())
+    })
+}
+
+/// The records at the tail of a reader, each with its delimiter if it had
+/// one, from a ring buffer of the last ones. They're kept as bytes, as the
+/// input needn't be text.
+struct TailRecords<R> {
+    reader: R,
+    delimiter: Delimiter,
+    /// How many records are still to be skipped
+    skip: u64,
+    /// How many of the last records to keep, or None to pass them all on
+    keep: Option<usize>,
+    /// The last records, once everything has been read
+    tail: Option<VecDeque<Vec<u8>>>,
+}
+
+impl<R: BufRead> TailRecords<R> {
+    fn new(reader: R, take_val: &TakeValue, delimiter: Delimiter) -> Self {
+        let (skip, keep) = stream_window(take_val);
+        TailRecords { reader, delimiter, skip, keep, tail: None }
+    }
+
+    fn read(&mut self) -> io::Result<Option<Vec<u8>>> {
+        let mut record = Vec::new();
+        let len = self.reader.read_until(self.delimiter.byte(), &mut record)?;
+        Ok((len > 0).then_some(record))
+    }
+
+    /// Reads everything, keeping the last `keep` records.
+    fn fill(&mut self, keep: usize) -> io::Result<VecDeque<Vec<u8>>> {
+        let mut tail = VecDeque::new();
+        if keep == 0 {
+            return Ok(tail);
+        }
+        while let Some(record) = self.read()? {
+            if tail.len() == keep {
+                tail.pop_front();
+            }
+            tail.push_back(record);
+        }
+        Ok(tail)
+    }
+
+    /// Ends the records after an error.
+    fn fail(&mut self, e: io::Error) -> io::Error {
+        self.keep = Some(0);
+        self.tail = Some(VecDeque::new());
+        e
+    }
+}
+
+impl<R: BufRead> Iterator for TailRecords<R> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Some(keep) = self.keep else {
+            return loop {
+                match self.read() {
+                    Ok(Some(_)) if self.skip > 0 => self.skip -= 1,
+                    Ok(record) => break record.map(Ok),
+                    Err(e) => break Some(Err(self.fail(e))),
+                }
+            };
+        };
+        if self.tail.is_none() {
+            match self.fill(keep) {
+                Ok(tail) => self.tail = Some(tail),
+                Err(e) => return Some(Err(self.fail(e))),
+            }
+        }
+        self.tail.as_mut()?.pop_front().map(Ok)
+    }
+}
+
+/// What to print the tail of: a regular file, whose tail can be found by
+/// counting it and reading it again, or something that can only be read
+/// once, like stdin or a pipe.
+enum Input {
+    File(File),
+    Stream(Box<dyn BufRead>),
+}
+
+fn open_input(filename: &str) -> io::Result<Input> {
+    if filename == STDIN {
+        return Ok(Input::Stream(Box::new(io::stdin().lock())));
+    }
+    let file = File::open(filename)?;
+    if file.metadata()?.is_file() {
+        Ok(Input::File(file))
+    } else {
+        Ok(Input::Stream(Box::new(BufReader::new(file))))
+    }
+}
+
 fn get_start_index(take_val: &TakeValue, total: i64) -> Option<u64> {
     match take_val {
         PlusZero => if total > 0 { Some(0) } else { None },
@@ -108,101 +387,377 @@ fn get_start_index(take_val: &TakeValue, total: i64) -> Option<u64> {
     }
 }
 
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust tail")]
+struct Args {
+    /// Input file(s)
+    #[arg(value_name = "FILES", default_value = "-", conflicts_with = "files_from")]
+    files: Vec<String>,
+
+    #[command(flatten)]
+    files_from: FilesFromArgs,
+
+    /// Output last K lines
+    #[arg(
+        short = 'n',
+        long,
+        value_name = "LINES",
+        default_value = "-10",
+        allow_hyphen_values = true
+    )]
+    lines: String,
+
+    /// Output last K bytes
+    #[arg(
+        short = 'c',
+        long,
+        value_name = "BYTES",
+        conflicts_with = "lines",
+        allow_hyphen_values = true
+    )]
+    bytes: Option<String>,
+
+    /// Suppress printing of headers
+    #[arg(short = 'q', long, visible_alias = "silent", overrides_with = "verbose")]
+    quiet: bool,
+
+    /// Print headers even for a single file
+    #[arg(short = 'v', long, overrides_with = "quiet")]
+    verbose: bool,
+
+    /// Keep printing what is appended to the files, until interrupted
+    #[arg(
+        short = 'f',
+        long,
+        value_name = "HOW",
+        value_enum,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "descriptor"
+    )]
+    follow: Option<FollowBy>,
+
+    /// Same as --follow=name --retry
+    #[arg(short = 'F')]
+    follow_name_retry: bool,
+
+    /// Keep trying to open the files that can't be opened, when following
+    #[arg(long)]
+    retry: bool,
+
+    /// Seconds to wait between looks at the followed files
+    #[arg(short = 's', long, value_name = "SECS", default_value = "1.0")]
+    sleep_interval: String,
+
+    /// Map regular files into memory rather than reading them, on unix
+    #[arg(long)]
+    mmap: bool,
+
+    /// Print the first K lines or bytes instead, as headr does
+    #[arg(long, conflicts_with_all = ["follow", "follow_name_retry"])]
+    head: bool,
+
+    #[command(flatten)]
+    delimiter: DelimiterArgs,
+}
+
 pub fn get_args() -> MyResult<Config> {
-    let matches = App::new("tailr")
-        .about("Rust tail")
-        .author("OFFBLACK")
-        .version("0.1.0")
-        .arg(
-            Arg::with_name("files")
-                .help("Input file(s)")
-                .multiple(true)
-                .required(true)
-                .value_name("FILES")
-        )
-        .arg(
-            Arg::with_name("lines")
-                .help("Output last K lines")
-                .short("n")
-                .long("lines")
-                .default_value("-10")
-                .value_name("LINES")
-        )
-        .arg(
-            Arg::with_name("bytes")
-                .help("Output last K bytes")
-                .short("c")
-                .long("bytes")
-                .value_name("BYTES")
-                .conflicts_with("lines")
-        )
-        .arg(
-            Arg::with_name("quiet")
-                .help("Suppress printing of headers")
-                .short("q")
-                .long("quiet")
-        )
-        .get_matches();
-
-    let lines = matches
-        .value_of("lines")
-        .map(parse_num)
-        .unwrap()
-        .map_err(|e| format!("illegal line count -- {e}"))?;
-        
+    let args: Args = parse_args();
+
+    let lines = parse_num(&args.lines)
+        .map_err(|e| CoreError::Parse(format!("illegal line count -- {e}")))?;
 
-    let bytes = matches
-        .value_of("bytes")
+    let bytes = args
+        .bytes
+        .as_deref()
         .map(parse_num)
         .transpose()
-        .map_err(|e| format!("illegal byte count -- {e}"))?;
+        .map_err(|e| CoreError::Parse(format!("illegal byte count -- {e}")))?;
+
+    let sleep_interval = args
+        .sleep_interval
+        .parse::<f64>()
+        .ok()
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .ok_or_else(|| {
+            CoreError::Parse(format!("invalid number of seconds: '{}'", args.sleep_interval))
+        })?;
 
     Ok(Config {
-        files: matches.values_of_lossy("files").unwrap(),
+        files: args.files_from.files(args.files)?,
         lines,
         bytes,
-        quiet: matches.is_present("quiet")
+        quiet: args.quiet,
+        verbose: args.verbose,
+        follow: if args.follow_name_retry { Some(FollowBy::Name) } else { args.follow },
+        retry: args.retry || args.follow_name_retry,
+        sleep_interval,
+        mmap: args.mmap,
+        head: args.head,
+        delimiter: args.delimiter.delimiter(),
     })
 }
 
-pub fn run(config: Config) -> MyResult<()> {
-    for (id, filename) in config.files.iter().enumerate() {
-        match File::open(&filename) {
-            Err(e) => eprintln!("{filename}: {e}"),
-            Ok(file) => {
-                let (total_lines, total_bytes) = count_lines_bytes(&filename)?;
-                let file = BufReader::new(file);
-                if !config.quiet && config.files.len() > 1 {
-                    if id == 0 {
-                        println!("==> {} <==", filename);
+/// A file being followed with -f, and where in the list it was. There's no
+/// file while none with its name can be opened, with --retry or by name.
+struct Followed<'a> {
+    id: usize,
+    name: &'a str,
+    file: Option<File>,
+}
+
+/// What became of a followed file since the last look.
+enum Change {
+    Gone(io::Error),
+    Appeared,
+    Replaced,
+}
+
+/// Which file `meta` is about, to tell when a name leads to another one.
+/// Only unix can tell, so elsewhere a replaced file looks truncated.
+#[cfg(unix)]
+fn identity(meta: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((meta.dev(), meta.ino()))
+}
+
+#[cfg(not(unix))]
+fn identity(_meta: &Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+impl Followed<'_> {
+    /// Whether a file missing until now can be opened, and by name, whether
+    /// the name has gone or leads to another file than the open one.
+    fn change(&self, by: FollowBy) -> Option<Change> {
+        match (&self.file, fs::metadata(self.name)) {
+            (None, Ok(_)) => Some(Change::Appeared),
+            (None, Err(_)) => None,
+            (Some(_), _) if by == FollowBy::Descriptor => None,
+            (Some(_), Err(e)) => Some(Change::Gone(e)),
+            (Some(file), Ok(meta)) => {
+                let open = file.metadata().ok()?;
+                (identity(&open) != identity(&meta)).then_some(Change::Replaced)
+            }
+        }
+    }
+}
+
+/// Why something failed, as GNU tail words it, without the "(os error 2)"
+/// that Rust adds.
+fn reason(err: &io::Error) -> String {
+    let reason = io_error(err);
+    match reason.rfind(" (os error ") {
+        Some(i) if reason.ends_with(')') => reason[..i].to_string(),
+        _ => reason,
+    }
+}
+
+/// Prints `message` about `filename`, with `error` for any `{error}` in it,
+/// as GNU tail does.
+fn print_notice(message: &'static str, filename: &str, error: &str) {
+    eprintln!("tailr: {}", tr_fmt(message, &[("file", &filename), ("error", &error)]));
+}
+
+/// Prints `message` about `filename` and why it failed.
+fn print_error(message: &'static str, filename: &str, err: &io::Error) {
+    print_notice(message, filename, &reason(err));
+}
+
+/// Prints the header naming a file, after a blank line unless it's the first.
+fn print_header(filename: &str, first: bool) {
+    let name = if filename == STDIN { tr("standard input") } else { filename };
+    let header = tr_fmt("==> {file} <==", &[("file", &name)]);
+    if first {
+        println!("{header}");
+    } else {
+        println!("\n{header}");
+    }
+}
+
+/// Where what is appended to the followed files goes.
+struct Follower {
+    out: io::Stdout,
+    headers: bool,
+    /// The file whose header was printed last
+    last: Option<usize>,
+    buf: Vec<u8>,
+}
+
+impl Follower {
+    /// Prints what was written to the file since the last look, under a
+    /// header when it comes from another file than the last. A file that
+    /// gets shorter was truncated, and is read again from the start.
+    fn print_appended(&mut self, followed: &mut Followed) -> MyResult<()> {
+        let name = followed.name;
+        let Some(file) = &mut followed.file else {
+            return Ok(());
+        };
+        let pos = file.stream_position()?;
+        let len = file.metadata().map_err(|e| CoreError::io(name, e))?.len();
+        if len < pos {
+            print_notice("{file}: file truncated", name, "");
+            file.seek(SeekFrom::Start(0))?;
+        }
+        self.buf.clear();
+        file.read_to_end(&mut self.buf).map_err(|e| CoreError::io(name, e))?;
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+        if self.headers && self.last != Some(followed.id) {
+            print_header(name, self.last.is_none());
+            self.last = Some(followed.id);
+        }
+        self.out.write_all(&self.buf)?;
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// Prints whatever is written to the files from now on, looking every
+/// `interval`, until interrupted.
+/// By name, a file that is removed or replaced, as when a log is rotated,
+/// is read to its end, and whatever file then has its name is followed,
+/// from its start.
+fn follow(
+    mut files: Vec<Followed>,
+    by: FollowBy,
+    interval: Duration,
+    follower: &mut Follower,
+) -> MyResult<()> {
+    loop {
+        thread::sleep(interval);
+        for followed in &mut files {
+            let name = followed.name;
+            match followed.change(by) {
+                None => {}
+                Some(Change::Gone(e)) => {
+                    follower.print_appended(followed)?;
+                    followed.file = None;
+                    print_error("'{file}' has become inaccessible: {error}", name, &e);
+                }
+                Some(change) => {
+                    // It may have gone again already
+                    let Ok(file) = File::open(name) else {
+                        continue;
+                    };
+                    let notice = if let Change::Replaced = change {
+                        follower.print_appended(followed)?;
+                        "'{file}' has been replaced; following new file"
                     } else {
-                        println!("\n==> {} <==", filename);
-                    }
+                        "'{file}' has appeared; following new file"
+                    };
+                    print_notice(notice, name, "");
+                    followed.file = Some(file);
+                }
+            }
+            follower.print_appended(followed)?;
+        }
+    }
+}
+
+/// Prints the tail of every input and returns the exit status: 1 if any
+/// file failed to open or read, after going on with the rest. With -f, goes
+/// on to follow the regular files it could open, and with --retry, the ones
+/// it couldn't as well.
+pub fn run(config: Config) -> MyResult<i32> {
+    let mut status = 0;
+    // GNU tail prints nothing at all of a tail of 0, not even the headers,
+    // unless it goes on to follow the files
+    let empty = match &config.bytes {
+        Some(take_val) => *take_val == TakeNum(0),
+        None => config.lines == TakeNum(0),
+    };
+    let headers = !config.quiet
+        && (config.verbose || config.files.len() > 1)
+        && (config.head || !empty || config.follow.is_some());
+    let mut followed = vec![];
+    let mut last = None;
+    for (id, filename) in config.files.iter().enumerate() {
+        match open_input(filename) {
+            Err(e) => {
+                print_error("cannot open '{file}' for reading: {error}", filename, &e);
+                status = 1;
+                if config.follow.is_some() && config.retry {
+                    followed.push(Followed { id, name: filename, file: None });
+                }
+            }
+            Ok(Input::Stream(input)) => {
+                if headers {
+                    print_header(filename, last.is_none());
                 }
-                if let Some(ref take_val) = config.bytes {
-                    print_bytes(file, &take_val, total_bytes)?;
-                } else {
-                    print_lines(file, &config.lines, total_lines)?;
+                last = Some(id);
+                if let Err(e) = print_stream(input, &config) {
+                    io::stdout().flush()?;
+                    print_error("error reading '{file}': {error}", filename, &e);
+                    status = 1;
+                }
+            }
+            Ok(Input::File(mut file)) => {
+                if headers {
+                    print_header(filename, last.is_none());
+                }
+                last = Some(id);
+                print_file(filename, &mut file, &config)?;
+                if config.follow.is_some() {
+                    // Not all of the file is read when its tail is empty
+                    file.seek(SeekFrom::End(0))?;
+                    followed.push(Followed { id, name: filename, file: Some(file) });
                 }
             }
         }
     }
-    Ok(())
+    if let (Some(by), false) = (config.follow, followed.is_empty()) {
+        let mut follower = Follower { out: io::stdout(), headers, last, buf: Vec::new() };
+        follower.out.flush()?;
+        follow(followed, by, config.sleep_interval, &mut follower)?;
+    }
+    Ok(status)
+}
+
+/// Entry points for the cargo-fuzz targets in `../fuzz`, which can't reach
+/// private items. None of them may panic, whatever the input.
+#[cfg(feature = "fuzzing")]
+#[doc(hidden)]
+pub mod fuzzing {
+    pub fn parse_num(val: &str, total: i64) {
+        if let Ok(num) = super::parse_num(val) {
+            let _ = super::get_start_index(&num, total.max(0));
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{count_lines_bytes, parse_num, TakeValue::*};
+    use super::{
+        find_tail_start, get_start_index, parse_num, stream_window, tail_bytes, tail_lines,
+        CoreError, Delimiter, TakeValue::*, CHUNK,
+    };
+    use std::io::Cursor;
+    use proptest::prelude::*;
 
     #[test]
-    fn test_count_lines_bytes() {
-        let res = count_lines_bytes("tests/inputs/one.txt");
-        assert!(res.is_ok());
-        assert_eq!(res.unwrap(), (1, 24));
+    fn test_find_tail_start() {
+        let start = |text: &str, num, delimiter| {
+            find_tail_start(&mut Cursor::new(text), num, delimiter).unwrap()
+        };
+        assert_eq!(start("a\nb\nc\n", 1, Delimiter::Newline), 4);
+        assert_eq!(start("a\nb\nc\n", 2, Delimiter::Newline), 2);
+        assert_eq!(start("a\nb\nc\n", 3, Delimiter::Newline), 0);
+        assert_eq!(start("a\nb\nc\n", 4, Delimiter::Newline), 0);
+        // The last record needn't end with the delimiter
+        assert_eq!(start("a\nb\nc", 1, Delimiter::Newline), 4);
+        assert_eq!(start("a\n\n", 1, Delimiter::Newline), 2);
+        assert_eq!(start("", 1, Delimiter::Newline), 0);
 
-        let res = count_lines_bytes("tests/inputs/ten.txt");
-        assert!(res.is_ok());
-        assert_eq!(res.unwrap(), (10, 49));
+        // Without a NUL, the whole file is one record
+        assert_eq!(start("a\nb\n", 1, Delimiter::Nul), 0);
+        assert_eq!(start("a\0b\0", 1, Delimiter::Nul), 2);
+
+        // Records found across chunks
+        let long = format!("a\n{}\nb\n", "x".repeat(CHUNK as usize * 2));
+        assert_eq!(start(&long, 2, Delimiter::Newline), 2);
+        assert_eq!(start(&long, 3, Delimiter::Newline), 0);
     }
 
     #[test]
@@ -256,7 +811,76 @@ mod tests {
 
         // Any non-integer string is invalid
         let res = parse_num("foo");
-        assert!(res.is_err());
+        assert!(matches!(res, Err(CoreError::Parse(_))));
         assert_eq!(res.unwrap_err().to_string(), "foo");
     }
+
+    #[test]
+    fn test_stream_window() {
+        assert_eq!(stream_window(&PlusZero), (0, None));
+        assert_eq!(stream_window(&TakeNum(1)), (0, None));
+        assert_eq!(stream_window(&TakeNum(3)), (2, None));
+        assert_eq!(stream_window(&TakeNum(0)), (0, Some(0)));
+        assert_eq!(stream_window(&TakeNum(-3)), (0, Some(3)));
+    }
+
+    #[test]
+    fn test_tail_lines() {
+        let tail = |text: &str, num| {
+            tail_lines(Cursor::new(text), &num).collect::<Result<Vec<_>, _>>().unwrap()
+        };
+        assert_eq!(tail("a\nb\nc\n", TakeNum(-2)), ["b", "c"]);
+        assert_eq!(tail("a\nb\nc", TakeNum(-5)), ["a", "b", "c"]);
+        assert_eq!(tail("a\nb\nc\n", TakeNum(2)), ["b", "c"]);
+        assert_eq!(tail("a\nb\nc\n", PlusZero), ["a", "b", "c"]);
+        assert!(tail("a\nb\nc\n", TakeNum(0)).is_empty());
+        assert!(tail("", TakeNum(-1)).is_empty());
+
+        // As with lines(), only the line that isn't UTF-8 is an error
+        let mut lines = tail_lines(Cursor::new(b"a\n\xff\nb\n"), &TakeNum(1));
+        assert_eq!(lines.next().unwrap().unwrap(), "a");
+        assert!(lines.next().unwrap().is_err());
+        assert_eq!(lines.next().unwrap().unwrap(), "b");
+        assert!(lines.next().is_none());
+        let mut lines = tail_lines(Cursor::new(b"a\n\xff\nb\n"), &TakeNum(-1));
+        assert_eq!(lines.next().unwrap().unwrap(), "b");
+        assert!(lines.next().is_none());
+    }
+
+    #[test]
+    fn test_tail_bytes() {
+        let tail = |bytes: &[u8], num| {
+            let mut out = vec![];
+            tail_bytes(Cursor::new(bytes), &num, &mut out).unwrap();
+            out
+        };
+        assert_eq!(tail(b"abc\xffde", TakeNum(-3)), b"\xffde");
+        assert_eq!(tail(b"abcde", TakeNum(2)), b"bcde");
+        assert_eq!(tail(b"abcde", TakeNum(-9)), b"abcde");
+        assert_eq!(tail(b"abcde", PlusZero), b"abcde");
+        assert!(tail(b"abcde", TakeNum(0)).is_empty());
+        assert!(tail(b"abcde", TakeNum(9)).is_empty());
+    }
+
+    proptest! {
+        #[test]
+        fn parse_num_never_panics(val in "[+-]?[0-9]{0,25}|\\PC*") {
+            let _ = parse_num(&val);
+        }
+
+        #[test]
+        fn parse_num_signs(n in 0..=i64::MAX) {
+            prop_assert_eq!(parse_num(&n.to_string()).unwrap(), TakeNum(-n));
+            prop_assert_eq!(parse_num(&format!("-{n}")).unwrap(), TakeNum(-n));
+            let plus = if n == 0 { PlusZero } else { TakeNum(n) };
+            prop_assert_eq!(parse_num(&format!("+{n}")).unwrap(), plus);
+        }
+
+        #[test]
+        fn start_index_is_within_input(num in any::<i64>(), total in 0..=i64::MAX) {
+            if let Some(start) = get_start_index(&TakeNum(num), total) {
+                prop_assert!(start < total as u64);
+            }
+        }
+    }
 }
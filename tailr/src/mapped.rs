@@ -0,0 +1,59 @@
+//! A file mapped into memory, for --mmap.
+
+use std::{fs::File, io, ops::Deref, os::unix::io::AsRawFd, ptr, slice};
+
+/// The bytes of a file, mapped rather than read. Reading them faults if
+/// the file is truncated meanwhile, which is why --mmap is left to ask for.
+pub struct Mapped {
+    ptr: *mut libc::c_void,
+    len: usize,
+}
+
+impl Mapped {
+    pub fn new(file: &File) -> io::Result<Self> {
+        let len = usize::try_from(file.metadata()?.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "file is too large to map"))?;
+        // Nothing can map no bytes
+        if len == 0 {
+            return Ok(Mapped { ptr: ptr::null_mut(), len });
+        }
+        // SAFETY: a new read-only mapping of the open file, where the kernel
+        // chooses, which touches no memory of ours
+        let ptr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ,
+                libc::MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Mapped { ptr, len })
+    }
+}
+
+impl Deref for Mapped {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        if self.len == 0 {
+            return &[];
+        }
+        // SAFETY: mmap gave `len` readable bytes at `ptr`, mapped until drop
+        unsafe { slice::from_raw_parts(self.ptr.cast(), self.len) }
+    }
+}
+
+impl Drop for Mapped {
+    fn drop(&mut self) {
+        if self.len > 0 {
+            // SAFETY: `ptr` and `len` are the mapping new made, and nothing
+            // borrows it past drop
+            unsafe { libc::munmap(self.ptr, self.len) };
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use clap::{Parser, ValueEnum};
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, ErrorKind, Read, Write},
+};
+
+use coreutils_common::{ignore_interrupts, parse_args, reset_sigpipe, CoreError, MyResult};
+
+#[derive(Debug)]
+pub struct Config {
+    files: Vec<String>,
+    append: bool,
+    ignore_interrupts: bool,
+    output_error: Option<OutputError>,
+}
+
+/// What to do when writing to an output fails, as in GNU tee. The "nopipe"
+/// modes say nothing when the output was a pipe that closed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum OutputError {
+    /// Report the error and keep writing to the other outputs
+    Warn,
+    /// Like warn, but silently drop a closed pipe
+    WarnNopipe,
+    /// Report the error and stop
+    Exit,
+    /// Like exit, but silently drop a closed pipe
+    ExitNopipe,
+}
+
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust tee")]
+struct Args {
+    /// Output file(s)
+    #[arg(value_name = "FILE")]
+    files: Vec<String>,
+
+    /// Append to the files rather than overwriting them
+    #[arg(short = 'a', long)]
+    append: bool,
+
+    /// Ignore interrupt signals
+    #[arg(short = 'i', long)]
+    ignore_interrupts: bool,
+
+    /// Handle write errors by MODE (default: warn-nopipe) instead of dying on a closed pipe
+    #[arg(
+        long,
+        value_name = "MODE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "warn-nopipe"
+    )]
+    output_error: Option<OutputError>,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let args: Args = parse_args();
+
+    Ok(Config {
+        files: args.files,
+        append: args.append,
+        ignore_interrupts: args.ignore_interrupts,
+        output_error: args.output_error,
+    })
+}
+
+/// An output and the name its errors are reported under.
+struct Output {
+    name: String,
+    writer: Box<dyn Write>,
+}
+
+/// Copies stdin to stdout and every file, and returns the exit status: 1 if
+/// any file couldn't be opened or written.
+pub fn run(config: Config) -> MyResult<i32> {
+    // Without --output-error, a closed pipe stops teer like any other tool;
+    // with it, the broken pipe is an error that the mode decides about
+    if config.output_error.is_none() {
+        reset_sigpipe();
+    }
+    if config.ignore_interrupts {
+        ignore_interrupts();
+    }
+
+    let mut status = 0;
+    let mut outputs = vec![Some(Output {
+        name: "standard output".to_string(),
+        writer: Box::new(io::stdout()),
+    })];
+    for name in &config.files {
+        let file = if config.append {
+            OpenOptions::new().append(true).create(true).open(name)
+        } else {
+            File::create(name)
+        };
+        match file {
+            Ok(file) => outputs.push(Some(Output { name: name.clone(), writer: Box::new(file) })),
+            Err(e) => {
+                eprintln!("{}", CoreError::io(name, e));
+                status = 1;
+            }
+        }
+    }
+
+    let mut stdin = io::stdin().lock();
+    let mut buf = [0; 8192];
+    while outputs.iter().any(Option::is_some) {
+        let len = match stdin.read(&mut buf) {
+            Ok(0) => break,
+            Ok(len) => len,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(CoreError::io("standard input", e)),
+        };
+        for slot in &mut outputs {
+            let Some(output) = slot else { continue };
+            let Err(e) = output.writer.write_all(&buf[..len]).and_then(|_| output.writer.flush())
+            else {
+                continue;
+            };
+            let err = CoreError::io(&output.name, e);
+            if failed(&err, config.output_error) {
+                if matches!(config.output_error, Some(OutputError::Exit | OutputError::ExitNopipe)) {
+                    return Err(err);
+                }
+                eprintln!("{err}");
+                status = 1;
+            }
+            // Nothing more is written to a failed output
+            *slot = None;
+        }
+    }
+    Ok(status)
+}
+
+/// Whether a write error counts against the exit status and is reported.
+/// A closed pipe only does in the modes that don't say "nopipe"; without a
+/// mode, SIGPIPE normally ends teer before it sees the error at all.
+fn failed(err: &CoreError, mode: Option<OutputError>) -> bool {
+    let closed_pipe =
+        matches!(err, CoreError::Io { source, .. } if source.kind() == ErrorKind::BrokenPipe);
+    !closed_pipe || matches!(mode, Some(OutputError::Warn | OutputError::Exit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{failed, OutputError::*};
+    use coreutils_common::CoreError;
+    use std::io::{Error, ErrorKind};
+
+    #[test]
+    fn test_failed() {
+        let pipe = || CoreError::io("out", Error::from(ErrorKind::BrokenPipe));
+        let full = || CoreError::io("out", Error::other("No space left on device"));
+
+        for mode in [None, Some(Warn), Some(WarnNopipe), Some(Exit), Some(ExitNopipe)] {
+            assert!(failed(&full(), mode));
+        }
+        assert!(failed(&pipe(), Some(Warn)));
+        assert!(failed(&pipe(), Some(Exit)));
+        assert!(!failed(&pipe(), Some(WarnNopipe)));
+        assert!(!failed(&pipe(), Some(ExitNopipe)));
+        assert!(!failed(&pipe(), None));
+    }
+}
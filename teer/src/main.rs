@@ -0,0 +1,9 @@
+fn main() {
+    match teer::get_args().and_then(teer::run) {
+        Ok(status) => std::process::exit(status),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(e.exit_code());
+        }
+    }
+}
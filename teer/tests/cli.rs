@@ -0,0 +1,117 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "teer";
+const INPUT: &str = "The quick brown fox\njumps over the lazy dog.\n";
+
+// --------------------------------------------------
+#[test]
+fn copies_to_stdout_and_files() -> TestResult {
+    let dir = TempDir::new()?;
+    let (out1, out2) = (dir.path().join("out1"), dir.path().join("out2"));
+    Command::cargo_bin(PRG)?
+        .args([&out1, &out2])
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(INPUT);
+    assert_eq!(fs::read_to_string(&out1)?, INPUT);
+    assert_eq!(fs::read_to_string(&out2)?, INPUT);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn stdout_only() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .write_stdin(INPUT)
+        .assert()
+        .success()
+        .stdout(INPUT);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn overwrites_or_appends() -> TestResult {
+    let dir = TempDir::new()?;
+    let out = dir.path().join("out");
+    fs::write(&out, "old\n")?;
+    Command::cargo_bin(PRG)?.arg(&out).write_stdin("new\n").assert().success();
+    assert_eq!(fs::read_to_string(&out)?, "new\n");
+
+    Command::cargo_bin(PRG)?
+        .args(["-a".as_ref(), out.as_os_str()])
+        .write_stdin(INPUT)
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(&out)?, format!("new\n{INPUT}"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn skips_bad_file() -> TestResult {
+    let dir = TempDir::new()?;
+    let bad = dir.path().join("no/such/dir");
+    let good = dir.path().join("good");
+    Command::cargo_bin(PRG)?
+        .args([&bad, &good])
+        .write_stdin(INPUT)
+        .assert()
+        .code(1)
+        .stdout(INPUT)
+        .stderr(predicate::str::starts_with(format!("{}: ", bad.display())));
+    assert_eq!(fs::read_to_string(&good)?, INPUT);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_output_error() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .arg("--output-error=sometimes")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "invalid value 'sometimes' for '--output-error[=<MODE>]'",
+        ));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[cfg(target_os = "linux")]
+#[test]
+fn output_error_modes() -> TestResult {
+    let dir = TempDir::new()?;
+    let good = dir.path().join("good");
+    let good = good.to_str().unwrap();
+
+    // A full disk is reported in every mode; warn keeps going
+    for args in [&[][..], &["--output-error"], &["--output-error=warn"]] {
+        Command::cargo_bin(PRG)?
+            .args(args)
+            .args(["/dev/full", good])
+            .write_stdin(INPUT)
+            .assert()
+            .code(1)
+            .stdout(INPUT)
+            .stderr(predicate::str::starts_with("/dev/full: "));
+        assert_eq!(fs::read_to_string(good)?, INPUT);
+    }
+
+    // exit stops at the first error, before the outputs after it
+    Command::cargo_bin(PRG)?
+        .args(["--output-error=exit", "/dev/full", good])
+        .write_stdin(INPUT)
+        .assert()
+        .code(1)
+        .stdout(INPUT)
+        .stderr(predicate::str::starts_with("/dev/full: "));
+    assert_eq!(fs::read_to_string(good)?, "");
+    Ok(())
+}
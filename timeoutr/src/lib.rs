@@ -0,0 +1,185 @@
+//! timeoutr runs a command until it ends or its time is up, with the
+//! process group and signal handling of [`coreutils_common::job`], so it is
+//! only built for Unix.
+#![cfg(unix)]
+
+use clap::Parser;
+use std::{io, process::Command, time::Duration};
+
+use coreutils_common::{
+    job::{self, Exit, Job, Signal},
+    try_parse_args, CoreError, MyResult,
+};
+
+/// The exit status when the command ran out of time
+pub const TIMED_OUT: i32 = 124;
+/// The exit status when timeoutr itself failed
+pub const FAILED: i32 = 125;
+/// The exit status when the command was found but couldn't be run
+const CANNOT_RUN: i32 = 126;
+/// The exit status when the command wasn't found
+const NOT_FOUND: i32 = 127;
+
+#[derive(Debug)]
+pub struct Config {
+    /// None for no limit
+    duration: Option<Duration>,
+    signal: Signal,
+    kill_after: Option<Duration>,
+    preserve_status: bool,
+    command: Vec<String>,
+}
+
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust timeout")]
+struct Args {
+    /// How long the command may run: seconds, or a number followed by s, m,
+    /// h or d; 0 for no limit
+    #[arg(value_name = "DURATION")]
+    duration: String,
+
+    /// The command to run, and its arguments
+    #[arg(
+        value_name = "COMMAND",
+        required = true,
+        trailing_var_arg = true,
+        allow_hyphen_values = true
+    )]
+    command: Vec<String>,
+
+    /// The signal to send when the time is up, as a name like TERM or KILL,
+    /// or a number
+    #[arg(short = 's', long, value_name = "SIGNAL", default_value = "TERM")]
+    signal: String,
+
+    /// Send KILL if the command is still running this long after the
+    /// first signal
+    #[arg(short = 'k', long, value_name = "DURATION")]
+    kill_after: Option<String>,
+
+    /// Exit with the command's status even when it ran out of time
+    #[arg(long)]
+    preserve_status: bool,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    // Usage errors exit with FAILED, like the other failures of timeoutr
+    let args: Args = try_parse_args().unwrap_or_else(|e| {
+        if e.use_stderr() {
+            let _ = e.print();
+            std::process::exit(FAILED);
+        }
+        e.exit()
+    });
+
+    Ok(Config {
+        duration: parse_duration(&args.duration)?,
+        signal: Signal::parse(&args.signal)
+            .ok_or_else(|| CoreError::Parse(format!("'{}': invalid signal", args.signal)))?,
+        kill_after: args.kill_after.as_deref().map(parse_duration).transpose()?.flatten(),
+        preserve_status: args.preserve_status,
+        command: args.command,
+    })
+}
+
+/// The time given by `val`, like `10`, `1.5m` or `inf`, or None for no limit.
+fn parse_duration(val: &str) -> MyResult<Option<Duration>> {
+    let (number, unit) = match val.strip_suffix(['s', 'm', 'h', 'd']) {
+        Some(number) => (number, &val[number.len()..]),
+        None => (val, "s"),
+    };
+    let scale = match unit {
+        "m" => 60.0,
+        "h" => 60.0 * 60.0,
+        "d" => 24.0 * 60.0 * 60.0,
+        _ => 1.0,
+    };
+    let secs = number
+        .parse::<f64>()
+        .ok()
+        .filter(|secs| *secs >= 0.0)
+        .ok_or_else(|| CoreError::Parse(format!("invalid time interval '{val}'")))?;
+    // Too long to ever end is the same as no limit
+    Ok(Duration::try_from_secs_f64(secs * scale).ok().filter(|duration| !duration.is_zero()))
+}
+
+/// Runs the command and returns its exit status, or TIMED_OUT if its time
+/// ran out. A signal that ended it ends timeoutr too, as does the KILL
+/// sent after -k; either way, the command is run in a process group of its
+/// own so everything it starts is signalled with it. SIGINT, SIGTERM and
+/// the like sent to timeoutr are passed on.
+pub fn run(config: Config) -> MyResult<i32> {
+    let passed_on = [Signal::HUP, Signal::INT, Signal::QUIT, Signal::TERM, config.signal];
+    job::catch(&[&[Signal::ALRM][..], &passed_on].concat())?;
+
+    let mut command = Command::new(&config.command[0]);
+    command.args(&config.command[1..]);
+    let mut child = match Job::spawn(&mut command) {
+        Ok(child) => child,
+        Err(e) => {
+            let status = if e.kind() == io::ErrorKind::NotFound { NOT_FOUND } else { CANNOT_RUN };
+            let what = format!("failed to run command '{}'", config.command[0]);
+            eprintln!("{}", CoreError::io(what, e));
+            return Ok(status);
+        }
+    };
+    if let Some(duration) = config.duration {
+        job::alarm_after(duration)?;
+    }
+
+    let mut signal = config.signal;
+    let mut kill_after = config.kill_after;
+    let mut timed_out = false;
+    let mut killed = false;
+    let exit = loop {
+        if let Some(exit) = child.wait()? {
+            break exit;
+        }
+        let Some(mut caught) = job::caught() else {
+            continue;
+        };
+        if caught == Signal::ALRM {
+            timed_out = true;
+            caught = signal;
+        }
+        // The next alarm, if any, is for the KILL
+        if let Some(kill_after) = kill_after.take() {
+            signal = Signal::KILL;
+            job::alarm_after(kill_after)?;
+        }
+        killed |= caught == Signal::KILL;
+        child.signal(caught)?;
+    };
+
+    match exit {
+        _ if timed_out && !killed && !config.preserve_status => Ok(TIMED_OUT),
+        Exit::Code(code) => Ok(code),
+        Exit::Signal(signal) => job::die_by(signal),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_duration;
+    use std::time::Duration;
+
+    #[test]
+    fn test_parse_duration() {
+        let parse = |val| parse_duration(val).unwrap();
+        assert_eq!(parse("10"), Some(Duration::from_secs(10)));
+        assert_eq!(parse("0.5s"), Some(Duration::from_millis(500)));
+        assert_eq!(parse(".5m"), Some(Duration::from_secs(30)));
+        assert_eq!(parse("2h"), Some(Duration::from_secs(7200)));
+        assert_eq!(parse("1d"), Some(Duration::from_secs(86400)));
+        assert_eq!(parse("1e-1"), Some(Duration::from_millis(100)));
+        // No limit
+        assert_eq!(parse("0"), None);
+        assert_eq!(parse("inf"), None);
+        assert_eq!(parse("1e300d"), None);
+
+        for val in ["", "x", "-1", "1x", "s", "1ss", "nan"] {
+            let err = parse_duration(val).unwrap_err();
+            assert_eq!(err.to_string(), format!("invalid time interval '{val}'"));
+        }
+    }
+}
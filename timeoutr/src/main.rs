@@ -0,0 +1,18 @@
+#[cfg(unix)]
+fn main() {
+    coreutils_common::reset_sigpipe();
+    match timeoutr::get_args().and_then(timeoutr::run) {
+        Ok(status) => std::process::exit(status),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(timeoutr::FAILED);
+        }
+    }
+}
+
+/// Without signals there's no way to stop the command.
+#[cfg(not(unix))]
+fn main() {
+    eprintln!("timeoutr needs Unix signals");
+    std::process::exit(125);
+}
@@ -0,0 +1,109 @@
+#![cfg(unix)]
+
+use assert_cmd::Command;
+use std::{
+    os::unix::process::ExitStatusExt,
+    time::{Duration, Instant},
+};
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "timeoutr";
+
+// --------------------------------------------------
+/// The signal that ended timeoutr when run with `args`.
+fn signal(args: &[&str]) -> Result<Option<i32>, Box<dyn std::error::Error>> {
+    Ok(Command::cargo_bin(PRG)?.args(args).output()?.status.signal())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_duration() -> TestResult {
+    for duration in ["x", "-1", "1y"] {
+        Command::cargo_bin(PRG)?
+            .args(["--", duration, "true"])
+            .assert()
+            .code(125)
+            .stderr(format!("invalid time interval '{duration}'\n"));
+    }
+    Command::cargo_bin(PRG)?
+        .args(["-k", "x", "1", "true"])
+        .assert()
+        .code(125)
+        .stderr("invalid time interval 'x'\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_signal() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-s", "FOO", "1", "true"])
+        .assert()
+        .code(125)
+        .stderr("'FOO': invalid signal\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_no_command() -> TestResult {
+    Command::cargo_bin(PRG)?.arg("1").assert().code(125);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn cannot_run() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["1", "does-not-exist"])
+        .assert()
+        .code(127)
+        .stderr("failed to run command 'does-not-exist': No such file or directory (os error 2)\n");
+    Command::cargo_bin(PRG)?.args(["1", "/"]).assert().code(126);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn in_time() -> TestResult {
+    // The command's options are its own
+    Command::cargo_bin(PRG)?.args(["5", "echo", "-n", "hi"]).assert().success().stdout("hi");
+    Command::cargo_bin(PRG)?.args(["0", "sh", "-c", "exit 3"]).assert().code(3);
+    // A signal that ends the command ends timeoutr too
+    assert_eq!(signal(&["5", "sh", "-c", "kill -USR1 $$"])?, Some(10));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn timed_out() -> TestResult {
+    Command::cargo_bin(PRG)?.args(["0.1", "sleep", "10"]).assert().code(124);
+    // Even if the command exits rather than being ended by the signal
+    let trap = ["-s", "USR1", "0.1", "sh", "-c", "trap 'exit 5' USR1; sleep 10 & wait"];
+    Command::cargo_bin(PRG)?.args(trap).assert().code(124);
+    Command::cargo_bin(PRG)?.arg("--preserve-status").args(trap).assert().code(5);
+    assert_eq!(signal(&["--preserve-status", "0.1", "sleep", "10"])?, Some(15));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn kill_after() -> TestResult {
+    let start = Instant::now();
+    let args = ["-k", "0.1", "0.1", "sh", "-c", "trap '' TERM; sleep 10"];
+    assert_eq!(signal(&args)?, Some(9));
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert_eq!(signal(&["-s", "KILL", "0.1", "sleep", "10"])?, Some(9));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn whole_group() -> TestResult {
+    // The sleep in the background holds stdout open until it is stopped too
+    let start = Instant::now();
+    Command::cargo_bin(PRG)?.args(["0.1", "sh", "-c", "sleep 10 & sleep 10"]).assert().code(124);
+    assert!(start.elapsed() < Duration::from_secs(5));
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use clap::Parser;
+use std::io::{self, IsTerminal, Write};
+
+use coreutils_common::{try_parse_args, MyResult};
+
+#[derive(Debug)]
+pub struct Config {
+    silent: bool,
+}
+
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust tty")]
+struct Args {
+    /// Print nothing, only exit with the status
+    #[arg(short = 's', long, visible_alias = "quiet")]
+    silent: bool,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    // Usage errors keep clap's exit status, which is tty's 2
+    let args: Args = try_parse_args().unwrap_or_else(|e| e.exit());
+
+    Ok(Config { silent: args.silent })
+}
+
+/// The name of the terminal device that stdin is, like /dev/pts/0.
+#[cfg(unix)]
+fn tty_name() -> Option<String> {
+    use std::{ffi::CStr, os::unix::io::AsRawFd};
+
+    let mut name = [0 as libc::c_char; 256];
+    let fd = io::stdin().as_raw_fd();
+    // SAFETY: ttyname_r writes at most `name.len()` bytes, ending in a NUL
+    if unsafe { libc::ttyname_r(fd, name.as_mut_ptr(), name.len()) } != 0 {
+        return None;
+    }
+    // SAFETY: it succeeded, so `name` ends in a NUL
+    let name = unsafe { CStr::from_ptr(name.as_ptr()) };
+    Some(name.to_string_lossy().into_owned())
+}
+
+/// Terminals have no device names off Unix, so this is the console's.
+#[cfg(not(unix))]
+fn tty_name() -> Option<String> {
+    Some("CON".to_string())
+}
+
+/// Prints the name of the terminal on stdin, or "not a tty", and returns
+/// the exit status: 1 if it isn't one.
+pub fn run(config: Config) -> MyResult<i32> {
+    let name = if io::stdin().is_terminal() { tty_name() } else { None };
+    if !config.silent {
+        let mut out = io::stdout();
+        writeln!(out, "{}", name.as_deref().unwrap_or("not a tty"))?;
+        out.flush()?;
+    }
+    Ok(if name.is_some() { 0 } else { 1 })
+}
@@ -0,0 +1,11 @@
+fn main() {
+    coreutils_common::reset_sigpipe();
+    match ttyr::get_args().and_then(ttyr::run) {
+        Ok(status) => std::process::exit(status),
+        Err(e) => {
+            eprintln!("{e}");
+            // Only writing can fail, which is 3 for GNU tty
+            std::process::exit(3);
+        }
+    }
+}
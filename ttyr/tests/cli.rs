@@ -0,0 +1,76 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "ttyr";
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_args() -> TestResult {
+    for args in [&["--bogus"][..], &["x"]] {
+        Command::cargo_bin(PRG)?
+            .args(args)
+            .assert()
+            .code(2)
+            .stderr(predicate::str::contains("Usage"));
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn not_a_tty() -> TestResult {
+    Command::cargo_bin(PRG)?.write_stdin("").assert().code(1).stdout("not a tty\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn silent() -> TestResult {
+    for flag in ["-s", "--silent", "--quiet"] {
+        Command::cargo_bin(PRG)?.arg(flag).write_stdin("").assert().code(1).stdout("");
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+#[cfg(unix)]
+fn terminal() -> TestResult {
+    use assert_cmd::{assert::OutputAssertExt, cargo::cargo_bin};
+    use std::{
+        ffi::CStr,
+        fs::File,
+        os::unix::io::{AsRawFd, FromRawFd},
+        process, ptr,
+    };
+
+    // A new pseudo-terminal, whose far end is stdin
+    let (mut leader, mut follower) = (0, 0);
+    let null = ptr::null_mut();
+    // SAFETY: openpty only writes the two descriptors
+    let opened = unsafe { libc::openpty(&mut leader, &mut follower, null, null as _, null as _) };
+    if opened != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    // SAFETY: openpty just opened both, and nothing else owns them
+    let (_leader, follower) = unsafe { (File::from_raw_fd(leader), File::from_raw_fd(follower)) };
+    // SAFETY: ttyname returns a NUL-terminated string for a terminal
+    let name = unsafe { CStr::from_ptr(libc::ttyname(follower.as_raw_fd())) };
+    let name = name.to_string_lossy().into_owned();
+
+    // assert_cmd's Command can only write stdin, so this is std's
+    process::Command::new(cargo_bin(PRG))
+        .stdin(follower.try_clone()?)
+        .assert()
+        .success()
+        .stdout(format!("{name}\n"));
+    process::Command::new(cargo_bin(PRG))
+        .arg("-s")
+        .stdin(follower)
+        .assert()
+        .success()
+        .stdout("");
+    Ok(())
+}
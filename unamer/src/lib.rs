@@ -0,0 +1,158 @@
+use clap::Parser;
+
+use coreutils_common::{parse_args, MyResult};
+
+/// What's printed for something this system doesn't say.
+const UNKNOWN: &str = "unknown";
+
+#[derive(Debug)]
+pub struct Config {
+    kernel_name: bool,
+    nodename: bool,
+    kernel_release: bool,
+    kernel_version: bool,
+    machine: bool,
+    processor: bool,
+    hardware_platform: bool,
+    operating_system: bool,
+    /// With -a, what isn't known is left out
+    all: bool,
+}
+
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust uname")]
+struct Args {
+    /// Print everything, in this order, leaving out -p and -i if unknown
+    #[arg(short = 'a', long)]
+    all: bool,
+
+    /// Print the kernel name (the default)
+    #[arg(short = 's', long)]
+    kernel_name: bool,
+
+    /// Print the host's name on the network
+    #[arg(short = 'n', long)]
+    nodename: bool,
+
+    /// Print the kernel release
+    #[arg(short = 'r', long)]
+    kernel_release: bool,
+
+    /// Print the kernel version
+    #[arg(short = 'v', long)]
+    kernel_version: bool,
+
+    /// Print the machine's hardware name
+    #[arg(short = 'm', long)]
+    machine: bool,
+
+    /// Print the processor type
+    #[arg(short = 'p', long)]
+    processor: bool,
+
+    /// Print the hardware platform
+    #[arg(short = 'i', long)]
+    hardware_platform: bool,
+
+    /// Print the operating system
+    #[arg(short = 'o', long)]
+    operating_system: bool,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let args: Args = parse_args();
+
+    let any = args.kernel_name
+        || args.nodename
+        || args.kernel_release
+        || args.kernel_version
+        || args.machine
+        || args.processor
+        || args.hardware_platform
+        || args.operating_system;
+    Ok(Config {
+        kernel_name: args.kernel_name || args.all || !any,
+        nodename: args.nodename || args.all,
+        kernel_release: args.kernel_release || args.all,
+        kernel_version: args.kernel_version || args.all,
+        machine: args.machine || args.all,
+        processor: args.processor || args.all,
+        hardware_platform: args.hardware_platform || args.all,
+        operating_system: args.operating_system || args.all,
+        all: args.all,
+    })
+}
+
+/// What uname(2) tells about the system.
+#[derive(Debug)]
+struct System {
+    sysname: String,
+    nodename: String,
+    release: String,
+    version: String,
+    machine: String,
+}
+
+#[cfg(unix)]
+fn system() -> MyResult<System> {
+    use std::{ffi::CStr, io, mem};
+
+    // SAFETY: utsname is plain arrays of bytes, for which zero is valid
+    let mut name: libc::utsname = unsafe { mem::zeroed() };
+    // SAFETY: `name` is a valid utsname for the call to fill in
+    if unsafe { libc::uname(&mut name) } != 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+    let field = |field: &[libc::c_char]| {
+        // SAFETY: uname ends each field with a NUL within it
+        unsafe { CStr::from_ptr(field.as_ptr()) }.to_string_lossy().into_owned()
+    };
+    Ok(System {
+        sysname: field(&name.sysname),
+        nodename: field(&name.nodename),
+        release: field(&name.release),
+        version: field(&name.version),
+        machine: field(&name.machine),
+    })
+}
+
+/// Without uname(2), what Rust was built for, and the host's name if the
+/// environment has it, as on Windows.
+#[cfg(not(unix))]
+fn system() -> MyResult<System> {
+    Ok(System {
+        sysname: std::env::consts::OS.to_string(),
+        nodename: std::env::var("COMPUTERNAME").unwrap_or_else(|_| UNKNOWN.to_string()),
+        release: UNKNOWN.to_string(),
+        version: UNKNOWN.to_string(),
+        machine: std::env::consts::ARCH.to_string(),
+    })
+}
+
+/// The name of the operating system as a whole, as GNU uname -o prints it.
+fn operating_system(sysname: &str) -> &str {
+    match sysname {
+        "Linux" => "GNU/Linux",
+        sysname => sysname,
+    }
+}
+
+/// Prints what was asked for, in a fixed order, on one line.
+pub fn run(config: Config) -> MyResult<i32> {
+    let system = system()?;
+    let fields = [
+        (config.kernel_name, system.sysname.as_str()),
+        (config.nodename, &system.nodename),
+        (config.kernel_release, &system.release),
+        (config.kernel_version, &system.version),
+        (config.machine, &system.machine),
+        // Neither is known from uname(2), so -a leaves them out
+        (config.processor && !config.all, UNKNOWN),
+        (config.hardware_platform && !config.all, UNKNOWN),
+        (config.operating_system, operating_system(&system.sysname)),
+    ];
+    let fields: Vec<&str> =
+        fields.iter().filter(|(wanted, _)| *wanted).map(|&(_, value)| value).collect();
+    println!("{}", fields.join(" "));
+    Ok(0)
+}
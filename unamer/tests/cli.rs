@@ -0,0 +1,75 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "unamer";
+
+// --------------------------------------------------
+/// What the program prints with `args`, less the newline.
+fn output(args: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+    let output = Command::cargo_bin(PRG)?.args(args).output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    Ok(stdout.strip_suffix('\n').ok_or("no newline")?.to_string())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_flag() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .arg("-x")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unexpected argument"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn kernel_name_by_default() -> TestResult {
+    assert_eq!(output(&[])?, output(&["-s"])?);
+    #[cfg(target_os = "linux")]
+    assert_eq!(output(&[])?, "Linux");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn machine() -> TestResult {
+    #[cfg(target_os = "linux")]
+    assert_eq!(output(&["-m"])?, std::env::consts::ARCH);
+    assert!(!output(&["--machine"])?.is_empty());
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn fixed_order() -> TestResult {
+    // However they're given, fields come out in the same order
+    let name = output(&["-s"])?;
+    let release = output(&["-r"])?;
+    let machine = output(&["-m"])?;
+    assert_eq!(output(&["-m", "-r", "-s"])?, format!("{name} {release} {machine}"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn all() -> TestResult {
+    // The version is often several words, so check both ends
+    let all = output(&["-a"])?;
+    let start = output(&["-snr"])?;
+    let end = output(&["-mo"])?;
+    assert!(all.starts_with(&format!("{start} ")), "{all}");
+    assert!(all.ends_with(&format!(" {end}")), "{all}");
+    assert_eq!(all, output(&["-snrvmo"])?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn unknown_processor() -> TestResult {
+    assert_eq!(output(&["-p", "-i"])?, "unknown unknown");
+    Ok(())
+}
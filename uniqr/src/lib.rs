@@ -1,81 +1,214 @@
-use clap::{App, Arg};
-use std::{error::Error, fs::File, io::{self, BufRead, BufReader, Write}};
+use clap::Parser;
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{self, File},
+    io::{BufRead, BufWriter, Write},
+    mem,
+};
 
-type MyResult<T> = Result<T, Box<dyn Error>>;
+use coreutils_common::{
+    i18n::tr_fmt, open, parse_args, CoreError, Delimiter, DelimiterArgs, MyResult, Output,
+};
 
 #[derive(Debug)]
 pub struct Config {
     in_file: String,
     out_file: Option<String>,
     count: bool,
+    count_width: usize,
+    repeated: bool,
+    unique: bool,
+    skip_fields: usize,
+    skip_chars: usize,
+    check_chars: Option<usize>,
+    global: bool,
+    delimiter: Delimiter,
 }
 
-fn open(filename: &str) -> MyResult<Box<dyn BufRead>> {
-    match filename {
-        "-" => Ok(Box::new(BufReader::new(io::stdin()))),
-        _ => Ok(Box::new(BufReader::new(File::open(filename)?))),
-    }
+
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust uniq")]
+struct Args {
+    /// number lines
+    #[arg(short = 'c', long)]
+    count: bool,
+
+    /// pad counts to N columns
+    #[arg(long, value_name = "N", default_value = "4")]
+    count_width: String,
+
+    /// only print duplicate lines, one for each group
+    #[arg(short = 'd', long)]
+    repeated: bool,
+
+    /// only print unique lines
+    #[arg(short = 'u', long)]
+    unique: bool,
+
+    /// avoid comparing the first N fields
+    #[arg(short = 'f', long, value_name = "N", default_value = "0")]
+    skip_fields: String,
+
+    /// avoid comparing the first N characters
+    #[arg(short = 's', long, value_name = "N", default_value = "0")]
+    skip_chars: String,
+
+    /// compare no more than N characters in lines
+    #[arg(short = 'w', long, value_name = "N")]
+    check_chars: Option<String>,
+
+    /// remove duplicates anywhere in the input, not just adjacent ones (holds every distinct line in memory)
+    #[arg(long)]
+    global: bool,
+
+    #[command(flatten)]
+    delimiter: DelimiterArgs,
+
+    /// Input file
+    #[arg(value_name = "INPUT", default_value = "-")]
+    in_file: String,
+
+    /// Output file
+    #[arg(value_name = "OUTPUT")]
+    out_file: Option<String>,
 }
 
 pub fn get_args() -> MyResult<Config> {
-    let matches = App::new("uniqr")
-        .about("Rust uniq")
-        .version("0.1.0")
-        .author("OFFBLACK")
-        .arg(
-            Arg::with_name("count")
-                .help("number lines")
-                .short("c")
-                .long("count")
-        )
-        .arg(
-            Arg::with_name("in_file")
-                .value_name("INPUT")
-                .help("Input file")
-                .default_value("-")
-        )
-        .arg(
-            Arg::with_name("out_file")
-                .help("Output file")
-                .value_name("OUTPUT")
-        )
-        .get_matches();
+    let args: Args = parse_args();
+
+    let count_width = parse_count(&args.count_width, "columns for counts")?;
+    let skip_fields = parse_count(&args.skip_fields, "fields to skip")?;
+    let skip_chars = parse_count(&args.skip_chars, "characters to skip")?;
+    let check_chars = args
+        .check_chars
+        .as_deref()
+        .map(|val| parse_count(val, "characters to compare"))
+        .transpose()?;
 
     Ok(Config {
-        in_file: matches.value_of_lossy("in_file").unwrap().to_string(),
-        out_file: matches.value_of("out_file").map(|v| v.to_string()),
-        count: matches.is_present("count")
+        in_file: args.in_file,
+        out_file: args.out_file,
+        count: args.count,
+        count_width,
+        repeated: args.repeated,
+        unique: args.unique,
+        skip_fields,
+        skip_chars,
+        check_chars,
+        global: args.global,
+        delimiter: args.delimiter.delimiter(),
     })
 }
 
-pub fn run(config: Config) -> MyResult<()> {
-    let mut file = open(&config.in_file)
-        .map_err(|e| format!("{}: {}", config.in_file, e))?;
+fn parse_count(val: &str, what: &str) -> MyResult<usize> {
+    val.parse()
+        .map_err(|_| CoreError::Parse(format!("invalid number of {what}: \"{val}\"")))
+}
+
+/// Returns the part of `line` that is compared against its neighbours.
+/// A field is a run of blanks followed by a run of non-blanks; characters
+/// are skipped after fields, and -w applies to whatever remains.
+fn compare_key<'a>(line: &'a str, config: &Config) -> &'a str {
+    let mut key = config.delimiter.strip(line).trim_end();
+    for _ in 0..config.skip_fields {
+        key = key.trim_start_matches([' ', '\t']);
+        key = key.trim_start_matches(|c| c != ' ' && c != '\t');
+    }
+    key = key
+        .char_indices()
+        .nth(config.skip_chars)
+        .map_or("", |(i, _)| &key[i..]);
+    match config.check_chars {
+        Some(width) => key.char_indices().nth(width).map_or(key, |(i, _)| &key[..i]),
+        None => key,
+    }
+}
+
+/// Whether both paths name the same existing file, e.g. through a link.
+#[cfg(unix)]
+fn same_file(path1: &str, path2: &str) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    match (fs::metadata(path1), fs::metadata(path2)) {
+        (Ok(meta1), Ok(meta2)) => meta1.dev() == meta2.dev() && meta1.ino() == meta2.ino(),
+        _ => false,
+    }
+}
+
+/// Whether both paths name the same existing file, e.g. through a link.
+#[cfg(not(unix))]
+fn same_file(path1: &str, path2: &str) -> bool {
+    match (fs::canonicalize(path1), fs::canonicalize(path2)) {
+        (Ok(path1), Ok(path2)) => path1 == path2,
+        _ => false,
+    }
+}
+
+/// Removes duplicate lines wherever they occur, keeping the first of each.
+/// Every distinct key is held in memory. Lines are printed as soon as they
+/// are first seen unless -c, -d or -u need the final counts, in which case
+/// the first occurrence of every line is held as well until the end.
+fn dedup_global(
+    file: &mut dyn BufRead,
+    config: &Config,
+    mut output: impl FnMut(u64, &str) -> MyResult<()>,
+) -> MyResult<()> {
+    let needs_counts = config.count || config.repeated || config.unique;
+    let mut seen = HashSet::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut firsts: Vec<(u64, String)> = Vec::new();
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if config.delimiter.read_record(file, &mut line)? == 0 {
+            break;
+        }
+
+        let key = compare_key(&line, config);
+        if !needs_counts {
+            if !seen.contains(key) {
+                seen.insert(key.to_string());
+                output(1, &line)?;
+            }
+            continue;
+        }
+        match index.get(key) {
+            Some(&i) => firsts[i].0 += 1,
+            None => {
+                index.insert(key.to_string(), firsts.len());
+                // A final line without a delimiter may not be printed last
+                if !line.ends_with(config.delimiter.as_char()) {
+                    line.push(config.delimiter.as_char());
+                }
+                firsts.push((1, mem::take(&mut line)));
+            }
+        }
+    }
+
+    for (count, line) in &firsts {
+        output(*count, line)?;
+    }
+    Ok(())
+}
+
+/// Collapses runs of adjacent lines with the same key, keeping the first.
+fn dedup_adjacent(
+    file: &mut dyn BufRead,
+    config: &Config,
+    mut output: impl FnMut(u64, &str) -> MyResult<()>,
+) -> MyResult<()> {
     let mut line = String::new();
     let mut prev_line = String::new();
     let mut count = 0u64;
-    let mut outfile: Box<dyn Write> = match config.out_file {
-        Some(out_file) => Box::new(File::create(out_file)?),
-        None => Box::new(io::stdout()),
-    };
-    let mut output = |count: u64, line: &str| -> MyResult<()> {
-        if count > 0 {
-            match config.count {
-                true => write!(outfile, "{:>4} {}", count, line)?,
-                false => write!(outfile, "{line}")?,
-            };
-        }
-        Ok(())
-    };
     loop {
-        let bytes = file.read_line(&mut line)?;
+        let bytes = config.delimiter.read_record(file, &mut line)?;
         if bytes == 0 {
             break;
         }
 
-        if line.trim_end() != prev_line.trim_end() {
+        if count == 0 || compare_key(&line, config) != compare_key(&prev_line, config) {
             output(count, &prev_line)?;
-            prev_line = line.clone();
+            mem::swap(&mut prev_line, &mut line);
             count = 0;
         }
 
@@ -83,6 +216,206 @@ pub fn run(config: Config) -> MyResult<()> {
         line.clear();
     }
 
-    output(count, &prev_line)?;
+    output(count, &prev_line)
+}
+
+pub fn run(config: Config) -> MyResult<()> {
+    let mut file = open(&config.in_file)?;
+    let mut outfile: Box<dyn Write> = match &config.out_file {
+        Some(out_file) => {
+            // Creating the output would truncate the input before it is read
+            if config.in_file != "-" && same_file(&config.in_file, out_file) {
+                return Err(CoreError::Input(tr_fmt(
+                    "{path}: input file is output file",
+                    &[("path", out_file)],
+                )));
+            }
+            Box::new(BufWriter::new(File::create(out_file)?))
+        }
+        None => Box::new(Output::stdout()),
+    };
+    let output = |count: u64, line: &str| -> MyResult<()> {
+        let skip = (config.repeated && count == 1) || (config.unique && count > 1);
+        if count > 0 && !skip {
+            match config.count {
+                true => write!(outfile, "{:>width$} {}", count, line, width = config.count_width)?,
+                false => write!(outfile, "{line}")?,
+            };
+        }
+        Ok(())
+    };
+
+    if config.global {
+        dedup_global(&mut file, &config, output)?;
+    } else {
+        dedup_adjacent(&mut file, &config, output)?;
+    }
+    outfile.flush()?;
     Ok(())
 }
+
+/// Entry points for the cargo-fuzz targets in `../fuzz`, which can't reach
+/// private items. None of them may panic, whatever the input.
+#[cfg(feature = "fuzzing")]
+#[doc(hidden)]
+pub mod fuzzing {
+    use super::{dedup_adjacent, dedup_global, Config, Delimiter};
+
+    pub fn dedup(
+        input: &[u8],
+        skip_fields: usize,
+        skip_chars: usize,
+        check_chars: Option<usize>,
+        global: bool,
+    ) {
+        let config = Config {
+            in_file: "-".to_string(),
+            out_file: None,
+            count: true,
+            count_width: 4,
+            repeated: false,
+            unique: false,
+            skip_fields,
+            skip_chars,
+            check_chars,
+            global,
+            delimiter: Delimiter::Newline,
+        };
+        let mut input = input;
+        let _ = match global {
+            true => dedup_global(&mut input, &config, |_, _| Ok(())),
+            false => dedup_adjacent(&mut input, &config, |_, _| Ok(())),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare_key, dedup_adjacent, dedup_global, Config, Delimiter};
+    use proptest::prelude::*;
+    use std::io::Cursor;
+
+    fn config() -> Config {
+        Config {
+            in_file: "-".to_string(),
+            out_file: None,
+            count: false,
+            count_width: 4,
+            repeated: false,
+            unique: false,
+            skip_fields: 0,
+            skip_chars: 0,
+            check_chars: None,
+            global: false,
+            delimiter: Delimiter::Newline,
+        }
+    }
+
+    #[test]
+    fn test_compare_key() {
+        let mut config = config();
+        assert_eq!(compare_key("10:00 alpha ok\n", &config), "10:00 alpha ok");
+
+        config.skip_fields = 1;
+        assert_eq!(compare_key("10:00 alpha ok\n", &config), " alpha ok");
+        assert_eq!(compare_key("  10:00\t alpha", &config), "\t alpha");
+
+        config.skip_fields = 5;
+        assert_eq!(compare_key("10:00 alpha ok\n", &config), "");
+
+        config.skip_fields = 0;
+        config.skip_chars = 6;
+        assert_eq!(compare_key("10:00 alpha ok\n", &config), "alpha ok");
+        assert_eq!(compare_key("10:00 é", &config), "é");
+        assert_eq!(compare_key("10:00", &config), "");
+
+        config.skip_fields = 1;
+        config.skip_chars = 1;
+        assert_eq!(compare_key("10:00 alpha ok\n", &config), "alpha ok");
+
+        config.check_chars = Some(5);
+        assert_eq!(compare_key("10:00 alpha ok\n", &config), "alpha");
+        assert_eq!(compare_key("10:00 al", &config), "al");
+
+        config.skip_fields = 0;
+        config.skip_chars = 0;
+        config.check_chars = Some(0);
+        assert_eq!(compare_key("10:00 alpha ok\n", &config), "");
+
+        config.check_chars = None;
+        config.delimiter = Delimiter::Nul;
+        assert_eq!(compare_key("10:00\nalpha\0", &config), "10:00\nalpha");
+    }
+
+    /// A config with -c and random key options, so every group is output.
+    fn key_config() -> impl Strategy<Value = Config> {
+        (0..3usize, 0..3usize, prop::option::of(0..3usize)).prop_map(
+            |(skip_fields, skip_chars, check_chars)| Config {
+                count: true,
+                skip_fields,
+                skip_chars,
+                check_chars,
+                ..config()
+            },
+        )
+    }
+
+    /// Runs `lines` through the dedup pass `config` selects and returns each
+    /// (count, line) it output.
+    fn collect(lines: &[String], config: &Config) -> Vec<(u64, String)> {
+        let mut out = Vec::new();
+        let mut input = Cursor::new(lines.concat());
+        let output = |count, line: &str| {
+            if count > 0 {
+                out.push((count, line.to_string()));
+            }
+            Ok(())
+        };
+        match config.global {
+            true => dedup_global(&mut input, config, output),
+            false => dedup_adjacent(&mut input, config, output),
+        }
+        .unwrap();
+        out
+    }
+
+    proptest! {
+        #[test]
+        fn compare_key_never_panics(line in "\\PC*", config in key_config()) {
+            let _ = compare_key(&line, &config);
+        }
+
+        #[test]
+        fn dedup_adjacent_leaves_no_neighbours(
+            lines in prop::collection::vec("[ab \t\u{e9}]{0,4}\n", 0..20),
+            config in key_config(),
+        ) {
+            let out = collect(&lines, &config);
+            prop_assert_eq!(out.iter().map(|(count, _)| count).sum::<u64>(), lines.len() as u64);
+            for pair in out.windows(2) {
+                prop_assert_ne!(compare_key(&pair[0].1, &config), compare_key(&pair[1].1, &config));
+            }
+        }
+
+        #[test]
+        fn dedup_global_leaves_no_duplicates(
+            lines in prop::collection::vec("[ab \t\u{e9}]{0,4}\n", 0..20),
+            config in key_config(),
+        ) {
+            let config = Config { global: true, ..config };
+            let out = collect(&lines, &config);
+            prop_assert_eq!(out.iter().map(|(count, _)| count).sum::<u64>(), lines.len() as u64);
+            let mut keys: Vec<_> = out.iter().map(|(_, line)| compare_key(line, &config)).collect();
+            keys.sort();
+            keys.dedup();
+            prop_assert_eq!(keys.len(), out.len());
+        }
+
+        #[test]
+        fn dedup_rejects_invalid_utf8_without_panicking(bytes in prop::collection::vec(any::<u8>(), 0..64)) {
+            let config = config();
+            let _ = dedup_adjacent(&mut Cursor::new(&bytes), &config, |_, _| Ok(()));
+            let _ = dedup_global(&mut Cursor::new(&bytes), &config, |_, _| Ok(()));
+        }
+    }
+}
@@ -13,6 +13,7 @@ struct Test {
 }
 
 const PRG: &str = "uniqr";
+const FIELDS: &str = "tests/inputs/fields.txt";
 
 const EMPTY: Test = Test {
     input: "tests/inputs/empty.txt",
@@ -124,7 +125,7 @@ fn run(test: &Test) -> TestResult {
 fn run_count(test: &Test) -> TestResult {
     let expected = fs::read_to_string(test.out_count)?;
     Command::cargo_bin(PRG)?
-        .args(&[test.input, "-c"])
+        .args([test.input, "-c"])
         .assert()
         .success()
         .stdout(expected);
@@ -162,12 +163,12 @@ fn run_outfile(test: &Test) -> TestResult {
     let outfile = NamedTempFile::new()?;
     let outpath = &outfile.path().to_str().unwrap();
     Command::cargo_bin(PRG)?
-        .args(&[test.input, outpath])
+        .args([test.input, outpath])
         .assert()
         .success()
         .stdout("");
 
-    let contents = fs::read_to_string(&outpath)?;
+    let contents = fs::read_to_string(outpath)?;
     assert_eq!(&expected, &contents);
 
     Ok(())
@@ -179,13 +180,13 @@ fn run_outfile_count(test: &Test) -> TestResult {
     let outpath = &outfile.path().to_str().unwrap();
 
     Command::cargo_bin(PRG)?
-        .args(&[test.input, outpath, "--count"])
+        .args([test.input, outpath, "--count"])
         .assert()
         .success()
         .stdout("");
 
     let expected = fs::read_to_string(test.out_count)?;
-    let contents = fs::read_to_string(&outpath)?;
+    let contents = fs::read_to_string(outpath)?;
     assert_eq!(&expected, &contents);
 
     Ok(())
@@ -198,13 +199,13 @@ fn run_stdin_outfile_count(test: &Test) -> TestResult {
     let outpath = &outfile.path().to_str().unwrap();
 
     Command::cargo_bin(PRG)?
-        .args(&["-", outpath, "-c"])
+        .args(["-", outpath, "-c"])
         .write_stdin(input)
         .assert()
         .stdout("");
 
     let expected = fs::read_to_string(test.out_count)?;
-    let contents = fs::read_to_string(&outpath)?;
+    let contents = fs::read_to_string(outpath)?;
     assert_eq!(&expected, &contents);
 
     Ok(())
@@ -605,3 +606,242 @@ fn t6_outfile_count() -> TestResult {
 fn t6_stdin_outfile_count() -> TestResult {
     run_stdin_outfile_count(&T6)
 }
+
+// --------------------------------------------------
+fn run_args(args: &[&str], expected_file: &str) -> TestResult {
+    let expected = fs::read_to_string(expected_file)?;
+    Command::cargo_bin(PRG)?
+        .args(args)
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn three_unique() -> TestResult {
+    run_args(&["-u", THREE.input], "tests/expected/three.txt.u.out")
+}
+
+#[test]
+fn three_repeated() -> TestResult {
+    run_args(&["--repeated", THREE.input], "tests/expected/three.txt.d.out")
+}
+
+#[test]
+fn three_repeated_count() -> TestResult {
+    run_args(&["-c", "-d", THREE.input], "tests/expected/three.txt.cd.out")
+}
+
+#[test]
+fn three_unique_repeated() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-u", "-d", THREE.input])
+        .assert()
+        .success()
+        .stdout("");
+    Ok(())
+}
+
+#[test]
+fn skip_unique() -> TestResult {
+    run_args(&["--unique", SKIP.input], "tests/expected/skip.txt.u.out")
+}
+
+#[test]
+fn skip_repeated() -> TestResult {
+    run_args(&["-d", SKIP.input], "tests/expected/skip.txt.d.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn fields_skip_1() -> TestResult {
+    run_args(&["-f", "1", FIELDS], "tests/expected/fields.txt.f1.out")
+}
+
+#[test]
+fn fields_skip_2() -> TestResult {
+    run_args(&["--skip-fields", "2", FIELDS], "tests/expected/fields.txt.f2.out")
+}
+
+#[test]
+fn fields_skip_past_end() -> TestResult {
+    run_args(&["-f5", FIELDS], "tests/expected/fields.txt.f5.out")
+}
+
+#[test]
+fn dies_bad_skip_fields() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-f", "x", FIELDS])
+        .assert()
+        .failure()
+        .stderr("invalid number of fields to skip: \"x\"\n");
+    Ok(())
+}
+
+#[test]
+fn fields_skip_chars() -> TestResult {
+    run_args(&["-s", "6", FIELDS], "tests/expected/fields.txt.s6.out")
+}
+
+#[test]
+fn fields_skip_fields_then_chars() -> TestResult {
+    run_args(
+        &["--skip-fields=1", "--skip-chars=1", "-c", FIELDS],
+        "tests/expected/fields.txt.f1s1.c.out",
+    )
+}
+
+#[test]
+fn dies_bad_skip_chars() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-s", "abc", FIELDS])
+        .assert()
+        .failure()
+        .stderr("invalid number of characters to skip: \"abc\"\n");
+    Ok(())
+}
+
+#[test]
+fn fields_check_chars() -> TestResult {
+    run_args(&["-w", "2", "-c", FIELDS], "tests/expected/fields.txt.w2.c.out")
+}
+
+#[test]
+fn fields_skip_then_check_chars() -> TestResult {
+    run_args(
+        &["-f1", "-s1", "--check-chars=4", FIELDS],
+        "tests/expected/fields.txt.f1s1w4.out",
+    )
+}
+
+#[test]
+fn dies_bad_check_chars() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-w", "wide", FIELDS])
+        .assert()
+        .failure()
+        .stderr("invalid number of characters to compare: \"wide\"\n");
+    Ok(())
+}
+
+#[test]
+fn three_global() -> TestResult {
+    run_args(&["--global", THREE.input], "tests/expected/three.txt.global.out")
+}
+
+#[test]
+fn three_global_count() -> TestResult {
+    run_args(
+        &["--global", "-c", THREE.input],
+        "tests/expected/three.txt.global.c.out",
+    )
+}
+
+#[test]
+fn three_global_unique() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["--global", "-u", THREE.input])
+        .assert()
+        .success()
+        .stdout("");
+    Ok(())
+}
+
+#[test]
+fn fields_global_skip_repeated_count() -> TestResult {
+    run_args(
+        &["--global", "-f", "1", "-d", "-c", FIELDS],
+        "tests/expected/fields.txt.global.f1.cd.out",
+    )
+}
+
+#[test]
+fn global_stdin_keeps_first_seen_order() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .arg("--global")
+        .write_stdin("c\na\nc\nb\na\n")
+        .assert()
+        .success()
+        .stdout("c\na\nb\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_same_in_and_out_file() -> TestResult {
+    let outfile = NamedTempFile::new()?;
+    fs::copy(THREE.input, outfile.path())?;
+    let outpath = outfile.path().to_str().unwrap();
+    Command::cargo_bin(PRG)?
+        .args([outpath, outpath])
+        .assert()
+        .failure()
+        .stderr(format!("{outpath}: input file is output file\n"));
+
+    // The input must be left intact
+    let expected = fs::read_to_string(THREE.input)?;
+    assert_eq!(expected, fs::read_to_string(outpath)?);
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn dies_same_in_and_out_file_through_link() -> TestResult {
+    let dir = tempfile::tempdir()?;
+    let input = dir.path().join("input.txt");
+    let link = dir.path().join("link.txt");
+    fs::copy(THREE.input, &input)?;
+    std::os::unix::fs::symlink(&input, &link)?;
+    Command::cargo_bin(PRG)?
+        .args([input.to_str().unwrap(), link.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("input file is output file"));
+
+    let expected = fs::read_to_string(THREE.input)?;
+    assert_eq!(expected, fs::read_to_string(&input)?);
+    Ok(())
+}
+
+#[test]
+fn three_count_width_matches_gnu() -> TestResult {
+    run_args(
+        &["-c", "--count-width", "7", THREE.input],
+        "tests/expected/three.txt.c7.out",
+    )
+}
+
+#[test]
+fn count_width_past_default() -> TestResult {
+    let input = "a\n".repeat(12345);
+    Command::cargo_bin(PRG)?
+        .args(["-c", "--count-width=6"])
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(" 12345 a\n");
+    Ok(())
+}
+
+#[test]
+fn dies_bad_count_width() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-c", "--count-width", "x", THREE.input])
+        .assert()
+        .failure()
+        .stderr("invalid number of columns for counts: \"x\"\n");
+    Ok(())
+}
+
+#[test]
+fn zero_terminated() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .args(["-z", "-c"])
+        .write_stdin("a\0a\0b\nb\0")
+        .assert()
+        .success()
+        .stdout("   2 a\0   1 b\nb\0");
+    Ok(())
+}
@@ -0,0 +1,158 @@
+use chrono::{Local, TimeZone};
+use clap::Parser;
+
+use coreutils_common::{parse_args, CoreError, MyResult};
+
+mod sys;
+
+const MINUTE: u64 = 60;
+const HOUR: u64 = 60 * MINUTE;
+const DAY: u64 = 24 * HOUR;
+
+#[derive(Debug)]
+pub struct Config {
+    format: Format,
+}
+
+/// How to print the uptime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    /// The time, uptime, users and load, as uptime and w print them
+    Summary,
+    /// In words, like up 2 days, 3 hours
+    Pretty,
+    /// When the system booted
+    Since,
+}
+
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust uptime")]
+struct Args {
+    /// Print the uptime in words
+    #[arg(short = 'p', long, overrides_with = "since")]
+    pretty: bool,
+
+    /// Print when the system booted, as yyyy-mm-dd HH:MM:SS
+    #[arg(short = 's', long, overrides_with = "pretty")]
+    since: bool,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let args: Args = parse_args();
+
+    let format = if args.pretty {
+        Format::Pretty
+    } else if args.since {
+        Format::Since
+    } else {
+        Format::Summary
+    };
+    Ok(Config { format })
+}
+
+/// `n` and `unit`, with an s unless there is one.
+fn count(n: u64, unit: &str) -> String {
+    format!("{n} {unit}{}", if n == 1 { "" } else { "s" })
+}
+
+/// The uptime of `secs` seconds, users and load on one line after `time`,
+/// as procps uptime does: up 3 days,  2:05,  1 user,  load average: ...
+fn summary(time: &str, secs: u64, users: usize, load: [f64; 3]) -> String {
+    let mut line = format!(" {time} up ");
+    let days = secs / DAY;
+    if days > 0 {
+        line += &format!("{}, ", count(days, "day"));
+    }
+    let (hours, minutes) = (secs % DAY / HOUR, secs % HOUR / MINUTE);
+    if hours > 0 {
+        line += &format!("{hours:2}:{minutes:02}, ");
+    } else {
+        line += &format!("{minutes} min, ");
+    }
+    // Only more than one user is plural here, as in procps
+    let user = if users > 1 { "users" } else { "user" };
+    let [one, five, fifteen] = load;
+    line + &format!("{users:2} {user},  load average: {one:.2}, {five:.2}, {fifteen:.2}")
+}
+
+/// The uptime of `secs` seconds in words, in the largest units that fit,
+/// as up 1 week, 2 days, 5 minutes.
+fn pretty(secs: u64) -> String {
+    let units = [
+        (3650 * DAY, "decade"),
+        (365 * DAY, "year"),
+        (7 * DAY, "week"),
+        (DAY, "day"),
+        (HOUR, "hour"),
+        (MINUTE, "minute"),
+    ];
+    let mut rest = secs;
+    let mut parts = vec![];
+    for (size, unit) in units {
+        let n = rest / size;
+        rest %= size;
+        if n > 0 {
+            parts.push(count(n, unit));
+        }
+    }
+    if parts.is_empty() {
+        parts.push(count(0, "minute"));
+    }
+    format!("up {}", parts.join(", "))
+}
+
+/// Prints how long the system has been up, in the format asked for.
+pub fn run(config: Config) -> MyResult<i32> {
+    let uptime = sys::uptime()
+        .map_err(|e| CoreError::Input(format!("cannot get system uptime: {e}")))?;
+    let secs = uptime as u64;
+    let now = Local::now();
+    let line = match config.format {
+        Format::Pretty => pretty(secs),
+        Format::Since => {
+            let boot = Local.timestamp_millis(now.timestamp_millis() - (uptime * 1e3) as i64);
+            boot.format("%Y-%m-%d %H:%M:%S").to_string()
+        }
+        Format::Summary => {
+            let load = sys::load_average()
+                .map_err(|e| CoreError::Input(format!("cannot get load average: {e}")))?;
+            let time = now.format("%H:%M:%S").to_string();
+            summary(&time, secs, sys::user_count(), load)
+        }
+    };
+    println!("{line}");
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pretty, summary, DAY, HOUR, MINUTE};
+
+    #[test]
+    fn test_summary() {
+        let load = [1.1, 0.75, 0.5];
+        assert_eq!(
+            summary("16:47:08", HOUR + 52 * MINUTE, 0, load),
+            " 16:47:08 up  1:52,  0 user,  load average: 1.10, 0.75, 0.50"
+        );
+        assert_eq!(
+            summary("01:02:03", 3 * DAY + 12 * HOUR + 5 * MINUTE + 59, 2, [0.0; 3]),
+            " 01:02:03 up 3 days, 12:05,  2 users,  load average: 0.00, 0.00, 0.00"
+        );
+        assert_eq!(
+            summary("01:02:03", DAY + 7 * MINUTE, 1, [0.0; 3]),
+            " 01:02:03 up 1 day, 7 min,  1 user,  load average: 0.00, 0.00, 0.00"
+        );
+    }
+
+    #[test]
+    fn test_pretty() {
+        assert_eq!(pretty(59), "up 0 minutes");
+        assert_eq!(pretty(MINUTE), "up 1 minute");
+        assert_eq!(pretty(HOUR + 52 * MINUTE), "up 1 hour, 52 minutes");
+        assert_eq!(pretty(2 * HOUR), "up 2 hours");
+        assert_eq!(pretty(9 * DAY + 3 * MINUTE), "up 1 week, 2 days, 3 minutes");
+        assert_eq!(pretty(400 * DAY), "up 1 year, 5 weeks");
+        assert_eq!(pretty(3650 * DAY), "up 1 decade");
+    }
+}
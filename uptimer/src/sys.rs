@@ -0,0 +1,100 @@
+//! How long the system has been up, how busy it is and who is logged in,
+//! from wherever the platform keeps them.
+
+use std::io;
+
+/// Seconds since the system booted, from /proc/uptime.
+#[cfg(target_os = "linux")]
+pub fn uptime() -> io::Result<f64> {
+    let uptime = std::fs::read_to_string("/proc/uptime")?;
+    uptime.split_whitespace().next().and_then(|secs| secs.parse().ok()).ok_or_else(malformed)
+}
+
+/// The average number of processes ready to run over the last 1, 5 and 15
+/// minutes, from /proc/loadavg.
+#[cfg(target_os = "linux")]
+pub fn load_average() -> io::Result<[f64; 3]> {
+    let loadavg = std::fs::read_to_string("/proc/loadavg")?;
+    let mut loads = loadavg.split_whitespace().map(|load| load.parse().ok());
+    match (loads.next(), loads.next(), loads.next()) {
+        (Some(Some(one)), Some(Some(five)), Some(Some(fifteen))) => Ok([one, five, fifteen]),
+        _ => Err(malformed()),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn malformed() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "unexpected contents in /proc")
+}
+
+/// Seconds since the boot time in the kern.boottime sysctl.
+#[cfg(target_os = "macos")]
+pub fn uptime() -> io::Result<f64> {
+    use std::{mem, ptr, time::SystemTime};
+
+    let mut mib = [libc::CTL_KERN, libc::KERN_BOOTTIME];
+    // SAFETY: timeval is plain data, for which zero is valid
+    let mut boot: libc::timeval = unsafe { mem::zeroed() };
+    let mut len = mem::size_of::<libc::timeval>();
+    // SAFETY: `boot` has room for the `len` bytes sysctl may write
+    let ok = unsafe {
+        libc::sysctl(
+            mib.as_mut_ptr(),
+            mib.len() as _,
+            ptr::addr_of_mut!(boot).cast(),
+            &mut len,
+            ptr::null_mut(),
+            0,
+        )
+    };
+    if ok != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default();
+    Ok(now.as_secs_f64() - boot.tv_sec as f64 - boot.tv_usec as f64 / 1e6)
+}
+
+/// The load averages that the vm.loadavg sysctl keeps.
+#[cfg(target_os = "macos")]
+pub fn load_average() -> io::Result<[f64; 3]> {
+    let mut loads = [0.0; 3];
+    // SAFETY: `loads` has room for the 3 asked for
+    if unsafe { libc::getloadavg(loads.as_mut_ptr(), 3) } != 3 {
+        return Err(io::Error::other("cannot get load average"));
+    }
+    Ok(loads)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn uptime() -> io::Result<f64> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn load_average() -> io::Result<[f64; 3]> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+/// How many users are logged in, by their entries in utmp.
+#[cfg(any(all(target_os = "linux", target_env = "gnu"), target_os = "macos"))]
+pub fn user_count() -> usize {
+    let mut count = 0;
+    // SAFETY: the entries are only read between setutxent and endutxent,
+    // each before the next call replaces it
+    unsafe {
+        libc::setutxent();
+        while let Some(entry) = libc::getutxent().as_ref() {
+            if entry.ut_type == libc::USER_PROCESS {
+                count += 1;
+            }
+        }
+        libc::endutxent();
+    }
+    count
+}
+
+/// Without utmp, no one is known to be logged in.
+#[cfg(not(any(all(target_os = "linux", target_env = "gnu"), target_os = "macos")))]
+pub fn user_count() -> usize {
+    0
+}
@@ -0,0 +1,60 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "uptimer";
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_args() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .arg("x")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("unexpected argument 'x'"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn summary() -> TestResult {
+    let line = concat!(
+        r"^ \d\d:\d\d:\d\d up (\d+ days?, )?(\d+ min|[ \d]\d:\d\d), +\d+ users?,  ",
+        r"load average: \d+\.\d\d, \d+\.\d\d, \d+\.\d\d\n$"
+    );
+    Command::cargo_bin(PRG)?
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(line)?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn pretty() -> TestResult {
+    let unit = r"\d+ (decade|year|week|day|hour|minute)s?";
+    Command::cargo_bin(PRG)?
+        .arg("-p")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(format!("^up {unit}(, {unit})*\n$"))?);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn since() -> TestResult {
+    let since = r"^\d{4}-\d\d-\d\d \d\d:\d\d:\d\d\n$";
+    for flag in ["-s", "--since"] {
+        Command::cargo_bin(PRG)?
+            .arg(flag)
+            .assert()
+            .success()
+            .stdout(predicate::str::is_match(since)?);
+    }
+    Ok(())
+}
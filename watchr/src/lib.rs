@@ -0,0 +1,212 @@
+use ansi_term::Style;
+use chrono::Local;
+use clap::Parser;
+use std::{
+    io::{self, Read, Write},
+    process::{Command, ExitStatus, Stdio},
+    thread,
+    time::Duration,
+};
+
+use coreutils_common::{
+    host, parse_args,
+    term::{self, Size, CLEAR},
+    CoreError, MyResult,
+};
+
+/// The shortest interval, as in procps watch.
+const MIN_INTERVAL: f64 = 0.1;
+
+#[derive(Debug)]
+pub struct Config {
+    command: String,
+    interval: Duration,
+    differences: bool,
+    errexit: bool,
+    title: bool,
+}
+
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust watch")]
+struct Args {
+    /// The command to run with sh -c, as one argument or several
+    #[arg(
+        value_name = "COMMAND",
+        required = true,
+        trailing_var_arg = true,
+        allow_hyphen_values = true
+    )]
+    command: Vec<String>,
+
+    /// Seconds to wait between runs, at least 0.1
+    #[arg(short = 'n', long, value_name = "SECS", default_value = "2")]
+    interval: String,
+
+    /// Highlight what changed since the last run
+    #[arg(short = 'd', long)]
+    differences: bool,
+
+    /// Exit with the command's status once it fails
+    #[arg(short = 'e', long)]
+    errexit: bool,
+
+    /// Leave out the header
+    #[arg(short = 't', long)]
+    no_title: bool,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let args: Args = parse_args();
+
+    let interval = args
+        .interval
+        .parse::<f64>()
+        .ok()
+        .filter(|secs| secs.is_finite())
+        .and_then(|secs| Duration::try_from_secs_f64(secs.max(MIN_INTERVAL)).ok())
+        .ok_or_else(|| CoreError::Parse(format!("invalid interval '{}'", args.interval)))?;
+    Ok(Config {
+        command: args.command.join(" "),
+        interval,
+        differences: args.differences,
+        errexit: args.errexit,
+        title: !args.no_title,
+    })
+}
+
+/// Runs `command` in the shell, and returns what it wrote to stdout and
+/// stderr, together as a terminal would show them, and how it ended.
+fn run_command(command: &str) -> MyResult<(String, ExitStatus)> {
+    #[cfg(unix)]
+    let mut shell = Command::new("sh");
+    #[cfg(unix)]
+    shell.arg("-c");
+    #[cfg(not(unix))]
+    let mut shell = Command::new("cmd");
+    #[cfg(not(unix))]
+    shell.arg("/C");
+
+    let (mut reader, writer) = io::pipe()?;
+    let mut child = shell
+        .arg(command)
+        .stdin(Stdio::null())
+        .stdout(writer.try_clone()?)
+        .stderr(writer)
+        .spawn()
+        .map_err(|e| CoreError::io(format!("cannot run '{command}'"), e))?;
+    // The Command holds copies of the write end, so reading would never
+    // see the end of the output while it lives
+    drop(shell);
+    let mut output = vec![];
+    reader.read_to_end(&mut output)?;
+    let status = child.wait()?;
+    Ok((String::from_utf8_lossy(&output).into_owned(), status))
+}
+
+/// The header line: the interval and command on the left, and the time and
+/// host on the right, `columns` wide.
+fn header(config: &Config, host: &str, time: &str, columns: usize) -> String {
+    let left = format!("Every {:.1}s: {}", config.interval.as_secs_f64(), config.command);
+    let right = format!("{host}: {time}");
+    let room = columns.saturating_sub(right.chars().count() + 1);
+    let left: String = left.chars().take(room).collect();
+    format!("{left:<room$} {right}")
+}
+
+/// `output` cut to fit `size`, with each character that isn't where it was
+/// in `previous` in reverse video.
+fn render(output: &str, previous: Option<&str>, size: Option<Size>) -> String {
+    let (columns, rows) = size.map_or((usize::MAX, usize::MAX), |size| (size.columns, size.rows));
+    let mut old_lines = previous.map(str::lines);
+    let mut screen = String::new();
+    for line in output.lines().take(rows) {
+        let old: Vec<char> = match &mut old_lines {
+            Some(lines) => lines.next().unwrap_or_default().chars().collect(),
+            None => vec![],
+        };
+        for (i, c) in line.chars().take(columns).enumerate() {
+            if previous.is_some() && old.get(i) != Some(&c) {
+                screen += &Style::new().reverse().paint(c.to_string()).to_string();
+            } else {
+                screen.push(c);
+            }
+        }
+        screen.push('\n');
+    }
+    screen
+}
+
+/// Runs the command every interval, clearing the screen to show what it
+/// printed, until interrupted, or with -e, until the command fails. Unlike
+/// procps watch, -e exits at once rather than waiting for a key, so it can
+/// end a script's wait.
+pub fn run(config: Config) -> MyResult<i32> {
+    let host = host::hostname().unwrap_or_default();
+    let mut out = io::stdout();
+    let mut previous: Option<String> = None;
+    loop {
+        let (output, status) = run_command(&config.command)?;
+        let size = term::size();
+        let mut screen = String::from(CLEAR);
+        // The header and the blank line after it take two rows
+        let mut body_size = size;
+        if config.title {
+            let time = Local::now().format("%a %b %e %H:%M:%S %Y").to_string();
+            let columns = size.map_or(80, |size| size.columns);
+            screen += &header(&config, &host, &time, columns);
+            screen += "\n\n";
+            body_size = size.map(|size| Size { rows: size.rows.saturating_sub(2), ..size });
+        }
+        let previous_output = previous.as_deref().filter(|_| config.differences);
+        screen += &render(&output, previous_output, body_size);
+        out.write_all(screen.as_bytes())?;
+        out.flush()?;
+
+        if config.errexit && !status.success() {
+            return Ok(status.code().unwrap_or(1));
+        }
+        previous = Some(output);
+        thread::sleep(config.interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{header, render, Config};
+    use coreutils_common::term::Size;
+    use std::time::Duration;
+
+    #[test]
+    fn test_header() {
+        let config = Config {
+            command: "ls -l".to_string(),
+            interval: Duration::from_secs(2),
+            differences: false,
+            errexit: false,
+            title: true,
+        };
+        let time = "Wed Oct 14 16:47:08 2026";
+        assert_eq!(
+            header(&config, "vm", time, 50),
+            "Every 2.0s: ls -l     vm: Wed Oct 14 16:47:08 2026"
+        );
+        // The command is cut short to leave room for the time
+        assert_eq!(header(&config, "vm", time, 34), "Every vm: Wed Oct 14 16:47:08 2026");
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(render("a\nb\n", None, None), "a\nb\n");
+        let size = Some(Size { columns: 3, rows: 2 });
+        assert_eq!(render("abcd\nefgh\nijkl\n", None, size), "abc\nefg\n");
+
+        let changed = |c: &str| format!("\x1b[7m{c}\x1b[0m");
+        assert_eq!(render("run 1\n", Some("run 1\n"), None), "run 1\n");
+        assert_eq!(render("run 2\n", Some("run 1\n"), None), format!("run {}\n", changed("2")));
+        // Everything on a new line is new
+        assert_eq!(
+            render("a\nbc\n", Some("a\n"), None),
+            format!("a\n{}{}\n", changed("b"), changed("c"))
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "watchr";
+
+/// Counts its runs in the file n, printing the count, and fails on the
+/// third, so -e stops watching.
+#[cfg(unix)]
+const COUNT_TO_3: &str =
+    r#"n=$(cat n 2>/dev/null || echo 0); n=$((n+1)); echo $n > n; echo "run $n"; [ $n -lt 3 ]"#;
+
+// --------------------------------------------------
+#[test]
+fn dies_no_command() -> TestResult {
+    Command::cargo_bin(PRG)?
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Usage"));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn dies_bad_interval() -> TestResult {
+    // Too long for a Duration, as well as not a number
+    for bad in ["soon", "1e300", "inf"] {
+        Command::cargo_bin(PRG)?
+            .args(["-n", bad, "true"])
+            .assert()
+            .code(1)
+            .stderr(format!("invalid interval '{bad}'\n"));
+    }
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+#[cfg(unix)]
+fn errexit() -> TestResult {
+    // The words of the command are joined, and its options aren't watchr's
+    Command::cargo_bin(PRG)?
+        .args(["-e", "echo", "-n", "out;", "echo err >&2; exit 5"])
+        .assert()
+        .code(5)
+        .stdout(
+            predicate::str::starts_with("\x1b[H\x1b[2JEvery 2.0s: echo -n out; echo err")
+                .and(predicate::str::ends_with("\n\nouterr\n")),
+        );
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+#[cfg(unix)]
+fn repeats() -> TestResult {
+    let dir = tempfile::tempdir()?;
+    Command::cargo_bin(PRG)?
+        .current_dir(dir.path())
+        .args(["-t", "-n", "0.1", "-e", COUNT_TO_3])
+        .assert()
+        .code(1)
+        .stdout("\x1b[H\x1b[2Jrun 1\n\x1b[H\x1b[2Jrun 2\n\x1b[H\x1b[2Jrun 3\n");
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+#[cfg(unix)]
+fn differences() -> TestResult {
+    let dir = tempfile::tempdir()?;
+    Command::cargo_bin(PRG)?
+        .current_dir(dir.path())
+        .args(["-d", "-t", "-n", "0", "-e", COUNT_TO_3])
+        .assert()
+        .code(1)
+        .stdout(
+            "\x1b[H\x1b[2Jrun 1\n\
+            \x1b[H\x1b[2Jrun \x1b[7m2\x1b[0m\n\
+            \x1b[H\x1b[2Jrun \x1b[7m3\x1b[0m\n",
+        );
+    Ok(())
+}
@@ -1,7 +1,8 @@
-use clap::{App, Arg};
-use std::{error::Error, fs::File, io::{self, BufRead, BufReader, Read}};
+use clap::Parser;
+use serde::Serialize;
+use std::io::{self, BufRead};
 
-type MyResult<T> = Result<T, Box<dyn Error>>;
+use coreutils_common::{open, parse_args, write_json, FilesFromArgs, MyResult};
 
 #[derive(Debug)]
 pub struct Config {
@@ -10,6 +11,7 @@ pub struct Config {
     words: bool,
     bytes: bool,
     chars: bool,
+    json: bool,
 }
 
 #[derive(PartialEq, Debug)]
@@ -48,6 +50,33 @@ pub fn count(mut file: impl BufRead) -> MyResult<FileInfo> {
 
 }
 
+/// One line of --json output: the selected counts for a file, or for all of
+/// them when `file` is null.
+#[derive(Debug, Serialize)]
+struct Counts<'a> {
+    file: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lines: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    words: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bytes: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chars: Option<usize>,
+}
+
+impl<'a> Counts<'a> {
+    fn new(file: Option<&'a str>, info: &FileInfo, config: &Config) -> Self {
+        Counts {
+            file,
+            lines: config.lines.then_some(info.num_lines),
+            words: config.words.then_some(info.num_words),
+            bytes: config.bytes.then_some(info.num_bytes),
+            chars: config.chars.then_some(info.num_chars),
+        }
+    }
+}
+
 fn format_field(num: usize, show: bool) -> String {
     if show {
         format!("{:>8}", num)
@@ -56,113 +85,111 @@ fn format_field(num: usize, show: bool) -> String {
     }
 }
 
-fn open(filename: &str) -> MyResult<Box<dyn BufRead>> {
-    match filename {
-        "-" => Ok(Box::new(BufReader::new(io::stdin()))),
-        _ => Ok(Box::new(BufReader::new(File::open(filename)?))),
-    }
+
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust wc")]
+struct Args {
+    /// Input file(s)
+    #[arg(value_name = "FILE", default_value = "-", conflicts_with = "files_from")]
+    files: Vec<String>,
+
+    #[command(flatten)]
+    files_from: FilesFromArgs,
+
+    /// print the newline counts
+    #[arg(short = 'l', long)]
+    lines: bool,
+
+    /// print the word counts
+    #[arg(short = 'w', long)]
+    words: bool,
+
+    /// print the byte counts
+    #[arg(short = 'c', long)]
+    bytes: bool,
+
+    /// print the character counts
+    #[arg(short = 'm', long, conflicts_with = "bytes")]
+    chars: bool,
+
+    /// print the counts for each file as a line of JSON
+    #[arg(long)]
+    json: bool,
 }
 
 pub fn get_args() -> MyResult<Config> {
-    let matches = App::new("wcr")
-        .about("Rust wc")
-        .author("OFFBLACK")
-        .version("0.1.0")        
-        .arg(
-            Arg::with_name("files")
-                .value_name("FILE")
-                .help("Input file(s)")
-                .multiple(true)
-                .default_value("-")
-        )
-        .arg(
-            Arg::with_name("lines")
-                .short("l")
-                .long("lines")
-                .help("print the newline counts")
-                .takes_value(false)
-        )
-        .arg(
-            Arg::with_name("words")
-                .short("w")
-                .long("words")
-                .takes_value(false)
-                .help("print the word counts")
-        )
-        .arg(
-            Arg::with_name("bytes")
-                .short("c")
-                .long("bytes")
-                .takes_value(false)
-                .help("print the byte counts")
-        )
-        .arg(
-            Arg::with_name("chars")
-                .short("m")
-                .long("chars")
-                .takes_value(false)
-                .help("print the character counts")
-                .conflicts_with("bytes")
-        )
-        .get_matches();
-
-    let lines = matches.is_present("lines");
-    let words = matches.is_present("words");
-    let bytes = matches.is_present("bytes");
-    let chars = matches.is_present("chars");
+    let Args { files, files_from, lines, words, bytes, chars, json } = parse_args();
 
     let any_present = lines || words || bytes || chars;
 
     Ok(Config {
-        files: matches.values_of_lossy("files").unwrap(),
+        files: files_from.files(files)?,
         lines: if any_present { lines} else { true },
         words: if any_present { words } else { true },
         bytes: if any_present { bytes } else { true },
         chars: if any_present { chars } else { false },
+        json,
     })
 }
 
-pub fn run(config: Config) -> MyResult<()> {
-    let mut total_lines = 0;
-    let mut total_words = 0;
-    let mut total_bytes = 0;
-    let mut total_chars = 0;
+/// Prints the counts for every input and returns the exit status: 1 if any
+/// file failed to open.
+pub fn run(config: Config) -> MyResult<i32> {
+    let mut status = 0;
+    let mut total = FileInfo {
+        num_lines: 0,
+        num_words: 0,
+        num_bytes: 0,
+        num_chars: 0,
+    };
+    let mut out = io::stdout().lock();
 
     for filename in &config.files {
         match open(filename) {
-            Err(err) => eprintln!("{}: {}", filename, err),
+            Err(err) => {
+                eprintln!("{}", err);
+                status = 1;
+            }
             Ok(file) => {
                 if let Ok(info) = count(file) {
-                    println!("{}{}{}{}{}", 
-                        format_field(info.num_lines, config.lines),
-                        format_field(info.num_words, config.words),
-                        format_field(info.num_bytes, config.bytes),
-                        format_field(info.num_chars, config.chars),
-                        if filename == "-" {
-                            "".to_string()
-                        } else {
-                            format!(" {}", filename)
-                        }
-                    );
-                    total_lines += info.num_lines;
-                    total_words += info.num_words;
-                    total_bytes += info.num_bytes;
-                    total_chars += info.num_chars;
+                    if config.json {
+                        write_json(&mut out, &Counts::new(Some(filename), &info, &config))?;
+                    } else {
+                        println!("{}{}{}{}{}", 
+                            format_field(info.num_lines, config.lines),
+                            format_field(info.num_words, config.words),
+                            format_field(info.num_bytes, config.bytes),
+                            format_field(info.num_chars, config.chars),
+                            if filename == "-" {
+                                "".to_string()
+                            } else {
+                                format!(" {}", filename)
+                            }
+                        );
+                    }
+                    total.num_lines += info.num_lines;
+                    total.num_words += info.num_words;
+                    total.num_bytes += info.num_bytes;
+                    total.num_chars += info.num_chars;
                 }
             }
         }
     }
 
     if config.files.len() > 1 {
-        println!(
-            "{}{}{}{} total",
-            format_field(total_lines, config.lines),
-            format_field(total_words, config.words),
-            format_field(total_bytes, config.bytes),
-            format_field(total_chars, config.chars),
-        );
+        if config.json {
+            write_json(&mut out, &Counts::new(None, &total, &config))?;
+        } else {
+            println!(
+                "{}{}{}{} total",
+                format_field(total.num_lines, config.lines),
+                format_field(total.num_words, config.words),
+                format_field(total.num_bytes, config.bytes),
+                format_field(total.num_chars, config.chars),
+            );
+        }
     }
-    Ok(())
+    Ok(status)
 }
 
 #[cfg(test)]
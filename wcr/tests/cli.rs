@@ -29,11 +29,11 @@ fn gen_bad_file() -> String {
 #[test]
 fn dies_chars_and_bytes() -> TestResult {
     Command::cargo_bin(PRG)?
-        .args(&["-m", "-c"])
+        .args(["-m", "-c"])
         .assert()
         .failure()
         .stderr(predicate::str::contains(
-            "The argument '--bytes' cannot be used with '--chars'",
+            "the argument '--chars' cannot be used with '--bytes'",
         ));
     Ok(())
 }
@@ -57,7 +57,7 @@ fn skips_bad_file() -> TestResult {
     Command::cargo_bin(PRG)?
         .arg(bad)
         .assert()
-        .success()
+        .code(1)
         .stderr(predicate::str::is_match(expected)?);
     Ok(())
 }
@@ -212,3 +212,29 @@ fn test_all_words_lines() -> TestResult {
 fn test_all_bytes_lines() -> TestResult {
     run(&["-cl", EMPTY, FOX, ATLAMAL], "tests/expected/all.cl.out")
 }
+
+// --------------------------------------------------
+#[test]
+fn test_fox_lines_chars_json() -> TestResult {
+    run(&["--json", "-lm", FOX], "tests/expected/fox.txt.ml.json.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn test_all_json() -> TestResult {
+    run(&["--json", EMPTY, FOX, ATLAMAL], "tests/expected/all.json.out")
+}
+
+// --------------------------------------------------
+#[test]
+fn test_all_files_from() -> TestResult {
+    // As written by `findr -z`
+    let expected = fs::read_to_string("tests/expected/all.out")?;
+    Command::cargo_bin(PRG)?
+        .args(["--files-from", "-"])
+        .write_stdin(format!("{EMPTY}\0{FOX}\0{ATLAMAL}\0"))
+        .assert()
+        .success()
+        .stdout(expected);
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use clap::Parser;
+use std::io::Write;
+
+use coreutils_common::{parse_args, MyResult, Output};
+
+/// How much is written at a time. It's larger than the buffer of [`Output`],
+/// so each write goes straight to stdout without being copied first.
+const BUF_SIZE: usize = 16 * 1024;
+
+#[derive(Debug)]
+pub struct Config {
+    line: String,
+}
+
+#[derive(Debug, Parser)]
+#[command(author = "OFFBLACK", version = "0.1.0", about = "Rust yes")]
+struct Args {
+    /// The text to repeat, one line per copy
+    #[arg(value_name = "STRING", default_value = "y")]
+    strings: Vec<String>,
+}
+
+pub fn get_args() -> MyResult<Config> {
+    let args: Args = parse_args();
+
+    Ok(Config {
+        line: format!("{}\n", args.strings.join(" ")),
+    })
+}
+
+/// As many copies of `line` as fit in `size` bytes, but at least one, so
+/// every write ends on a whole line.
+fn fill(line: &str, size: usize) -> Vec<u8> {
+    line.as_bytes().repeat((size / line.len()).max(1))
+}
+
+/// Prints the line until the reader goes away, which ends the process: by
+/// SIGPIPE, or where there's none, by [`Output`] exiting on the broken pipe.
+/// It makes an endless, cheap input for timing the other tools, e.g.
+/// `yesr 'a line of text' | head -c 1G | wcr`.
+pub fn run(config: Config) -> MyResult<i32> {
+    let buf = fill(&config.line, BUF_SIZE);
+    let mut out = Output::stdout();
+    loop {
+        out.write_all(&buf)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fill;
+
+    #[test]
+    fn test_fill() {
+        assert_eq!(fill("y\n", 6), b"y\ny\ny\n");
+        assert_eq!(fill("y\n", 7), b"y\ny\ny\n");
+        assert_eq!(fill("longer\n", 4), b"longer\n");
+    }
+}
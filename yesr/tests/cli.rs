@@ -0,0 +1,80 @@
+use std::{
+    io::{BufRead, BufReader, Read},
+    process::{Child, Command, Stdio},
+};
+
+type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+const PRG: &str = "yesr";
+
+// --------------------------------------------------
+fn spawn(args: &[&str]) -> std::io::Result<Child> {
+    Command::new(assert_cmd::cargo::cargo_bin(PRG))
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+}
+
+// --------------------------------------------------
+/// The first `count` lines yesr prints, after which it is hung up on.
+fn first_lines(args: &[&str], count: usize) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut child = spawn(args)?;
+    let lines = BufReader::new(child.stdout.take().unwrap())
+        .lines()
+        .take(count)
+        .collect::<Result<_, _>>()?;
+    child.wait()?;
+    Ok(lines)
+}
+
+// --------------------------------------------------
+#[test]
+fn default_y() -> TestResult {
+    assert_eq!(first_lines(&[], 3)?, ["y", "y", "y"]);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn one_string() -> TestResult {
+    assert_eq!(first_lines(&["no"], 2)?, ["no", "no"]);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn strings_joined() -> TestResult {
+    assert_eq!(first_lines(&["a", "b  c", ""], 2)?, ["a b  c ", "a b  c "]);
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn whole_lines() -> TestResult {
+    // Lines that don't divide the buffer still come out whole
+    let line = "x".repeat(1000);
+    assert!(first_lines(&[&line], 100)?.iter().all(|next| *next == line));
+    Ok(())
+}
+
+// --------------------------------------------------
+#[test]
+fn closed_pipe_stops_quietly() -> TestResult {
+    let mut child = spawn(&[])?;
+    let mut stdout = child.stdout.take().unwrap();
+    let mut buf = [0; 1 << 20];
+    stdout.read_exact(&mut buf)?;
+    drop(stdout);
+    let output = child.wait_with_output()?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        assert_eq!(output.status.signal(), Some(13));
+    }
+    #[cfg(not(unix))]
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+    Ok(())
+}